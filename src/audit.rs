@@ -0,0 +1,53 @@
+use std::time::SystemTime;
+
+use crate::Profile;
+
+/// A read of a secret value during an extraction, as reported to the
+/// callbacks registered via
+/// [`Figment::audit_secrets()`](crate::Figment::audit_secrets()).
+///
+/// A value is secret if its key path is, or is nested under, a key path
+/// registered via [`Figment::redact()`](crate::Figment::redact()). The secret
+/// value itself is never part of the record.
+///
+/// With the `tracing` feature enabled, every secret read is additionally
+/// logged as an `INFO` event with the target `figment::audit` and the fields
+/// `key`, `requested`, and `profile`, whether or not callbacks are registered.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use figment::{Figment, SecretRead, util::map};
+///
+/// let log: Arc<Mutex<Vec<SecretRead>>> = Arc::default();
+/// let sink = log.clone();
+/// let figment = Figment::from(("database", map!["password" => "hunter2", "user" => "admin"]))
+///     .redact(["database.password"])
+///     .audit_secrets(move |read| sink.lock().unwrap().push(read.clone()));
+///
+/// let _: String = figment.extract_inner("database.user").unwrap();
+/// assert!(log.lock().unwrap().is_empty());
+///
+/// let _: figment::value::Dict = figment.extract_inner("database").unwrap();
+/// let log = log.lock().unwrap();
+/// assert_eq!(log.len(), 1);
+/// assert_eq!(log[0].key, "database.password");
+/// assert_eq!(log[0].requested, "database");
+/// assert_eq!(log[0].profile, "global");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecretRead {
+    /// The key path of the secret value that was read, as in
+    /// `database.password`, with array indices as components, as in
+    /// `servers.0.password`.
+    pub key: String,
+    /// The key path that was requested, as by
+    /// [`Figment::extract_inner()`](crate::Figment::extract_inner()), or the
+    /// empty string if the entire configuration was extracted.
+    pub requested: String,
+    /// The profile the value was read from.
+    pub profile: Profile,
+    /// When the extraction that read the value started.
+    pub time: SystemTime,
+}
@@ -0,0 +1,252 @@
+use std::path::PathBuf;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{Figment, Error};
+use crate::providers::{Env, Format, Serialized, Toml};
+
+/// A builder for the canonical three-layer figment: defaults, overridden by a
+/// TOML file, overridden by environment variables.
+///
+/// [`FigmentBuilder::standard()`] configures the file and the environment
+/// variable prefix. [`FigmentBuilder::defaults()`] adds defaults.
+/// [`FigmentBuilder::build()`] returns the figment, and
+/// [`FigmentBuilder::extract()`] extracts from it directly. For the common
+/// case of defaults from a type's `Default` implementation, see [`extract()`].
+///
+/// Environment variables are read with [`Env::prefixed()`] using the prefix
+/// followed by `_`. A double underscore, `__`, in a variable's name separates
+/// the keys of a nested value, so `APP_SERVER__PORT` sets `server.port`.
+///
+/// # Example
+///
+/// ```rust
+/// use serde::{Serialize, Deserialize};
+/// use figment::FigmentBuilder;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Config { name: String, port: u16, debug: bool }
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("App.toml", "name = \"app\"\nport = 80")?;
+///     jail.set_env("APP_PORT", 8080);
+///
+///     let config: Config = FigmentBuilder::standard("APP", "App.toml")
+///         .defaults(Config { name: "default".into(), port: 0, debug: false })
+///         .extract()?;
+///
+///     assert_eq!(config, Config { name: "app".into(), port: 8080, debug: false });
+///     Ok(())
+/// });
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(nightly, doc(cfg(all(feature = "env", feature = "toml"))))]
+pub struct FigmentBuilder {
+    defaults: Figment,
+    file: Option<PathBuf>,
+    env_prefix: Option<String>,
+}
+
+impl FigmentBuilder {
+    /// Returns a builder with no layers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{FigmentBuilder, util::map};
+    ///
+    /// let figment = FigmentBuilder::new().defaults(map!["port" => 80]).build();
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 80);
+    /// ```
+    pub fn new() -> Self {
+        FigmentBuilder::default()
+    }
+
+    /// Returns a builder that reads the TOML file at `file` and environment
+    /// variables prefixed with `prefix` followed by `_`, in that order of
+    /// increasing precedence. A missing file is treated as empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::FigmentBuilder;
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", "[server]\nport = 80\nhost = \"localhost\"")?;
+    ///     jail.set_env("APP_SERVER__PORT", 8080);
+    ///
+    ///     let figment = FigmentBuilder::standard("APP", "App.toml").build();
+    ///     assert_eq!(figment.extract_inner::<u16>("server.port")?, 8080);
+    ///     assert_eq!(figment.extract_inner::<String>("server.host")?, "localhost");
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn standard<P: Into<PathBuf>>(prefix: &str, file: P) -> Self {
+        FigmentBuilder::new().file(file).env(prefix)
+    }
+
+    /// Adds `defaults` as the lowest-precedence layer. Defaults accumulate:
+    /// later defaults override earlier ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{FigmentBuilder, util::map};
+    ///
+    /// let figment = FigmentBuilder::new()
+    ///     .defaults(map!["port" => 80, "workers" => 4])
+    ///     .defaults(map!["port" => 8080])
+    ///     .build();
+    ///
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8080);
+    /// assert_eq!(figment.extract_inner::<u16>("workers").unwrap(), 4);
+    /// ```
+    #[track_caller]
+    pub fn defaults<T: Serialize>(mut self, defaults: T) -> Self {
+        self.defaults = self.defaults.merge(Serialized::defaults(defaults));
+        self
+    }
+
+    /// Sets the TOML file layer to the file at `path`, replacing any previous
+    /// file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::FigmentBuilder;
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Other.toml", "port = 8080")?;
+    ///
+    ///     let figment = FigmentBuilder::standard("APP", "App.toml")
+    ///         .file("Other.toml")
+    ///         .build();
+    ///
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8080);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// Sets the environment variable layer to variables prefixed with
+    /// `prefix` followed by `_`, replacing any previous prefix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::FigmentBuilder;
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.set_env("MY_APP_PORT", 8080);
+    ///
+    ///     let figment = FigmentBuilder::new().env("MY_APP").build();
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8080);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn env(mut self, prefix: &str) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Returns the figment: the defaults, merged with the file, merged with
+    /// the environment variables.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{FigmentBuilder, util::map};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", "port = 80")?;
+    ///
+    ///     let figment = FigmentBuilder::standard("APP", "App.toml")
+    ///         .defaults(map!["port" => 0])
+    ///         .build()
+    ///         .merge(("port", 443));
+    ///
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 443);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn build(self) -> Figment {
+        let mut figment = self.defaults;
+        if let Some(path) = self.file {
+            figment = figment.merge(Toml::file(path));
+        }
+
+        if let Some(prefix) = self.env_prefix {
+            figment = figment.merge(Env::prefixed(&format!("{}_", prefix)).split("__"));
+        }
+
+        figment
+    }
+
+    /// Builds the figment and extracts a `T` from it. Equivalent to
+    /// `self.build().extract()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::FigmentBuilder;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config { workers: usize }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_WORKERS", 4);
+    ///
+    ///     let config: Config = FigmentBuilder::standard("APP", "App.toml").extract()?;
+    ///     assert_eq!(config.workers, 4);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn extract<T: DeserializeOwned>(self) -> Result<T, Error> {
+        self.build().extract()
+    }
+}
+
+/// Extracts a `T` from the [standard](FigmentBuilder::standard()) figment for
+/// `prefix` and `file`, with defaults from `T::default()`.
+///
+/// This is the canonical three-layer setup in a single call: the values of
+/// `T::default()`, overridden by the TOML file at `file`, overridden by
+/// environment variables prefixed with `prefix` followed by `_`. Use
+/// [`FigmentBuilder`] to customize the layers.
+///
+/// # Example
+///
+/// ```rust
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Config { name: String, port: u16, debug: bool }
+///
+/// impl Default for Config {
+///     fn default() -> Self {
+///         Config { name: "app".into(), port: 80, debug: false }
+///     }
+/// }
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("App.toml", "port = 8000\ndebug = true")?;
+///     jail.set_env("APP_DEBUG", false);
+///
+///     let config: Config = figment::extract("APP", "App.toml")?;
+///     assert_eq!(config, Config { name: "app".into(), port: 8000, debug: false });
+///     Ok(())
+/// });
+/// ```
+#[track_caller]
+#[cfg_attr(nightly, doc(cfg(all(feature = "env", feature = "toml"))))]
+pub fn extract<T, P>(prefix: &str, file: P) -> Result<T, Error>
+    where T: Default + Serialize + DeserializeOwned, P: Into<PathBuf>
+{
+    FigmentBuilder::standard(prefix, file)
+        .defaults(T::default())
+        .extract()
+}
@@ -1,5 +1,8 @@
+use uncased::UncasedStr;
+
+use crate::prelude::*;
 use crate::Profile;
-use crate::value::{Value, Map};
+use crate::value::{Value, Map, Dict, Empty, KeyCase};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Order {
@@ -28,18 +31,209 @@ impl Coalescible for Value {
     }
 }
 
-impl<K: Eq + std::hash::Hash + Ord, V: Coalescible> Coalescible for Map<K, V> {
-    fn coalesce(self, mut other: Self, order: Order) -> Self {
-        let mut joined = Map::new();
-        for (a_key, a_val) in self {
-            match other.remove(&a_key) {
-                Some(b_val) => joined.insert(a_key, a_val.coalesce(b_val, order)),
-                None => joined.insert(a_key, a_val),
-            };
+impl<K: Ord, V: Coalescible> Coalescible for Map<K, V> {
+    fn coalesce(self, other: Self, order: Order) -> Self {
+        coalesce_maps(self, other, |a, b| a.coalesce(b, order))
+    }
+}
+
+#[cfg(feature = "preserve-order")]
+impl Coalescible for Dict {
+    fn coalesce(self, other: Self, order: Order) -> Self {
+        coalesce_dicts(self, other, |a, b| a.coalesce(b, order))
+    }
+}
+
+/// Coalesces the dictionaries `a` and `b` as [`coalesce_maps()`] does maps.
+#[cfg(not(feature = "preserve-order"))]
+pub fn coalesce_dicts<F>(a: Dict, b: Dict, f: F) -> Dict
+    where F: FnMut(Value, Value) -> Value
+{
+    coalesce_maps(a, b, f)
+}
+
+/// Coalesces the dictionaries `a` and `b`, calling `f` to coalesce the values
+/// of keys in both. The keys of `a` keep their position, followed by the keys
+/// only in `b` in the order they appear in `b`.
+#[cfg(feature = "preserve-order")]
+pub fn coalesce_dicts<F>(a: Dict, b: Dict, mut f: F) -> Dict
+    where F: FnMut(Value, Value) -> Value
+{
+    // Removing from `b` would perturb the order of its remaining keys, so its
+    // values are taken out in place instead.
+    let indices: Vec<Option<usize>> = a.keys().map(|k| b.get_index_of(k)).collect();
+    let mut b: Vec<Option<(String, Value)>> = b.into_iter().map(Some).collect();
+    let mut joined = Dict::with_capacity(a.len() + b.len());
+    for ((key, a_val), i) in a.into_iter().zip(indices) {
+        match i.and_then(|i| b[i].take()) {
+            Some((_, b_val)) => joined.insert(key, f(a_val, b_val)),
+            None => joined.insert(key, a_val),
+        };
+    }
+
+    joined.extend(b.into_iter().flatten());
+    joined
+}
+
+/// Coalesces `a` and `b`, calling `f` to coalesce the values of keys in both.
+pub fn coalesce_maps<K, V, F>(a: Map<K, V>, mut b: Map<K, V>, mut f: F) -> Map<K, V>
+    where K: Ord, F: FnMut(V, V) -> V
+{
+    let mut joined = Map::new();
+    for (a_key, a_val) in a {
+        match b.remove(&a_key) {
+            Some(b_val) => joined.insert(a_key, f(a_val, b_val)),
+            None => joined.insert(a_key, a_val),
+        };
+    }
+
+    // `b` contains `b - a`, i.e, additions. keep them all.
+    joined.extend(b);
+    joined
+}
+
+/// Like [`Coalescible::coalesce()`] for dictionaries except that arrays in
+/// which every element is a dictionary with a `key` field are coalesced
+/// element-wise: elements with equal `key` values are coalesced, the remaining
+/// elements of `b` are appended, and the order of elements in `a` is kept.
+pub fn coalesce_keyed(a: Dict, b: Dict, order: Order, key: &str) -> Dict {
+    fn is_keyed(array: &[Value], key: &str) -> bool {
+        !array.is_empty() && array.iter().all(|v| v.as_dict().is_some_and(|d| d.contains_key(key)))
+    }
+
+    fn id<'v>(value: &'v Value, key: &str) -> Option<&'v Value> {
+        value.as_dict()?.get(key)
+    }
+
+    fn coalesce(a: Value, b: Value, order: Order, key: &str) -> Value {
+        use {Value::Dict as D, Value::Array as A, Order::Join as L, Order::Merge as R};
+        match (a, b, order) {
+            (D(t, a), D(_, b), L) | (D(_, a), D(t, b), R) => D(t, coalesce_keyed(a, b, order, key)),
+            (A(t, a), A(_, b), L) | (A(_, a), A(t, b), R) if is_keyed(&a, key) && is_keyed(&b, key) => {
+                let mut b: Vec<Option<Value>> = b.into_iter().map(Some).collect();
+                let mut array = Vec::with_capacity(a.len() + b.len());
+                for a_val in a {
+                    let b_val = b.iter_mut()
+                        .find(|b_val| b_val.as_ref().and_then(|v| id(v, key)) == id(&a_val, key))
+                        .and_then(Option::take);
+
+                    match b_val {
+                        Some(b_val) => array.push(coalesce(a_val, b_val, order, key)),
+                        None => array.push(a_val),
+                    }
+                }
+
+                array.extend(b.into_iter().flatten());
+                A(t, array)
+            }
+            (a, b, order) => a.coalesce(b, order),
+        }
+    }
+
+    coalesce_dicts(a, b, |a, b| coalesce(a, b, order, key))
+}
+
+/// Renames the keys of `new`, recursively, to the spelling of the first key in
+/// `old` or, failing that, in `new` itself that is equal to it ignoring ASCII
+/// case. Values of keys in `new` that are thereby unified are merged, with later
+/// keys taking precedence.
+pub fn unify_keys(old: Option<&Dict>, new: Dict) -> Dict {
+    fn spelling<'d>(dict: Option<&'d Dict>, key: &str) -> Option<&'d String> {
+        dict?.keys().find(|k| UncasedStr::new(k) == key)
+    }
+
+    let mut unified = Dict::new();
+    for (key, value) in new {
+        let key = spelling(old, &key)
+            .or_else(|| spelling(Some(&unified), &key))
+            .cloned()
+            .unwrap_or(key);
+
+        match unified.get_mut(&key) {
+            Some(prev) => *prev = core::mem::replace(prev, Empty::None.into()).merge(value),
+            None => { unified.insert(key, value); }
+        }
+    }
+
+    for (key, value) in unified.iter_mut() {
+        if let Value::Dict(_, dict) = value {
+            let old = old.and_then(|old| old.get(key)).and_then(Value::as_dict);
+            *dict = unify_keys(old, core::mem::take(dict));
+        }
+    }
+
+    unified
+}
+
+/// Converts the keys of `dict`, recursively, including those in dictionaries
+/// nested in arrays, to `case`. Values of keys that are thereby unified are
+/// merged, with later keys taking precedence.
+pub fn canonicalize_keys(dict: Dict, case: KeyCase) -> Dict {
+    fn canonicalize(value: &mut Value, case: KeyCase) {
+        match value {
+            Value::Dict(_, dict) => *dict = canonicalize_keys(core::mem::take(dict), case),
+            Value::Array(_, array) => array.iter_mut().for_each(|v| canonicalize(v, case)),
+            _ => {}
+        }
+    }
+
+    let mut canonical = Dict::new();
+    for (key, mut value) in dict {
+        canonicalize(&mut value, case);
+        let key = case.apply(&key);
+        match canonical.get_mut(&key) {
+            Some(prev) => *prev = core::mem::replace(prev, Empty::None.into()).merge(value),
+            None => { canonical.insert(key, value); }
+        }
+    }
+
+    canonical
+}
+
+/// Removes every key of `dict`, recursively, including those in dictionaries
+/// nested in arrays, whose value is [`Empty::Unset`].
+pub fn remove_unset(dict: Dict) -> Dict {
+    fn remove(value: &mut Value) {
+        match value {
+            Value::Dict(_, dict) => *dict = remove_unset(core::mem::take(dict)),
+            Value::Array(_, array) => array.iter_mut().for_each(remove),
+            _ => {}
         }
+    }
+
+    dict.into_iter()
+        .filter(|(_, v)| !matches!(v, Value::Empty(_, Empty::Unset)))
+        .map(|(k, mut v)| { remove(&mut v); (k, v) })
+        .collect()
+}
+
+/// Returns the key paths of the keys in `dict`, recursively, whose values are
+/// replaced when keys that `unified` maps to the same key are merged, with
+/// later keys taking precedence. Values of dictionaries are merged, not
+/// replaced, and are thus only searched.
+pub fn shadowed_keys<F: Fn(&str) -> String>(dict: &Dict, unified: &F) -> Vec<String> {
+    fn walk<F>(dict: &Dict, unified: &F, path: &mut Vec<String>, shadowed: &mut Vec<String>)
+        where F: Fn(&str) -> String
+    {
+        let keys: Vec<_> = dict.iter().map(|(k, v)| (unified(k), k, v)).collect();
+        for (i, (unified_key, key, value)) in keys.iter().enumerate() {
+            path.push(key.to_string());
+            let replaced = keys[i + 1..].iter()
+                .filter(|(later, ..)| later == unified_key)
+                .any(|(.., later)| !(value.as_dict().is_some() && later.as_dict().is_some()));
 
-        // `b` contains `b - a`, i.e, additions. keep them all.
-        joined.extend(other);
-        joined
+            if replaced {
+                let keys: Vec<_> = path.iter().map(|k| crate::util::escape_key(k)).collect();
+                shadowed.push(keys.join("."));
+            } else if let Value::Dict(_, dict) = value {
+                walk(dict, unified, path, shadowed);
+            }
+
+            path.pop();
+        }
     }
+
+    let mut shadowed = vec![];
+    walk(dict, unified, &mut vec![], &mut shadowed);
+    shadowed
 }
@@ -0,0 +1,340 @@
+use core::fmt::Write;
+
+use serde::Serialize;
+
+use crate::prelude::*;
+use crate::{Figment, Metadata};
+use crate::error::{Error, Kind, Actual, Result};
+use crate::value::{Value, Dict, Empty, KeyCase, Compact};
+use crate::util::{split_key, escape_key};
+
+/// Documentation of the keys of a [`Figment`], as returned by
+/// [`Figment::docs()`].
+///
+/// `Docs` lists every leaf value in the selected profile(s), that is, every
+/// value which isn't a dictionary, as well as every key path with a
+/// [description](Figment::describe()), whether or not it has a value. Keys
+/// appear in the order they are found in the merged value, followed by the
+/// described keys without a value, followed by the keys added by
+/// [`Docs::defaults()`].
+///
+/// The documentation can be rendered as a Markdown table with
+/// [`Docs::to_markdown()`] or as an HTML table with [`Docs::to_html()`]. Both
+/// have the columns `key`, `type`, `default`, `value`, `source`, and
+/// `description`; values are rendered compactly on a single line, and absent
+/// entries are left empty.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, util::map};
+///
+/// let figment = Figment::from(("name", "app"))
+///     .merge(("workers", 4))
+///     .describe("workers", "Number of worker threads.")
+///     .describe("tls.cert", "Path to the TLS certificate | chain.");
+///
+/// let docs = figment.docs().unwrap().defaults(map!["workers" => 1]).unwrap();
+/// let keys: Vec<_> = docs.keys.iter().map(|doc| doc.key.as_str()).collect();
+/// assert_eq!(keys, ["name", "workers", "tls.cert"]);
+///
+/// assert_eq!(docs.to_markdown(), "\
+///     | key | type | default | value | source | description |\n\
+///     |-----|------|---------|-------|--------|-------------|\n\
+///     | `name` | string |  | `\"app\"` | `global.name` in (&str, &str) |  |\n\
+///     | `workers` | integer | `1` | `4` | `global.workers` in (&str, i32) | \
+///         Number of worker threads. |\n\
+///     | `tls.cert` |  |  |  |  | Path to the TLS certificate \\| chain. |\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Docs<'a> {
+    /// The documentation of each key.
+    pub keys: Vec<KeyDoc<'a>>,
+    key_case: Option<KeyCase>,
+}
+
+/// The documentation of a single key path in [`Docs`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyDoc<'a> {
+    /// The key path, with components escaped as by
+    /// [`escape_key()`](crate::util::escape_key()).
+    pub key: String,
+    /// The type of the value, or of the default if there is no value, one of
+    /// `string`, `char`, `bool`, `integer`, `float`, `empty`, `dict`, or
+    /// `array`. `None` if there is neither.
+    pub kind: Option<&'static str>,
+    /// The default value, if any. Set by [`Docs::defaults()`].
+    pub default: Option<Value>,
+    /// The current value in the selected profile(s), if any.
+    pub value: Option<Value>,
+    /// The metadata of the provider of the current value, if known.
+    pub metadata: Option<&'a Metadata>,
+    /// Where the current value was provided, as in ``"`APP_PORT` in `APP_`
+    /// environment variable(s)"``, if known.
+    pub source: Option<String>,
+    /// The registered description of the key, if any.
+    pub description: Option<&'a str>,
+}
+
+/// Returns the name of the type of `value`.
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::String(..) => "string",
+        Value::Char(..) => "char",
+        Value::Bool(..) => "bool",
+        Value::Num(_, n) => match n.to_actual() {
+            Actual::Float(_) => "float",
+            _ => "integer",
+        },
+        Value::Empty(..) => "empty",
+        Value::Dict(..) => "dict",
+        Value::Array(..) => "array",
+    }
+}
+
+/// Returns the value at the key path `keys` in `dict`, if any.
+fn find<'v>(dict: &'v Dict, keys: &[String]) -> Option<&'v Value> {
+    let (first, rest) = keys.split_first()?;
+    rest.iter().try_fold(dict.get(first)?, |value, key| value.as_dict()?.get(key))
+        .filter(|value| !matches!(value, Value::Empty(_, Empty::Unset)))
+}
+
+/// Returns the key paths of the leaves of `dict`: its values which are not
+/// non-empty dictionaries.
+fn leaves(dict: &Dict, path: &mut Vec<String>, keys: &mut Vec<Vec<String>>) {
+    for (key, value) in dict {
+        path.push(key.clone());
+        match value {
+            Value::Dict(_, dict) if !dict.is_empty() => leaves(dict, path, keys),
+            _ => keys.push(path.clone()),
+        }
+
+        path.pop();
+    }
+}
+
+/// Returns the key path `keys` as a string, escaping each component.
+fn join(keys: &[String]) -> String {
+    keys.iter().map(|k| escape_key(k)).collect::<Vec<_>>().join(".")
+}
+
+impl<'a> Docs<'a> {
+    pub(crate) fn new(figment: &'a Figment) -> Result<Docs<'a>> {
+        let merged = figment.merged_unchecked(&figment.profile, &figment.base_profiles)?;
+        let mut paths = vec![];
+        leaves(&merged, &mut vec![], &mut paths);
+
+        let mut descriptions: Vec<(Vec<String>, &str)> = vec![];
+        for (key, description) in &figment.descriptions {
+            let keys: Vec<String> = split_key(key).into_iter()
+                .map(|k| match figment.key_case {
+                    Some(case) => case.apply(&k),
+                    None => k,
+                })
+                .collect();
+
+            if !keys.is_empty() && !paths.contains(&keys) {
+                paths.push(keys.clone());
+            }
+
+            descriptions.push((keys, description));
+        }
+
+        let keys = paths.into_iter()
+            .map(|path| {
+                let value = find(&merged, &path).cloned();
+                let metadata = value.as_ref().and_then(|v| figment.get_metadata(v.tag()));
+                let source = value.as_ref().zip(metadata).map(|(value, md)| {
+                    let profile = value.tag().profile().unwrap_or_else(|| figment.profile.clone());
+                    let key = md.interpolate(&profile, &path);
+                    match &md.source {
+                        Some(source) => format!("`{}` in {} `{}`", key, md.name, source),
+                        None => format!("`{}` in {}", key, md.name),
+                    }
+                });
+
+                KeyDoc {
+                    key: join(&path),
+                    kind: value.as_ref().map(kind),
+                    default: None,
+                    description: descriptions.iter().find(|(k, _)| *k == path).map(|(_, d)| *d),
+                    value,
+                    metadata,
+                    source,
+                }
+            })
+            .collect();
+
+        Ok(Docs { keys, key_case: figment.key_case })
+    }
+
+    /// Sets the [`default`](KeyDoc::default) of every key to its value in
+    /// `defaults`, typically the `Default` implementation of the
+    /// configuration structure. Keys with a default but without a value or a
+    /// description are added. Returns an error if `defaults` doesn't
+    /// serialize to a dictionary.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use figment::Figment;
+    ///
+    /// #[derive(Default, Serialize)]
+    /// struct Config {
+    ///     port: u16,
+    ///     proxy: Option<String>,
+    /// }
+    ///
+    /// let figment = Figment::from(("port", 8080));
+    /// let docs = figment.docs().unwrap().defaults(Config::default()).unwrap();
+    ///
+    /// let port = docs.get("port").unwrap();
+    /// assert_eq!(port.default, Some(0u16.into()));
+    /// assert_eq!(port.value, Some(8080.into()));
+    ///
+    /// let proxy = docs.get("proxy").unwrap();
+    /// assert_eq!(proxy.kind, Some("empty"));
+    /// assert!(proxy.value.is_none());
+    /// ```
+    pub fn defaults<T: Serialize>(mut self, defaults: T) -> Result<Self> {
+        let dict = match Value::serialize(defaults)? {
+            Value::Dict(_, dict) => dict,
+            value => {
+                let kind = Kind::InvalidType(value.to_actual(), "map".into());
+                return Err(Error::from(kind));
+            }
+        };
+
+        let dict = match self.key_case {
+            Some(case) => crate::coalesce::canonicalize_keys(dict, case),
+            None => dict,
+        };
+
+        let mut paths = vec![];
+        leaves(&dict, &mut vec![], &mut paths);
+        for path in paths {
+            let default = find(&dict, &path).cloned();
+            let key = join(&path);
+            match self.keys.iter_mut().find(|doc| doc.key == key) {
+                Some(doc) => {
+                    doc.kind = doc.kind.or_else(|| default.as_ref().map(kind));
+                    doc.default = default;
+                }
+                None => self.keys.push(KeyDoc {
+                    key,
+                    kind: default.as_ref().map(kind),
+                    default,
+                    value: None,
+                    metadata: None,
+                    source: None,
+                    description: None,
+                }),
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the documentation of the key path `key`, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("server", figment::util::map!["port" => 80]));
+    /// let docs = figment.docs().unwrap();
+    /// assert_eq!(docs.get("server.port").unwrap().kind, Some("integer"));
+    /// assert!(docs.get("server").is_none());
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&KeyDoc<'a>> {
+        let key: Vec<_> = split_key(key).into_iter().map(|k| escape_key(&k).into_owned()).collect();
+        let key = key.join(".");
+        self.keys.iter().find(|doc| doc.key == key)
+    }
+
+    /// Renders the documentation as a Markdown table.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("port", 8080)).describe("port", "The port.");
+    /// let markdown = figment.docs().unwrap().to_markdown();
+    /// assert!(markdown.ends_with("| `port` | integer |  | `8080` | `global.port` in (&str, i32) | The port. |\n"));
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        fn cell(text: &str) -> String {
+            text.replace('|', "\\|").replace('\n', " ")
+        }
+
+        fn code(value: &Option<Value>) -> String {
+            value.as_ref().map(|v| format!("`{}`", cell(&Compact(v).to_string()))).unwrap_or_default()
+        }
+
+        let mut out = String::new();
+        out.push_str("| key | type | default | value | source | description |\n");
+        out.push_str("|-----|------|---------|-------|--------|-------------|\n");
+        for doc in &self.keys {
+            let _ = writeln!(out, "| `{}` | {} | {} | {} | {} | {} |",
+                cell(&doc.key),
+                doc.kind.unwrap_or(""),
+                code(&doc.default),
+                code(&doc.value),
+                cell(doc.source.as_deref().unwrap_or("")),
+                cell(doc.description.unwrap_or("")));
+        }
+
+        out
+    }
+
+    /// Renders the documentation as an HTML table.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("port", 8080)).describe("port", "The <b>port</b>.");
+    /// let html = figment.docs().unwrap().to_html();
+    /// assert!(html.starts_with("<table>\n<thead>"));
+    /// assert!(html.contains("<td><code>port</code></td><td>integer</td>"));
+    /// assert!(html.contains("<td>The &lt;b&gt;port&lt;/b&gt;.</td>"));
+    /// ```
+    pub fn to_html(&self) -> String {
+        fn escape(text: &str) -> String {
+            text.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        }
+
+        fn code(value: &Option<Value>) -> String {
+            value.as_ref()
+                .map(|v| format!("<code>{}</code>", escape(&Compact(v).to_string())))
+                .unwrap_or_default()
+        }
+
+        let mut out = String::from("<table>\n<thead>\n<tr>");
+        for column in &["key", "type", "default", "value", "source", "description"] {
+            let _ = write!(out, "<th>{}</th>", column);
+        }
+
+        out.push_str("</tr>\n</thead>\n<tbody>\n");
+        for doc in &self.keys {
+            let _ = writeln!(out,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&doc.key),
+                doc.kind.unwrap_or(""),
+                code(&doc.default),
+                code(&doc.value),
+                escape(doc.source.as_deref().unwrap_or("")),
+                escape(doc.description.unwrap_or("")));
+        }
+
+        out.push_str("</tbody>\n</table>\n");
+        out
+    }
+}
@@ -1,14 +1,24 @@
 //! Error values produces when extracting configurations.
 
-use std::fmt::{self, Display};
-use std::borrow::Cow;
+use core::fmt::{self, Display};
+use alloc::borrow::Cow;
+
+#[cfg(feature = "std")]
+use {core::cell::RefCell, std::path::PathBuf};
 
 use serde::{ser, de};
 
+use crate::prelude::*;
 use crate::{Figment, Profile, Metadata, value::Tag};
 
+#[cfg(feature = "pretty")]
+mod pretty;
+
+#[cfg(feature = "pretty")]
+pub use pretty::Pretty;
+
 /// A simple alias to `Result` with an error type of [`Error`].
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// An error that occured while producing data or extracting a configuration.
 ///
@@ -71,6 +81,9 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// invalid type: found sequence, expected u16: `staging.port` in TOML file Config.toml
 /// ```
 ///
+/// If the metadata or profile is unknown, the path is displayed as returned by
+/// [`Error::display_path()`] instead.
+///
 /// # Iterator
 ///
 /// An `Error` may contain more than one error. To process all errors, iterate
@@ -95,11 +108,37 @@ pub struct Error {
     pub path: Vec<String>,
     /// The error kind.
     pub kind: Kind,
+    /// Rarely set details, boxed to keep `Error`, and thus every `Result` with
+    /// an `Error`, small. `None` when every detail is unset.
+    extra: Option<Box<Extra>>,
     prev: Option<Box<Error>>,
 }
 
+/// The rarely set details of an [`Error`].
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Extra {
+    /// Context added via [`Error::with_context()`], innermost first.
+    context: Vec<String>,
+    /// A suggestion for resolving the error, if any. See [`Error::help()`].
+    help: Option<String>,
+    /// Whether each leading component of `path` is a sequence index. Missing
+    /// entries are not indices. Kept in sync by the `prefixed` methods.
+    indices: Vec<bool>,
+}
+
 /// An error kind, encapsulating serde's [`serde::de::Error`].
+///
+/// This enum is `#[non_exhaustive]`: new kinds may be added in minor releases,
+/// and some kinds only exist when the `std` feature is enabled, so matches
+/// must include a wildcard arm.
+///
+/// # Compatibility
+///
+/// In figment 0.10 and earlier, `Kind` was exhaustive and had no `Io` or
+/// `Denied` kinds. Exhaustive matches on a `Kind` must add a wildcard arm as
+/// of figment 0.11.
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum Kind {
     /// A custom error message.
     Message(String),
@@ -136,27 +175,113 @@ pub enum Kind {
 
     /// The type `.0` cannot be used for keys, need a `.1`.
     UnsupportedKey(Actual, Cow<'static, str>),
+
+    /// The file at `.1` could not be read: (kind, path). Emitted by file
+    /// providers such as [`Data`](crate::providers::Data) so that callers can
+    /// distinguish, for instance, a missing file from an unreadable one.
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
+    Io(std::io::ErrorKind, PathBuf),
+
+    /// The provider's access was denied by the [`Policy`](crate::Policy) in
+    /// effect: (capability).
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
+    Denied(crate::Capability),
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// An error stashed by [`Error::into_generic()`] to be reclaimed, intact,
+    /// by `<Error as de::Error>::custom()`.
+    static STASHED: RefCell<Option<Error>> = const { RefCell::new(None) };
 }
 
 impl Error {
-    pub(crate) fn prefixed(mut self, path: &str) -> Self {
-        self.path.insert(0, path.into());
+    pub(crate) fn prefixed(self, path: &str) -> Self {
+        self.prefixed_with(path.into(), false)
+    }
+
+    /// Prefixes the path with the sequence index `index`.
+    pub(crate) fn prefixed_index(self, index: usize) -> Self {
+        self.prefixed_with(index.to_string(), true)
+    }
+
+    fn prefixed_with(mut self, component: String, index: bool) -> Self {
+        let mut error = Some(&mut self);
+        while let Some(e) = error {
+            e.path.insert(0, component.clone());
+            match (&mut e.extra, index) {
+                (Some(extra), _) => extra.indices.insert(0, index),
+                (None, true) => e.extra_mut().indices.push(index),
+                (None, false) => {},
+            }
+
+            error = e.prev.as_deref_mut();
+        }
+
         self
     }
 
+    /// Removes the first path component of each error in `self` if it is one
+    /// of `keys`. Used to hide the internal fields of magic values.
+    pub(crate) fn unprefixed(mut self, keys: &[&str]) -> Self {
+        let mut error = Some(&mut self);
+        while let Some(e) = error {
+            if e.path.first().is_some_and(|k| keys.contains(&k.as_str())) {
+                e.path.remove(0);
+                if let Some(extra) = e.extra.as_mut().filter(|x| !x.indices.is_empty()) {
+                    extra.indices.remove(0);
+                }
+            }
+
+            error = e.prev.as_deref_mut();
+        }
+
+        self
+    }
+
+    /// Appends `self` to the end of the chain of errors in `error`.
     pub(crate) fn chain(self, mut error: Error) -> Self {
-        error.prev = Some(Box::new(self));
+        let mut tail = &mut error;
+        while tail.prev.is_some() {
+            tail = tail.prev.as_deref_mut().expect("is_some");
+        }
+
+        tail.prev = Some(Box::new(self));
         error
     }
 
     pub(crate) fn retagged(mut self, tag: Tag) -> Self {
-        if self.tag.is_default() {
-            self.tag = tag;
+        let mut error = Some(&mut self);
+        while let Some(e) = error {
+            if e.tag.is_default() {
+                e.tag = tag;
+            }
+
+            error = e.prev.as_deref_mut();
         }
 
         self
     }
 
+    /// Converts `self` into any deserializer error `E`. If `E` is `Error`,
+    /// `self` is returned unchanged, including its kind, path, and tags.
+    /// Otherwise, `E` is constructed from `self`'s `Display` implementation.
+    ///
+    /// This allows `Deserialize` implementations that are generic over the
+    /// deserializer, like `Either`, to propagate structured errors. Without
+    /// the `std` feature, only the message is propagated.
+    pub(crate) fn into_generic<E: de::Error>(self) -> E {
+        let message = self.to_string();
+        #[cfg(feature = "std")]
+        STASHED.with(|stash| *stash.borrow_mut() = Some(self));
+        let error = E::custom(message);
+        #[cfg(feature = "std")]
+        STASHED.with(|stash| stash.borrow_mut().take());
+        error
+    }
+
     pub(crate) fn resolved(mut self, config: &Figment) -> Self {
         let mut error = Some(&mut self);
         while let Some(e) = error {
@@ -201,6 +326,68 @@ impl Error {
         self
     }
 
+    /// Adds `context` to every error in `self`, preserving all other details.
+    /// Context is displayed before the error message, outermost first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("port", "eighty"));
+    /// let error = figment.extract_inner::<u16>("port").unwrap_err()
+    ///     .with_context("while loading plugin `web`");
+    ///
+    /// assert_eq!(error.context(), ["while loading plugin `web`"]);
+    /// assert_eq!(error.path, vec!["port"]);
+    /// assert!(error.to_string().starts_with("while loading plugin `web`: invalid type"));
+    /// ```
+    pub fn with_context<C: Display>(mut self, context: C) -> Self {
+        let context = context.to_string();
+        let mut error = Some(&mut self);
+        while let Some(e) = error {
+            e.extra_mut().context.push(context.clone());
+            error = e.prev.as_deref_mut();
+        }
+
+        self
+    }
+
+    /// Prefixes the path of every error in `self` with the key path `key`.
+    /// See [`Value::find()`](crate::value::Value::find()) for the key syntax.
+    ///
+    /// This is useful when an error was produced by deserializing a value
+    /// extracted from `key`, for instance from a [focused](crate::Figment::focus())
+    /// figment, and should be reported relative to the root.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("plugins.web.port", "eighty"));
+    /// let error = figment.focus("plugins.web")
+    ///     .extract_inner::<u16>("port")
+    ///     .unwrap_err()
+    ///     .in_key("plugins.web");
+    ///
+    /// assert_eq!(error.path, vec!["plugins", "web", "port"]);
+    /// assert_eq!(error.key().unwrap(), "plugins.web.port");
+    /// ```
+    pub fn in_key(mut self, key: &str) -> Self {
+        let keys = crate::util::split_key(key);
+        let mut error = Some(&mut self);
+        while let Some(e) = error {
+            e.path.splice(0..0, keys.iter().cloned());
+            if let Some(extra) = e.extra.as_mut().filter(|x| !x.indices.is_empty()) {
+                extra.indices.splice(0..0, keys.iter().map(|_| false));
+            }
+            error = e.prev.as_deref_mut();
+        }
+
+        self
+    }
+
     /// Returns the number of errors represented by `self`.
     ///
     /// # Example
@@ -231,6 +418,352 @@ impl Error {
     pub fn count(&self) -> usize {
         1 + self.prev.as_ref().map_or(0, |e| e.count())
     }
+
+    /// Returns a suggestion for resolving the error, if there is one.
+    ///
+    /// Currently, a suggestion is made for missing fields. If the figment
+    /// contains a key that is likely a misspelling of the missing field, such
+    /// as an environment variable `APP_DATABSE_URL` for `database.url`, the
+    /// key is named as it appears in its source. Otherwise, if the figment
+    /// contains a provider of defaults such as
+    /// [`Serialized::defaults()`](crate::providers::Serialized::defaults()),
+    /// the suggestion is to provide a default there. The suggestion is also
+    /// displayed, in parentheses, after the error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::{Serialize, Deserialize};
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Config { name: String, host: String, port: u16 }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Defaults { name: &'static str }
+    ///
+    /// let figment = Figment::from(Serialized::defaults(Defaults { name: "app" }));
+    /// let error = figment.extract::<Config>().unwrap_err();
+    /// let help = error.help().unwrap();
+    /// assert!(help.contains("`host`") && help.contains("Defaults at "));
+    /// ```
+    pub fn help(&self) -> Option<&str> {
+        self.extra.as_ref()?.help.as_deref()
+    }
+
+    /// Sets the suggestion returned by [`Error::help()`] to `help`.
+    pub(crate) fn set_help(&mut self, help: Option<String>) {
+        if help.is_some() || self.extra.is_some() {
+            self.extra_mut().help = help;
+        }
+    }
+
+    /// Returns the context added to this error via
+    /// [`Error::with_context()`], innermost first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Error;
+    ///
+    /// let error = Error::from("bad value".to_string())
+    ///     .with_context("while parsing")
+    ///     .with_context("while loading");
+    ///
+    /// assert_eq!(error.context(), ["while parsing", "while loading"]);
+    /// assert!(Error::from("bad value".to_string()).context().is_empty());
+    /// ```
+    pub fn context(&self) -> &[String] {
+        self.extra.as_ref().map_or(&[], |extra| &extra.context)
+    }
+
+    /// Returns the rarely set details of `self`, allocating them if unset.
+    fn extra_mut(&mut self) -> &mut Extra {
+        self.extra.get_or_insert_with(Default::default)
+    }
+
+    /// Returns the stable, machine-readable code for this error's kind. This
+    /// is exactly `self.kind.code()`. See [`Kind::code()`] for the list of
+    /// codes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("port", "eighty"));
+    /// let error = figment.extract_inner::<u16>("port").unwrap_err();
+    /// assert_eq!(error.code(), "invalid_type");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    /// Returns the path to the configuration key that errored as a single key
+    /// path string (`a.b.c`), or `None` if the path is unknown. Components
+    /// containing a `.` are escaped via [`escape_key()`]. Unlike the key
+    /// displayed by the `Display` implementation, the key path is not
+    /// interpolated by the provider's metadata and does not include the
+    /// profile.
+    ///
+    /// [`escape_key()`]: crate::util::escape_key()
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, util::map};
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Config { server: Server }
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Server { port: u16 }
+    ///
+    /// let figment = Figment::from(("server", map!["port" => "eighty"]));
+    /// let error = figment.extract::<Config>().unwrap_err();
+    /// assert_eq!(error.key().as_deref(), Some("server.port"));
+    /// ```
+    pub fn key(&self) -> Option<String> {
+        if self.path.is_empty() {
+            return None;
+        }
+
+        let keys: Vec<_> = self.path.iter().map(|k| crate::util::escape_key(k)).collect();
+        Some(keys.join("."))
+    }
+
+    /// Returns the path to the configuration key that errored as interpolated
+    /// by the [`Metadata`] of the provider it originated from, or `None` if the
+    /// path, profile, or metadata is unknown. For [`Env`], for example, it is
+    /// the name of the environment variable, such as `APP_DATABASE_URL`.
+    ///
+    /// This is the key displayed by the `Display` implementation unless the
+    /// value was read from a recorded environment variable, in which case the
+    /// figment key is displayed followed by the variable's name, exactly as it
+    /// was set.
+    ///
+    /// [`Env`]: crate::providers::Env
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::Env};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Config { database: Database }
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Database { url: String, pool: u32 }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_DATABASE_URL", "postgres://localhost");
+    ///     jail.set_env("APP_DATABASE_POOL", "many");
+    ///
+    ///     let figment = Figment::from(Env::prefixed("APP_").split("_"));
+    ///     let error = figment.extract::<Config>().unwrap_err();
+    ///     assert_eq!(error.key().as_deref(), Some("database.pool"));
+    ///     assert_eq!(error.interpolated_key().as_deref(), Some("APP_DATABASE_POOL"));
+    ///     assert!(error.to_string().ends_with("for key \"database.pool\" from \
+    ///         `APP_DATABASE_POOL` in `APP_` environment variable(s)"));
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn interpolated_key(&self) -> Option<String> {
+        match (&self.profile, &self.metadata) {
+            (Some(profile), Some(md)) if !self.path.is_empty() => {
+                Some(md.interpolate(profile, &self.path))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the path to the configuration value that errored in the style
+    /// of `serde_path_to_error`, or `None` if the path is unknown. Map keys
+    /// are separated by `.` and escaped as in [`Error::key()`]; sequence
+    /// indices are written in brackets, as in `servers[1].tls.cert`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, util::map};
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Config { servers: Vec<Server> }
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Server { tls: Tls }
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Tls { version: u8 }
+    ///
+    /// let figment = Figment::from(("servers", [
+    ///     map!["tls" => map!["version" => 3]],
+    ///     map!["tls" => map!["version" => 1000]],
+    /// ]));
+    ///
+    /// let error = figment.extract::<Config>().unwrap_err();
+    /// assert_eq!(error.path, vec!["servers", "1", "tls", "version"]);
+    /// assert_eq!(error.display_path().unwrap(), "servers[1].tls.version");
+    /// assert_eq!(error.key().unwrap(), "servers.1.tls.version");
+    /// ```
+    pub fn display_path(&self) -> Option<String> {
+        if self.path.is_empty() {
+            return None;
+        }
+
+        let mut string = String::new();
+        for (i, key) in self.path.iter().enumerate() {
+            if self.extra.as_ref().and_then(|x| x.indices.get(i)).copied().unwrap_or(false) {
+                string.push('[');
+                string.push_str(key);
+                string.push(']');
+            } else {
+                if i > 0 {
+                    string.push('.');
+                }
+
+                string.push_str(&crate::util::escape_key(key));
+            }
+        }
+
+        Some(string)
+    }
+
+    /// Returns a description of what was expected, if known. This is exactly
+    /// `self.kind.expected()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("port", "eighty"));
+    /// let error = figment.extract_inner::<u16>("port").unwrap_err();
+    /// assert_eq!(error.expected().as_deref(), Some("u16"));
+    /// ```
+    pub fn expected(&self) -> Option<Cow<'_, str>> {
+        self.kind.expected()
+    }
+
+    /// Returns the actual value or type that was found, if known. This is
+    /// exactly `self.kind.actual()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, error::Actual};
+    ///
+    /// let figment = Figment::from(("port", "eighty"));
+    /// let error = figment.extract_inner::<u16>("port").unwrap_err();
+    /// assert_eq!(error.actual(), Some(&Actual::Str("eighty".into())));
+    /// ```
+    pub fn actual(&self) -> Option<&Actual> {
+        self.kind.actual()
+    }
+}
+
+impl Kind {
+    /// Returns a stable, machine-readable code identifying the kind of error.
+    /// Codes are `snake_case` and never change between releases:
+    ///
+    /// | kind                           | code                 |
+    /// |--------------------------------|----------------------|
+    /// | [`Kind::Message`]              | `message`            |
+    /// | [`Kind::InvalidType`]          | `invalid_type`       |
+    /// | [`Kind::InvalidValue`]         | `invalid_value`      |
+    /// | [`Kind::InvalidLength`]        | `invalid_length`     |
+    /// | [`Kind::UnknownVariant`]       | `unknown_variant`    |
+    /// | [`Kind::UnknownField`]         | `unknown_field`      |
+    /// | [`Kind::MissingField`]         | `missing_field`      |
+    /// | [`Kind::DuplicateField`]       | `duplicate_field`    |
+    /// | [`Kind::ISizeOutOfRange`]      | `isize_out_of_range` |
+    /// | [`Kind::USizeOutOfRange`]      | `usize_out_of_range` |
+    /// | [`Kind::Unsupported`]          | `unsupported`        |
+    /// | [`Kind::UnsupportedKey`]       | `unsupported_key`    |
+    /// | [`Kind::Io`]                   | `io`                 |
+    /// | [`Kind::Denied`]               | `denied`             |
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::error::Kind;
+    ///
+    /// assert_eq!(Kind::MissingField("port".into()).code(), "missing_field");
+    /// assert_eq!(Kind::Message("oh no".into()).code(), "message");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            Kind::Message(..) => "message",
+            Kind::InvalidType(..) => "invalid_type",
+            Kind::InvalidValue(..) => "invalid_value",
+            Kind::InvalidLength(..) => "invalid_length",
+            Kind::UnknownVariant(..) => "unknown_variant",
+            Kind::UnknownField(..) => "unknown_field",
+            Kind::MissingField(..) => "missing_field",
+            Kind::DuplicateField(..) => "duplicate_field",
+            Kind::ISizeOutOfRange(..) => "isize_out_of_range",
+            Kind::USizeOutOfRange(..) => "usize_out_of_range",
+            Kind::Unsupported(..) => "unsupported",
+            Kind::UnsupportedKey(..) => "unsupported_key",
+            #[cfg(feature = "std")]
+            Kind::Io(..) => "io",
+            #[cfg(feature = "std")]
+            Kind::Denied(..) => "denied",
+        }
+    }
+
+    /// Returns a description of what was expected, if this kind carries one.
+    /// For unknown variants and fields, this is the list of valid names.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::error::{Kind, Actual};
+    ///
+    /// let kind = Kind::InvalidType(Actual::Bool(true), "a string".into());
+    /// assert_eq!(kind.expected().as_deref(), Some("a string"));
+    ///
+    /// let kind = Kind::UnknownField("prot".into(), &["port", "host"]);
+    /// assert_eq!(kind.expected().as_deref(), Some("`port` or `host`"));
+    ///
+    /// assert!(Kind::MissingField("port".into()).expected().is_none());
+    /// ```
+    pub fn expected(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Kind::InvalidType(_, exp)
+                | Kind::InvalidValue(_, exp)
+                | Kind::InvalidLength(_, exp) => Some(exp.as_str().into()),
+            Kind::UnknownVariant(_, exp)
+                | Kind::UnknownField(_, exp) => Some(OneOf(exp).to_string().into()),
+            Kind::UnsupportedKey(_, exp) => Some(exp.as_ref().into()),
+            _ => None,
+        }
+    }
+
+    /// Returns the actual value or type that was found, if this kind carries
+    /// one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::error::{Kind, Actual};
+    ///
+    /// let kind = Kind::InvalidType(Actual::Bool(true), "a string".into());
+    /// assert_eq!(kind.actual(), Some(&Actual::Bool(true)));
+    ///
+    /// assert!(Kind::MissingField("port".into()).actual().is_none());
+    /// ```
+    pub fn actual(&self) -> Option<&Actual> {
+        match self {
+            Kind::InvalidType(actual, _)
+                | Kind::InvalidValue(actual, _)
+                | Kind::Unsupported(actual)
+                | Kind::UnsupportedKey(actual, _) => Some(actual),
+            _ => None,
+        }
+    }
 }
 
 /// An iterator over all errors in an [`Error`].
@@ -335,6 +868,11 @@ impl From<de::Unexpected<'_>> for Actual {
 
 impl de::Error for Error {
     fn custom<T: Display>(msg: T) -> Self {
+        #[cfg(feature = "std")]
+        if let Some(error) = STASHED.with(|stash| stash.borrow_mut().take()) {
+            return error;
+        }
+
         Kind::Message(msg.to_string()).into()
     }
 
@@ -380,6 +918,7 @@ impl From<Kind> for Error {
             path: vec![],
             profile: None,
             metadata: None,
+            extra: None,
             prev: None,
             kind,
         }
@@ -429,19 +968,39 @@ impl Display for Kind {
             Kind::UnsupportedKey(a, e) => {
                 write!(f, "unsupported type `{}` for key: must be `{}`", a, e)
             }
+            #[cfg(feature = "std")]
+            Kind::Io(kind, path) => {
+                write!(f, "failed to read `{}`: {}", path.display(), kind)
+            }
+            #[cfg(feature = "std")]
+            Kind::Denied(capability) => {
+                write!(f, "policy denies {}", capability)
+            }
         }
     }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for context in self.context().iter().rev() {
+            write!(f, "{}: ", context)?;
+        }
+
         self.kind.fmt(f)?;
 
-        if let (Some(profile), Some(md)) = (&self.profile, &self.metadata) {
-            if !self.path.is_empty() {
-                let key = md.interpolate(profile, &self.path);
+        let variable = match (&self.profile, &self.metadata) {
+            (Some(profile), Some(md)) => md.variable(profile, &self.path),
+            _ => None,
+        };
+
+        if let (Some(variable), Some(path)) = (variable, self.display_path()) {
+            write!(f, " for key {:?} from `{}`", path, variable)?;
+        } else if self.profile.is_some() && self.metadata.is_some() {
+            if let Some(key) = self.interpolated_key() {
                 write!(f, " for key {:?}", key)?;
             }
+        } else if let Some(path) = self.display_path() {
+            write!(f, " for key {:?}", path)?;
         }
 
         if let Some(md) = &self.metadata {
@@ -452,6 +1011,10 @@ impl Display for Error {
             }
         }
 
+        if let Some(help) = self.help() {
+            write!(f, " ({})", help)?;
+        }
+
         if let Some(prev) = &self.prev {
             write!(f, "\n{}", prev)?;
         }
@@ -460,7 +1023,122 @@ impl Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+/// `std::error::Error`, or, without the `std` feature, its `core` equivalent.
+impl de::StdError for Error {}
+
+/// An `Error` serializes as a sequence of one or more error objects, one for
+/// each error in `self`, in iteration order. Each object has the following
+/// fields:
+///
+///   * `code`: the [error code](Error::code()), a string
+///   * `message`: the displayed error [`Kind`], a string
+///   * `key`: the [key path](Error::key()), a string or `null`
+///   * `display_path`: the [display path](Error::display_path()), a string or
+///     `null`
+///   * `path`: the [`path`](Error::path), a sequence of strings
+///   * `profile`: the [`profile`](Error::profile), a string or `null`
+///   * `expected`: the [expected](Error::expected()) description, a string
+///     or `null`
+///   * `actual`: the displayed [actual](Error::actual()) value, a string or
+///     `null`
+///   * `help`: the [suggestion](Error::help()), a string or `null`
+///   * `context`: the [`context`](Error::context), innermost first, a
+///     sequence of strings
+///   * `provider`: `null` or, if the error's [`metadata`](Error::metadata)
+///     is known, an object with the provider's `name`, the displayed `source`
+///     or `null`, and the `interpolated` key or `null`
+///
+/// # Example
+///
+/// ```rust
+/// use figment::Figment;
+///
+/// let figment = Figment::from(("port", "eighty"));
+/// let error = figment.extract_inner::<u16>("port").unwrap_err();
+///
+/// let json = serde_json::to_value(&error).unwrap();
+/// assert_eq!(json[0]["code"], "invalid_type");
+/// assert_eq!(json[0]["expected"], "u16");
+/// assert_eq!(json[0]["profile"], "global");
+/// assert!(json[0]["provider"]["name"].as_str().unwrap().contains("&str"));
+/// ```
+impl ser::Serialize for Error {
+    fn serialize<S: ser::Serializer>(&self, s: S) -> core::result::Result<S::Ok, S::Error> {
+        use ser::{SerializeSeq, SerializeStruct};
+
+        struct Provider<'a>(&'a Error, &'a Metadata);
+
+        impl ser::Serialize for Provider<'_> {
+            fn serialize<S: ser::Serializer>(&self, s: S) -> core::result::Result<S::Ok, S::Error> {
+                let Provider(error, md) = *self;
+                let interpolated = match (&error.profile, error.path.is_empty()) {
+                    (Some(profile), false) => Some(md.interpolate(profile, &error.path)),
+                    _ => None,
+                };
+
+                let mut s = s.serialize_struct("Provider", 3)?;
+                s.serialize_field("name", &md.name)?;
+                s.serialize_field("source", &md.source.as_ref().map(|s| s.to_string()))?;
+                s.serialize_field("interpolated", &interpolated)?;
+                s.end()
+            }
+        }
+
+        struct Single<'a>(&'a Error);
+
+        impl ser::Serialize for Single<'_> {
+            fn serialize<S: ser::Serializer>(&self, s: S) -> core::result::Result<S::Ok, S::Error> {
+                let error = self.0;
+                let mut s = s.serialize_struct("Error", 11)?;
+                s.serialize_field("code", error.code())?;
+                s.serialize_field("message", &error.kind.to_string())?;
+                s.serialize_field("key", &error.key())?;
+                s.serialize_field("display_path", &error.display_path())?;
+                s.serialize_field("path", &error.path)?;
+                s.serialize_field("profile", &error.profile)?;
+                s.serialize_field("expected", &error.expected())?;
+                s.serialize_field("actual", &error.actual().map(|a| a.to_string()))?;
+                s.serialize_field("help", &error.help())?;
+                s.serialize_field("context", error.context())?;
+                s.serialize_field("provider", &error.metadata.as_ref().map(|m| Provider(error, m)))?;
+                s.end()
+            }
+        }
+
+        let mut seq = s.serialize_seq(Some(self.count()))?;
+        let mut error = Some(self);
+        while let Some(e) = error {
+            seq.serialize_element(&Single(e))?;
+            error = e.prev.as_deref();
+        }
+
+        seq.end()
+    }
+}
+
+#[cfg(feature = "json")]
+#[cfg_attr(nightly, doc(cfg(feature = "json")))]
+impl Error {
+    /// Serializes `self` into a JSON string as described in the
+    /// [`Serialize`](#impl-Serialize-for-Error) implementation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Config { name: String, port: u16 }
+    ///
+    /// let figment = Figment::from(("port", 80));
+    /// let error = figment.extract::<Config>().unwrap_err();
+    /// let json = error.to_json();
+    /// assert!(json.starts_with(r#"[{"code":"missing_field","#));
+    /// ```
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("errors always serialize to JSON")
+    }
+}
 
 /// A structure that implements [`de::Expected`] signaling that one of the types
 /// in the slice was expected.
@@ -0,0 +1,212 @@
+use std::fmt::{self, Display};
+
+use crate::error::{Error, Kind};
+use crate::Source;
+use crate::util::distance;
+
+/// A rich, human-oriented rendering of an [`Error`], returned by
+/// [`Error::pretty()`].
+///
+/// Each error in the `Error` is rendered with its [code](Error::code()), the
+/// provider and source it originated from, the interpolated key, and, when
+/// the source is a file, a snippet of the file with the offending value
+/// underlined. Unknown fields and variants that resemble an expected name
+/// include a suggestion.
+///
+/// Because provider data does not retain source positions, the snippet is
+/// located by searching the file for the key path. When no matching line is
+/// found, the snippet is omitted.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, providers::{Format, Toml}};
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Config { name: String, port: u16 }
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("Config.toml", r#"
+///         name = "app"
+///         port = "eighty"
+///     "#)?;
+///
+///     let error = Figment::from(Toml::file("Config.toml"))
+///         .extract::<Config>()
+///         .unwrap_err();
+///
+///     let pretty = error.pretty().to_string();
+///     assert!(pretty.starts_with("error[invalid_type]: invalid type: found string \"eighty\""));
+///     assert!(pretty.contains("--> TOML file Config.toml:3:16"));
+///     assert!(pretty.contains(r#"3 |         port = "eighty""#));
+///     assert!(pretty.contains("  |                ^^^^^^^^ expected u16"));
+///     Ok(())
+/// });
+/// ```
+#[cfg_attr(nightly, doc(cfg(feature = "pretty")))]
+pub struct Pretty<'a>(pub(crate) &'a Error);
+
+/// The location of a value in a source file.
+struct Snippet {
+    line: String,
+    number: usize,
+    column: usize,
+    len: usize,
+}
+
+impl Snippet {
+    /// Searches `text` for the last component of `path`, requiring each
+    /// component to appear, in order, as a key on or after the line of the
+    /// previous component.
+    fn find(text: &str, path: &[String]) -> Option<Snippet> {
+        fn key_column(line: &str, key: &str) -> Option<usize> {
+            for (i, _) in line.match_indices(key) {
+                let before = line[..i].chars().next_back();
+                let after = line[i + key.len()..].trim_start_matches(['"', '\'']);
+                let after = after.trim_start().chars().next();
+                let bounded = !before.is_some_and(|c| c.is_alphanumeric() || c == '_');
+                if bounded && matches!(after, Some('=') | Some(':') | Some(']') | Some('.')) {
+                    return Some(i);
+                }
+            }
+
+            None
+        }
+
+        let lines: Vec<&str> = text.lines().collect();
+        let (mut start, mut found) = (0, None);
+        for key in path {
+            let (n, line) = lines.iter().enumerate().skip(start)
+                .find(|(_, line)| key_column(line, key).is_some())?;
+
+            start = n;
+            found = Some((n, *line, key));
+        }
+
+        let (n, line, key) = found?;
+        let key_end = key_column(line, key)? + key.len();
+        let value = line[key_end..].find(['=', ':']).map(|i| key_end + i + 1)?;
+        let value_start = value + line[value..].len() - line[value..].trim_start().len();
+        let value = line[value_start..].split(" #").next().unwrap_or("").trim_end();
+        let value = value.strip_suffix(',').unwrap_or(value);
+        if value.is_empty() {
+            return None;
+        }
+
+        Some(Snippet {
+            line: line.to_string(),
+            number: n + 1,
+            column: line[..value_start].chars().count() + 1,
+            len: value.chars().count(),
+        })
+    }
+}
+
+/// Returns the name in `expected` closest to `actual`, if it is close enough
+/// to plausibly be a typo.
+fn suggestion<'a>(actual: &str, expected: &[&'a str]) -> Option<&'a str> {
+    expected.iter()
+        .map(|name| (distance(actual, name), *name))
+        .filter(|(d, name)| *d <= std::cmp::max(1, name.len() / 3))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, name)| name)
+}
+
+fn render(f: &mut fmt::Formatter<'_>, error: &Error) -> fmt::Result {
+    writeln!(f, "error[{}]: {}", error.code(), error.kind)?;
+
+    let file = error.metadata.as_ref()
+        .and_then(|md| md.source.as_ref())
+        .and_then(Source::file_path);
+
+    let snippet = file
+        .and_then(|path| crate::Policy::read_to_string(path).ok())
+        .and_then(|text| Snippet::find(&text, &error.path));
+
+    if let Some(md) = &error.metadata {
+        write!(f, "  --> {}", md.name)?;
+        if let Some(source) = &md.source {
+            write!(f, " {}", source)?;
+        }
+
+        if let Some(snippet) = &snippet {
+            write!(f, ":{}:{}", snippet.number, snippet.column)?;
+        }
+
+        writeln!(f)?;
+    }
+
+    let gutter = snippet.as_ref().map_or(1, |s| s.number.to_string().len());
+    if let Some(snippet) = &snippet {
+        let label = error.expected().map(|e| format!(" expected {}", e)).unwrap_or_default();
+        writeln!(f, "{:w$} |", "", w = gutter)?;
+        writeln!(f, "{} | {}", snippet.number, snippet.line)?;
+        writeln!(f, "{:w$} | {:c$}{}{}", "", "", "^".repeat(snippet.len), label,
+            w = gutter, c = snippet.column - 1)?;
+    }
+
+    if let Some(key) = error.interpolated_key() {
+        writeln!(f, "{:w$} = key: {}", "", key, w = gutter)?;
+    }
+
+    let help = match &error.kind {
+        _ if error.help().is_some() => error.help().map(|h| h.to_string()),
+        Kind::UnknownField(actual, expected) | Kind::UnknownVariant(actual, expected) => {
+            suggestion(actual, expected).map(|s| format!("did you mean `{}`?", s))
+        }
+        Kind::MissingField(field) => match error.key() {
+            Some(key) => Some(format!("add a value for `{}.{}`", key, field)),
+            None => Some(format!("add a value for `{}`", field)),
+        },
+        _ => None,
+    };
+
+    for context in error.context().iter().rev() {
+        writeln!(f, "{:w$} = note: {}", "", context, w = gutter)?;
+    }
+
+    if let Some(help) = help {
+        writeln!(f, "{:w$} = help: {}", "", help, w = gutter)?;
+    }
+
+    Ok(())
+}
+
+impl Display for Pretty<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut error = Some(self.0);
+        while let Some(e) = error {
+            render(f, e)?;
+            error = e.prev.as_deref();
+            if error.is_some() {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Error {
+    /// Returns a value that displays `self` as a rich, source-annotated
+    /// diagnostic. See [`Pretty`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// #[serde(deny_unknown_fields)]
+    /// struct Config { port: u16 }
+    ///
+    /// let error = Figment::from(("prot", 80)).extract::<Config>().unwrap_err();
+    /// let pretty = error.pretty().to_string();
+    /// assert!(pretty.starts_with("error[unknown_field]"));
+    /// assert!(pretty.contains("help: did you mean `port`?"));
+    /// ```
+    #[cfg_attr(nightly, doc(cfg(feature = "pretty")))]
+    pub fn pretty(&self) -> Pretty<'_> {
+        Pretty(self)
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::sync::Arc;
+use core::fmt;
+
+use crate::prelude::*;
+use crate::{Figment, Profile, Metadata};
+use crate::error::Result;
+use crate::value::{Map, Dict, Coercer, magic::Decryptor};
+
+/// A reusable bundle of configuration applied to a [`Figment`] via
+/// [`Figment::with()`].
+///
+/// Extension crates, such as framework integrations, implement `FigmentExt`
+/// to add providers and register [hooks](Figment#hooks) without requiring
+/// applications to reproduce or fork the resolution pipeline. Any closure of
+/// type `FnOnce(Figment) -> Figment` is also a `FigmentExt`.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, FigmentExt, Error, providers::Serialized};
+///
+/// /// Validates that `port` is nonzero, and provides a default `port`.
+/// struct Validated;
+///
+/// impl FigmentExt for Validated {
+///     fn apply(self, figment: Figment) -> Figment {
+///         figment.join(Serialized::default("port", 8000))
+///             .pre_extract(|dict| match dict.get("port").and_then(|v| v.to_u128()) {
+///                 Some(0) => Err(Error::from("`port` must be nonzero".to_string())),
+///                 _ => Ok(()),
+///             })
+///     }
+/// }
+///
+/// let figment = Figment::new().with(Validated);
+/// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+///
+/// let figment = Figment::from(("port", 0u16)).with(Validated);
+/// assert!(figment.extract_inner::<u16>("port").is_err());
+///
+/// // A closure works too.
+/// let figment = Figment::new().with(|f: Figment| f.merge(("port", 80u16)));
+/// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 80);
+/// ```
+pub trait FigmentExt {
+    /// Applies `self` to `figment`, returning the extended figment.
+    fn apply(self, figment: Figment) -> Figment;
+}
+
+impl<F: FnOnce(Figment) -> Figment> FigmentExt for F {
+    fn apply(self, figment: Figment) -> Figment {
+        self(figment)
+    }
+}
+
+type PreMerge = dyn Fn(&Metadata, &mut Map<Profile, Dict>) -> Result<()> + Send + Sync;
+type PostMerge = dyn Fn(&mut Map<Profile, Dict>) -> Result<()> + Send + Sync;
+type PreExtract = dyn Fn(&mut Dict) -> Result<()> + Send + Sync;
+#[cfg(feature = "std")]
+type Audit = dyn Fn(&crate::SecretRead) + Send + Sync;
+
+/// The hooks registered on a [`Figment`], each in order of registration.
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+    pub pre_merge: Vec<Arc<PreMerge>>,
+    pub post_merge: Vec<Arc<PostMerge>>,
+    pub pre_extract: Vec<Arc<PreExtract>>,
+    /// String coercers, keyed by the serde name of the type they produce.
+    pub coercers: Map<&'static str, Arc<Coercer>>,
+    /// Decryptors of `Encrypted` values, keyed by identifier.
+    pub decryptors: Map<String, Arc<dyn Decryptor>>,
+    /// Callbacks for reads of secret values.
+    #[cfg(feature = "std")]
+    pub audit: Vec<Arc<Audit>>,
+}
+
+impl Hooks {
+    pub fn pre_merge(&self, metadata: &Metadata, data: &mut Map<Profile, Dict>) -> Result<()> {
+        self.pre_merge.iter().try_for_each(|hook| hook(metadata, data))
+    }
+
+    pub fn post_merge(&self, data: &mut Map<Profile, Dict>) -> Result<()> {
+        self.post_merge.iter().try_for_each(|hook| hook(data))
+    }
+
+    pub fn pre_extract(&self, dict: &mut Dict) -> Result<()> {
+        self.pre_extract.iter().try_for_each(|hook| hook(dict))
+    }
+
+    #[cfg(feature = "std")]
+    pub fn audit(&self, read: &crate::SecretRead) {
+        self.audit.iter().for_each(|hook| hook(read))
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut f = f.debug_struct("Hooks");
+        f.field("pre_merge", &self.pre_merge.len())
+            .field("post_merge", &self.post_merge.len())
+            .field("pre_extract", &self.pre_extract.len())
+            .field("coercers", &self.coercers.keys().collect::<Vec<_>>())
+            .field("decryptors", &self.decryptors.keys().collect::<Vec<_>>());
+
+        #[cfg(feature = "std")]
+        f.field("audit", &self.audit.len());
+
+        f.finish()
+    }
+}
@@ -1,11 +1,17 @@
-use std::panic::Location;
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::panic::Location;
 
-use serde::de::Deserialize;
+use serde::de::{Deserialize, DeserializeOwned};
 
-use crate::{Profile, Provider, Metadata};
-use crate::error::{Kind, Result};
-use crate::value::{Value, Map, Dict, Tag, ConfiguredValueDe};
+use crate::prelude::*;
+use crate::{Profile, Provider, Metadata, Source};
+use crate::error::{Error, Kind, Result};
+use crate::value::{Value, Map, Dict, Tag, Coercion, Limits, KeyCase, ConfiguredValueDe, Reads, ScopedDe, Redacted};
 use crate::coalesce::{Coalescible, Order};
+use crate::ext::{FigmentExt, Hooks};
+use crate::frozen::Frozen;
+use crate::docs::Docs;
+use crate::warning::{Warning, Warnings, Kind as WarningKind};
 
 /// Combiner of [`Provider`]s for configuration value extraction.
 ///
@@ -42,6 +48,16 @@ use crate::coalesce::{Coalescible, Order};
 /// assert_eq!(joined, "replaced");
 /// ```
 ///
+/// A merged [`Empty::Unset`](crate::value::Empty::Unset) value removes a key
+/// entirely:
+///
+/// ```rust
+/// use figment::{Figment, value::Empty};
+///
+/// let figment = Figment::from(("key", "original")).merge(("key", Empty::Unset));
+/// assert!(figment.find_value("key").is_err());
+/// ```
+///
 /// ## Extraction
 ///
 /// The configuration or a subset thereof can be extracted from a `Figment` in
@@ -74,11 +90,106 @@ use crate::coalesce::{Coalescible, Order};
 ///     [`Tag`], itself retrieved via [`Tagged`] or [`Value::tag()`].
 ///
 /// [`Tagged`]: crate::value::magic::Tagged
+///
+/// ## Hooks
+///
+/// Hooks inject behavior, like validation or secret resolution, at fixed
+/// points of the resolution pipeline. Each kind of hook is called in the order
+/// of registration, and the first error returned by a hook fails the figment
+/// or extraction. Hooks are typically registered by a [`FigmentExt`]:
+///
+///   * [`Figment::pre_merge()`] hooks see, and may modify, the data of every
+///     provider merged or joined _after_ the hook is registered, before it is
+///     combined with existing data.
+///   * [`Figment::post_merge()`] hooks see, and may modify, the combined data
+///     of all profiles after every such provider is merged or joined.
+///   * [`Figment::pre_extract()`] hooks see, and may modify, the value of the
+///     selected profile(s) before it is extracted from or searched.
+///
+/// ## Consistency
+///
+/// A `Figment` reads every provider exactly once, when the provider is merged
+/// or joined, via [`Provider::snapshot()`]: files are read and parsed and the
+/// environment is read then, as a unit, and never again. Extractions, and
+/// every other method, read only from the data resolved then. As such, every
+/// extraction from a `Figment` observes a single, consistent snapshot of its
+/// providers, even if files or environment variables change in the middle of
+/// the extraction or between extractions.
+///
+/// To observe changes, as on a configuration reload, build a new `Figment`.
+/// A [`SharedFigment`](crate::SharedFigment) swaps in a new figment
+/// atomically; an extraction that is in progress when it does completes
+/// against the figment it started with. Values resolved on access, like
+/// [`Deferred`](crate::value::magic::Deferred), are read when accessed and
+/// are not part of the snapshot.
+///
+/// ```rust
+/// use figment::{Figment, Jail, providers::{Env, Format, Toml}};
+///
+/// Jail::expect_with(|jail| {
+///     jail.create_file("App.toml", "name = \"app\"\nworkers = 4")?;
+///     jail.set_env("APP_WORKERS", 8);
+///
+///     let figment = Figment::from(Toml::file("App.toml")).merge(Env::prefixed("APP_"));
+///     jail.create_file("App.toml", "name = \"new\"\nworkers = 1")?;
+///     jail.set_env("APP_WORKERS", 16);
+///     assert_eq!(figment.extract_inner::<String>("name")?, "app");
+///     assert_eq!(figment.extract_inner::<usize>("workers")?, 8);
+///
+///     // A new figment reads the providers anew.
+///     let figment = Figment::from(Toml::file("App.toml")).merge(Env::prefixed("APP_"));
+///     assert_eq!(figment.extract_inner::<String>("name")?, "new");
+///     assert_eq!(figment.extract_inner::<usize>("workers")?, 16);
+///     Ok(())
+/// });
+/// ```
 #[derive(Clone, Debug)]
 pub struct Figment {
     pub(crate) profile: Profile,
+    /// Additional selected profiles with lower precedence than `profile`, in
+    /// order of increasing precedence. See [`Figment::select_all()`].
+    pub(crate) base_profiles: Vec<Profile>,
     pub(crate) metadata: Map<Tag, Metadata>,
     pub(crate) value: Result<Map<Profile, Dict>>,
+    pub(crate) coercion: Coercion,
+    pub(crate) limits: Limits,
+    pub(crate) case_insensitive: bool,
+    /// Whether empty strings are extracted as `None`. See
+    /// [`Figment::empty_as_none()`].
+    pub(crate) empty_as_none: bool,
+    pub(crate) key_case: Option<KeyCase>,
+    pub(crate) array_key: Option<String>,
+    pub(crate) hooks: Hooks,
+    pub(crate) warnings: Warnings,
+    /// `Some` if duplicate providers are skipped, with the metadata, priority,
+    /// and data of the provider added last, if any, while they are. See
+    /// [`Figment::dedup_providers()`].
+    pub(crate) seen: Option<Option<(Metadata, u32, Map<Profile, Dict>)>>,
+    /// Key paths that must be present in the selected profile(s). See
+    /// [`Figment::require()`].
+    pub(crate) required: Vec<String>,
+    /// Descriptions of key paths, keyed by key path. See
+    /// [`Figment::describe()`].
+    pub(crate) descriptions: Map<String, String>,
+    /// Key paths whose values are sensitive. See [`Figment::redact()`].
+    pub(crate) redacted: Vec<String>,
+    /// The profiles the application knows, if declared. See
+    /// [`Figment::known_profiles()`].
+    pub(crate) known_profiles: Option<Vec<Profile>>,
+    /// Restrictions on what providers may access. See [`Figment::policy()`].
+    #[cfg(feature = "std")]
+    pub(crate) policy: crate::Policy,
+    /// The namespaces of the libraries added via
+    /// [`Figment::adjoin_library()`].
+    pub(crate) libraries: Vec<String>,
+    /// The data of every provider merged with a nonzero priority, and its
+    /// priority, in order of addition. See [`Figment::merge_with_priority()`].
+    pub(crate) prioritized: Vec<(u32, Map<Profile, Dict>)>,
+    /// Values set via [`Figment::set()`] and its siblings, tagged with `.0`.
+    /// They are merged over the data of every provider added.
+    pub(crate) overrides: Option<(Tag, Map<Profile, Dict>)>,
+    /// Values deserialized by [`Figment::extract_cached()`].
+    pub(crate) extracted: Extracted,
 }
 
 impl Figment {
@@ -96,7 +207,27 @@ impl Figment {
         Figment {
             metadata: Map::new(),
             profile: Profile::Default,
+            base_profiles: vec![],
             value: Ok(Map::new()),
+            coercion: Coercion::default(),
+            limits: Limits::default(),
+            case_insensitive: false,
+            empty_as_none: false,
+            key_case: None,
+            array_key: None,
+            hooks: Hooks::default(),
+            warnings: Warnings::default(),
+            seen: None,
+            required: vec![],
+            descriptions: Map::new(),
+            redacted: vec![],
+            known_profiles: None,
+            #[cfg(feature = "std")]
+            policy: crate::Policy::default(),
+            libraries: vec![],
+            prioritized: vec![],
+            overrides: None,
+            extracted: Extracted::default(),
         }
     }
 
@@ -116,35 +247,257 @@ impl Figment {
         Figment::new().merge(provider)
     }
 
+    /// Returns `self` as changed by `f`, forgetting the values cached by
+    /// [`Figment::extract_cached()`], which the change may invalidate. Every
+    /// method that changes `self` does so through `update()`.
+    fn update(self, f: impl FnOnce(Self) -> Self) -> Self {
+        let mut figment = f(self);
+        figment.extracted.clear();
+        figment
+    }
+
     #[track_caller]
-    fn provide<T: Provider>(mut self, provider: T, order: Order) -> Self {
-        if let Some(map) = provider.__metadata_map() {
-            self.metadata.extend(map);
+    fn provide<T: Provider>(self, provider: T, order: Order, priority: u32) -> Self {
+        let location = Location::caller();
+        self.update(|figment| figment.provide_at(location, provider, order, priority))
+    }
+
+    fn provide_at<T: Provider>(
+        mut self,
+        location: &'static Location<'static>,
+        provider: T,
+        order: Order,
+        priority: u32,
+    ) -> Self {
+        #[cfg(feature = "std")]
+        let (_policy, _limits) = (self.policy.enter(), self.limits.enter());
+
+        // Read the provider exactly once, as a unit. See `Figment#consistency`.
+        let provider = provider.snapshot();
+        let mut metadata = provider.metadata();
+        metadata.provide_location = Some(location);
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("provide", provider = %metadata.name, ?order).entered();
+
+        let data = provider.data();
+
+        #[cfg(feature = "tracing")]
+        match &data {
+            Ok(map) => tracing::debug!(profiles = ?map.keys().collect::<Vec<_>>(), "loaded data"),
+            Err(e) => tracing::warn!(code = e.code(), count = e.count(), "failed to load data"),
+        }
+
+        // Only a repeat of the last provider is skipped: re-adding a provider
+        // after another would change which values take precedence.
+        if let (Some(last), Ok(map)) = (&mut self.seen, &data) {
+            if last.as_ref().is_some_and(|(md, p, last)| *md == metadata && *p == priority && last == map) {
+                let mut warning = Warning::new(WarningKind::DuplicateProvider);
+                warning.metadata = Some(metadata);
+                self.warnings.0.push(warning);
+                return self;
+            }
+
+            *last = Some((metadata.clone(), priority, map.clone()));
+        }
+
+        let nested = match provider.__metadata_map() {
+            Some(map) => { self.metadata.extend(map); true }
+            None => false,
+        };
+
+        let tags = provider.tags();
+        for (tag, metadata) in &tags {
+            let mut metadata = metadata.clone();
+            metadata.provide_location = Some(location);
+            self.metadata.insert(*tag, metadata);
         }
 
         if let Some(profile) = provider.profile() {
-            self.profile = self.profile.coalesce(profile, order);
+            let profile = self.profile.clone().coalesce(profile, order);
+            if profile != self.profile {
+                self.profile = profile;
+                self.base_profiles.clear();
+            }
         }
 
-        let mut metadata = provider.metadata();
-        metadata.provide_location = Some(Location::caller());
+        // Each per-key source gets its own copy of the metadata and thus a tag.
+        let mut key_tags: Vec<(Vec<String>, Tag)> = metadata.take_key_sources()
+            .into_iter()
+            .map(|(key, source)| {
+                let key_tag = Tag::unique();
+                let key_metadata = metadata.clone().source(source);
+                self.metadata.insert(key_tag, key_metadata);
+                (crate::util::split_key(&key), key_tag)
+            })
+            .collect();
+
+        key_tags.sort_by_key(|(path, _)| core::cmp::Reverse(path.len()));
 
-        let tag = Tag::next();
+        let tag = Tag::unique();
         self.metadata.insert(tag, metadata);
-        self.value = match (provider.data(), self.value) {
+        for mut warning in provider.warnings() {
+            warning.metadata = warning.metadata.or_else(|| Some(self.metadata[&tag].clone()));
+            self.warnings.0.push(warning);
+        }
+
+        let untagged = core::cell::Cell::new(false);
+        self.value = match (data, self.value) {
             (Ok(_), e@Err(_)) => e,
             (Err(e), Ok(_)) => Err(e.retagged(tag)),
             (Err(e), Err(prev)) => Err(e.retagged(tag).chain(prev)),
             (Ok(mut new), Ok(old)) => {
+                // Values keep the tags they were provided with, such as those
+                // in `Provider::tags()` or those of another figment's values,
+                // only if the tags' metadata is known. The provider's own tags
+                // are for the profile of the value they tag.
+                let metadata = &self.metadata;
+                let known = |tag| metadata.contains_key(&tag);
+                let forget = |t: &mut Tag| if !t.is_default() && !known(*t) { *t = Tag::Default };
+                for (p, dict) in new.iter_mut() {
+                    let profiled = |t: &mut Tag| if tags.contains_key(t) { *t = t.for_profile(p) };
+                    dict.values_mut().for_each(|v| { v.map_tags(forget); v.map_tags(profiled); });
+                }
+
+                // Only untagged values are tagged, so the most specific key wins.
+                for (p, dict) in new.iter_mut() {
+                    for (path, key_tag) in &key_tags {
+                        if let Some(value) = find_mut(dict, path) {
+                            value.map_tag(|t| *t = key_tag.for_profile(p));
+                        }
+                    }
+                }
+
                 new.iter_mut()
-                    .map(|(p, map)| std::iter::repeat(p).zip(map.values_mut()))
-                    .flatten()
-                    .for_each(|(p, v)| v.map_tag(|t| *t = tag.for_profile(p)));
+                    .flat_map(|(p, map)| core::iter::repeat(p).zip(map.values_mut()))
+                    .for_each(|(p, v)| v.map_tag(|t| {
+                        untagged.set(true);
+                        *t = tag.for_profile(p);
+                    }));
+
+                #[cfg(feature = "tracing")]
+                if tracing::enabled!(tracing::Level::TRACE) {
+                    Self::trace_conflicts(&self.metadata, &old, &new, order);
+                }
+
+                for (profile, suggestion) in Self::unknown_profiles(&self.known_profiles, &new) {
+                    let mut warning = Warning::new(WarningKind::UnknownProfile(suggestion))
+                        .with_profile(profile);
+
+                    warning.metadata = Some(self.metadata[&tag].clone());
+                    self.warnings.0.push(warning);
+                }
+
+                if self.key_case.is_some() || self.case_insensitive {
+                    let (case, uncased) = (self.key_case, self.case_insensitive);
+                    let unified = |key: &str| {
+                        let key = case.map_or_else(|| key.to_string(), |case| case.apply(key));
+                        if uncased { key.to_ascii_lowercase() } else { key }
+                    };
+
+                    for (p, dict) in &new {
+                        for key in crate::coalesce::shadowed_keys(dict, &unified) {
+                            let mut warning = Warning::new(WarningKind::Shadowed)
+                                .with_key(key)
+                                .with_profile(p.clone());
+
+                            warning.metadata = Some(self.metadata[&tag].clone());
+                            self.warnings.0.push(warning);
+                        }
+                    }
+                }
 
-                Ok(old.coalesce(new, order))
+                if let Some(case) = self.key_case {
+                    new = new.into_iter()
+                        .map(|(p, dict)| (p, crate::coalesce::canonicalize_keys(dict, case)))
+                        .collect();
+                }
+
+                if self.case_insensitive {
+                    new = new.into_iter()
+                        .map(|(p, dict)| {
+                            let dict = crate::coalesce::unify_keys(old.get(&p), dict);
+                            (p, dict)
+                        })
+                        .collect();
+                }
+
+                let (array_key, limits, hooks) = (&self.array_key, &self.limits, &self.hooks);
+                let prioritized = &mut self.prioritized;
+                hooks.pre_merge(&self.metadata[&tag], &mut new)
+                    .map_err(|e| e.retagged(tag))
+                    .and_then(|_| Self::check_limits(limits, &new, tag))
+                    .map(|_| {
+                        if priority > 0 {
+                            prioritized.push((priority, new.clone()));
+                        }
+
+                        let map = Self::coalesce_with(array_key, old, new, order);
+
+                        // Providers with a higher priority win regardless of
+                        // when they were added, so they're merged again.
+                        let mut higher: Vec<_> = prioritized.iter()
+                            .filter(|(p, _)| *p > priority)
+                            .collect();
+
+                        higher.sort_by_key(|(p, _)| *p);
+                        higher.into_iter().fold(map, |map, (_, new)| {
+                            Self::coalesce_with(array_key, map, new.clone(), Order::Merge)
+                        })
+                    })
+                    .and_then(|mut map| hooks.post_merge(&mut map).map(|_| map))
             }
         };
 
+        // The values of a nested figment retain the tags, and thus metadata,
+        // of their original providers. Its own metadata is recorded only if
+        // some value had no tag.
+        if nested && self.value.is_ok() && !untagged.get() {
+            self.metadata.remove(&tag);
+        }
+
+        self.apply_overrides().check_combined_limits()
+    }
+
+    /// Coalesces `new` into `old` in `order`, matching the elements of arrays
+    /// of dictionaries by `array_key`, if any.
+    fn coalesce_with(
+        array_key: &Option<String>,
+        old: Map<Profile, Dict>,
+        new: Map<Profile, Dict>,
+        order: Order,
+    ) -> Map<Profile, Dict> {
+        match array_key {
+            Some(key) => crate::coalesce::coalesce_maps(old, new, |a, b| {
+                crate::coalesce::coalesce_keyed(a, b, order, key)
+            }),
+            None => old.coalesce(new, order),
+        }
+    }
+
+    /// Merges the values set via [`Figment::set()`] and its siblings over the
+    /// current data.
+    fn apply_overrides(mut self) -> Self {
+        let new = match (&self.overrides, &self.value) {
+            (Some((_, overrides)), Ok(_)) => overrides.clone(),
+            _ => return self,
+        };
+
+        let (uncased, array_key) = (self.case_insensitive, &self.array_key);
+        self.value = self.value.map(|old| {
+            let new = match uncased {
+                true => new.into_iter()
+                    .map(|(p, dict)| {
+                        let dict = crate::coalesce::unify_keys(old.get(&p), dict);
+                        (p, dict)
+                    })
+                    .collect(),
+                false => new,
+            };
+
+            Self::coalesce_with(array_key, old, new, Order::Merge)
+        });
+
         self
     }
 
@@ -160,7 +513,7 @@ impl Figment {
     /// ```
     #[track_caller]
     pub fn join<T: Provider>(self, provider: T) -> Self {
-        self.provide(provider, Order::Join)
+        self.provide(provider, Order::Join, 0)
     }
 
     /// Merges `provider` into the current figment. See [merging vs.
@@ -175,46 +528,238 @@ impl Figment {
     /// ```
     #[track_caller]
     pub fn merge<T: Provider>(self, provider: T) -> Self {
-        self.provide(provider, Order::Merge)
+        self.provide(provider, Order::Merge, 0)
     }
 
-    /// Sets the profile to extract from to `profile`.
+    /// Merges `provider` into the current figment with the priority
+    /// `priority`. Providers are layered by priority rather than by the order
+    /// in which they're added: the values of `provider` override those of
+    /// providers with a lower priority and are overridden by those of
+    /// providers with a higher priority, whether added before or after
+    /// `provider`. Among providers with the same priority, the one added last
+    /// wins, as with [`Figment::merge()`]. Priorities order the values within
+    /// each profile; profiles are then combined as usual, so a value in the
+    /// [`Global`](Profile::Global) profile still overrides one in the default
+    /// profile.
+    ///
+    /// Providers added in any other way, like by [`Figment::merge()`] or
+    /// [`Figment::join()`], have priority `0`, so a provider with a nonzero
+    /// priority overrides all of them. This is useful when providers are
+    /// registered in a nondeterministic order, such as by plugins from
+    /// different crates, yet need a stable precedence.
+    ///
+    /// The data of every provider with a nonzero priority is retained so it
+    /// can be merged again over providers added later.
     ///
     /// # Example
     ///
-    /// ```
-    /// use figment::Figment;
+    /// ```rust
+    /// use figment::{Figment, providers::Serialized};
     ///
-    /// let figment = Figment::new().select("staging");
-    /// assert_eq!(figment.profile(), "staging");
+    /// let site = Serialized::default("port", 8080);
+    /// let plugin = Serialized::default("port", 9090);
+    ///
+    /// // The plugin's value wins regardless of the order of registration.
+    /// let a = Figment::new()
+    ///     .merge_with_priority(50, plugin.clone())
+    ///     .merge_with_priority(10, site.clone());
+    ///
+    /// let b = Figment::new()
+    ///     .merge_with_priority(10, site)
+    ///     .merge_with_priority(50, plugin);
+    ///
+    /// assert_eq!(a.extract_inner::<u16>("port").unwrap(), 9090);
+    /// assert_eq!(b.extract_inner::<u16>("port").unwrap(), 9090);
+    ///
+    /// // Providers merged without a priority lose to both.
+    /// let c = a.merge(Serialized::default("port", 80))
+    ///     .merge(Serialized::default("workers", 4));
+    ///
+    /// assert_eq!(c.extract_inner::<u16>("port").unwrap(), 9090);
+    /// assert_eq!(c.extract_inner::<u16>("workers").unwrap(), 4);
     /// ```
-    pub fn select<P: Into<Profile>>(mut self, profile: P) -> Self {
-        self.profile = profile.into();
-        self
+    #[track_caller]
+    pub fn merge_with_priority<T: Provider>(self, priority: u32, provider: T) -> Self {
+        self.provide(provider, Order::Merge, priority)
     }
 
-    /// Merges the selected profile with the default and global profiles.
-    fn merged(&self) -> Result<Value> {
-        let mut map = self.value.clone().map_err(|e| e.resolved(self))?;
-        let def = map.remove(&Profile::Default).unwrap_or_default();
-        let global = map.remove(&Profile::Global).unwrap_or_default();
+    /// Adds `provider` _below_ all providers already in the figment, so that
+    /// its values are used only where no existing provider sets a value. This
+    /// is useful for low-priority defaults that are discovered late, such as
+    /// those of a plugin registered after the figment is otherwise built.
+    ///
+    /// A figment combines provider data as providers are added and does not
+    /// retain the providers themselves, so a provider can't be inserted
+    /// between two existing providers. Prepending is exactly
+    /// [joining](Figment::join()): the existing value wins every conflict,
+    /// including the selected profile.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml, Serialized}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         [plugin]
+    ///         enabled = true
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml"));
+    ///
+    ///     // Later, a plugin registers its defaults.
+    ///     let figment = figment.prepend(Serialized::defaults(figment::util::map! {
+    ///         "plugin" => figment::util::map! { "enabled" => false, "verbose" => false },
+    ///     }));
+    ///
+    ///     assert_eq!(figment.extract_inner::<bool>("plugin.enabled")?, true);
+    ///     assert_eq!(figment.extract_inner::<bool>("plugin.verbose")?, false);
+    ///     Ok(())
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn prepend<T: Provider>(self, provider: T) -> Self {
+        self.join(provider)
+    }
 
-        let map = match map.remove(&self.profile) {
-            Some(v) if self.profile.is_custom() => def.merge(v).merge(global),
-            _ => def.merge(global)
+    /// Joins the values of `library`, a figment with a library's defaults,
+    /// nested under the key path `namespace`, so that libraries can
+    /// contribute their configuration to an application's figment without
+    /// clobbering one another's or the application's values.
+    ///
+    /// The values of `library` in each profile are nested under `namespace`
+    /// in the same profile and [joined](Figment::join()): values already in
+    /// the figment, and those of providers merged later, take precedence. The
+    /// values keep the metadata of the library's providers, which interpolate
+    /// keys as they would in `library`. The profile `library` selects is
+    /// ignored. The library's warnings, [required](Figment::require()) keys,
+    /// and [descriptions](Figment::describe()) are carried over, nested under
+    /// `namespace`.
+    ///
+    /// Each library must have its own namespace: if `namespace` is equal to,
+    /// nested under, or contains the namespace of a library added earlier, the
+    /// library is not added, and extraction fails with an error naming both
+    /// namespaces.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml, Serialized}};
+    ///
+    /// mod mylib {
+    ///     use figment::{Figment, providers::Serialized};
+    ///
+    ///     pub fn figment() -> Figment {
+    ///         Figment::from(Serialized::default("pool", 8))
+    ///             .merge(Serialized::default("timeout", 30))
+    ///     }
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", "[mylib]\npool = 16")?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml"))
+    ///         .adjoin_library("mylib", mylib::figment());
+    ///
+    ///     assert_eq!(figment.extract_inner::<u32>("mylib.pool")?, 16);
+    ///     assert_eq!(figment.extract_inner::<u32>("mylib.timeout")?, 30);
+    ///
+    ///     // A second library can't claim the same namespace.
+    ///     let figment = figment.adjoin_library("mylib", mylib::figment());
+    ///     let error = figment.extract::<figment::value::Value>().unwrap_err();
+    ///     assert!(error.to_string().contains("`mylib` collides"));
+    ///     Ok(())
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn adjoin_library(mut self, namespace: &str, library: Figment) -> Self {
+        let case = self.key_case;
+        let path = |key: &str| -> Vec<String> {
+            crate::util::split_key(key).into_iter()
+                .map(|k| match case {
+                    Some(case) => case.apply(&k),
+                    None => k,
+                })
+                .collect()
         };
 
-        Ok(Value::Dict(Tag::Default, map))
+        let prefix = path(namespace);
+        let existing = self.libraries.iter().find(|other| {
+            let other = path(other);
+            prefix.starts_with(&other) || other.starts_with(&prefix)
+        });
+
+        if let Some(existing) = existing {
+            let error = Error::from(format!(
+                "library namespace `{}` collides with library namespace `{}`", namespace, existing
+            ));
+
+            return self.update(|mut figment| {
+                figment.value = match figment.value {
+                    Ok(_) => Err(error),
+                    Err(prev) => Err(error.chain(prev)),
+                };
+
+                figment
+            });
+        }
+
+        let library = Library { namespace: prefix, provider: library };
+        for key in &library.provider.required {
+            self.required.push(library.key(key));
+        }
+
+        for (key, description) in &library.provider.descriptions {
+            self.descriptions.insert(library.key(key), description.clone());
+        }
+
+        for key in &library.provider.redacted {
+            self.redacted.push(library.key(key));
+        }
+
+        self.libraries.push(namespace.to_string());
+        self.join(library)
     }
 
-    /// Returns a new `Figment` containing only the sub-dictionaries at `key`.
+    /// Merges `provider` into the current figment with all of its data emitted
+    /// to `profile`, regardless of the profile(s) `provider` emits to. If
+    /// `provider` emits to multiple profiles, their data is first combined as
+    /// if selecting each of its custom profiles: default data is overridden by
+    /// custom profile data, which in turn is overridden by global data.
     ///
-    /// This "sub-figment" is a _focusing_ of `self` with the property that:
+    /// Any profile `provider` requests to select is ignored.
     ///
-    ///   * `self.find(key + ".sub")` <=> `focused.find("sub")`
+    /// # Example
     ///
-    /// In other words, all values in `self` with a key starting with `key` are
-    /// in `focused` _without_ the prefix and vice-versa.
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml, Serialized}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Release.toml", "port = 9999")?;
+    ///
+    ///     let figment = Figment::from(Serialized::default("port", 8000))
+    ///         .merge_into_profile("release", Toml::file("Release.toml"));
+    ///
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8000);
+    ///     assert_eq!(figment.select("release").extract_inner::<u16>("port")?, 9999);
+    ///     Ok(())
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn merge_into_profile<P, T>(self, profile: P, provider: T) -> Self
+        where P: Into<Profile>, T: Provider
+    {
+        self.provide(Scoped { profile: profile.into(), provider }, Order::Merge, 0)
+    }
+
+    /// Returns a new figment, a view of `self`, in which the values in
+    /// `overrides`, each a key path and a serializable value, take precedence
+    /// over all other values. `self` is left unchanged, so the overrides apply
+    /// only for as long as the returned figment is used.
+    ///
+    /// Overrides are emitted to the [`Global`](Profile::Global) profile and
+    /// thus win regardless of the selected profile. This is intended for tests
+    /// and for temporarily tweaking settings at runtime.
     ///
     /// # Example
     ///
@@ -222,140 +767,2706 @@ impl Figment {
     /// use figment::{Figment, providers::{Format, Toml}};
     ///
     /// figment::Jail::expect_with(|jail| {
-    ///     jail.create_file("Config.toml", r#"
-    ///         cat = [1, 2, 3]
-    ///         dog = [4, 5, 6]
+    ///     jail.create_file("App.toml", r#"
+    ///         log.level = "info"
+    ///         log.color = true
+    ///     "#)?;
     ///
-    ///         [subtree]
-    ///         cat = "meow"
-    ///         dog = "woof!"
+    ///     let figment = Figment::from(Toml::file("App.toml"));
+    ///     {
+    ///         let tweaked = figment.overlay([("log.level", "trace")]);
+    ///         assert_eq!(tweaked.extract_inner::<String>("log.level")?, "trace");
+    ///         assert_eq!(tweaked.extract_inner::<bool>("log.color")?, true);
+    ///     }
     ///
-    ///         [subtree.bark]
-    ///         dog = true
-    ///         cat = false
-    ///     "#)?;
+    ///     assert_eq!(figment.extract_inner::<String>("log.level")?, "info");
+    ///     Ok(())
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn overlay<I, K, V>(&self, overrides: I) -> Figment
+        where I: IntoIterator<Item = (K, V)>, K: AsRef<str>, V: serde::Serialize
+    {
+        let mut figment = self.clone();
+        for (key, value) in overrides {
+            figment = figment.merge(crate::providers::Serialized::global(key.as_ref(), value));
+        }
+
+        figment
+    }
+
+    /// Sets the value at the key path `key` to `value` in the
+    /// [`Global`](Profile::Global) profile, so that it takes precedence over
+    /// the values of every provider regardless of the selected profile.
     ///
-    ///     let root = Figment::from(Toml::file("Config.toml"));
-    ///     assert_eq!(root.extract_inner::<Vec<u8>>("cat").unwrap(), vec![1, 2, 3]);
-    ///     assert_eq!(root.extract_inner::<Vec<u8>>("dog").unwrap(), vec![4, 5, 6]);
-    ///     assert_eq!(root.extract_inner::<String>("subtree.cat").unwrap(), "meow");
-    ///     assert_eq!(root.extract_inner::<String>("subtree.dog").unwrap(), "woof!");
+    /// Unlike a merged provider, a set value remains on top: it is merged over
+    /// the data of every provider added afterwards, too. Setting a value at a
+    /// key that was already set merges the values, as by
+    /// [`Figment::merge()`]. This is intended for programmatic changes at
+    /// runtime, such as from an administrative API or a settings pane. Set
+    /// values are attributed to metadata named `runtime override` with the
+    /// location of the most recent call.
     ///
-    ///     let subtree = root.focus("subtree");
-    ///     assert_eq!(subtree.extract_inner::<String>("cat").unwrap(), "meow");
-    ///     assert_eq!(subtree.extract_inner::<String>("dog").unwrap(), "woof!");
-    ///     assert_eq!(subtree.extract_inner::<bool>("bark.cat").unwrap(), false);
-    ///     assert_eq!(subtree.extract_inner::<bool>("bark.dog").unwrap(), true);
+    /// # Example
     ///
-    ///     let bark = subtree.focus("bark");
-    ///     assert_eq!(bark.extract_inner::<bool>("cat").unwrap(), false);
-    ///     assert_eq!(bark.extract_inner::<bool>("dog").unwrap(), true);
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml}};
     ///
-    ///     let not_a_dict = root.focus("cat");
-    ///     assert!(not_a_dict.extract_inner::<bool>("cat").is_err());
-    ///     assert!(not_a_dict.extract_inner::<bool>("dog").is_err());
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         [default]
+    ///         workers = 4
+    ///
+    ///         [debug]
+    ///         workers = 1
+    ///     "#)?;
     ///
+    ///     let figment = Figment::from(Toml::file("App.toml").nested())
+    ///         .set("workers", 8)
+    ///         .select("debug");
+    ///
+    ///     assert_eq!(figment.extract_inner::<u32>("workers")?, 8);
+    ///
+    ///     let figment = figment.merge(("workers", 16));
+    ///     assert_eq!(figment.extract_inner::<u32>("workers")?, 8);
+    ///     assert_eq!(figment.find_metadata("workers").unwrap().name, "runtime override");
     ///     Ok(())
     /// });
     /// ```
-    pub fn focus(&self, key: &str) -> Self {
-        fn try_focus(figment: &Figment, key: &str) -> Result<Map<Profile, Dict>> {
-            let map = figment.value.clone().map_err(|e| e.resolved(figment))?;
-            let new_map = map.into_iter()
-                .filter_map(|(k, v)| {
-                    let focused = Value::Dict(Tag::Default, v).find(key)?;
-                    let dict = focused.into_dict()?;
-                    Some((k, dict))
-                })
+    #[track_caller]
+    pub fn set<V: serde::Serialize>(self, key: &str, value: V) -> Self {
+        self.set_in_profile(Profile::Global, key, value)
+    }
+
+    /// Like [`Figment::set()`] but sets the value at `key` in `profile`
+    /// instead of the `Global` profile. The value takes precedence over the
+    /// values every provider emits to `profile` but, like any other value in
+    /// `profile`, only if `profile` is selected and only over the values of
+    /// lower precedence profiles.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// let figment = Figment::from(Serialized::default("port", 80))
+    ///     .set_in_profile("debug", "port", 8080)
+    ///     .merge(Serialized::default("port", 8000).profile("debug"));
+    ///
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 80);
+    /// assert_eq!(figment.select("debug").extract_inner::<u16>("port").unwrap(), 8080);
+    /// ```
+    #[track_caller]
+    pub fn set_in_profile<P, V>(self, profile: P, key: &str, value: V) -> Self
+        where P: Into<Profile>, V: serde::Serialize
+    {
+        let location = Location::caller();
+        self.update(|figment| figment.set_at(location, profile.into(), key, value))
+    }
+
+    fn set_at<V>(mut self, location: &'static Location<'static>, profile: Profile, key: &str, value: V) -> Self
+        where V: serde::Serialize
+    {
+        let tag = self.overrides.as_ref().map_or_else(Tag::unique, |(tag, _)| *tag);
+        let mut metadata = Metadata::named("runtime override");
+        metadata.provide_location = Some(location);
+        self.metadata.insert(tag, metadata);
+
+        let provider = crate::providers::Serialized::global(key, value).profile(profile);
+        let data = provider.data().and_then(|new| Self::check_limits(&self.limits, &new, tag).map(|_| new));
+        let mut new = match data {
+            Ok(new) => new,
+            Err(e) => {
+                self.value = match self.value {
+                    Ok(_) => Err(e.retagged(tag)),
+                    Err(prev) => Err(e.retagged(tag).chain(prev)),
+                };
+
+                return self;
+            }
+        };
+
+        let known = |tag| self.metadata.contains_key(&tag);
+        for (p, dict) in new.iter_mut() {
+            let forget = |t: &mut Tag| if !t.is_default() && !known(*t) { *t = Tag::Default };
+            dict.values_mut().for_each(|v| v.map_tags(forget));
+            dict.values_mut().for_each(|v| v.map_tag(|t| *t = tag.for_profile(p)));
+        }
+
+        if let Some(case) = self.key_case {
+            new = new.into_iter()
+                .map(|(p, dict)| (p, crate::coalesce::canonicalize_keys(dict, case)))
                 .collect();
+        }
+
+        let overrides = self.overrides.take().map_or_else(Map::new, |(_, overrides)| overrides);
+        self.overrides = Some((tag, overrides.merge(new)));
+        self.apply_overrides().check_combined_limits()
+    }
+
+    /// Removes the value at the key path `key`, as if it had never been
+    /// provided, by [setting](Figment::set()) it to
+    /// [`Empty::Unset`](crate::value::Empty::Unset). As such, the key remains
+    /// removed even if a provider added afterwards sets it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("proxy", "http://localhost"))
+    ///     .merge(("timeout", 30))
+    ///     .unset("proxy");
+    ///
+    /// assert!(figment.find_value("proxy").is_err());
+    ///
+    /// let figment = figment.merge(("proxy", "http://example.com"));
+    /// assert!(figment.find_value("proxy").is_err());
+    ///
+    /// let figment = figment.set("proxy", "http://example.com");
+    /// assert_eq!(figment.extract_inner::<String>("proxy").unwrap(), "http://example.com");
+    /// ```
+    #[track_caller]
+    pub fn unset(self, key: &str) -> Self {
+        self.set(key, crate::value::Empty::Unset)
+    }
+
+    /// Applies the [JSON Patch] (RFC 6902) `patch`, a sequence of operations,
+    /// to the merged value of the selected profile and returns a figment with
+    /// the changes [set](Figment::set()) as runtime overrides. `patch` is
+    /// typically a `serde_json::Value` received by an administrative API.
+    ///
+    /// Paths are JSON Pointers that index dictionaries by key and arrays by
+    /// index. The operations are applied atomically: if any operation fails,
+    /// including a failing `test`, an error is returned and no change is made.
+    /// Removed keys are [unset](Figment::unset()). Because the changes are
+    /// overrides, they take precedence over providers merged afterwards.
+    ///
+    /// [JSON Patch]: https://datatracker.ietf.org/doc/html/rfc6902
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    /// use serde_json::json;
+    ///
+    /// let figment = Figment::from(("server", json!({ "port": 80, "hosts": ["a", "b"] })))
+    ///     .merge(("debug", true));
+    ///
+    /// let figment = figment.patch(json!([
+    ///     { "op": "test", "path": "/server/port", "value": 80 },
+    ///     { "op": "replace", "path": "/server/port", "value": 8080 },
+    ///     { "op": "add", "path": "/server/hosts/-", "value": "c" },
+    ///     { "op": "remove", "path": "/debug" },
+    /// ])).unwrap();
+    ///
+    /// assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+    /// assert_eq!(figment.extract_inner::<Vec<String>>("server.hosts").unwrap(), ["a", "b", "c"]);
+    /// assert!(figment.find_value("debug").is_err());
+    ///
+    /// // A failing `test` rejects the entire patch.
+    /// let result = figment.patch(json!([
+    ///     { "op": "remove", "path": "/server" },
+    ///     { "op": "test", "path": "/server/port", "value": 80 },
+    /// ]));
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    #[track_caller]
+    pub fn patch<P: serde::Serialize>(&self, patch: P) -> Result<Figment> {
+        let old = self.merged()?;
+        let mut new = old.clone();
+        crate::patch::apply_patch(&mut new, Value::serialize(patch)?)?;
+        self.override_with(&old, &new)
+    }
+
+    /// Applies the [JSON Merge Patch] (RFC 7386) `patch`, a dictionary, to the
+    /// merged value of the selected profile and returns a figment with the
+    /// changes [set](Figment::set()) as runtime overrides.
+    ///
+    /// Dictionaries in `patch` are merged recursively; a `null` removes, or
+    /// [unsets](Figment::unset()), the key; any other value replaces the value
+    /// at its key. Because the changes are overrides, they take precedence
+    /// over providers merged afterwards. Returns an error if `patch` isn't a
+    /// dictionary.
+    ///
+    /// [JSON Merge Patch]: https://datatracker.ietf.org/doc/html/rfc7386
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    /// use serde_json::json;
+    ///
+    /// let figment = Figment::from(("server", json!({ "port": 80, "tls": { "key": "a.pem" } })))
+    ///     .merge_patch(json!({ "server": { "port": 8080, "tls": null } }))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+    /// assert!(figment.find_value("server.tls").is_err());
+    ///
+    /// let figment = figment.merge(("server.port", 9000));
+    /// assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+    /// ```
+    #[track_caller]
+    pub fn merge_patch<P: serde::Serialize>(&self, patch: P) -> Result<Figment> {
+        let old = self.merged()?;
+        let mut new = old.clone();
+        crate::patch::apply_merge_patch(&mut new, Value::serialize(patch)?)?;
+        self.override_with(&old, &new)
+    }
+
+    /// Returns `self` with the changes that turn `old`, the merged value of
+    /// the selected profile, into `new` set as runtime overrides.
+    #[track_caller]
+    fn override_with(&self, old: &Value, new: &Value) -> Result<Figment> {
+        let (lower, _) = self.layers(&self.profile, &self.base_profiles)?;
+        let figment = match crate::patch::overriding(old, new, Some(&lower.into())) {
+            Some(value) => self.clone().set_in_profile(Profile::Global, "", value),
+            None => self.clone(),
+        };
+
+        Ok(figment)
+    }
+
+    /// Merges the selected profile(s) once and returns a [`Frozen`] figment
+    /// onto which small overrides can be cheaply layered, as for per-request
+    /// configuration. Unlike [`Figment::overlay()`], which clones `self`, an
+    /// override on a `Frozen` figment neither copies nor re-merges the frozen
+    /// value. Returns an error if merging fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let frozen = Figment::from(("workers", 4)).merge(("debug", false)).freeze().unwrap();
+    /// for workers in 1..=2 {
+    ///     let tweaked = frozen.overlay([("workers", workers)]).unwrap();
+    ///     assert_eq!(tweaked.extract_inner::<usize>("workers").unwrap(), workers);
+    ///     assert_eq!(tweaked.extract_inner::<bool>("debug").unwrap(), false);
+    /// }
+    /// ```
+    pub fn freeze(&self) -> Result<Frozen> {
+        Frozen::new(self.clone())
+    }
+
+    /// Sets the profile to extract from to `profile`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::new().select("staging");
+    /// assert_eq!(figment.profile(), "staging");
+    /// ```
+    pub fn select<P: Into<Profile>>(self, profile: P) -> Self {
+        self.update(|mut figment| {
+            figment.profile = profile.into();
+            figment.base_profiles.clear();
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(profile = %figment.profile, "selected profile");
+            figment
+        })
+    }
+
+    /// Like [`Figment::select()`], but returns an error if no provider
+    /// supplies data for `profile`, that is, if `profile` isn't one of
+    /// [`Figment::profiles()`]. The default and global profiles are always
+    /// accepted. The error lists the known custom profiles and, if one is
+    /// close to `profile`, suggests it in its [help](Error::help()).
+    ///
+    /// If merging the figment fails, the merge error is returned instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// let figment = Figment::new()
+    ///     .merge(Serialized::default("port", 80))
+    ///     .merge(Serialized::default("port", 8080).profile("staging"));
+    ///
+    /// let figment = figment.select_existing("staging").unwrap();
+    /// assert_eq!(figment.profile(), "staging");
+    ///
+    /// let error = figment.select_existing("stagin").unwrap_err();
+    /// assert!(error.to_string().contains("known profiles: `staging`"));
+    /// assert_eq!(error.help(), Some("did you mean `staging`?"));
+    /// ```
+    pub fn select_existing<P: Into<Profile>>(self, profile: P) -> Result<Self> {
+        let profile = profile.into();
+        let map = self.value.as_ref().map_err(|e| e.clone())?;
+        if !profile.is_custom() || map.contains_key(&profile) {
+            return Ok(self.select(profile));
+        }
+
+        let mut known: Vec<&str> = map.keys()
+            .filter(|p| p.is_custom())
+            .map(|p| p.as_str().as_str())
+            .collect();
+
+        known.sort_unstable();
+
+        let mut error = match known.is_empty() {
+            true => Error::from(format!("profile `{}` has no configuration \
+                    (no custom profiles are known)", profile)),
+            false => {
+                let list = known.iter()
+                    .map(|p| format!("`{}`", p))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                Error::from(format!("profile `{}` has no configuration \
+                    (known profiles: {})", profile, list))
+            }
+        };
+
+        let needle = profile.as_str().as_str().to_lowercase();
+        error.set_help(known.iter()
+            .map(|p| (crate::util::distance(&needle, &p.to_lowercase()), *p))
+            .filter(|(d, p)| *d <= core::cmp::max(1, p.len() / 3))
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, p)| format!("did you mean `{}`?", p)));
+
+        Err(error)
+    }
+
+    /// Selects all of `profiles`, in order of increasing precedence: values
+    /// in later profiles override values in earlier ones. As with
+    /// [`Figment::select()`], the default profile has lower precedence and the
+    /// global profile higher precedence than all selected profiles.
+    ///
+    /// The last profile in `profiles` becomes the [selected
+    /// profile](Figment::profile()). If `profiles` is empty, the default
+    /// profile is selected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", r#"
+    ///         [default]
+    ///         region = "none"
+    ///         workers = 1
+    ///         debug = true
+    ///
+    ///         [us-east]
+    ///         region = "us-east-1"
+    ///         workers = 4
+    ///
+    ///         [prod]
+    ///         workers = 16
+    ///         debug = false
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("Config.toml").nested())
+    ///         .select_all(["us-east", "prod"]);
+    ///
+    ///     assert_eq!(figment.profile(), "prod");
+    ///     assert_eq!(figment.extract_inner::<String>("region")?, "us-east-1");
+    ///     assert_eq!(figment.extract_inner::<usize>("workers")?, 16);
+    ///     assert_eq!(figment.extract_inner::<bool>("debug")?, false);
+    ///
+    ///     let figment = figment.select_all(["prod", "us-east"]);
+    ///     assert_eq!(figment.profile(), "us-east");
+    ///     assert_eq!(figment.extract_inner::<usize>("workers")?, 4);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn select_all<I>(self, profiles: I) -> Self
+        where I: IntoIterator, I::Item: Into<Profile>
+    {
+        self.update(|mut figment| {
+            figment.base_profiles = profiles.into_iter().map(|p| p.into()).collect();
+            figment.profile = figment.base_profiles.pop().unwrap_or(Profile::Default);
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(profile = %figment.profile, base = ?figment.base_profiles, "selected profiles");
+            figment
+        })
+    }
+
+    /// Sets the numeric [`Coercion`] policy used during extraction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::{Figment, value::Coercion};
+    ///
+    /// let figment = Figment::from(("timeout", 30.0));
+    /// assert!(figment.extract_inner::<u64>("timeout").is_err());
+    ///
+    /// let figment = figment.coerce(Coercion::lenient());
+    /// assert_eq!(figment.extract_inner::<u64>("timeout").unwrap(), 30);
+    /// ```
+    pub fn coerce(self, coercion: Coercion) -> Self {
+        self.update(|mut figment| {
+            figment.coercion = coercion;
+            figment
+        })
+    }
+
+    /// Registers `f` as the string coercer for `T`: whenever a string value
+    /// that doesn't deserialize as `T` as-is is extracted as `T`, from any
+    /// provider and at any depth, `f` is called with the string, and `T` is
+    /// deserialized from the value `f` returns instead. An error returned by
+    /// `f` is returned by the extraction.
+    ///
+    /// `T` must be a struct, an enum, or a primitive other than a string, like
+    /// `u64` or `bool`. Coercers are keyed by the type's serde name, so
+    /// registering a coercer for a type with the same name as a previously
+    /// registered type replaces the latter's coercer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is a string or a type without a serde name, like a
+    /// sequence, map, or `Option`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::{Serialize, Deserialize};
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// enum Level { Debug, Info }
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Config { level: Level, timeout: u64 }
+    ///
+    /// let figment = Figment::from(("level", "Level::Debug"))
+    ///     .merge(("timeout", "2m"))
+    ///     .coerce_str(|s: &str| match s.strip_prefix("Level::") {
+    ///         Some("Debug") => Ok(Level::Debug),
+    ///         Some("Info") => Ok(Level::Info),
+    ///         _ => Err(format!("unknown level `{}`", s)),
+    ///     })
+    ///     .coerce_str(|s: &str| match s.strip_suffix('m') {
+    ///         Some(minutes) => minutes.parse::<u64>().map(|m| m * 60),
+    ///         None => s.parse::<u64>(),
+    ///     });
+    ///
+    /// let config: Config = figment.extract().unwrap();
+    /// assert_eq!(config, Config { level: Level::Debug, timeout: 120 });
+    ///
+    /// // Strings that deserialize as-is aren't coerced.
+    /// let info = figment.clone().merge(("level", "info"));
+    /// assert_eq!(info.extract_inner::<Level>("level").unwrap(), Level::Info);
+    ///
+    /// let bad = figment.merge(("level", "Level::Trace"));
+    /// let error = bad.extract_inner::<Level>("level").unwrap_err();
+    /// assert_eq!(error.to_string(), "unknown level `Level::Trace` for key \"global.level\" in (&str, &str)");
+    /// ```
+    #[track_caller]
+    pub fn coerce_str<T, E, F>(self, f: F) -> Self
+        where T: serde::Serialize + DeserializeOwned,
+              E: core::fmt::Display,
+              F: Fn(&str) -> core::result::Result<T, E> + Send + Sync + 'static,
+    {
+        let (name, coercer) = crate::value::coercer(f)
+            .unwrap_or_else(|| panic!("`{}` has no serde name", core::any::type_name::<T>()));
+
+        self.update(|mut figment| {
+            figment.hooks.coercers.insert(name, coercer);
+            figment
+        })
+    }
+
+    /// Registers `decryptor` as the decryptor of
+    /// [`Encrypted`](crate::value::magic::Encrypted) values of the form
+    /// `enc:{id}:{payload}`, replacing any decryptor previously registered
+    /// under `id`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, Error, value::magic::Encrypted};
+    ///
+    /// // `YnFx` is `bqq` in base64, and `bqq` is `app` shifted by one.
+    /// let figment = Figment::from(("name", "enc:shift:YnFx"))
+    ///     .decryptor("shift", |c: &[u8]| Ok(c.iter().map(|b| b - 1).collect()));
+    ///
+    /// let name = figment.extract_inner::<Encrypted<String>>("name").unwrap();
+    /// assert_eq!(*name, "app");
+    ///
+    /// let figment = figment.decryptor("shift", |_: &[u8]| Err(Error::from("bad key".to_string())));
+    /// let error = figment.extract_inner::<Encrypted<String>>("name").unwrap_err();
+    /// assert_eq!(error.to_string(), "bad key for key \"global.name\" in (&str, &str)");
+    /// ```
+    pub fn decryptor<D>(self, id: &str, decryptor: D) -> Self
+        where D: crate::value::magic::Decryptor + 'static
+    {
+        self.update(|mut figment| {
+            figment.hooks.decryptors.insert(id.to_string(), alloc::sync::Arc::new(decryptor));
+            figment
+        })
+    }
+
+    /// Sets the [`Limits`] on the size and shape of configuration values.
+    ///
+    /// Limits are checked against the data already in the figment, once, and
+    /// against the data of every provider merged or joined afterwards, and of
+    /// every value [set](Figment::set()), before and again after it is
+    /// combined with the rest. As such, limits should generally be set before
+    /// adding any providers.
+    ///
+    /// File providers additionally enforce the nesting depth and, for sources
+    /// with a single profile, the number of values while parsing. See
+    /// [`Limits`]. To bound the input of a file provider, use
+    /// [`Data::max_size()`](crate::providers::Data::max_size()).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::{Figment, value::Limits};
+    ///
+    /// let figment = Figment::new()
+    ///     .limits(Limits::none().max_string_len(4))
+    ///     .merge(("name", "bob"));
+    ///
+    /// assert!(figment.extract_inner::<String>("name").is_ok());
+    ///
+    /// let figment = figment.merge(("name", "robert"));
+    /// assert!(figment.extract_inner::<String>("name").is_err());
+    /// ```
+    pub fn limits(self, limits: Limits) -> Self {
+        self.update(|mut figment| {
+            figment.limits = limits;
+            figment.check_combined_limits()
+        })
+    }
+
+    /// Sets the [`Policy`](crate::Policy) restricting what providers may
+    /// access, such as files outside of a directory or the environment.
+    ///
+    /// The policy is in effect while providers merged or joined afterwards
+    /// provide their data, and while values are extracted, as by a
+    /// [`Deferred`](crate::value::magic::Deferred) value that reads a file. As
+    /// such, a policy should generally be set before adding any providers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, Policy, providers::{Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_dir_all("config")?;
+    ///     jail.create_file("Outside.toml", "port = 80")?;
+    ///
+    ///     // A path given by a user, say, on the command line.
+    ///     let path = "config/../Outside.toml";
+    ///     let figment = Figment::new()
+    ///         .policy(Policy::unrestricted().file_root("config"))
+    ///         .merge(Toml::file(path));
+    ///
+    ///     let error = figment.extract_inner::<u16>("port").unwrap_err();
+    ///     assert_eq!(error.code(), "denied");
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
+    pub fn policy(self, policy: crate::Policy) -> Self {
+        self.update(|mut figment| {
+            figment.policy = policy;
+            figment
+        })
+    }
+
+    /// Sets whether keys are compared case-insensitively, ignoring ASCII case.
+    ///
+    /// When enabled, keys that differ only in case, like `Host` and `host`,
+    /// refer to the same value: they are unified when providers are merged or
+    /// joined, and when profiles are combined during extraction, keeping the
+    /// spelling of the key that was seen first. Key lookups, such as in
+    /// [`Figment::find_value()`], and the fields of extracted structures are
+    /// likewise matched ignoring case. Keys of maps, like a [`Map`], are
+    /// extracted as spelled. This option is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Server {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let figment = Figment::new()
+    ///     .case_insensitive(true)
+    ///     .merge(Serialized::default("Server.Host", "localhost"))
+    ///     .merge(Serialized::default("server.HOST", "example.com"))
+    ///     .merge(Serialized::default("SERVER.port", 80));
+    ///
+    /// let server: Server = figment.extract_inner("server").unwrap();
+    /// assert_eq!(server, Server { host: "example.com".into(), port: 80 });
+    ///
+    /// // The first spelling of a key is kept.
+    /// let value = figment.find_value("SeRvEr").unwrap();
+    /// assert!(value.find_ref("Host").is_some());
+    /// assert!(value.find_ref("port").is_some());
+    /// ```
+    pub fn case_insensitive(self, enabled: bool) -> Self {
+        self.update(|mut figment| {
+            figment.case_insensitive = enabled;
+            if let (true, Ok(map)) = (enabled, &mut figment.value) {
+                for dict in map.values_mut() {
+                    *dict = crate::coalesce::unify_keys(None, core::mem::take(dict));
+                }
+            }
+
+            figment
+        })
+    }
+
+    /// Sets whether empty strings are extracted as `None` when extracting an
+    /// `Option<T>`. This option is disabled by default.
+    ///
+    /// Providers differ in how they express an absent value: a JSON `null` or
+    /// a YAML `~` is an empty value, while an environment variable set to
+    /// nothing, as in `APP_PROXY=`, or a TOML `proxy = ""` is an empty string.
+    /// The rules for extracting an `Option<T>` are:
+    ///
+    /// | value                     | default        | enabled        |
+    /// |---------------------------|----------------|----------------|
+    /// | missing key               | `None`         | `None`         |
+    /// | empty value (`null`, `~`) | `None`         | `None`         |
+    /// | empty string (`""`)       | `T` from `""`  | `None`         |
+    /// | any other value           | `T` from value | `T` from value |
+    ///
+    /// By default, then, an empty string is extracted as `Some("")` into an
+    /// `Option<String>` and fails to extract into an `Option<u16>`. Strings
+    /// containing only whitespace are not empty. Empty strings extracted into
+    /// types other than `Option<T>` are unaffected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use figment::{Figment, Jail, providers::{Env, Format, Json}};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Config {
+    ///     proxy: Option<String>,
+    ///     port: Option<u16>,
+    ///     name: String,
+    /// }
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_PROXY", "");
+    ///     jail.set_env("APP_PORT", "");
+    ///     jail.set_env("APP_NAME", "");
+    ///
+    ///     let figment = Figment::from(Env::prefixed("APP_"));
+    ///     assert!(figment.extract::<Config>().is_err());
+    ///     assert_eq!(figment.extract_inner::<Option<String>>("proxy")?, Some("".into()));
+    ///
+    ///     let config: Config = figment.empty_as_none(true).extract()?;
+    ///     assert_eq!(config, Config { proxy: None, port: None, name: "".into() });
+    ///
+    ///     // Now the same as a JSON `null`.
+    ///     let json = Json::string(r#"{ "proxy": null, "port": null, "name": "" }"#);
+    ///     assert_eq!(Figment::from(json).extract::<Config>()?, config);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn empty_as_none(self, enabled: bool) -> Self {
+        self.update(|mut figment| {
+            figment.empty_as_none = enabled;
+            figment
+        })
+    }
+
+    /// Converts the keys of all values from all providers, those already
+    /// present and those added later, to the naming convention `case` before
+    /// they are merged, so that keys like `max-connections` from one provider
+    /// and `maxConnections` from another refer to the same value. See
+    /// [`KeyCase`] for how keys are converted.
+    ///
+    /// Keys in key paths passed to lookup methods like
+    /// [`Figment::find_value()`] are converted as well. Extracted structures
+    /// must expect keys in the canonical convention, for instance via
+    /// `#[serde(rename_all = "...")]`. This option is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, value::KeyCase, providers::{Format, Json, Yaml, Env}};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Config {
+    ///     max_connections: usize,
+    ///     idle_timeout: u64,
+    ///     log_level: String,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.yaml", "max-connections: 10\nidle-timeout: 30")?;
+    ///     jail.create_file("App.json", r#"{ "logLevel": "info", "idleTimeout": 60 }"#)?;
+    ///     jail.set_env("APP_MAX_CONNECTIONS", 100);
+    ///
+    ///     let figment = Figment::new()
+    ///         .canonicalize_keys(KeyCase::Snake)
+    ///         .merge(Yaml::file("App.yaml"))
+    ///         .merge(Json::file("App.json"))
+    ///         .merge(Env::prefixed("APP_"));
+    ///
+    ///     let config: Config = figment.extract()?;
+    ///     assert_eq!(config, Config {
+    ///         max_connections: 100,
+    ///         idle_timeout: 60,
+    ///         log_level: "info".into(),
+    ///     });
+    ///
+    ///     assert_eq!(figment.extract_inner::<u64>("idleTimeout")?, 60);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn canonicalize_keys(self, case: KeyCase) -> Self {
+        self.update(|mut figment| {
+            figment.key_case = Some(case);
+            if let Ok(map) = &mut figment.value {
+                for dict in map.values_mut() {
+                    *dict = crate::coalesce::canonicalize_keys(core::mem::take(dict), case);
+                }
+            }
+
+            figment
+        })
+    }
+
+    /// Merges and joins arrays of dictionaries element-wise instead of
+    /// replacing them as a whole when every element of both arrays has a `key`
+    /// field.
+    ///
+    /// Elements are matched by the value of their `key` field. Matching
+    /// elements are themselves merged or joined, elements only in the new
+    /// array are appended, and the order of the existing elements is kept. This
+    /// applies both when providers are merged or joined and when profiles are
+    /// combined during extraction, so an array of servers in the default
+    /// profile can be amended in another profile. All other arrays are
+    /// replaced as usual. This option is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Server {
+    ///     name: String,
+    ///     port: u16,
+    ///     tls: bool,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         [[default.servers]]
+    ///         name = "api"
+    ///         port = 8000
+    ///         tls = false
+    ///
+    ///         [[default.servers]]
+    ///         name = "admin"
+    ///         port = 9000
+    ///         tls = false
+    ///
+    ///         [[release.servers]]
+    ///         name = "api"
+    ///         tls = true
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::new()
+    ///         .merge_arrays_by("name")
+    ///         .merge(Toml::file("App.toml").nested())
+    ///         .select("release");
+    ///
+    ///     let servers: Vec<Server> = figment.extract_inner("servers")?;
+    ///     assert_eq!(servers, vec![
+    ///         Server { name: "api".into(), port: 8000, tls: true },
+    ///         Server { name: "admin".into(), port: 9000, tls: false },
+    ///     ]);
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn merge_arrays_by(self, key: &str) -> Self {
+        self.update(|mut figment| {
+            figment.array_key = Some(key.into());
+            figment
+        })
+    }
+
+    /// When `enabled`, skips any provider that is subsequently merged or
+    /// joined if the provider added immediately before it has equal
+    /// [`Metadata`], that is, the same name and source, and equal data,
+    /// emitting a [`DuplicateProvider`](WarningKind::DuplicateProvider)
+    /// warning instead. This guards against a provider being added twice in
+    /// a row, as when helper functions that each add the same file are
+    /// composed. Disabled by default.
+    ///
+    /// A provider that repeats an earlier one but not the last is never
+    /// skipped: adding it again gives its values precedence over those of
+    /// the providers in between, as it would without deduplication.
+    ///
+    /// To support the check, `self` retains a copy of the data of the last
+    /// provider added while enabled. To instead check for a provider before
+    /// adding it, inspect [`Figment::metadata()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml}, warning::Kind};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", "port = 8000")?;
+    ///
+    ///     fn with_app_config(figment: Figment) -> Figment {
+    ///         figment.merge(Toml::file("App.toml"))
+    ///     }
+    ///
+    ///     let figment = Figment::new().dedup_providers(true);
+    ///     let figment = with_app_config(with_app_config(figment));
+    ///     assert_eq!(figment.metadata().count(), 1);
+    ///
+    ///     let warning = figment.warnings().iter().next().unwrap();
+    ///     assert_eq!(warning.kind, Kind::DuplicateProvider);
+    ///
+    ///     // Providers with different data are never skipped.
+    ///     jail.create_file("App.toml", "port = 9000")?;
+    ///     let figment = with_app_config(figment);
+    ///     assert_eq!(figment.metadata().count(), 2);
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 9000);
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn dedup_providers(self, enabled: bool) -> Self {
+        self.update(|mut figment| {
+            match (enabled, &figment.seen) {
+                (true, None) => figment.seen = Some(None),
+                (false, _) => figment.seen = None,
+                (true, Some(_)) => {},
+            }
+
+            figment
+        })
+    }
+
+    /// Declares `profiles` as profiles known to the application, in addition
+    /// to any previously declared. Once declared, data for any other custom
+    /// profile, as from a misspelled `[prodction]` table in a
+    /// [nested](crate::providers::Data::nested()) TOML file, is flagged: an
+    /// [`UnknownProfile`](WarningKind::UnknownProfile) warning is emitted for
+    /// each such profile of each provider, whether the provider was added
+    /// before or after the declaration, and [`Figment::check_profiles()`]
+    /// fails. Warnings for profiles that later become known are withdrawn.
+    /// The default and global profiles are always known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, Profile, providers::{Format, Toml}, warning::Kind};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         [default]
+    ///         workers = 4
+    ///
+    ///         [prodction]
+    ///         workers = 16
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml").nested())
+    ///         .known_profiles(["debug", "production"]);
+    ///
+    ///     let warning = figment.warnings().iter().next().unwrap();
+    ///     assert_eq!(warning.kind, Kind::UnknownProfile(Some(Profile::new("production"))));
+    ///     assert_eq!(warning.profile, Some(Profile::new("prodction")));
+    ///     assert_eq!(warning.to_string(), "profile `prodction` is unknown \
+    ///         (did you mean `production`?) in App.toml TOML file");
+    ///
+    ///     assert!(figment.check_profiles().is_err());
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn known_profiles<I, P>(self, profiles: I) -> Self
+        where I: IntoIterator<Item = P>, P: Into<Profile>
+    {
+        self.update(|mut figment| {
+            let known = figment.known_profiles.get_or_insert_with(Vec::new);
+            for profile in profiles {
+                let profile = profile.into();
+                if !known.contains(&profile) {
+                    known.push(profile);
+                }
+            }
+
+            let map = match &figment.value {
+                Ok(map) => map,
+                Err(_) => return figment,
+            };
+
+            // Warnings for data already added are recomputed with every profile
+            // known so far.
+            figment.warnings.0.retain(|w| !matches!(w.kind, WarningKind::UnknownProfile(_)));
+            let mut warnings = vec![];
+            for (profile, suggestion) in Self::unknown_profiles(&figment.known_profiles, map) {
+                let mut tags: Vec<Tag> = map[&profile].values().map(|v| v.tag()).collect();
+                tags.dedup();
+                for tag in tags {
+                    let mut warning = Warning::new(WarningKind::UnknownProfile(suggestion.clone()))
+                        .with_profile(profile.clone());
+
+                    warning.metadata = figment.get_metadata(tag).cloned();
+                    if !warnings.contains(&warning) {
+                        warnings.push(warning);
+                    }
+                }
+            }
+
+            figment.warnings.0.extend(warnings);
+            figment
+        })
+    }
+
+    /// Returns an error for every custom profile with data that isn't one of
+    /// the [known profiles](Figment::known_profiles()). If no profiles were
+    /// declared, every profile is known. Each error's
+    /// [`profile`](Error::profile) is the unknown profile, and its
+    /// [help](Error::help()) suggests the known profile it is likely a
+    /// misspelling of, if any.
+    ///
+    /// If merging the figment fails, the merge error is returned instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// let figment = Figment::new()
+    ///     .merge(Serialized::default("port", 8080).profile("staging"))
+    ///     .merge(Serialized::default("port", 443).profile("prodution"));
+    ///
+    /// assert!(figment.check_profiles().is_ok());
+    ///
+    /// let figment = figment.known_profiles(["staging", "production"]);
+    /// let error = figment.check_profiles().unwrap_err();
+    /// assert_eq!(error.count(), 1);
+    /// assert_eq!(error.profile.as_ref().unwrap(), "prodution");
+    /// assert_eq!(error.help(), Some("did you mean `production`?"));
+    /// assert!(error.to_string().starts_with("profile `prodution` is unknown \
+    ///     (known profiles: `production`, `staging`)"));
+    /// ```
+    pub fn check_profiles(&self) -> Result<()> {
+        let map = self.value.as_ref().map_err(|e| e.clone().resolved(self))?;
+        let mut known: Vec<&str> = self.known_profiles.iter().flatten()
+            .map(|p| p.as_str().as_str())
+            .collect();
+
+        known.sort_unstable();
+        let list = known.iter()
+            .map(|p| format!("`{}`", p))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut errors: Option<Error> = None;
+        for (profile, suggestion) in Self::unknown_profiles(&self.known_profiles, map) {
+            let mut error = Error::from(format!("profile `{}` is unknown (known profiles: {})", profile, list));
+            error.metadata = map[&profile].values().next().and_then(|v| self.get_metadata(v.tag())).cloned();
+            error.set_help(suggestion.map(|p| format!("did you mean `{}`?", p)));
+            error.profile = Some(profile);
+            errors = Some(match errors {
+                Some(errors) => error.chain(errors),
+                None => error,
+            });
+        }
+
+        errors.map_or(Ok(()), Err)
+    }
+
+    /// Returns every custom profile in `map` that isn't one of the `known`
+    /// profiles, with the known profile it is likely a misspelling of, if
+    /// any. Returns nothing if no profiles were declared.
+    fn unknown_profiles(
+        known: &Option<Vec<Profile>>,
+        map: &Map<Profile, Dict>
+    ) -> Vec<(Profile, Option<Profile>)> {
+        let known = match known {
+            Some(known) => known,
+            None => return vec![],
+        };
+
+        map.keys()
+            .filter(|p| p.is_custom() && !known.contains(p))
+            .map(|profile| {
+                let needle = profile.as_str().as_str().to_lowercase();
+                let suggestion = known.iter()
+                    .filter(|p| p.is_custom())
+                    .map(|p| (crate::util::distance(&needle, &p.as_str().as_str().to_lowercase()), p))
+                    .filter(|(d, p)| *d <= core::cmp::max(1, p.as_str().len() / 3))
+                    .min_by_key(|(d, _)| *d)
+                    .map(|(_, p)| p.clone());
+
+                (profile.clone(), suggestion)
+            })
+            .collect()
+    }
+
+    /// Requires every key path in `keys` to be present in the selected
+    /// profile(s). The check is made whenever the figment is resolved, as by
+    /// [`Figment::extract()`], [`Figment::extract_inner()`], or
+    /// [`Figment::find_value()`], independent of what is being extracted. If
+    /// any required keys are absent, the error contains one
+    /// [`MissingField`](Kind::MissingField) error for each, in the order they
+    /// were required, each with a [`help`](Error::help()) suggestion naming
+    /// the key as each provider in the figment could supply it.
+    ///
+    /// Required keys are checked after [`pre_extract`](Figment::pre_extract())
+    /// hooks run. A key [focused](Figment::focus()) away is no longer required.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Env, Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", "[database]\nurl = \"postgres://localhost\"")?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml"))
+    ///         .merge(Env::prefixed("APP_").split("_"))
+    ///         .require(["database.url", "auth.token"]);
+    ///
+    ///     let error = figment.find_value("database").unwrap_err();
+    ///     assert_eq!(error.count(), 1);
+    ///     assert!(error.missing());
+    ///     assert_eq!(error.to_string(), "missing field `token` for key \"auth\" \
+    ///         (`auth.token` can be provided as `default.auth.token` in TOML file, \
+    ///         or `APP_AUTH_TOKEN` in `APP_` environment variable(s))");
+    ///
+    ///     jail.set_env("APP_AUTH_TOKEN", "hunter2");
+    ///     let figment = figment.merge(Env::prefixed("APP_").split("_"));
+    ///     assert!(figment.find_value("database").is_ok());
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn require<I, K>(self, keys: I) -> Self
+        where I: IntoIterator<Item = K>, K: Into<String>
+    {
+        self.update(|mut figment| {
+            figment.required.extend(keys.into_iter().map(Into::into));
+            figment
+        })
+    }
+
+    /// Registers `description` as the documentation of the key path `key`,
+    /// replacing any previous description of `key`. Descriptions are reported
+    /// by [`Figment::docs()`] alongside each key's current value and source;
+    /// they have no effect on extraction.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("port", 8080))
+    ///     .describe("port", "The port to listen on.")
+    ///     .describe("tls.key", "Path to the TLS private key.");
+    ///
+    /// let docs = figment.docs().unwrap();
+    /// assert_eq!(docs.get("port").unwrap().description, Some("The port to listen on."));
+    /// assert!(docs.get("tls.key").unwrap().value.is_none());
+    /// ```
+    pub fn describe(self, key: &str, description: &str) -> Self {
+        self.update(|mut figment| {
+            figment.descriptions.insert(key.into(), description.into());
+            figment
+        })
+    }
+
+    /// Registers every key path in `keys` as sensitive. The values at these
+    /// key paths, whatever they are, are replaced by `<redacted>` when a value
+    /// is rendered via [`Figment::redacted()`]; an empty key path redacts
+    /// every value. Reads of these values during extraction can be audited
+    /// via [`Figment::audit_secrets()`]. Redaction has no effect on
+    /// extraction otherwise. A
+    /// [focused](Figment::focus()) figment retains redactions relative to the
+    /// focused key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::Value};
+    ///
+    /// let figment = Figment::from(("name", "app"))
+    ///     .merge(("password", "hunter2"))
+    ///     .redact(["password"]);
+    ///
+    /// let value: Value = figment.extract().unwrap();
+    /// let rendered = figment.redacted(&value).to_string();
+    /// assert_eq!(rendered, r#"{name = "app", password = <redacted>}"#);
+    /// assert_eq!(figment.extract_inner::<String>("password").unwrap(), "hunter2");
+    /// ```
+    pub fn redact<I, K>(self, keys: I) -> Self
+        where I: IntoIterator<Item = K>, K: Into<String>
+    {
+        self.update(|mut figment| {
+            figment.redacted.extend(keys.into_iter().map(Into::into));
+            figment
+        })
+    }
+
+    /// Returns a rendering of `value`, a [`Value`] or [`Dict`] typically
+    /// extracted from `self`, that is safe to log: the values at every key
+    /// path registered via [`Figment::redact()`] are redacted, and huge values
+    /// are truncated. See [`Redacted`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, util::map, value::Dict};
+    ///
+    /// let figment = Figment::from(("database", map!["url" => "postgres://a:b@c"]))
+    ///     .merge(("log", "x".repeat(10_000)))
+    ///     .redact(["database.url"]);
+    ///
+    /// let dict: Dict = figment.extract().unwrap();
+    /// let rendered = format!("{:?}", figment.redacted(&dict).max_len(8));
+    /// assert_eq!(rendered, r#"{database = {url = <redacted>}, log = "xxxxxxxx"... (+9992 chars)}"#);
+    ///
+    /// let focused = figment.focus("database");
+    /// let dict: Dict = focused.extract().unwrap();
+    /// assert_eq!(focused.redacted(&dict).to_string(), "{url = <redacted>}");
+    /// ```
+    pub fn redacted<'v, V: Into<Redacted<'v>>>(&self, value: V) -> Redacted<'v> {
+        let keys = self.redacted.iter()
+            .map(|key| match key.is_empty() {
+                true => vec![],
+                false => crate::util::split_key(key).into_iter()
+                    .map(|k| match self.key_case {
+                        Some(case) => case.apply(&k),
+                        None => k,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        value.into().redact_paths(keys)
+    }
+
+    /// Registers `callback` to be called with a [`SecretRead`](crate::SecretRead)
+    /// for every secret value read during an extraction, as by
+    /// [`Figment::extract()`] or [`Figment::extract_inner()`]. A value is
+    /// secret if its key path is, or is nested under, a key path registered
+    /// via [`Figment::redact()`]. A value is read if it is deserialized, so
+    /// secrets skipped by the extracted type are not reported, nor are values
+    /// served from the cache of [`Figment::extract_cached()`].
+    ///
+    /// With the `tracing` feature enabled, secret reads are also logged as
+    /// events with the target `figment::audit`; see
+    /// [`SecretRead`](crate::SecretRead).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use figment::{Figment, util::map};
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Config { name: String }
+    ///
+    /// let log: Arc<Mutex<Vec<String>>> = Arc::default();
+    /// let sink = log.clone();
+    /// let figment = Figment::from(("name", "app"))
+    ///     .merge(("tokens", vec!["abc", "def"]))
+    ///     .redact(["tokens"])
+    ///     .audit_secrets(move |read| sink.lock().unwrap().push(read.key.clone()));
+    ///
+    /// let _: Config = figment.extract().unwrap();
+    /// assert!(log.lock().unwrap().is_empty());
+    ///
+    /// let _: Vec<String> = figment.extract_inner("tokens").unwrap();
+    /// assert_eq!(*log.lock().unwrap(), ["tokens.0", "tokens.1"]);
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
+    pub fn audit_secrets<F>(self, callback: F) -> Self
+        where F: Fn(&crate::SecretRead) + Send + Sync + 'static
+    {
+        self.update(|mut figment| {
+            figment.hooks.audit.push(alloc::sync::Arc::new(callback));
+            figment
+        })
+    }
+
+    /// Applies the extension `ext` to `self`. Equivalent to `ext.apply(self)`.
+    /// See [`FigmentExt`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// let defaults = |figment: Figment| figment.join(Serialized::default("workers", 4));
+    /// let figment = Figment::new().merge(("workers", 8)).with(defaults);
+    /// assert_eq!(figment.extract_inner::<usize>("workers").unwrap(), 8);
+    /// ```
+    pub fn with<E: FigmentExt>(self, ext: E) -> Self {
+        ext.apply(self)
+    }
+
+    /// Registers a hook called with the metadata and data of every provider
+    /// that is subsequently merged or joined, before the data is combined
+    /// with existing data. Values in the data are already tagged. An error
+    /// returned by the hook fails the figment as an error from the provider
+    /// would. See [hooks](Figment#hooks).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::Value, providers::{Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"password = "secret:db""#)?;
+    ///
+    ///     // Resolve `secret:` references in every subsequent provider's data.
+    ///     let figment = Figment::new()
+    ///         .pre_merge(|_, data| {
+    ///             for dict in data.values_mut() {
+    ///                 if let Some(Value::String(_, s)) = dict.get_mut("password") {
+    ///                     if s.as_str() == "secret:db" {
+    ///                         *s = "hunter2".into();
+    ///                     }
+    ///                 }
+    ///             }
+    ///
+    ///             Ok(())
+    ///         })
+    ///         .merge(Toml::file("App.toml"));
+    ///
+    ///     let password: String = figment.extract_inner("password")?;
+    ///     assert_eq!(password, "hunter2");
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn pre_merge<F>(self, hook: F) -> Self
+        where F: Fn(&Metadata, &mut Map<Profile, Dict>) -> Result<()> + Send + Sync + 'static
+    {
+        self.update(|mut figment| {
+            figment.hooks.pre_merge.push(alloc::sync::Arc::new(hook));
+            figment
+        })
+    }
+
+    /// Registers a hook called with the combined data of all profiles after
+    /// every provider that is subsequently merged or joined is combined with
+    /// existing data. An error returned by the hook fails the figment. See
+    /// [hooks](Figment#hooks).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, Error, Profile, providers::Serialized};
+    ///
+    /// // Disallow a `debug` key in the global profile.
+    /// let figment = Figment::new()
+    ///     .post_merge(|data| match data.get(&Profile::Global) {
+    ///         Some(dict) if dict.contains_key("debug") => {
+    ///             Err(Error::from("`debug` cannot be global".to_string()))
+    ///         }
+    ///         _ => Ok(()),
+    ///     });
+    ///
+    /// let default = figment.clone().merge(Serialized::default("debug", true));
+    /// assert!(default.extract_inner::<bool>("debug").is_ok());
+    ///
+    /// let global = figment.merge(Serialized::global("debug", true));
+    /// assert!(global.extract_inner::<bool>("debug").is_err());
+    /// ```
+    pub fn post_merge<F>(self, hook: F) -> Self
+        where F: Fn(&mut Map<Profile, Dict>) -> Result<()> + Send + Sync + 'static
+    {
+        self.update(|mut figment| {
+            figment.hooks.post_merge.push(alloc::sync::Arc::new(hook));
+            figment
+        })
+    }
+
+    /// Registers a hook called with the combined value of the selected
+    /// profile(s) before every extraction or search, like
+    /// [`Figment::extract()`] or [`Figment::find_value()`]. An error returned
+    /// by the hook is returned by the extraction or search. See
+    /// [hooks](Figment#hooks).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, Error};
+    ///
+    /// let figment = Figment::from(("workers", 0))
+    ///     .pre_extract(|dict| match dict.get("workers").and_then(|v| v.to_i128()) {
+    ///         Some(0) => Err(Error::from("`workers` must be nonzero".to_string())),
+    ///         _ => Ok(()),
+    ///     });
+    ///
+    /// let error = figment.extract_inner::<usize>("workers").unwrap_err();
+    /// assert_eq!(error.to_string(), "`workers` must be nonzero");
+    /// assert!(figment.merge(("workers", 2)).extract_inner::<usize>("workers").is_ok());
+    /// ```
+    pub fn pre_extract<F>(self, hook: F) -> Self
+        where F: Fn(&mut Dict) -> Result<()> + Send + Sync + 'static
+    {
+        self.update(|mut figment| {
+            figment.hooks.pre_extract.push(alloc::sync::Arc::new(hook));
+            figment
+        })
+    }
+
+    /// Finds the value at `key` in `value`, ignoring case and converting keys
+    /// to the canonical convention if configured.
+    fn find_in(&self, value: Value, key: &str) -> Result<Value> {
+        let canonical_key;
+        let key = match self.key_case {
+            Some(case) => {
+                canonical_key = crate::util::split_key(key).iter()
+                    .map(|k| crate::util::escape_key(&case.apply(k)).into_owned())
+                    .collect::<Vec<_>>()
+                    .join(".");
+
+                &canonical_key
+            }
+            None => key,
+        };
+
+        let found = match self.case_insensitive {
+            true => value.find_uncased(key),
+            false => value.find(key),
+        };
+
+        found.ok_or_else(|| Kind::MissingField(key.to_string().into()).into())
+    }
+
+    /// Emits a trace event for every leaf in `new` that conflicts with a
+    /// value in `old`, naming the providers of the kept and discarded values.
+    #[cfg(feature = "tracing")]
+    fn trace_conflicts(
+        metadata: &Map<Tag, Metadata>,
+        old: &Map<Profile, Dict>,
+        new: &Map<Profile, Dict>,
+        order: Order
+    ) {
+        type Metadatas = Map<Tag, Metadata>;
+
+        fn walk(md: &Metadatas, old: &Dict, new: &Dict, order: Order, path: &mut Vec<String>) {
+            for (key, b) in new {
+                let a = match old.get(key) {
+                    Some(a) => a,
+                    None => continue,
+                };
+
+                path.push(key.clone());
+                match (a, b) {
+                    (Value::Dict(_, a), Value::Dict(_, b)) => walk(md, a, b, order, path),
+                    _ => {
+                        let (kept, discarded) = match order {
+                            Order::Merge => (b, a),
+                            Order::Join => (a, b),
+                        };
+
+                        let name = |v: &Value| md.get(&v.tag())
+                            .map_or("unknown", |md| &*md.name);
+
+                        let keys: Vec<_> = path.iter().map(|k| crate::util::escape_key(k)).collect();
+                        tracing::trace!(key = %keys.join("."), kept = name(kept),
+                            discarded = name(discarded), "resolved conflicting values");
+                    }
+                }
+
+                path.pop();
+            }
+        }
+
+        for (profile, new) in new {
+            if let Some(old) = old.get(profile) {
+                let _span = tracing::trace_span!("profile", %profile).entered();
+                walk(metadata, old, new, order, &mut vec![]);
+            }
+        }
+    }
+
+    /// Checks the combined data against the limits, which values that are
+    /// each within them may exceed together.
+    fn check_combined_limits(mut self) -> Self {
+        if let Ok(value) = &self.value {
+            if let Err(e) = Self::check_limits(&self.limits, value, Tag::Default) {
+                self.value = Err(e);
+            }
+        }
+
+        self
+    }
+
+    fn check_limits(limits: &Limits, map: &Map<Profile, Dict>, tag: Tag) -> Result<()> {
+        for (profile, dict) in map {
+            limits.check(dict).map_err(|e| e.retagged(tag.for_profile(profile)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Merges the selected profile with the default and global profiles.
+    pub(crate) fn merged(&self) -> Result<Value> {
+        self.merged_for(&self.profile, &self.base_profiles)
+    }
+
+    /// Merges `profile` and the `base` profiles, in order of increasing
+    /// precedence, with the default profile. Returns the merged dictionary
+    /// and, separately, the global profile's dictionary.
+    fn layers(&self, profile: &Profile, base: &[Profile]) -> Result<(Dict, Dict)> {
+        let mut map = self.value.clone().map_err(|e| e.resolved(self))?;
+        let def = map.remove(&Profile::Default).unwrap_or_default();
+        let global = map.remove(&Profile::Global).unwrap_or_default();
+
+        let mut dict = def;
+        for profile in base.iter().chain(Some(profile)) {
+            if let (true, Some(v)) = (profile.is_custom(), map.remove(profile)) {
+                dict = self.merge_dicts(dict, v);
+            }
+        }
+
+        Ok((dict, global))
+    }
+
+    /// Merges `profile` and the `base` profiles, in order of increasing
+    /// precedence, with the default and global profiles.
+    fn merged_for(&self, profile: &Profile, base: &[Profile]) -> Result<Value> {
+        let map = self.merged_unchecked(profile, base)?;
+        self.check_required(&map, profile)?;
+
+        Ok(Value::Dict(Tag::Default, map))
+    }
+
+    /// Like [`Figment::merged_for()`] but without checking that required keys
+    /// are present.
+    pub(crate) fn merged_unchecked(&self, profile: &Profile, base: &[Profile]) -> Result<Dict> {
+        let (dict, global) = self.layers(profile, base)?;
+        let mut map = crate::coalesce::remove_unset(self.merge_dicts(dict, global));
+        self.hooks.pre_extract(&mut map).map_err(|e| e.resolved(self))?;
+        Ok(map)
+    }
+
+    /// Returns an error with a missing field error for every required key
+    /// path that is absent from `dict`, the merged value of `profile`.
+    fn check_required(&self, dict: &Dict, profile: &Profile) -> Result<()> {
+        let mut errors: Option<Error> = None;
+        for key in &self.required {
+            let keys: Vec<String> = crate::util::split_key(key).into_iter()
+                .map(|k| match self.key_case {
+                    Some(case) => case.apply(&k),
+                    None => k,
+                })
+                .collect();
+
+            let (field, parents) = match keys.split_last() {
+                Some(split) => split,
+                None => continue,
+            };
+
+            if crate::value::find_keys_in(dict, &keys, self.case_insensitive).is_some() {
+                continue;
+            }
+
+            // Providers defined in code supply fixed values; only those that
+            // read from outside the program, such as files, could supply it.
+            let mut sources: Vec<String> = vec![];
+            for md in self.metadata.values().filter(|md| md.extra().is_some_and(|extra| extra.external)) {
+                let source = format!("`{}` in {}", md.interpolate(profile, &keys), md.name);
+                if !sources.contains(&source) {
+                    sources.push(source);
+                }
+            }
+
+            let mut error = Error::from(Kind::MissingField(field.clone().into()));
+            error.path = parents.to_vec();
+            if !sources.is_empty() {
+                error.set_help(Some(format!("`{}` can be provided as {}", key, sources.join(", or "))));
+            }
+
+            errors = Some(match errors {
+                Some(errors) => error.chain(errors),
+                None => error,
+            });
+        }
+
+        errors.map_or(Ok(()), Err)
+    }
+
+    /// Merges `v` into `dict`, unifying keys and merging keyed arrays if
+    /// configured.
+    fn merge_dicts(&self, dict: Dict, v: Dict) -> Dict {
+        let v = match self.case_insensitive {
+            true => crate::coalesce::unify_keys(Some(&dict), v),
+            false => v,
+        };
+
+        match &self.array_key {
+            Some(key) => crate::coalesce::coalesce_keyed(dict, v, Order::Merge, key),
+            None => dict.merge(v),
+        }
+    }
+
+    /// Deserializes `value` into `T`. If a field is missing, the error
+    /// suggests how to provide it. See [`Error::help()`].
+    ///
+    /// If any provider's metadata has a read hook, the hook is called with the
+    /// key path, relative to `path`, of every value it provided that was read.
+    pub(crate) fn deserialize<'a, T: Deserialize<'a>>(&self, path: &[String], value: &Value) -> Result<T> {
+        self.deserialize_tracked(path, value, None)
+    }
+
+    /// Like [`Figment::deserialize()`], additionally recording the address of
+    /// every value that is read in `tracked`, if it is `Some`.
+    fn deserialize_tracked<'a, T: Deserialize<'a>>(
+        &self,
+        path: &[String],
+        value: &Value,
+        tracked: Option<&Reads>,
+    ) -> Result<T> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("extract",
+            key = %path.join("."), ty = core::any::type_name::<T>()).entered();
+
+        #[cfg(feature = "std")]
+        let _policy = self.policy.enter();
+
+        let hooked = self.metadata.values().any(|md| md.extra().is_some_and(|extra| extra.read_hook.is_some()));
+        #[cfg(feature = "std")]
+        let audited = self.audited();
+        #[cfg(not(feature = "std"))]
+        let audited = false;
+
+        let de = ConfiguredValueDe::from(self, value);
+
+        let result = if !hooked && !audited && tracked.is_none() {
+            de.scoped(T::deserialize).map_err(|e| self.with_missing_field_help(value, e))
+        } else {
+            let local = Reads::default();
+            let reads = tracked.unwrap_or(&local);
+            let de = ConfiguredValueDe { reads: Some(reads), ..de };
+            let result = de.scoped(T::deserialize).map_err(|e| self.with_missing_field_help(value, e));
+            if hooked {
+                self.report_reads(value, &reads.borrow(), &mut path.to_vec());
+            }
+
+            #[cfg(feature = "std")]
+            if audited {
+                self.audit_reads(path, value, &reads.borrow());
+            }
+
+            result
+        };
+
+        // Only the error code, path, and source are logged: never values.
+        #[cfg(feature = "tracing")]
+        if let Err(error) = &result {
+            for e in error.clone() {
+                let keys: Vec<_> = e.path.iter().map(|k| crate::util::escape_key(k)).collect();
+                tracing::warn!(code = e.code(), path = %keys.join("."),
+                    source = e.metadata.as_ref().map(|md| &*md.name),
+                    profile = e.profile.as_ref().map(|p| p.as_str().as_str()), "extraction failed");
+            }
+        }
+
+        result
+    }
+
+    /// Calls the read hook of the metadata for every leaf or array in `value`
+    /// whose address is in `reads`. `path` is the key path to `value`.
+    fn report_reads(&self, value: &Value, reads: &BTreeSet<*const Value>, path: &mut Vec<String>) {
+        if !matches!(value, Value::Dict(..)) && reads.contains(&(value as *const Value)) {
+            let hook = self.get_metadata(value.tag()).and_then(|md| md.extra()?.read_hook.as_ref());
+            if let Some(hook) = hook {
+                let keys: Vec<_> = path.iter().map(|k| crate::util::escape_key(k)).collect();
+                (hook.0)(&keys.join("."));
+            }
+        }
+
+        match value {
+            Value::Dict(_, dict) => for (key, value) in dict {
+                path.push(key.clone());
+                self.report_reads(value, reads, path);
+                path.pop();
+            },
+            Value::Array(_, vec) => for (i, value) in vec.iter().enumerate() {
+                path.push(i.to_string());
+                self.report_reads(value, reads, path);
+                path.pop();
+            },
+            _ => {}
+        }
+    }
+
+    /// Returns `true` if reads of secret values are reported: if any keys are
+    /// secret and there are audit callbacks or an interested subscriber.
+    #[cfg(feature = "std")]
+    fn audited(&self) -> bool {
+        #[cfg(feature = "tracing")]
+        let traced = tracing::enabled!(target: "figment::audit", tracing::Level::INFO);
+        #[cfg(not(feature = "tracing"))]
+        let traced = false;
+
+        !self.redacted.is_empty() && (traced || !self.hooks.audit.is_empty())
+    }
+
+    /// Reports every secret leaf in `value`, the value at the key
+    /// path `path`, whose address is in `reads` as a [`crate::SecretRead`].
+    #[cfg(feature = "std")]
+    fn audit_reads(&self, path: &[String], value: &Value, reads: &BTreeSet<*const Value>) {
+        struct Walk<'a> {
+            figment: &'a Figment,
+            secrets: Vec<Vec<String>>,
+            reads: &'a BTreeSet<*const Value>,
+            requested: String,
+            time: std::time::SystemTime,
+        }
+
+        impl Walk<'_> {
+            // `keys` is the key path with array indices; `named`, without.
+            fn walk(&self, value: &Value, keys: &mut Vec<String>, named: &mut Vec<String>) {
+                let leaf = !matches!(value, Value::Dict(..) | Value::Array(..));
+                let secret = self.secrets.iter().any(|s| named.starts_with(s));
+                if leaf && secret && self.reads.contains(&(value as *const Value)) {
+                    let keys: Vec<_> = keys.iter().map(|k| crate::util::escape_key(k)).collect();
+                    let read = crate::SecretRead {
+                        key: keys.join("."),
+                        requested: self.requested.clone(),
+                        profile: value.tag().profile().unwrap_or_else(|| self.figment.profile.clone()),
+                        time: self.time,
+                    };
+
+                    #[cfg(feature = "tracing")]
+                    tracing::info!(target: "figment::audit", key = %read.key,
+                        requested = %read.requested, profile = %read.profile, "secret read");
+
+                    self.figment.hooks.audit(&read);
+                }
+
+                match value {
+                    Value::Dict(_, dict) => for (key, value) in dict {
+                        keys.push(key.clone());
+                        named.push(key.clone());
+                        self.walk(value, keys, named);
+                        named.pop();
+                        keys.pop();
+                    },
+                    Value::Array(_, vec) => for (i, value) in vec.iter().enumerate() {
+                        keys.push(i.to_string());
+                        self.walk(value, keys, named);
+                        keys.pop();
+                    },
+                    _ => {}
+                }
+            }
+        }
+
+        let canonical = |key: &str| match self.key_case {
+            Some(case) => case.apply(key),
+            None => key.to_string(),
+        };
+
+        let secrets = self.redacted.iter()
+            .map(|key| match key.is_empty() {
+                true => vec![],
+                false => crate::util::split_key(key).iter().map(|k| canonical(k)).collect(),
+            })
+            .collect();
+
+        let requested: Vec<_> = path.iter().map(|k| crate::util::escape_key(k)).collect();
+        let walk = Walk {
+            figment: self,
+            secrets,
+            reads,
+            requested: requested.join("."),
+            time: std::time::SystemTime::now(),
+        };
+
+        let mut keys: Vec<String> = path.iter().map(|k| canonical(k)).collect();
+        let mut named = keys.clone();
+        walk.walk(value, &mut keys, &mut named);
+    }
+
+    /// Given that deserializing `value` failed with `error`, returns `error`
+    /// with a suggestion for resolving it if it is a missing field error.
+    ///
+    /// Serde stops at the first missing field, so only that field is
+    /// reported: discovering the rest would require deserializing again,
+    /// re-running `T`'s and the figment's own, possibly effectful, code.
+    fn with_missing_field_help(&self, value: &Value, mut error: Error) -> Error {
+        let field = match &error.kind {
+            Kind::MissingField(field) if error.count() == 1 => field,
+            _ => return error,
+        };
+
+        let key: Vec<_> = error.path.iter()
+            .map(|k| crate::util::escape_key(k))
+            .chain(Some(crate::util::escape_key(field)))
+            .collect();
+
+        let key = key.join(".");
+        if let Some(found) = self.similar_key(value, &key) {
+            error.set_help(Some(format!("found {}: did you mean `{}`?", found, key)));
+        } else if let Some(md) = self.defaults_provider() {
+            error.set_help(Some(match &md.source {
+                Some(source) => format!("a default for `{}` can be provided by {} at {}",
+                    key, md.name, source),
+                None => format!("a default for `{}` can be provided by {}", key, md.name),
+            }));
+        }
+
+        error
+    }
+
+    /// Returns a description of the key path in `value` closest to the missing
+    /// key path `key`, if one is close enough to plausibly be a typo, such as
+    /// `APP_DATABSE_URL` for `database.url`. The key path is interpolated by
+    /// the metadata of the provider of its value, if known.
+    fn similar_key(&self, value: &Value, key: &str) -> Option<String> {
+        fn paths<'v>(value: &'v Value, prefix: &str, out: &mut Vec<(String, &'v Value)>) {
+            if let Value::Dict(_, dict) = value {
+                for (k, v) in dict {
+                    let path = match prefix.is_empty() {
+                        true => crate::util::escape_key(k).into_owned(),
+                        false => format!("{}.{}", prefix, crate::util::escape_key(k)),
+                    };
+
+                    paths(v, &path, out);
+                    out.push((path, v));
+                }
+            }
+        }
+
+        // Tolerate as many edits as a typo in the last component could make.
+        let last = crate::util::split_key(key).pop().unwrap_or_default();
+        let max = core::cmp::max(1, last.chars().count() / 3);
+
+        let mut found = vec![];
+        paths(value, "", &mut found);
+        let (path, value) = found.iter()
+            .map(|(path, value)| (crate::util::distance(key, path), path, value))
+            .filter(|(d, ..)| *d <= max)
+            .min_by_key(|(d, ..)| *d)
+            .map(|(_, path, value)| (path, value))?;
+
+        let tag = value.tag();
+        match (self.get_metadata(tag), tag.profile()) {
+            (Some(md), Some(profile)) => {
+                let keys = crate::util::split_key(path);
+                Some(format!("`{}` in {}", md.interpolate(&profile, &keys), md.name))
+            }
+            _ => Some(format!("`{}`", path)),
+        }
+    }
+
+    /// Returns the metadata of the first provider defined in code, such as
+    /// [`Serialized::defaults()`], that contributes to the default profile.
+    ///
+    /// [`Serialized::defaults()`]: crate::providers::Serialized::defaults()
+    fn defaults_provider(&self) -> Option<&Metadata> {
+        self.profile_info(Profile::Default)?.providers.into_iter()
+            .find(|md| matches!(md.source, Some(Source::Code(_))))
+    }
+
+    /// Returns a new `Figment` containing only the sub-dictionaries at `key`.
+    ///
+    /// This "sub-figment" is a _focusing_ of `self` with the property that:
+    ///
+    ///   * `self.find(key + ".sub")` <=> `focused.find("sub")`
+    ///
+    /// In other words, all values in `self` with a key starting with `key` are
+    /// in `focused` _without_ the prefix and vice-versa.
+    ///
+    /// The focused figment retains the metadata, selected profiles, and
+    /// configuration of `self`, so a subsystem can be handed only its slice of
+    /// the configuration and extract its own structure from it. The
+    /// [`path`](Error::path) of an extraction error is relative to `key`, and
+    /// can be made relative to the root with [`Error::in_key()`]. The key
+    /// displayed by the error, as [interpolated](Metadata::interpolate()) by
+    /// the provider, is that of the value in `self`:
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::Env};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_SERVER_PORT", "eighty");
+    ///
+    ///     let figment = Figment::from(Env::prefixed("APP_").split("_"));
+    ///     let error = figment.focus("server").extract_inner::<u16>("port").unwrap_err();
+    ///     assert_eq!(error.path, ["port"]);
+    ///     assert_eq!(error.interpolated_key().as_deref(), Some("APP_SERVER_PORT"));
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", r#"
+    ///         cat = [1, 2, 3]
+    ///         dog = [4, 5, 6]
+    ///
+    ///         [subtree]
+    ///         cat = "meow"
+    ///         dog = "woof!"
+    ///
+    ///         [subtree.bark]
+    ///         dog = true
+    ///         cat = false
+    ///     "#)?;
+    ///
+    ///     let root = Figment::from(Toml::file("Config.toml"));
+    ///     assert_eq!(root.extract_inner::<Vec<u8>>("cat").unwrap(), vec![1, 2, 3]);
+    ///     assert_eq!(root.extract_inner::<Vec<u8>>("dog").unwrap(), vec![4, 5, 6]);
+    ///     assert_eq!(root.extract_inner::<String>("subtree.cat").unwrap(), "meow");
+    ///     assert_eq!(root.extract_inner::<String>("subtree.dog").unwrap(), "woof!");
+    ///
+    ///     let subtree = root.focus("subtree");
+    ///     assert_eq!(subtree.extract_inner::<String>("cat").unwrap(), "meow");
+    ///     assert_eq!(subtree.extract_inner::<String>("dog").unwrap(), "woof!");
+    ///     assert_eq!(subtree.extract_inner::<bool>("bark.cat").unwrap(), false);
+    ///     assert_eq!(subtree.extract_inner::<bool>("bark.dog").unwrap(), true);
+    ///
+    ///     let bark = subtree.focus("bark");
+    ///     assert_eq!(bark.extract_inner::<bool>("cat").unwrap(), false);
+    ///     assert_eq!(bark.extract_inner::<bool>("dog").unwrap(), true);
+    ///
+    ///     let not_a_dict = root.focus("cat");
+    ///     assert!(not_a_dict.extract_inner::<bool>("cat").is_err());
+    ///     assert!(not_a_dict.extract_inner::<bool>("dog").is_err());
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn focus(&self, key: &str) -> Self {
+        fn focus_map(figment: &Figment, map: Map<Profile, Dict>, key: &str) -> Map<Profile, Dict> {
+            map.into_iter()
+                .filter_map(|(k, v)| {
+                    let focused = figment.find_in(Value::Dict(Tag::Default, v), key).ok()?;
+                    let dict = focused.into_dict()?;
+                    Some((k, dict))
+                })
+                .collect()
+        }
+
+        let prefix: Vec<String> = crate::util::split_key(key).into_iter()
+            .map(|k| match self.key_case {
+                Some(case) => case.apply(&k),
+                None => k,
+            })
+            .collect();
+
+        let metadata = self.metadata.iter()
+            .map(|(tag, md)| (*tag, md.clone().rebased(prefix.clone())))
+            .collect();
+
+        let split = |key: &str| -> Vec<String> {
+            crate::util::split_key(key).into_iter()
+                .map(|k| match self.key_case {
+                    Some(case) => case.apply(&k),
+                    None => k,
+                })
+                .collect()
+        };
+
+        let rebase = |key: &str| {
+            let keys = split(key);
+            let rest = keys.strip_prefix(&prefix[..])?;
+            let rest: Vec<_> = rest.iter().map(|k| crate::util::escape_key(k)).collect();
+            Some(rest.join(".")).filter(|key| !key.is_empty())
+        };
+
+        let required = self.required.iter().filter_map(|key| rebase(key)).collect();
+        let redacted = self.redacted.iter()
+            .filter_map(|key| match rebase(key) {
+                // An empty key, or one containing the focused key, redacts
+                // all of the focused value.
+                None if key.is_empty() || prefix.starts_with(&split(key)) => Some(String::new()),
+                rebased => rebased,
+            })
+            .collect();
+        let libraries = self.libraries.iter().filter_map(|key| rebase(key)).collect();
+        let descriptions = self.descriptions.iter()
+            .filter_map(|(key, docs)| Some((rebase(key)?, docs.clone())))
+            .collect();
+
+        Figment {
+            profile: self.profile.clone(),
+            base_profiles: self.base_profiles.clone(),
+            metadata,
+            value: self.value.clone()
+                .map_err(|e| e.resolved(self))
+                .map(|map| focus_map(self, map, key)),
+            coercion: self.coercion,
+            limits: self.limits,
+            case_insensitive: self.case_insensitive,
+            empty_as_none: self.empty_as_none,
+            key_case: self.key_case,
+            array_key: self.array_key.clone(),
+            hooks: self.hooks.clone(),
+            warnings: self.warnings.clone(),
+            seen: self.seen.clone(),
+            required,
+            descriptions,
+            redacted,
+            known_profiles: self.known_profiles.clone(),
+            #[cfg(feature = "std")]
+            policy: self.policy.clone(),
+            libraries,
+            prioritized: self.prioritized.iter()
+                .map(|(priority, map)| (*priority, focus_map(self, map.clone(), key)))
+                .collect(),
+            overrides: self.overrides.clone()
+                .map(|(tag, map)| (tag, focus_map(self, map, key))),
+            extracted: Extracted::default(),
+        }
+    }
+
+    /// Returns a new `Figment` in which the values under `tenants.{tenant}`
+    /// override the top-level values, for configurations shared by several
+    /// tenants. Every top-level key that isn't overridden falls back to its
+    /// value in `self`. The `tenants` dictionary itself is removed.
+    ///
+    /// Tenant values override top-level values profile by profile: the values
+    /// for `tenant` in a given profile override the values in that profile
+    /// only. Profiles are then merged as usual, so a top-level value in the
+    /// selected profile takes precedence over a tenant value in the default
+    /// profile. As with [`Figment::focus()`], metadata is retained, and the
+    /// `tenants` key respects the figment's
+    /// [key configuration](Figment#key-configuration).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// #[serde(deny_unknown_fields)]
+    /// struct Config {
+    ///     plan: String,
+    ///     seats: u32,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         plan = "free"
+    ///         seats = 5
+    ///
+    ///         [tenants.acme]
+    ///         plan = "enterprise"
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml"));
+    ///
+    ///     let acme: Config = figment.for_tenant("acme").extract()?;
+    ///     assert_eq!(acme, Config { plan: "enterprise".into(), seats: 5 });
+    ///
+    ///     let other: Config = figment.for_tenant("other").extract()?;
+    ///     assert_eq!(other, Config { plan: "free".into(), seats: 5 });
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn for_tenant(&self, tenant: &str) -> Self {
+        let root = match self.key_case {
+            Some(case) => case.apply("tenants"),
+            None => "tenants".into(),
+        };
+
+        let tenant = crate::util::escape_key(tenant);
+        let mut figment = self.clone();
+        figment.value = figment.value.map(|map| map.into_iter()
+            .map(|(profile, mut dict)| {
+                let key = dict.keys()
+                    .find(|k| match self.case_insensitive {
+                        true => uncased::UncasedStr::new(k) == root.as_str(),
+                        false => **k == root,
+                    })
+                    .cloned();
+
+                let overrides = key.and_then(|key| crate::value::dict_remove(&mut dict, &key))
+                    .and_then(|tenants| self.find_in(tenants, &tenant).ok())
+                    .and_then(|value| value.into_dict());
+
+                match overrides {
+                    Some(overrides) => (profile, self.merge_dicts(dict, overrides)),
+                    None => (profile, dict),
+                }
+            })
+            .collect());
+
+        figment
+    }
+
+    /// Deserializes the collected value into `T`.
+    ///
+    /// If any required fields are missing, the returned error contains one
+    /// [`MissingField`](Kind::MissingField) error for each. When a key that is
+    /// likely a misspelling of a missing field is present, such as an
+    /// environment variable `APP_DATABSE_URL` for `database.url`, the missing
+    /// field error carries a [`help`](Error::help()) suggestion naming the key.
+    /// Otherwise, when a provider of defaults, such as
+    /// [`Serialized::defaults()`], is present, the suggestion is to add a
+    /// default.
+    ///
+    /// [`Serialized::defaults()`]: crate::providers::Serialized::defaults()
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    ///
+    /// use figment::{Figment, providers::{Format, Toml, Json, Env}};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Config {
+    ///     name: String,
+    ///     numbers: Option<Vec<usize>>,
+    ///     debug: bool,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", r#"
+    ///         name = "test"
+    ///         numbers = [1, 2, 3, 10]
+    ///     "#)?;
+    ///
+    ///     jail.set_env("config_name", "env-test");
+    ///
+    ///     jail.create_file("Config.json", r#"
+    ///         {
+    ///             "name": "json-test",
+    ///             "debug": true
+    ///         }
+    ///     "#)?;
+    ///
+    ///     let config: Config = Figment::new()
+    ///         .merge(Toml::file("Config.toml"))
+    ///         .merge(Env::prefixed("CONFIG_"))
+    ///         .join(Json::file("Config.json"))
+    ///         .extract()?;
+    ///
+    ///     assert_eq!(config, Config {
+    ///         name: "env-test".into(),
+    ///         numbers: vec![1, 2, 3, 10].into(),
+    ///         debug: true
+    ///     });
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn extract<'a, T: Deserialize<'a>>(&self) -> Result<T> {
+        self.deserialize(&[], &self.merged()?)
+    }
+
+    /// Deserializes the collected value into `T`, as by
+    /// [`Figment::extract()`], or returns the value previously deserialized
+    /// into a `T` if there is one. Errors are not cached.
+    ///
+    /// The cache is cleared whenever `self` is changed, as by merging a
+    /// provider or selecting a profile, and a clone of `self` starts with an
+    /// empty cache. This makes repeated extractions of the same type from a
+    /// shared figment cheap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use serde::Deserialize;
+    /// use figment::Figment;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Server { port: u16 }
+    ///
+    /// let figment = Figment::from(("port", 8000));
+    /// let a = figment.extract_cached::<Server>().unwrap();
+    /// let b = figment.extract_cached::<Server>().unwrap();
+    /// assert!(Arc::ptr_eq(&a, &b));
+    ///
+    /// let figment = figment.merge(("port", 9000));
+    /// assert_eq!(figment.extract_cached::<Server>().unwrap().port, 9000);
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
+    pub fn extract_cached<T>(&self) -> Result<std::sync::Arc<T>>
+        where T: serde::de::DeserializeOwned + Send + Sync + 'static
+    {
+        if let Some(value) = self.extracted.get::<T>() {
+            return Ok(value);
+        }
+
+        Ok(self.extracted.insert(self.extract::<T>()?))
+    }
+
+    /// Deserializes the value at the `key` path in the collected value into
+    /// `T`. The [`path`](crate::Error::path) of any deserialization error is the full
+    /// path from the root, including `key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml, Json}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", r#"
+    ///         numbers = [1, 2, 3, 10]
+    ///     "#)?;
+    ///
+    ///     jail.create_file("Config.json", r#"{ "debug": true } "#)?;
+    ///
+    ///     let numbers: Vec<usize> = Figment::new()
+    ///         .merge(Toml::file("Config.toml"))
+    ///         .join(Json::file("Config.json"))
+    ///         .extract_inner("numbers")?;
+    ///
+    ///     assert_eq!(numbers, vec![1, 2, 3, 10]);
+    ///
+    ///     let error = Figment::from(Toml::file("Config.toml"))
+    ///         .extract_inner::<Vec<String>>("numbers")
+    ///         .unwrap_err();
+    ///
+    ///     assert_eq!(error.path, vec!["numbers", "0"]);
+    ///     assert!(error.metadata.unwrap().name.contains("TOML"));
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn extract_inner<'a, T: Deserialize<'a>>(&self, key: &str) -> Result<T> {
+        self.deserialize(&crate::util::split_key(key), &self.find_value(key)?)
+            .map_err(|e| e.in_key(key))
+    }
+
+    /// Returns an iterator that deserializes each element of the array at the
+    /// `key` path into `T`, one element per call to `next()`, instead of into
+    /// a `Vec<T>` all at once. This is useful for large arrays, where only one
+    /// deserialized element need be live at a time.
+    ///
+    /// The [`path`](crate::Error::path) of any error yielded by the iterator is
+    /// the full path from the root, including `key` and the element's index.
+    /// If the value at `key` is not an array, an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::{Format, Json}};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Event {
+    ///     id: u32,
+    ///     kind: String,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Events.json", r#"{
+    ///         "events": [
+    ///             { "id": 1, "kind": "start" },
+    ///             { "id": 2, "kind": "stop" },
+    ///             { "id": "three", "kind": "start" }
+    ///         ]
+    ///     }"#)?;
+    ///
+    ///     let figment = Figment::from(Json::file("Events.json"));
+    ///     let mut events = figment.extract_iter::<Event>("events")?;
+    ///     assert_eq!(events.next().unwrap()?.kind, "start");
+    ///     assert_eq!(events.next().unwrap()?.id, 2);
+    ///
+    ///     let error = events.next().unwrap().unwrap_err();
+    ///     assert_eq!(error.path, ["events", "2", "id"]);
+    ///     assert!(events.next().is_none());
+    ///
+    ///     assert!(figment.extract_iter::<Event>("events.0").is_err());
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn extract_iter<'a, T: Deserialize<'a>>(
+        &self,
+        key: &str,
+    ) -> Result<impl Iterator<Item = Result<T>> + '_> {
+        let path = crate::util::split_key(key);
+        let elements = match self.find_value(key)? {
+            Value::Array(_, vec) => vec,
+            value => {
+                let kind = Kind::InvalidType(value.to_actual(), "an array".into());
+                return Err(Error::from(kind).retagged(value.tag()).resolved(self).in_key(key));
+            }
+        };
+
+        let key = key.to_string();
+        Ok(elements.into_iter().enumerate().map(move |(i, value)| {
+            let mut path = path.clone();
+            path.push(i.to_string());
+            self.deserialize(&path, &value)
+                .map_err(|e| e.prefixed_index(i).in_key(&key))
+        }))
+    }
+
+    /// Deserializes the collected value into `T`, like [`Figment::extract()`],
+    /// and additionally returns the key paths of the values that were read to
+    /// do so. See [`Consumed`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     name: String,
+    ///     ports: Vec<u16>,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         name = "app"
+    ///         ports = [80, 443]
+    ///         prot = 8000
+    ///
+    ///         [tls]
+    ///         cert = "cert.pem"
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml"));
+    ///     let (config, consumed) = figment.extract_consumed::<Config>()?;
+    ///     assert_eq!(consumed.keys().collect::<Vec<_>>(), ["name", "ports"]);
+    ///     assert_eq!(consumed.unused().collect::<Vec<_>>(), ["prot", "tls.cert"]);
+    ///     assert!(consumed.metadata("name").unwrap().name.contains("TOML"));
+    ///
+    ///     // Only changes to consumed keys need `Config` to be re-extracted.
+    ///     let update = figment.clone().merge(("tls.cert", "new.pem"));
+    ///     assert!(!consumed.changed(&update));
+    ///
+    ///     let update = figment.merge(("name", "new"));
+    ///     assert!(consumed.changed(&update));
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn extract_consumed<'a, T: Deserialize<'a>>(&self) -> Result<(T, Consumed)> {
+        let value = self.merged()?;
+        let reads = Reads::default();
+        let extracted = self.deserialize_tracked(&[], &value, Some(&reads))?;
+
+        let mut consumed = Consumed::default();
+        consumed.collect(self, &value, &reads.into_inner(), &mut vec![]);
+        Ok((extracted, consumed))
+    }
+
+    /// Deserializes the collected value into `T` as if `profile` were
+    /// [selected](Figment::select()), without modifying or cloning `self`.
+    ///
+    /// This is useful for comparing values across profiles. The selected
+    /// profile(s) of `self` have no bearing on the extracted value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Config {
+    ///     workers: usize,
+    ///     debug: bool,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         [default]
+    ///         workers = 4
+    ///         debug = true
+    ///
+    ///         [release]
+    ///         debug = false
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml").nested()).select("debug");
+    ///     let debug: Config = figment.extract()?;
+    ///     let release: Config = figment.extract_from("release")?;
+    ///     assert_eq!(debug, Config { workers: 4, debug: true });
+    ///     assert_eq!(release, Config { workers: 4, debug: false });
+    ///     assert_eq!(figment.profile(), "debug");
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn extract_from<'a, T: Deserialize<'a>, P: Into<Profile>>(&self, profile: P) -> Result<T> {
+        self.deserialize(&[], &self.merged_for(&profile.into(), &[])?)
+    }
+
+    /// Deserializes the value at the `key` path in the collected value into `T`
+    /// as if `profile` were [selected](Figment::select()), without modifying or
+    /// cloning `self`. See [`Figment::extract_inner()`] and
+    /// [`Figment::extract_from()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         [default]
+    ///         log.level = "info"
+    ///
+    ///         [debug]
+    ///         log.level = "trace"
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml").nested());
+    ///     for profile in ["default", "debug", "release"] {
+    ///         let level: String = figment.extract_inner_from(profile, "log.level")?;
+    ///         let expected = if profile == "debug" { "trace" } else { "info" };
+    ///         assert_eq!(level, expected);
+    ///     }
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn extract_inner_from<'a, T, P>(&self, profile: P, key: &str) -> Result<T>
+        where T: Deserialize<'a>, P: Into<Profile>
+    {
+        let value = self.find_in(self.merged_for(&profile.into(), &[])?, key)?;
+        self.deserialize(&crate::util::split_key(key), &value)
+            .map_err(|e| e.in_key(key))
+    }
+
+    /// Deserializes each top-level entry of the collected value into a key of
+    /// type `K` and a value of type `V`, returning the entries in map order
+    /// along with the metadata of each entry's value. This is useful when the
+    /// top level of the configuration is an open-ended map, such as one from
+    /// plugin names to plugin configurations, where extracting into a map of
+    /// [`Value`]s would lose the provenance of each entry.
+    ///
+    /// Entries are those of the merged value for the selected profile(s),
+    /// exactly as for [`Figment::extract()`]: entries in profiles that aren't
+    /// selected are absent, and an entry present in several selected profiles
+    /// is merged as any other dictionary. In particular, with a
+    /// [nested](crate::providers::Data::nested()) provider, entries must be
+    /// nested in profile tables; a top-level table is a profile, not an entry.
+    ///
+    /// The [`path`](crate::Error::path) of any error begins with the entry's
+    /// key. If several entries fail to deserialize, the returned error
+    /// contains an error for each.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Plugin {
+    ///     enabled: bool,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Plugins.toml", r#"
+    ///         [default.auth]
+    ///         enabled = true
+    ///
+    ///         [default.cache]
+    ///         enabled = false
+    ///
+    ///         [release.cache]
+    ///         enabled = true
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("Plugins.toml").nested());
+    ///     let plugins = figment.extract_map::<String, Plugin>()?;
+    ///     assert_eq!(plugins.len(), 2);
+    ///     assert_eq!(plugins[0].key, "auth");
+    ///     assert_eq!(plugins[1].value, Plugin { enabled: false });
+    ///     assert!(plugins[1].metadata.unwrap().name.contains("TOML"));
+    ///
+    ///     let release = figment.select("release");
+    ///     let plugins = release.extract_map::<String, Plugin>()?;
+    ///     assert_eq!(plugins[1].value, Plugin { enabled: true });
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn extract_map<'a, K, V>(&self) -> Result<Vec<MapEntry<'_, K, V>>>
+        where K: Deserialize<'a>, V: Deserialize<'a>
+    {
+        let dict = self.merged()?.into_dict().expect("merged value is a dict");
+
+        let (mut entries, mut error) = (vec![], None::<Error>);
+        for (key, value) in dict {
+            let path = [key.clone()];
+            let key_value = Value::from(key.clone());
+            let entry = self.deserialize::<K>(&path, &key_value)
+                .and_then(|k| Ok((k, self.deserialize::<V>(&path, &value)?)))
+                .map_err(|e| e.prefixed(&key));
+
+            match entry {
+                Ok((key, v)) => entries.push(MapEntry {
+                    key,
+                    value: v,
+                    metadata: self.get_metadata(value.tag()),
+                }),
+                Err(e) => error = Some(match error {
+                    Some(prev) => prev.chain(e),
+                    None => e,
+                }),
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(entries),
+        }
+    }
+
+    /// Returns the collected value as environment variable assignments, the
+    /// inverse of [`Env::prefixed(prefix).split("__")`](crate::providers::Env),
+    /// for passing configuration to child processes.
+    ///
+    /// Each value that isn't a dictionary is emitted as a variable named
+    /// `prefix` followed by the uppercased components of its key path joined
+    /// by `__`. Arrays are rendered with [`to_literal()`], as are strings that
+    /// would otherwise not parse back as the same string. [Empty] values are
+    /// skipped. Keys that differ only in case thus collide. Variables are
+    /// emitted in map order.
+    ///
+    /// [`to_literal()`]: crate::value::escape::to_literal()
+    /// [Empty]: crate::value::Empty
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Env, Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         name = "app"
+    ///         version = "1.10"
+    ///         ports = [80, 443]
+    ///
+    ///         [database]
+    ///         max_connections = 10
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml"));
+    ///     let mut vars = figment.to_env("APP_")?;
+    ///     vars.sort();
+    ///     assert_eq!(vars, [
+    ///         ("APP_DATABASE__MAX_CONNECTIONS".into(), "10".into()),
+    ///         ("APP_NAME".into(), "app".into()),
+    ///         ("APP_PORTS".into(), "[80, 443]".into()),
+    ///         ("APP_VERSION".into(), r#""1.10""#.into()),
+    ///     ]);
+    ///
+    ///     // The variables round-trip through `Env`.
+    ///     for (name, value) in &vars {
+    ///         jail.set_env(name, value);
+    ///     }
+    ///
+    ///     let env = Figment::from(Env::prefixed("APP_").split("__"));
+    ///     assert_eq!(env.extract_inner::<String>("version")?, "1.10");
+    ///     assert_eq!(env.extract_inner::<u32>("database.max_connections")?, 10);
+    ///     assert_eq!(env.extract_inner::<Vec<u16>>("ports")?, [80, 443]);
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    #[cfg(feature = "env")]
+    #[cfg_attr(nightly, doc(cfg(feature = "env")))]
+    pub fn to_env(&self, prefix: &str) -> Result<Vec<(String, String)>> {
+        fn flatten(prefix: &str, path: &mut Vec<String>, value: &Value, vars: &mut Vec<(String, String)>) {
+            let raw = match value {
+                Value::Dict(_, dict) => {
+                    for (key, value) in dict {
+                        path.push(crate::util::escape_key(key).to_ascii_uppercase());
+                        flatten(prefix, path, value, vars);
+                        path.pop();
+                    }
 
-            Ok(new_map)
-        }
+                    return;
+                }
+                Value::Empty(..) => return,
+                Value::String(_, string) => match string.parse::<Value>() {
+                    Ok(Value::String(_, parsed)) if parsed == *string => string.clone(),
+                    _ => crate::value::escape::to_literal(value),
+                },
+                value => crate::value::escape::to_literal(value),
+            };
 
-        Figment {
-            profile: self.profile.clone(),
-            metadata: self.metadata.clone(),
-            value: try_focus(self, key)
+            vars.push((format!("{}{}", prefix, path.join("__")), raw));
         }
+
+        let mut vars = vec![];
+        flatten(prefix, &mut vec![], &self.merged()?, &mut vars);
+        Ok(vars)
     }
 
-    /// Deserializes the collected value into `T`.
+    /// Returns a [`Command`](std::process::Command) for launching `program`
+    /// with the collected value injected as environment variables, as by
+    /// [`Figment::to_env()`] with `prefix`, in addition to the variables it
+    /// inherits. To inject only part of the configuration, call this method
+    /// on a [focused](Figment::focus()) figment. The command is configured but
+    /// not spawned.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use serde::Deserialize;
-    ///
-    /// use figment::{Figment, providers::{Format, Toml, Json, Env}};
-    ///
-    /// #[derive(Debug, PartialEq, Deserialize)]
-    /// struct Config {
-    ///     name: String,
-    ///     numbers: Option<Vec<usize>>,
-    ///     debug: bool,
-    /// }
+    /// use std::ffi::OsStr;
+    /// use figment::{Figment, providers::{Format, Toml}};
     ///
     /// figment::Jail::expect_with(|jail| {
-    ///     jail.create_file("Config.toml", r#"
-    ///         name = "test"
-    ///         numbers = [1, 2, 3, 10]
+    ///     jail.create_file("App.toml", r#"
+    ///         [worker]
+    ///         threads = 4
+    ///         queue = "jobs"
     ///     "#)?;
     ///
-    ///     jail.set_env("config_name", "env-test");
+    ///     let figment = Figment::from(Toml::file("App.toml"));
+    ///     let mut command = figment.focus("worker").command("worker", "WORKER_")?;
+    ///     command.arg("--verbose");
     ///
-    ///     jail.create_file("Config.json", r#"
-    ///         {
-    ///             "name": "json-test",
-    ///             "debug": true
-    ///         }
+    ///     let mut envs: Vec<_> = command.get_envs().collect();
+    ///     envs.sort();
+    ///     assert_eq!(envs, [
+    ///         (OsStr::new("WORKER_QUEUE"), Some(OsStr::new("jobs"))),
+    ///         (OsStr::new("WORKER_THREADS"), Some(OsStr::new("4"))),
+    ///     ]);
+    ///
+    ///     // Launch it with `command.spawn()`.
+    ///     Ok(())
+    /// });
+    /// ```
+    #[cfg(feature = "env")]
+    #[cfg_attr(nightly, doc(cfg(feature = "env")))]
+    pub fn command<S>(&self, program: S, prefix: &str) -> Result<std::process::Command>
+        where S: AsRef<std::ffi::OsStr>
+    {
+        let mut command = std::process::Command::new(program);
+        command.envs(self.to_env(prefix)?);
+        Ok(command)
+    }
+
+    /// Writes `value` at the key path `key` in the selected profile back to an
+    /// [editable](crate::providers::Data::editable()) TOML file, preserving
+    /// the file's comments, ordering, and formatting. The file is the one the
+    /// current value at `key` was read from, if it is editable, or else the
+    /// editable file that was merged or joined last.
+    ///
+    /// The file is read again before it is changed, so changes made to it
+    /// since it was provided are kept. Existing dictionaries are merged with
+    /// dictionary values, and the value at `key` in a nested file is written
+    /// to the table of the selected profile. `self` is not changed: create a
+    /// new figment to see the persisted value.
+    ///
+    /// Returns an error if there is no editable file, if the file cannot be
+    /// read, parsed, or written, or if `value` cannot be represented in TOML.
+    /// The figment's [`Policy`](crate::Policy) is in effect: if it denies
+    /// access to the file, an error of kind
+    /// [`Kind::Denied`](crate::error::Kind::Denied) is returned and the file
+    /// is left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         ## Window settings.
+    ///         [window]
+    ///         width = 640   # pixels
+    ///         height = 480  # pixels
     ///     "#)?;
     ///
-    ///     let config: Config = Figment::new()
-    ///         .merge(Toml::file("Config.toml"))
-    ///         .merge(Env::prefixed("CONFIG_"))
-    ///         .join(Json::file("Config.json"))
-    ///         .extract()?;
+    ///     let figment = Figment::from(("window.title", "App"))
+    ///         .merge(Toml::file("App.toml").editable());
     ///
-    ///     assert_eq!(config, Config {
-    ///         name: "env-test".into(),
-    ///         numbers: vec![1, 2, 3, 10].into(),
-    ///         debug: true
-    ///     });
+    ///     figment.persist("window.width", 800)?;
+    ///     figment.persist("window.title", "My App")?;
     ///
+    ///     let contents = std::fs::read_to_string("App.toml").unwrap();
+    ///     assert!(contents.contains("# Window settings."));
+    ///     assert!(contents.contains("width = 800   # pixels"));
+    ///     assert!(contents.contains("title = \"My App\""));
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml"));
+    ///     assert_eq!(figment.extract_inner::<u32>("window.width")?, 800);
+    ///     assert_eq!(figment.extract_inner::<u32>("window.height")?, 480);
     ///     Ok(())
     /// });
     /// ```
-    pub fn extract<'a, T: Deserialize<'a>>(&self) -> Result<T> {
-        T::deserialize(ConfiguredValueDe::from(self, &self.merged()?))
+    #[cfg(feature = "toml-edit")]
+    #[cfg_attr(nightly, doc(cfg(feature = "toml-edit")))]
+    pub fn persist<T: serde::Serialize>(&self, key: &str, value: T) -> Result<()> {
+        let value = Value::serialize(value)?;
+        let editable = self.find_metadata(key)
+            .and_then(|md| md.extra()?.editable.as_ref())
+            .or_else(|| self.metadata.values().rev().find_map(|md| md.extra()?.editable.as_ref()))
+            .ok_or_else(|| Error::from(format!("no editable file to persist `{}` to", key)))?;
+
+        let _policy = self.policy.enter();
+        editable.persist(&self.profile, key, value)
     }
 
-    /// Deserializes the value at the `key` path in the collected value into
-    /// `T`.
+    /// Returns the [`Warning`]s emitted while collecting configuration data,
+    /// in the order they were emitted. Warnings are emitted by providers, via
+    /// [`Provider::warnings()`], and when merging or joining their data, for
+    /// instance for keys [shadowed](WarningKind::Shadowed) by another spelling
+    /// of the same key. A warning never prevents extraction. See
+    /// [`warning`](crate::warning) for details.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use figment::{Figment, providers::{Format, Toml, Json}};
+    /// use figment::{Figment, providers::{Format, Toml}, warning::Kind};
     ///
     /// figment::Jail::expect_with(|jail| {
-    ///     jail.create_file("Config.toml", r#"
-    ///         numbers = [1, 2, 3, 10]
+    ///     jail.create_file("App.toml", r#"
+    ///         Log_Level = "info"
+    ///         log_level = "debug"
     ///     "#)?;
     ///
-    ///     jail.create_file("Config.json", r#"{ "debug": true } "#)?;
-    ///
-    ///     let numbers: Vec<usize> = Figment::new()
-    ///         .merge(Toml::file("Config.toml"))
-    ///         .join(Json::file("Config.json"))
-    ///         .extract_inner("numbers")?;
+    ///     let figment = Figment::new()
+    ///         .case_insensitive(true)
+    ///         .merge(Toml::file("App.toml"));
     ///
-    ///     assert_eq!(numbers, vec![1, 2, 3, 10]);
+    ///     let warning = figment.warnings().iter().next().unwrap();
+    ///     assert_eq!(warning.kind, Kind::Shadowed);
+    ///     assert_eq!(warning.key.as_deref(), Some("Log_Level"));
+    ///     assert!(warning.metadata.as_ref().unwrap().name.contains("TOML"));
+    ///     assert_eq!(figment.extract_inner::<String>("log_level")?, "debug");
     ///
     ///     Ok(())
     /// });
     /// ```
-    pub fn extract_inner<'a, T: Deserialize<'a>>(&self, key: &str) -> Result<T> {
-        T::deserialize(ConfiguredValueDe::from(self, &self.find_value(key)?))
+    pub fn warnings(&self) -> &Warnings {
+        &self.warnings
     }
 
     /// Returns an iterator over the metadata for all of the collected values in
@@ -444,6 +3555,123 @@ impl Figment {
             .flatten()
     }
 
+    /// Returns `true` if `profile` has a valid configuration in this figment,
+    /// that is, if `profile` is one of [`Figment::profiles()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// let figment = Figment::new()
+    ///     .join(Serialized::default("key", "hi"))
+    ///     .join(Serialized::default("key", "hey").profile("debug"));
+    ///
+    /// assert!(figment.has_profile("debug"));
+    /// assert!(figment.has_profile("DEBUG"));
+    /// assert!(figment.has_profile("default"));
+    /// assert!(!figment.has_profile("release"));
+    /// ```
+    pub fn has_profile<P: Into<Profile>>(&self, profile: P) -> bool {
+        let profile = profile.into();
+        self.profiles().any(|p| *p == profile)
+    }
+
+    /// Returns a summary of the configuration for `profile`, or `None` if
+    /// `profile` has no valid configuration in this figment. The summary
+    /// includes the number of leaf values in the profile and the metadata of
+    /// every provider that contributed to it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::{Figment, providers::{Format, Toml, Serialized}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", r#"
+    ///         [debug]
+    ///         port = 8000
+    ///         tls = { enabled = false }
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("Config.toml").nested())
+    ///         .merge(Serialized::default("workers", 4).profile("debug"));
+    ///
+    ///     let info = figment.profile_info("debug").unwrap();
+    ///     assert_eq!(info.profile, "debug");
+    ///     assert_eq!(info.keys, 3);
+    ///     assert_eq!(info.providers.len(), 2);
+    ///     assert_eq!(info.providers[0].name, "TOML file");
+    ///
+    ///     assert!(figment.profile_info("release").is_none());
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn profile_info<P: Into<Profile>>(&self, profile: P) -> Option<ProfileInfo<'_>> {
+        fn walk(value: &Value, keys: &mut usize, tags: &mut Vec<Tag>) {
+            if !value.tag().is_default() && !tags.contains(&value.tag()) {
+                tags.push(value.tag());
+            }
+
+            match value {
+                Value::Dict(_, dict) => dict.values().for_each(|v| walk(v, keys, tags)),
+                _ => *keys += 1,
+            }
+        }
+
+        let profile = profile.into();
+        let (profile, dict) = self.value.as_ref().ok()?.get_key_value(&profile)?;
+        let (mut keys, mut tags) = (0, vec![]);
+        dict.values().for_each(|v| walk(v, &mut keys, &mut tags));
+
+        tags.sort();
+        let providers = tags.into_iter()
+            .filter_map(|tag| self.get_metadata(tag))
+            .collect();
+
+        Some(ProfileInfo { profile, keys, providers })
+    }
+
+    /// Returns documentation of every key in the selected profile(s): each
+    /// leaf value and each key path with a [description](Figment::describe()),
+    /// with its type, its current value, and where the value was provided.
+    /// Defaults can be added with [`Docs::defaults()`]. The documentation can
+    /// be rendered as Markdown or HTML, as for generating operations runbooks.
+    /// See [`Docs`] for details.
+    ///
+    /// Unlike extraction, documenting a figment doesn't fail if
+    /// [required](Figment::require()) keys are missing; it fails only if the
+    /// figment failed to merge.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Env, Serialized}, util::map};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_PORT", 8080);
+    ///
+    ///     let figment = Figment::from(Serialized::default("port", 80))
+    ///         .merge(Env::prefixed("APP_"))
+    ///         .describe("port", "The port to listen on.");
+    ///
+    ///     let docs = figment.docs()?.defaults(map!["port" => 80])?;
+    ///     let port = docs.get("port").unwrap();
+    ///     assert_eq!(port.kind, Some("integer"));
+    ///     assert_eq!(port.default, Some(80.into()));
+    ///     assert_eq!(port.value, Some(8080u16.into()));
+    ///     assert_eq!(port.source.as_deref(), Some("`APP_PORT` in `APP_` environment variable(s)"));
+    ///     assert_eq!(port.description, Some("The port to listen on."));
+    ///
+    ///     let markdown = docs.to_markdown();
+    ///     assert!(markdown.contains("| `port` | integer | `80` | `8080` |"));
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn docs(&self) -> Result<Docs<'_>> {
+        Docs::new(self)
+    }
+
     /// Finds the value at `key` path in the combined value. See
     /// [`Value::find()`] for details on the syntax for `key`.
     ///
@@ -485,9 +3713,71 @@ impl Figment {
     /// });
     /// ```
     pub fn find_value(&self, key: &str) -> Result<Value> {
-        self.merged()?
-            .find(key)
-            .ok_or_else(|| Kind::MissingField(key.to_string().into()).into())
+        self.find_in(self.merged()?, key)
+    }
+
+    /// Returns the key path and value of every value in the collected value at
+    /// a key path matching `pattern`, in which a component of exactly `*`
+    /// matches any key of a dictionary and any index of an array. See
+    /// [`Value::find_all()`] for details. This is useful for validating values
+    /// across the configuration without deserializing all of it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Services.toml", r#"
+    ///         [services.api]
+    ///         port = 8000
+    ///         url = "https://api.example.com"
+    ///
+    ///         [services.admin]
+    ///         port = 8000
+    ///         url = "http://admin.example.com"
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("Services.toml"));
+    ///     let ports = figment.find_all("services.*.port")?;
+    ///     assert_eq!(ports.len(), 2);
+    ///     assert_eq!(ports[0].1, ports[1].1);
+    ///
+    ///     let insecure: Vec<_> = figment.find_all("services.*.url")?
+    ///         .into_iter()
+    ///         .filter(|(_, url)| !url.as_str().unwrap().starts_with("https://"))
+    ///         .map(|(path, _)| path)
+    ///         .collect();
+    ///
+    ///     assert_eq!(insecure, ["services.admin.url"]);
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn find_all(&self, pattern: &str) -> Result<Vec<(String, Value)>> {
+        let canonical;
+        let pattern = match self.key_case {
+            Some(case) => {
+                canonical = crate::util::split_key(pattern).iter()
+                    .map(|k| match k.as_str() {
+                        "*" => k.clone(),
+                        k => crate::util::escape_key(&case.apply(k)).into_owned(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(".");
+
+                &canonical
+            }
+            None => pattern,
+        };
+
+        let value = self.merged()?;
+        let found = value.find_all_with(pattern, self.case_insensitive)
+            .into_iter()
+            .map(|(path, value)| (path, value.clone()))
+            .collect();
+
+        Ok(found)
     }
 
     /// Finds the metadata for the value at `key` path. See [`Value::find()`]
@@ -556,29 +3846,371 @@ impl Figment {
     }
 }
 
+/// A `Figment` is a provider of its collected values. Each value retains the
+/// metadata of the provider it originated from, so a figment built by one
+/// component, such as a library, can be merged into another without losing
+/// provenance: values, errors, and [`Figment::find_metadata()`] refer to the
+/// original sources.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, providers::{Format, Toml, Serialized}};
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("Lib.toml", r#"port = "eighty""#)?;
+///
+///     let lib = Figment::from(Toml::file("Lib.toml"));
+///     let app = Figment::from(Serialized::default("name", "app")).merge(lib);
+///
+///     let mut names: Vec<_> = app.metadata().map(|md| &*md.name).collect();
+///     names.sort();
+///     assert_eq!(names, ["&str", "TOML file"]);
+///     assert!(app.find_metadata("port").unwrap().name.contains("TOML"));
+///
+///     let error = app.extract_inner::<u16>("port").unwrap_err();
+///     assert!(error.to_string().contains("Lib.toml"));
+///     Ok(())
+/// });
+/// ```
 impl Provider for Figment {
     fn metadata(&self) -> Metadata { Metadata::default() }
 
-    fn data(&self) -> Result<Map<Profile, Dict>> { self.value.clone() }
+    fn data(&self) -> Result<Map<Profile, Dict>> {
+        self.value.clone()
+    }
 
     fn profile(&self) -> Option<Profile> {
         Some(self.profile.clone())
     }
 
+    fn warnings(&self) -> Vec<Warning> {
+        self.warnings.0.clone()
+    }
+
     fn __metadata_map(&self) -> Option<Map<Tag, Metadata>> {
         Some(self.metadata.clone())
     }
 }
 
+/// A summary of the configuration for a profile in a [`Figment`], as returned
+/// by [`Figment::profile_info()`].
+#[derive(Debug, Clone)]
+pub struct ProfileInfo<'a> {
+    /// The profile being summarized.
+    pub profile: &'a Profile,
+    /// The number of leaf values, that is, values which are not dictionaries,
+    /// in the profile. An array counts as a single leaf value.
+    pub keys: usize,
+    /// The metadata of every provider that contributed a value to the
+    /// profile, in the order in which the providers were added.
+    pub providers: Vec<&'a Metadata>,
+}
+
+/// A top-level entry of a [`Figment`], as returned by
+/// [`Figment::extract_map()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapEntry<'a, K, V> {
+    /// The deserialized key.
+    pub key: K,
+    /// The deserialized value.
+    pub value: V,
+    /// The metadata of the provider of the entry's value, if known.
+    pub metadata: Option<&'a Metadata>,
+}
+
+/// Values deserialized by [`Figment::extract_cached()`], keyed by type. A clone
+/// starts out empty, as the cloned figment may later diverge from `self`.
+#[derive(Default)]
+pub(crate) struct Extracted {
+    #[cfg(feature = "std")]
+    values: std::sync::Mutex<Map<core::any::TypeId, std::sync::Arc<dyn core::any::Any + Send + Sync>>>,
+}
+
+impl Extracted {
+    /// Forgets every cached value.
+    fn clear(&mut self) {
+        #[cfg(feature = "std")]
+        self.values.get_mut().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Returns the cached `T`, if any.
+    #[cfg(feature = "std")]
+    fn get<T: Send + Sync + 'static>(&self) -> Option<std::sync::Arc<T>> {
+        let value = self.values.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&core::any::TypeId::of::<T>())
+            .cloned()?;
+
+        Some(value.downcast::<T>().expect("cache is keyed by `TypeId`"))
+    }
+
+    /// Caches `value` unless a `T` was concurrently cached, returning the
+    /// cached `T`.
+    #[cfg(feature = "std")]
+    fn insert<T: Send + Sync + 'static>(&self, value: T) -> std::sync::Arc<T> {
+        let value = self.values.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(core::any::TypeId::of::<T>())
+            .or_insert_with(|| std::sync::Arc::new(value))
+            .clone();
+
+        value.downcast::<T>().expect("cache is keyed by `TypeId`")
+    }
+}
+
+impl Clone for Extracted {
+    fn clone(&self) -> Self {
+        Extracted::default()
+    }
+}
+
+impl core::fmt::Debug for Extracted {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Extracted").finish_non_exhaustive()
+    }
+}
+
+/// The key paths of the values read during an extraction, as returned by
+/// [`Figment::extract_consumed()`].
+///
+/// A key path is recorded for every value that isn't a dictionary and was read.
+/// Arrays are recorded as a whole. A dictionary is recorded only if it was read
+/// without any of its values being read individually, as when it is
+/// deserialized into a [`Value`]. Keys that are absent, such as those of
+/// `Option` fields which were thus `None`, are not recorded. Every key path
+/// present in the figment that was neither recorded nor nested in a recorded
+/// value is [unused](Consumed::unused()).
+#[derive(Debug, Clone, Default)]
+pub struct Consumed {
+    values: BTreeMap<String, (Value, Option<Metadata>)>,
+    unused: Vec<String>,
+}
+
+impl Consumed {
+    /// Returns an iterator over the key paths of the values that were read, in
+    /// sorted order, not in the order they were read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let figment = Figment::from(("b.c", 2)).merge(("a", 1));
+    /// let (_, consumed) = figment.extract_consumed::<figment::value::Dict>().unwrap();
+    ///
+    /// // Each value of the `Dict`, including `b`, is read as a whole.
+    /// assert_eq!(consumed.keys().collect::<Vec<_>>(), ["a", "b"]);
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(|k| k.as_str())
+    }
+
+    /// Returns the value that was read at the key path `key`, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::{Dict, Value}};
+    ///
+    /// let figment = Figment::from(("port", 80));
+    /// let (_, consumed) = figment.extract_consumed::<Dict>().unwrap();
+    /// assert_eq!(consumed.get("port"), Some(&Value::from(80)));
+    /// assert!(consumed.get("host").is_none());
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key).map(|(value, _)| value)
+    }
+
+    /// Returns the metadata of the provider of the value that was read at the
+    /// key path `key`, if any.
+    pub fn metadata(&self, key: &str) -> Option<&Metadata> {
+        self.values.get(key).and_then(|(_, md)| md.as_ref())
+    }
+
+    /// Returns an iterator over the key paths present in the figment that were
+    /// not read. Useful for warning about unknown or misspelled keys.
+    pub fn unused(&self) -> impl Iterator<Item = &str> {
+        self.unused.iter().map(|k| k.as_str())
+    }
+
+    /// Returns `true` if the value at any consumed key path in `figment`
+    /// differs from the value that was read, or is absent, or if `figment`
+    /// fails to resolve. Re-extracting from `figment` would then yield a
+    /// different result; otherwise, it would yield the same.
+    ///
+    /// Because keys that were absent during the extraction aren't recorded,
+    /// a key that is added later, for example to set an `Option` field that
+    /// was `None`, is not considered a change.
+    pub fn changed(&self, figment: &Figment) -> bool {
+        let value = match figment.merged() {
+            Ok(value) => value,
+            Err(_) => return true,
+        };
+
+        self.values.iter().any(|(key, (read, _))| match value.find_ref(key) {
+            Some(current) => current != read,
+            None => true,
+        })
+    }
+
+    /// Records the values in `value`, at the key path `path`, whose addresses
+    /// are in `reads`. Returns `true` if any value was recorded.
+    fn collect(
+        &mut self,
+        figment: &Figment,
+        value: &Value,
+        reads: &BTreeSet<*const Value>,
+        path: &mut Vec<String>,
+    ) -> bool {
+        fn key(path: &[String]) -> String {
+            let keys: Vec<_> = path.iter().map(|k| crate::util::escape_key(k)).collect();
+            keys.join(".")
+        }
+
+        let read = reads.contains(&(value as *const Value));
+        if let Value::Dict(_, dict) = value {
+            let mut any = false;
+            for (k, v) in dict {
+                path.push(k.clone());
+                any |= self.collect(figment, v, reads, path);
+                path.pop();
+            }
+
+            // An empty dictionary has no values to be unused but itself.
+            if !read && dict.is_empty() && !path.is_empty() {
+                self.unused.push(key(path));
+            }
+
+            if !read || any || path.is_empty() {
+                return any;
+            }
+        } else if !read {
+            self.unused.push(key(path));
+            return false;
+        }
+
+        let metadata = figment.get_metadata(value.tag()).cloned();
+        self.values.insert(key(path), (value.clone(), metadata));
+        true
+    }
+}
+
+/// A provider that emits all of `provider`'s data to `profile`.
+struct Scoped<T> {
+    profile: Profile,
+    provider: T,
+}
+
+impl<T: Provider> Provider for Scoped<T> {
+    fn metadata(&self) -> Metadata { self.provider.metadata() }
+
+    fn data(&self) -> Result<Map<Profile, Dict>> {
+        let mut map = self.provider.data()?;
+        let def = map.remove(&Profile::Default).unwrap_or_default();
+        let global = map.remove(&Profile::Global).unwrap_or_default();
+        let dict = map.into_iter()
+            .fold(def, |dict, (_, custom)| dict.merge(custom))
+            .merge(global);
+
+        Ok(self.profile.collect(dict))
+    }
+
+    fn warnings(&self) -> Vec<Warning> {
+        self.provider.warnings()
+    }
+
+    fn tags(&self) -> Map<Tag, Metadata> {
+        self.provider.tags()
+    }
+
+    fn snapshot(&self) -> crate::providers::Snapshot {
+        let provider = self.provider.snapshot();
+        crate::providers::Snapshot::resolve(&Scoped { profile: self.profile.clone(), provider })
+    }
+
+    fn __metadata_map(&self) -> Option<Map<Tag, Metadata>> {
+        self.provider.__metadata_map()
+    }
+}
+
+/// A provider that emits the values of `provider`, a figment, nested under
+/// `namespace`. See [`Figment::adjoin_library()`].
+struct Library<P> {
+    namespace: Vec<String>,
+    provider: P,
+}
+
+impl<P> Library<P> {
+    /// Returns the key path `key` nested under the namespace.
+    fn key(&self, key: &str) -> String {
+        let namespace: Vec<_> = self.namespace.iter().map(|k| crate::util::escape_key(k)).collect();
+        format!("{}.{}", namespace.join("."), key)
+    }
+
+    fn nest(&self, dict: Dict) -> Dict {
+        self.namespace.iter().rev().fold(dict, |dict, key| {
+            let mut parent = Dict::new();
+            parent.insert(key.clone(), Value::from(dict));
+            parent
+        })
+    }
+}
+
+impl<P: Provider> Provider for Library<P> {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(format!("library `{}`", self.namespace.join(".")))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>> {
+        let map = self.provider.data()?;
+        Ok(map.into_iter()
+            .filter(|(_, dict)| !dict.is_empty())
+            .map(|(profile, dict)| (profile, self.nest(dict)))
+            .collect())
+    }
+
+    fn warnings(&self) -> Vec<Warning> {
+        self.provider.warnings()
+            .into_iter()
+            .map(|mut warning| {
+                warning.key = warning.key.map(|key| self.key(&key));
+                warning
+            })
+            .collect()
+    }
+
+    fn snapshot(&self) -> crate::providers::Snapshot {
+        let provider = self.provider.snapshot();
+        crate::providers::Snapshot::resolve(&Library { namespace: self.namespace.clone(), provider })
+    }
+
+    fn __metadata_map(&self) -> Option<Map<Tag, Metadata>> {
+        Some(self.provider.__metadata_map()?.into_iter()
+            .map(|(tag, md)| (tag, md.namespaced(self.namespace.clone())))
+            .collect())
+    }
+}
+
 impl Default for Figment {
     fn default() -> Self {
         Figment::new()
     }
 }
 
+/// Returns the value at the key path `path` in `dict`, if any.
+fn find_mut<'v>(dict: &'v mut Dict, path: &[String]) -> Option<&'v mut Value> {
+    let (first, rest) = path.split_first()?;
+    rest.iter().try_fold(dict.get_mut(first)?, |value, key| match value {
+        Value::Dict(_, dict) => dict.get_mut(key),
+        _ => None,
+    })
+}
+
 #[test]
 #[cfg(test)]
 fn is_send_sync() {
     fn check_for_send_sync<T: Send + Sync>() {}
     check_for_send_sync::<Figment>();
 }
+
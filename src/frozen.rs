@@ -0,0 +1,267 @@
+use alloc::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+use crate::{Figment, Metadata};
+use crate::error::{Kind, Result};
+use crate::value::{Value, Dict};
+use crate::coalesce::{Coalescible, Order};
+
+/// An immutable [`Figment`] with its selected profile(s) merged once, on top
+/// of which small overrides can be cheaply layered. Returned by
+/// [`Figment::freeze()`].
+///
+/// Merging providers and profiles is done once, when the figment is frozen.
+/// Cloning a `Frozen` or layering overrides onto it with
+/// [`Frozen::overlay()`] copies only the overrides, not the frozen value, and
+/// extracting from it copies only the part of the frozen value that is
+/// extracted. This makes `Frozen` suitable for hot paths, such as a web
+/// framework that overrides a few keys per request or tenant.
+///
+/// Overrides are merged, as by [`Figment::merge()`], into the frozen value and
+/// respect the figment's [key configuration](Figment#key-configuration) and
+/// [keyed array merging](Figment::merge_arrays_by()). They are not seen by
+/// [hooks](Figment#hooks), which run only when the figment is frozen, and they
+/// have no [`Metadata`].
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, providers::{Format, Toml}};
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("App.toml", r#"
+///         [limits]
+///         requests = 100
+///         timeout = 30
+///     "#)?;
+///
+///     let base = Figment::from(Toml::file("App.toml")).freeze()?;
+///
+///     // Per request or tenant:
+///     let tenant = base.overlay([("limits.requests", 10)])?;
+///     assert_eq!(tenant.extract_inner::<u32>("limits.requests")?, 10);
+///     assert_eq!(tenant.extract_inner::<u32>("limits.timeout")?, 30);
+///
+///     // The base is unchanged.
+///     assert_eq!(base.extract_inner::<u32>("limits.requests")?, 100);
+///
+///     Ok(())
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Frozen {
+    figment: Arc<Figment>,
+    base: Arc<Dict>,
+    overrides: Dict,
+}
+
+impl Frozen {
+    pub(crate) fn new(figment: Figment) -> Result<Frozen> {
+        let base = figment.merged()?.into_dict().expect("merged value is a dict");
+        Ok(Frozen { figment: Arc::new(figment), base: Arc::new(base), overrides: Dict::new() })
+    }
+
+    /// Returns a `Frozen` with the value at each key path in `overrides` set
+    /// to the corresponding value, layered on top of the overrides in `self`.
+    /// Returns an error if a value fails to serialize.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let base = Figment::from(("name", "app")).merge(("port", 8000)).freeze().unwrap();
+    /// let a = base.overlay([("port", 80)]).unwrap();
+    /// let b = a.overlay([("name", "b")]).unwrap();
+    ///
+    /// assert_eq!(a.extract_inner::<u16>("port").unwrap(), 80);
+    /// assert_eq!(a.extract_inner::<String>("name").unwrap(), "app");
+    /// assert_eq!(b.extract_inner::<u16>("port").unwrap(), 80);
+    /// assert_eq!(b.extract_inner::<String>("name").unwrap(), "b");
+    /// ```
+    pub fn overlay<I, K, V>(&self, overrides: I) -> Result<Frozen>
+        where I: IntoIterator<Item = (K, V)>, K: AsRef<str>, V: Serialize
+    {
+        let mut dict = self.overrides.clone();
+        for (key, value) in overrides {
+            let value = crate::util::nest(key.as_ref(), Value::serialize(value)?);
+            let nested = match value.into_dict() {
+                Some(dict) => dict,
+                None => return Err(Kind::Message("override key is empty".into()).into()),
+            };
+
+            let nested = match self.figment.key_case {
+                Some(case) => crate::coalesce::canonicalize_keys(nested, case),
+                None => nested,
+            };
+
+            let nested = match self.figment.case_insensitive {
+                true => crate::coalesce::unify_keys(Some(&self.base), nested),
+                false => nested,
+            };
+
+            dict = self.coalesce(dict.into(), nested.into())
+                .into_dict()
+                .expect("coalesced dicts are a dict");
+        }
+
+        Ok(Frozen { figment: self.figment.clone(), base: self.base.clone(), overrides: dict })
+    }
+
+    /// Returns the figment that was frozen.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let frozen = Figment::new().select("debug").freeze().unwrap();
+    /// assert_eq!(frozen.figment().profile(), "debug");
+    /// ```
+    pub fn figment(&self) -> &Figment {
+        &self.figment
+    }
+
+    /// Deserializes the frozen value, with overrides, into `T`. See
+    /// [`Figment::extract()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::Figment;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     name: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let frozen = Figment::from(("name", "app")).merge(("port", 8000)).freeze().unwrap();
+    /// let config: Config = frozen.overlay([("port", 80)]).unwrap().extract().unwrap();
+    /// assert_eq!(config.name, "app");
+    /// assert_eq!(config.port, 80);
+    /// ```
+    pub fn extract<'a, T: Deserialize<'a>>(&self) -> Result<T> {
+        self.figment.deserialize(&[], &self.lookup(&[])?)
+    }
+
+    /// Deserializes the value at the `key` path in the frozen value, with
+    /// overrides, into `T`. See [`Figment::extract_inner()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let frozen = Figment::from(("app.port", 8000)).freeze().unwrap();
+    /// assert_eq!(frozen.extract_inner::<u16>("app.port").unwrap(), 8000);
+    /// assert!(frozen.extract_inner::<u16>("app.name").is_err());
+    /// ```
+    pub fn extract_inner<'a, T: Deserialize<'a>>(&self, key: &str) -> Result<T> {
+        let keys = crate::util::split_key(key);
+        self.figment.deserialize(&keys, &self.lookup(&keys)?)
+            .map_err(|e| e.in_key(key))
+    }
+
+    /// Returns the value at the `key` path in the frozen value, with
+    /// overrides. See [`Figment::find_value()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let frozen = Figment::from(("app.port", 8000)).freeze().unwrap();
+    /// let frozen = frozen.overlay([("app.name", "app")]).unwrap();
+    ///
+    /// let app = frozen.find_value("app").unwrap();
+    /// assert_eq!(app.find_ref("name").unwrap().as_str(), Some("app"));
+    /// assert!(app.find_ref("port").is_some());
+    /// ```
+    pub fn find_value(&self, key: &str) -> Result<Value> {
+        self.lookup(&crate::util::split_key(key))
+    }
+
+    /// Returns the metadata for the value at the `key` path, if it is known.
+    /// Overridden values have no metadata. See [`Figment::find_metadata()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    ///
+    /// let frozen = Figment::from(("port", 8000)).freeze().unwrap();
+    /// assert!(frozen.find_metadata("port").is_some());
+    ///
+    /// let frozen = frozen.overlay([("port", 80)]).unwrap();
+    /// assert!(frozen.find_metadata("port").is_none());
+    /// ```
+    pub fn find_metadata(&self, key: &str) -> Option<&Metadata> {
+        self.figment.get_metadata(self.lookup(&crate::util::split_key(key)).ok()?.tag())
+    }
+
+    /// Finds the value at the key path `keys`, copying only that value and
+    /// merging into it any overrides.
+    fn lookup(&self, keys: &[String]) -> Result<Value> {
+        let keys: Vec<String> = keys.iter()
+            .filter(|k| !k.is_empty())
+            .map(|k| match self.figment.key_case {
+                Some(case) => case.apply(k),
+                None => k.clone(),
+            })
+            .collect();
+
+        let uncased = self.figment.case_insensitive;
+        let value = match (find(&self.base, &keys, uncased), find(&self.overrides, &keys, uncased)) {
+            (Some(base), Some(overrides)) => self.coalesce(base, overrides),
+            (None, Some(v)) | (Some(v), None) => v,
+            (None, None) => {
+                let key = keys.iter()
+                    .map(|k| crate::util::escape_key(k).into_owned())
+                    .collect::<Vec<_>>()
+                    .join(".");
+
+                return Err(Kind::MissingField(key.into()).into());
+            }
+        };
+
+        match value {
+            Value::Dict(tag, dict) => Ok(Value::Dict(tag, crate::coalesce::remove_unset(dict))),
+            value => Ok(value),
+        }
+    }
+
+    /// Merges `b` into `a`, respecting keyed array merging.
+    fn coalesce(&self, a: Value, b: Value) -> Value {
+        match (a, b, &self.figment.array_key) {
+            (Value::Dict(tag, a), Value::Dict(_, b), Some(key)) => {
+                Value::Dict(tag, crate::coalesce::coalesce_keyed(a, b, Order::Merge, key))
+            }
+            (a @ Value::Array(..), b @ Value::Array(..), Some(key)) => {
+                let wrap = |value| {
+                    let mut dict = Dict::new();
+                    dict.insert(String::new(), value);
+                    dict
+                };
+
+                crate::coalesce::coalesce_keyed(wrap(a), wrap(b), Order::Merge, key)
+                    .remove("")
+                    .expect("coalesced dicts contain the wrapped key")
+            }
+            (a, b, _) => a.merge(b),
+        }
+    }
+}
+
+/// Returns a copy of the value at the key path `keys` in `dict`, or of all of
+/// `dict` if `keys` is empty, as found by [`Figment::find_value()`]. Keys are
+/// matched ignoring case if `uncased`.
+fn find(dict: &Dict, keys: &[String], uncased: bool) -> Option<Value> {
+    match keys.is_empty() {
+        true => Some(dict.clone().into()),
+        false => crate::value::find_keys_in(dict, keys, uncased).cloned(),
+    }
+}
@@ -1,8 +1,12 @@
 use std::fs::File;
-use std::io::{Write, BufWriter};
+use std::io::{self, Read, Write, BufWriter};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::thread::JoinHandle;
+use std::time::Duration;
 use std::path::{Path, PathBuf};
 use std::fmt::Display;
-use std::ffi::{OsStr, OsString};
+use std::ffi::OsString;
 use std::collections::HashMap;
 
 use tempfile::TempDir;
@@ -17,12 +21,17 @@ use crate::error::Result;
 /// `Jail` creates a pseudo-sandboxed (not _actually_ sandboxed) environment for
 /// testing configurations. Specifically, `Jail`:
 ///
-///   * Synchronizes all calls to [`Jail::expect_with()`] and
-///     [`Jail::try_with()`] to prevent environment variables races.
+///   * Synchronizes all jails, including those that don't switch
+///     directories, so that only one runs at a time, to prevent environment
+///     variable races.
 ///   * Switches into a fresh temporary directory ([`Jail::directory()`]) where
-///     files can be created with [`Jail::create_file()`].
-///   * Keeps track of environment variables created with [`Jail::set_env()`]
-///     and clears them when the `Jail` exits.
+///     files can be created with [`Jail::create_file()`]. Jails created with
+///     [`Jail::expect_with_no_chdir()`] create the directory without switching
+///     into it.
+///   * Snapshots the process environment on entry and restores it exactly
+///     when the `Jail` exits, undoing any variables set with
+///     [`Jail::set_env()`], removed with [`Jail::clear_env()`], or otherwise
+///     modified while in the jail.
 ///   * Deletes the temporary directory and all of its contents when exiting.
 ///
 /// Additionally, because `Jail` expects functions that return a [`Result`],
@@ -58,8 +67,9 @@ use crate::error::Result;
 pub struct Jail {
     _directory: TempDir,
     canonical_dir: PathBuf,
-    saved_env_vars: HashMap<OsString, Option<OsString>>,
-    saved_cwd: PathBuf,
+    saved_env: HashMap<OsString, OsString>,
+    saved_cwd: Option<PathBuf>,
+    servers: Vec<Server>,
 }
 
 fn as_string<S: Display>(s: S) -> String { s.to_string() }
@@ -67,7 +77,8 @@ fn as_string<S: Display>(s: S) -> String { s.to_string() }
 static LOCK: Mutex<()> = parking_lot::const_mutex(());
 
 impl Jail {
-    /// Creates a new jail that calls `f`, passing itself to `f`.
+    /// Creates a new jail that calls `f`, passing itself to `f`. Returns the
+    /// value returned by `f`, if any.
     ///
     /// # Panics
     ///
@@ -83,15 +94,29 @@ impl Jail {
     ///     Ok(())
     /// });
     /// ```
+    ///
+    /// Values can be extracted in the jail and inspected outside of it:
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// let port: u16 = figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", "port = 8080")?;
+    ///     Figment::from(Toml::file("Config.toml")).extract_inner("port")
+    /// });
+    ///
+    /// assert_eq!(port, 8080);
+    /// ```
     #[track_caller]
-    pub fn expect_with<F: FnOnce(&mut Jail) -> Result<()>>(f: F) {
-        if let Err(e) = Jail::try_with(f) {
-            panic!("jail failed: {}", e)
+    pub fn expect_with<T, F: FnOnce(&mut Jail) -> Result<T>>(f: F) -> T {
+        match Jail::try_with(f) {
+            Ok(value) => value,
+            Err(e) => panic!("jail failed: {}", e)
         }
     }
 
     /// Creates a new jail that calls `f`, passing itself to `f`. Returns the
-    /// result from `f` if `f` does not panic.
+    /// result from `f`, including any value it returns, if `f` does not panic.
     ///
     /// # Panics
     ///
@@ -107,18 +132,144 @@ impl Jail {
     /// });
     /// ```
     #[track_caller]
-    pub fn try_with<F: FnOnce(&mut Jail) -> Result<()>>(f: F) -> Result<()> {
+    pub fn try_with<T, F: FnOnce(&mut Jail) -> Result<T>>(f: F) -> Result<T> {
+        let _lock = LOCK.lock();
+        let mut jail = Jail::enter(TempDir::new().map_err(as_string)?, true)?;
+        f(&mut jail)
+    }
+
+    /// Like [`Jail::expect_with()`], but the jail does not switch into its
+    /// directory: the process's current working directory is left untouched.
+    ///
+    /// Because the current working directory is process-global, changing it
+    /// can interfere with tests running in parallel that don't use a jail.
+    /// Tests that only need an isolated directory and environment can use this
+    /// method instead, creating files with methods like
+    /// [`Jail::create_file()`], which are always relative to
+    /// [`Jail::directory()`], and locating them with providers that accept an
+    /// explicit base directory such as
+    /// [`Data::file_in()`](crate::providers::Data::file_in()).
+    ///
+    /// This does not let jails run in parallel with one another. Like every
+    /// jail, a jail that doesn't switch directories snapshots and restores the
+    /// process-global environment, so it waits for any other jail to exit
+    /// before it is entered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` panics or if
+    /// [`Jail::try_with_no_chdir(f)`](Jail::try_with_no_chdir) returns an
+    /// `Err`; prints the error message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let cwd = std::env::current_dir().unwrap();
+    ///
+    /// figment::Jail::expect_with_no_chdir(|jail| {
+    ///     let path = jail.create_binary_file("Config.toml", b"key = 1")?;
+    ///     assert!(path.starts_with(jail.directory()));
+    ///     assert_eq!(std::env::current_dir().unwrap(), cwd);
+    ///     Ok(())
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn expect_with_no_chdir<T, F: FnOnce(&mut Jail) -> Result<T>>(f: F) -> T {
+        match Jail::try_with_no_chdir(f) {
+            Ok(value) => value,
+            Err(e) => panic!("jail failed: {}", e)
+        }
+    }
+
+    /// Like [`Jail::try_with()`], but the jail does not switch into its
+    /// directory. See [`Jail::expect_with_no_chdir()`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` panics.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let result = figment::Jail::try_with_no_chdir(|jail| {
+    ///     /* in the jail, but not in its directory */
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn try_with_no_chdir<T, F: FnOnce(&mut Jail) -> Result<T>>(f: F) -> Result<T> {
         let _lock = LOCK.lock();
-        let directory = TempDir::new().map_err(as_string)?;
-        let mut jail = Jail {
+        let mut jail = Jail::enter(TempDir::new().map_err(as_string)?, false)?;
+        f(&mut jail)
+    }
+
+    /// Creates a child jail that calls `f`, passing the child to `f`. Returns
+    /// the result from `f`.
+    ///
+    /// The child switches into a fresh temporary directory of its own, created
+    /// inside of the parent's directory, so providers that search parent
+    /// directories, like [`Data::file()`](crate::providers::Data::file()),
+    /// also find files in the parent jail. Environment variables set in the
+    /// child are restored to their values in the parent, and the parent's
+    /// directory is reentered, when the child exits. If the parent jail does
+    /// not change directories, neither does the child.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` panics.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml, Env}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Workspace.toml", "name = \"root\"")?;
+    ///     jail.set_env("APP_LEVEL", "parent");
+    ///
+    ///     jail.sub_jail(|member| {
+    ///         member.create_file("Member.toml", "name = \"member\"")?;
+    ///         member.set_env("APP_LEVEL", "child");
+    ///
+    ///         let figment = Figment::from(Toml::file("Workspace.toml"));
+    ///         assert_eq!(figment.extract_inner::<String>("name")?, "root");
+    ///
+    ///         let figment = Figment::from(Toml::file("Member.toml"));
+    ///         assert_eq!(figment.extract_inner::<String>("name")?, "member");
+    ///         Ok(())
+    ///     })?;
+    ///
+    ///     assert!(!std::path::Path::new("Member.toml").exists());
+    ///     assert_eq!(std::env::var("APP_LEVEL").unwrap(), "parent");
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn sub_jail<T, F: FnOnce(&mut Jail) -> Result<T>>(&self, f: F) -> Result<T> {
+        let directory = TempDir::new_in(self.directory()).map_err(as_string)?;
+        let mut child = Jail::enter(directory, self.saved_cwd.is_some())?;
+        f(&mut child)
+    }
+
+    /// Creates a jail in `directory` and switches into it. The caller must
+    /// hold `LOCK`, directly or via a parent jail.
+    fn enter(directory: TempDir, chdir: bool) -> Result<Jail> {
+        let jail = Jail {
             canonical_dir: directory.path().canonicalize().map_err(as_string)?,
             _directory: directory,
-            saved_cwd: std::env::current_dir().map_err(as_string)?,
-            saved_env_vars: HashMap::new(),
+            saved_cwd: match chdir {
+                true => Some(std::env::current_dir().map_err(as_string)?),
+                false => None,
+            },
+            saved_env: std::env::vars_os().collect(),
+            servers: vec![],
         };
 
-        std::env::set_current_dir(jail.directory()).map_err(as_string)?;
-        f(&mut jail)
+        if chdir {
+            std::env::set_current_dir(jail.directory()).map_err(as_string)?;
+        }
+
+        Ok(jail)
     }
 
     /// Returns the directory the jail has switched into. The contents of this
@@ -149,19 +300,271 @@ impl Jail {
     /// });
     /// ```
     pub fn create_file<P: AsRef<Path>>(&self, path: P, contents: &str) -> Result<File> {
-        let path = path.as_ref();
-        if !path.is_relative() {
-            return Err("Jail::create_file(): file path is absolute".to_string().into());
-        }
-
-        let file = File::create(self.directory().join(path)).map_err(as_string)?;
+        let path = self.safe_path("create_file", path.as_ref())?;
+        let file = File::create(path).map_err(as_string)?;
         let mut writer = BufWriter::new(file);
         writer.write_all(contents.as_bytes()).map_err(as_string)?;
         Ok(writer.into_inner().map_err(as_string)?)
     }
 
+    /// Creates a file with the binary contents `bytes` in the jail's directory.
+    /// Returns the absolute path to the file. The file will be deleted when
+    /// the jail is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// figment::Jail::expect_with(|jail| {
+    ///     let path = jail.create_binary_file("Config.bin", &[0xde, 0xad, 0xbe, 0xef])?;
+    ///     assert_eq!(path, jail.directory().join("Config.bin"));
+    ///     assert_eq!(std::fs::read(path).unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn create_binary_file<P: AsRef<Path>>(&self, path: P, bytes: &[u8]) -> Result<PathBuf> {
+        let path = self.safe_path("create_binary_file", path.as_ref())?;
+        std::fs::write(&path, bytes).map_err(as_string)?;
+        Ok(path)
+    }
+
+    /// Appends `contents` to the file at `path` in the jail's directory,
+    /// creating the file if it doesn't exist. Returns the absolute path to the
+    /// file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", "a = 1\n")?;
+    ///     let path = jail.append_file("Config.toml", "b = 2\n")?;
+    ///     assert_eq!(std::fs::read_to_string(path).unwrap(), "a = 1\nb = 2\n");
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn append_file<P, C>(&self, path: P, contents: C) -> Result<PathBuf>
+        where P: AsRef<Path>, C: AsRef<[u8]>
+    {
+        let path = self.safe_path("append_file", path.as_ref())?;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(contents.as_ref()))
+            .map_err(as_string)?;
+
+        Ok(path)
+    }
+
+    /// Replaces the contents of the existing file at `path` in the jail's
+    /// directory with `contents`. Returns the absolute path to the file.
+    ///
+    /// Unlike [`Jail::create_file()`], this method fails if the file does not
+    /// exist, making it suitable for simulating changes to watched files.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Format, Toml}};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", "port = 80")?;
+    ///     let figment = Figment::from(Toml::file("Config.toml"));
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 80);
+    ///
+    ///     jail.overwrite_file("Config.toml", "port = 8080")?;
+    ///     let figment = Figment::from(Toml::file("Config.toml"));
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8080);
+    ///
+    ///     assert!(jail.overwrite_file("Missing.toml", "port = 1").is_err());
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn overwrite_file<P, C>(&self, path: P, contents: C) -> Result<PathBuf>
+        where P: AsRef<Path>, C: AsRef<[u8]>
+    {
+        let path = self.safe_path("overwrite_file", path.as_ref())?;
+        std::fs::OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(contents.as_ref()))
+            .map_err(as_string)?;
+
+        Ok(path)
+    }
+
+    /// Creates a file with contents `contents` and Unix permissions `mode` in
+    /// the jail's directory. The file will be deleted when the jail is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(unix)] {
+    /// use std::os::unix::fs::PermissionsExt;
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     let file = jail.create_file_with_mode("secret.toml", "key = 1", 0o600)?;
+    ///     let mode = file.metadata().unwrap().permissions().mode();
+    ///     assert_eq!(mode & 0o777, 0o600);
+    ///     Ok(())
+    /// });
+    /// # }
+    /// ```
+    #[cfg(unix)]
+    pub fn create_file_with_mode<P>(&self, path: P, contents: &str, mode: u32) -> Result<File>
+        where P: AsRef<Path>
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = self.create_file(path, contents)?;
+        file.set_permissions(std::fs::Permissions::from_mode(mode)).map_err(as_string)?;
+        Ok(file)
+    }
+
+    /// Creates an empty file at `path` in the jail's directory if it doesn't
+    /// exist or updates its modification time if it does, like `touch`. The
+    /// contents of an existing file are left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", "key = 1")?;
+    ///     jail.touch("Config.toml")?;
+    ///     jail.touch("Empty.toml")?;
+    ///
+    ///     assert_eq!(std::fs::read_to_string("Config.toml").unwrap(), "key = 1");
+    ///     assert_eq!(std::fs::read_to_string("Empty.toml").unwrap(), "");
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn touch<P: AsRef<Path>>(&self, path: P) -> Result<File> {
+        let path = self.safe_path("touch", path.as_ref())?;
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(as_string)?;
+
+        file.set_modified(std::time::SystemTime::now()).map_err(as_string)?;
+        Ok(file)
+    }
+
+    /// Creates the directory `path`, and all of its missing parents, in the
+    /// jail's directory. Returns the absolute path to the directory. The
+    /// directory will be deleted when the jail is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// figment::Jail::expect_with(|jail| {
+    ///     let dir = jail.create_dir_all("config/app")?;
+    ///     assert_eq!(dir, jail.directory().join("config/app"));
+    ///
+    ///     jail.create_file("config/app/App.toml", "key = 1")?;
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn create_dir_all<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf> {
+        let path = self.safe_path("create_dir_all", path.as_ref())?;
+        std::fs::create_dir_all(&path).map_err(as_string)?;
+        Ok(path)
+    }
+
+    /// Creates a symbolic link at `dst` in the jail's directory that points to
+    /// `src`. Like `ln -s`, `src` is stored verbatim: when relative, it is
+    /// resolved relative to the directory containing `dst`. Returns the
+    /// absolute path to the link. The link will be deleted when the jail is
+    /// dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// figment::Jail::expect_with(|jail| {
+    ///     // A Kubernetes-style `ConfigMap` layout.
+    ///     jail.create_dir_all("config/..data_v1")?;
+    ///     jail.create_file("config/..data_v1/App.toml", "key = 1")?;
+    ///     jail.symlink("..data_v1", "config/..data")?;
+    ///     let link = jail.symlink("..data/App.toml", "config/App.toml")?;
+    ///
+    ///     assert_eq!(std::fs::read_to_string(link).unwrap(), "key = 1");
+    ///     Ok(())
+    /// });
+    /// ```
+    #[cfg(any(unix, windows))]
+    pub fn symlink<S: AsRef<Path>, D: AsRef<Path>>(&self, src: S, dst: D) -> Result<PathBuf> {
+        let dst = self.safe_path("symlink", dst.as_ref())?;
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(src, &dst).map_err(as_string)?;
+
+        #[cfg(windows)] {
+            let target = dst.parent().map_or(src.as_ref().into(), |p| p.join(&src));
+            match target.is_dir() {
+                true => std::os::windows::fs::symlink_dir(src, &dst).map_err(as_string)?,
+                false => std::os::windows::fs::symlink_file(src, &dst).map_err(as_string)?,
+            }
+        }
+
+        Ok(dst)
+    }
+
+    /// Starts a stub HTTP server on the loopback interface that responds to
+    /// `GET` requests with canned `responses` and returns its base URL, i.e,
+    /// `http://127.0.0.1:{port}`. The server is stopped when the jail is
+    /// dropped.
+    ///
+    /// Each response is a pair of a request path, like `/config.json`, and the
+    /// body to respond with. Query strings are ignored when matching paths.
+    /// Requests for any other path receive a `404 Not Found`. The response's
+    /// `Content-Type` is guessed from the path's extension.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{Read, Write};
+    /// use std::net::TcpStream;
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     let url = jail.serve([("/config.json", r#"{ "port": 8080 }"#)])?;
+    ///     assert!(url.starts_with("http://127.0.0.1:"));
+    ///
+    ///     let mut stream = TcpStream::connect(url.trim_start_matches("http://")).unwrap();
+    ///     stream.write_all(b"GET /config.json HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+    ///
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     assert!(response.starts_with("HTTP/1.1 200 OK"));
+    ///     assert!(response.contains("Content-Type: application/json"));
+    ///     assert!(response.ends_with(r#"{ "port": 8080 }"#));
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn serve<I, P, B>(&mut self, responses: I) -> Result<String>
+        where I: IntoIterator<Item = (P, B)>, P: Into<String>, B: Into<String>
+    {
+        let responses = responses.into_iter()
+            .map(|(path, body)| (path.into(), body.into()))
+            .collect();
+
+        let server = Server::start(responses)?;
+        let url = format!("http://{}", server.addr);
+        self.servers.push(server);
+        Ok(url)
+    }
+
+    /// Returns `path` joined to the jail's directory if it is relative.
+    fn safe_path(&self, method: &str, path: &Path) -> Result<PathBuf> {
+        if !path.is_relative() {
+            return Err(format!("Jail::{}(): file path is absolute", method).into());
+        }
+
+        Ok(self.directory().join(path))
+    }
+
     /// Set the environment variable `k` to value `v`. The variable will be
-    /// removed when the jail is dropped.
+    /// restored to its previous value, or removed if it had none, when the
+    /// jail is dropped.
     ///
     /// # Example
     ///
@@ -179,24 +582,128 @@ impl Jail {
     /// assert!(std::env::var(VAR_NAME).is_err());
     /// ```
     pub fn set_env<K: AsRef<str>, V: Display>(&mut self, k: K, v: V) {
-        let key = k.as_ref();
-        if !self.saved_env_vars.contains_key(OsStr::new(key)) {
-            self.saved_env_vars.insert(key.into(), std::env::var_os(key));
-        }
+        std::env::set_var(k.as_ref(), v.to_string());
+    }
 
-        std::env::set_var(key, v.to_string());
+    /// Removes every environment variable from the process environment. The
+    /// environment will be restored when the jail is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// std::env::set_var("MY_FIGMENT_OUTER_VAR", "value");
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.clear_env();
+    ///     assert!(std::env::vars_os().next().is_none());
+    ///
+    ///     jail.set_env("MY_FIGMENT_INNER_VAR", "value");
+    ///     Ok(())
+    /// });
+    ///
+    /// assert!(std::env::var("MY_FIGMENT_OUTER_VAR").is_ok());
+    /// assert!(std::env::var("MY_FIGMENT_INNER_VAR").is_err());
+    /// ```
+    pub fn clear_env(&mut self) {
+        for (key, _) in std::env::vars_os() {
+            std::env::remove_var(key);
+        }
     }
 }
 
 impl Drop for Jail {
     fn drop(&mut self) {
-        for (key, value) in self.saved_env_vars.iter() {
-            match value {
-                Some(val) => std::env::set_var(key, val),
-                None => std::env::remove_var(key)
+        for (key, _) in std::env::vars_os() {
+            if !self.saved_env.contains_key(&key) {
+                std::env::remove_var(key);
             }
         }
 
-        let _ = std::env::set_current_dir(&self.saved_cwd);
+        for (key, value) in self.saved_env.iter() {
+            if std::env::var_os(key).as_ref() != Some(value) {
+                std::env::set_var(key, value);
+            }
+        }
+
+        if let Some(cwd) = &self.saved_cwd {
+            let _ = std::env::set_current_dir(cwd);
+        }
+    }
+}
+
+/// A stub HTTP server started by [`Jail::serve()`]. Stopped when dropped.
+struct Server {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    fn start(responses: HashMap<String, String>) -> Result<Server> {
+        let listener = TcpListener::bind("127.0.0.1:0").map_err(as_string)?;
+        let addr = listener.local_addr().map_err(as_string)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::spawn({
+            let stop = stop.clone();
+            move || for stream in listener.incoming() {
+                if stop.load(Ordering::Acquire) {
+                    break;
+                }
+
+                if let Ok(stream) = stream {
+                    let _ = Server::respond(stream, &responses);
+                }
+            }
+        });
+
+        Ok(Server { addr, stop, thread: Some(thread) })
+    }
+
+    /// How long to wait on a client before giving up on its request. Bounds
+    /// how long stopping the server can take.
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    fn respond(mut stream: TcpStream, responses: &HashMap<String, String>) -> io::Result<()> {
+        stream.set_read_timeout(Some(Server::TIMEOUT))?;
+        stream.set_write_timeout(Some(Server::TIMEOUT))?;
+
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 8192 {
+            match stream.read(&mut buf)? {
+                0 => break,
+                n => request.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        let request = String::from_utf8_lossy(&request);
+        let target = request.split_whitespace().nth(1).unwrap_or("/");
+        let path = target.split('?').next().unwrap_or(target);
+        let (status, body) = match responses.get(path) {
+            Some(body) => ("200 OK", body.as_str()),
+            None => ("404 Not Found", "not found"),
+        };
+
+        let content_type = match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("json") => "application/json",
+            Some("toml") => "application/toml",
+            Some("yaml") | Some("yml") => "application/yaml",
+            _ => "text/plain",
+        };
+
+        write!(stream, "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+            Connection: close\r\n\r\n{}", status, content_type, body.len(), body)?;
+
+        stream.flush()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
     }
 }
@@ -1,4 +1,5 @@
 #![cfg_attr(nightly, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 
 //! Semi-hierarchical configuration so con-free, it's unreal.
@@ -291,6 +292,52 @@
 //! | `toml`  | [`providers::Toml`] | TOML file/string [`Provider`].          |
 //! | `json`  | [`providers::Json`] | JSON file/string [`Provider`].          |
 //! | `yaml`  | [`providers::Yaml`] | YAML file/string [`Provider`].          |
+//! | `config`| [`providers::ConfigRs`] | config-rs `Source` interop.         |
+//! | `clap`  | [`providers::Clap`] | `clap` argument [`Provider`].           |
+//! | `parse-value` | [`providers::Args`] | `--key=value` argument [`Provider`]. |
+//! | `web`   | [`providers::Js`], [`providers::LocalStorage`] | Browser [`Provider`]s. |
+//! | `shared`| [`SharedFigment`]   | Atomically swappable shared figment.    |
+//!
+//! Additionally, the `preserve-order` feature makes [`Dict`](value::Dict)s
+//! preserve key insertion order instead of sorting keys. See
+//! [`value::Dict`] for details. The `pretty` feature enables
+//! [`Error::pretty()`], which renders errors as rich diagnostics with source
+//! snippets. The `tracing` feature emits [`tracing`] spans and events as
+//! providers are loaded and merged, profiles selected, and values extracted.
+//! Events name providers, keys, and error codes but never include values.
+//! Additionally, `TRACE`-level events with the target `figment::value::de`
+//! record each step the deserializer takes, such as the fields, elements, and
+//! magic values it visits, to help debug why a value fails to deserialize.
+//! The `mmap` feature enables the `unsafe`
+//! [`Data::mmap()`](providers::Data::mmap()), which memory-maps files instead
+//! of reading them into a buffer. The
+//! `toml-edit` feature enables [`Figment::persist()`], which writes changes
+//! back to TOML files while preserving their comments and formatting. With
+//! both `env` and `toml` enabled, [`FigmentBuilder`] and [`extract()`] set up
+//! the canonical defaults, file, and environment variable layers in one call.
+//!
+//! ## `no_std`
+//!
+//! The `std` feature is enabled by default. Without it, figment is `no_std`
+//! and requires only `alloc`: [`Figment`], [`Value`](value::Value),
+//! [`Provider`], [`Serialized`](providers::Serialized), and merging and
+//! extraction remain available, while everything that touches the file system
+//! or environment, including [`Format`](providers::Format) providers,
+//! [`RelativePathBuf`](value::magic::RelativePathBuf),
+//! [`Source::File`], and [`Profile::from_env()`], is gated behind `std`. Every
+//! other feature implies `std`. Without `std`, an [`Error`] raised inside a
+//! [magic](value::magic) value like [`Either`](value::magic::Either) is
+//! propagated as a message.
+//!
+//! ## WASM
+//!
+//! Figment compiles for `wasm32-unknown-unknown`. As that target has neither
+//! environment variables nor a file system, `Env`, `FigmentBuilder`, and
+//! [`Jail`] are unavailable there, and [`Format`](providers::Format) files are always treated as
+//! missing, though [`Format::string()`](providers::Format::string()) works as
+//! usual. The `web` feature enables [`providers::Js`], which sources values
+//! from a JavaScript object, and [`providers::LocalStorage`], which sources
+//! values from the browser's `localStorage`.
 //!
 //! # Built-In Providers
 //!
@@ -300,6 +347,9 @@
 //! | provider                              | description                            |
 //! |---------------------------------------|----------------------------------------|
 //! | [`providers::Serialized`]             | Source from any [`Serialize`] type.    |
+//! | [`providers::BuildEnv`]               | Source from compile-time variables.    |
+//! | [`providers::SystemInfo`]             | Source host facts, like the CPU count. |
+//! | [`providers::Snapshot`]               | Source from a provider read only once. |
 //! | [`(impl AsRef<str>, impl Serialize)`] | Global source from a `("key", value)`. |
 //! | [`&T` _where_ `T: Provider`]          | Source from `T` as a reference.        |
 //!
@@ -473,22 +523,63 @@
 //!   * [`Error`] may contain more than one error: iterate over it to retrieve
 //!     all errors.
 
+extern crate alloc;
+
+/// The items of the `std` prelude that `no_std` crates import from `alloc`.
+#[allow(unused_imports)]
+mod prelude {
+    pub use alloc::borrow::ToOwned;
+    pub use alloc::boxed::Box;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec::Vec;
+    pub use alloc::{format, vec};
+}
+
 pub mod value;
 pub mod providers;
 pub mod error;
+pub mod warning;
 pub mod util;
 mod figment;
 mod profile;
 mod coalesce;
 mod metadata;
 mod provider;
+mod ext;
+mod frozen;
+mod patch;
+mod docs;
+#[cfg(feature = "std")]
+mod audit;
+#[cfg(feature = "shared")]
+mod shared;
+#[cfg(feature = "std")]
+mod policy;
+#[cfg(all(feature = "env", feature = "toml", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+mod builder;
 
-#[cfg(any(test, feature = "test"))] mod jail;
-#[cfg(any(test, feature = "test"))] pub use jail::Jail;
+#[cfg(all(any(test, feature = "test"), not(all(target_arch = "wasm32", target_os = "unknown"))))]
+mod jail;
+#[cfg(all(any(test, feature = "test"), not(all(target_arch = "wasm32", target_os = "unknown"))))]
+pub use jail::Jail;
+#[cfg(any(test, feature = "test"))]
+#[cfg_attr(nightly, doc(cfg(feature = "test")))]
+pub mod test;
 
 #[doc(inline)]
 pub use error::Error;
-pub use self::figment::Figment;
+pub use self::figment::{Figment, ProfileInfo, Consumed, MapEntry};
 pub use profile::Profile;
 pub use provider::*;
+pub use ext::FigmentExt;
+pub use frozen::Frozen;
+pub use docs::{Docs, KeyDoc};
+#[cfg(feature = "std")]
+pub use audit::SecretRead;
+#[cfg(feature = "std")]
+pub use policy::{Policy, Capability};
+#[cfg(all(feature = "env", feature = "toml", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+pub use builder::{FigmentBuilder, extract};
+#[cfg(feature = "shared")]
+pub use shared::SharedFigment;
 pub use metadata::*;
@@ -1,8 +1,11 @@
-use std::fmt;
-use std::borrow::Cow;
+use core::fmt;
+use alloc::borrow::Cow;
+#[cfg(feature = "std")]
 use std::path::{Path, PathBuf};
-use std::panic::Location;
+use core::panic::Location;
+use alloc::sync::Arc;
 
+use crate::prelude::*;
 use crate::Profile;
 
 /// Metadata about a configuration value: its source's name and location.
@@ -22,7 +25,7 @@ use crate::Profile;
 /// generate values like [`RelativePathBuf`] that know about their configuration
 /// source.
 ///
-/// [`Location`]: std::panic::Location
+/// [`Location`]: core::panic::Location
 ///
 /// ## Errors
 ///
@@ -41,8 +44,15 @@ use crate::Profile;
 /// ${profile}.${a}.${b}.${c}
 /// ```
 ///
+/// Keys containing a `.` are escaped with
+/// [`escape_key()`](crate::util::escape_key()) so that the interpolated path
+/// can be passed back to methods like
+/// [`Figment::find_value()`](crate::Figment::find_value()).
+///
 /// Providers are free to implement any interpolater for their metadata. For
-/// example, the interpolater for [`Env`] uppercases each path key:
+/// example, the interpolater for [`Env`] returns the name of the environment
+/// variable a key was read from, such as `APP_KEY_PATH`. A simpler interpolater
+/// might uppercase each path key:
 ///
 /// ```rust
 /// use figment::Metadata;
@@ -78,6 +88,37 @@ pub struct Metadata {
     /// containing figment, if it is known.
     pub provide_location: Option<&'static Location<'static>>,
     interpolater: Box<dyn Interpolator>,
+    /// Rarely set details, boxed to keep `Metadata`, and thus every `Error`,
+    /// small. `None` when every detail is unset.
+    extra: Option<Box<Extra>>,
+}
+
+/// The rarely set details of a [`Metadata`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Extra {
+    /// Whether the provider reads keys from outside of the program, as data
+    /// formats and the environment do, and so could supply a missing key.
+    pub(crate) external: bool,
+    pub(crate) read_hook: Option<ReadHook>,
+    pub(crate) key_sources: Vec<(String, Source)>,
+    /// The profile, key path, and name of every environment variable read by
+    /// [`Env`](crate::providers::Env), as in `APP_DATABASE__POOL_SIZE`.
+    pub(crate) variables: Vec<(Profile, Vec<String>, String)>,
+    /// The file changes can be persisted to. See
+    /// [`Figment::persist()`](crate::Figment::persist()).
+    #[cfg(feature = "toml-edit")]
+    pub(crate) editable: Option<crate::providers::Editable>,
+}
+
+/// Called with the key path of every value tagged with the metadata that is
+/// read during an extraction. Used by [`Recorder`](crate::test::Recorder).
+#[derive(Clone)]
+pub(crate) struct ReadHook(pub(crate) Arc<dyn Fn(&str) + Send + Sync>);
+
+impl fmt::Debug for ReadHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ReadHook")
+    }
 }
 
 impl Metadata {
@@ -132,6 +173,74 @@ impl Metadata {
         self
     }
 
+    /// Sets the source of the values at the key path `key` to `source`,
+    /// overriding `self.source` for `key` and all keys nested under it.
+    ///
+    /// This allows a single provider to report different sources for different
+    /// keys, for example, a provider that reads one file per key. When the
+    /// provider is merged or joined into a [`Figment`](crate::Figment), values
+    /// at `key` in every profile are tagged with a copy of `self` whose
+    /// `source` is `source`, so [`Figment::find_metadata()`], [`Tagged`], and
+    /// errors refer to the per-key source. If keys overlap, the most specific
+    /// key's source applies. Keys without a value are ignored.
+    ///
+    /// [`Figment::find_metadata()`]: crate::Figment::find_metadata()
+    /// [`Tagged`]: crate::value::magic::Tagged
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, Profile, Provider, Metadata, Error};
+    /// use figment::value::{Map, Dict};
+    /// use figment::util::map;
+    ///
+    /// struct Secrets;
+    ///
+    /// impl Provider for Secrets {
+    ///     fn metadata(&self) -> Metadata {
+    ///         Metadata::from("secrets", "vault://")
+    ///             .key_source("db.password", "vault://db")
+    ///             .key_source("api", "vault://api")
+    ///     }
+    ///
+    ///     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+    ///         let mut dict = Dict::new();
+    ///         dict.insert("db".into(), map! { "password" => "hunter2", "user" => "root" }.into());
+    ///         dict.insert("api".into(), map! { "token" => "abc" }.into());
+    ///         Ok(Profile::Default.collect(dict))
+    ///     }
+    /// }
+    ///
+    /// let figment = Figment::from(Secrets);
+    /// let source = |key| figment.find_metadata(key).unwrap().source.clone().unwrap();
+    /// assert_eq!(source("db.password").custom(), Some("vault://db"));
+    /// assert_eq!(source("db.user").custom(), Some("vault://"));
+    /// assert_eq!(source("api.token").custom(), Some("vault://api"));
+    /// ```
+    pub fn key_source<K: Into<String>, S: Into<Source>>(mut self, key: K, source: S) -> Self {
+        self.extra_mut().key_sources.push((key.into(), source.into()));
+        self
+    }
+
+    /// Returns an iterator over the key paths and sources set via
+    /// [`Metadata::key_source()`], in the order they were set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Metadata;
+    ///
+    /// let metadata = Metadata::named("secrets").key_source("db.password", "vault://db");
+    /// let (key, source) = metadata.key_sources().next().unwrap();
+    /// assert_eq!(key, "db.password");
+    /// assert_eq!(source.custom(), Some("vault://db"));
+    /// ```
+    pub fn key_sources(&self) -> impl Iterator<Item = (&str, &Source)> {
+        self.extra.iter()
+            .flat_map(|extra| extra.key_sources.iter())
+            .map(|(key, source)| (key.as_str(), source))
+    }
+
     /// Sets the `interpolater` of `self` to the function `f`. The interpolater
     /// can be invoked via [`Metadata::interpolate()`].
     ///
@@ -158,6 +267,7 @@ impl Metadata {
         where I: Fn(&Profile, &[&str]) -> String
     {
         self.interpolater = Box::new(f);
+        self.extra_mut().external = true;
         self
     }
 
@@ -186,6 +296,108 @@ impl Metadata {
         let keys: Vec<_> = keys.iter().map(|k| k.as_ref()).collect();
         (self.interpolater)(profile, &keys)
     }
+
+    /// Returns the rarely set details of `self`, if any are set.
+    pub(crate) fn extra(&self) -> Option<&Extra> {
+        self.extra.as_deref()
+    }
+
+    /// Returns the rarely set details of `self`, allocating them if unset.
+    pub(crate) fn extra_mut(&mut self) -> &mut Extra {
+        self.extra.get_or_insert_with(Default::default)
+    }
+
+    /// Removes and returns the key paths and sources set via
+    /// [`Metadata::key_source()`].
+    pub(crate) fn take_key_sources(&mut self) -> Vec<(String, Source)> {
+        self.extra.as_mut().map_or_else(Vec::new, |extra| core::mem::take(&mut extra.key_sources))
+    }
+
+    /// Prefixes the keys passed to the interpolater with `prefix`, so that the
+    /// keys of values in a [focused](crate::Figment::focus()) figment
+    /// interpolate as they would in the original figment.
+    pub(crate) fn rebased(mut self, prefix: Vec<String>) -> Self {
+        if prefix.is_empty() {
+            return self;
+        }
+
+        if let Some(extra) = &mut self.extra {
+            #[cfg(feature = "toml-edit")]
+            if let Some(editable) = &mut extra.editable {
+                editable.rebase(&prefix);
+            }
+
+            extra.variables = core::mem::take(&mut extra.variables).into_iter()
+                .filter(|(_, path, _)| path.len() > prefix.len() && is_prefix(&prefix, path))
+                .map(|(profile, path, name)| (profile, path[prefix.len()..].to_vec(), name))
+                .collect();
+        }
+
+        let interpolater = self.interpolater.clone();
+        self.interpolater = Box::new(move |profile: &Profile, keys: &[&str]| {
+            let keys: Vec<&str> = prefix.iter()
+                .map(|k| k.as_str())
+                .chain(keys.iter().copied())
+                .collect();
+
+            interpolater(profile, &keys)
+        });
+
+        self
+    }
+
+    /// Strips `prefix` from the keys passed to the interpolater, so that the
+    /// keys of values nested under `prefix`, as by
+    /// [`Figment::adjoin_library()`](crate::Figment::adjoin_library()),
+    /// interpolate as they would in the original figment. Such values can't
+    /// be persisted.
+    pub(crate) fn namespaced(mut self, prefix: Vec<String>) -> Self {
+        if prefix.is_empty() {
+            return self;
+        }
+
+        if let Some(extra) = &mut self.extra {
+            #[cfg(feature = "toml-edit")] {
+                extra.editable = None;
+            }
+
+            for (_, path, _) in &mut extra.variables {
+                path.splice(0..0, prefix.iter().cloned());
+            }
+        }
+
+        let interpolater = self.interpolater.clone();
+        self.interpolater = Box::new(move |profile: &Profile, keys: &[&str]| {
+            let nested = keys.len() >= prefix.len()
+                && keys.iter().zip(prefix.iter()).all(|(k, p)| k == p);
+
+            match nested {
+                true => interpolater(profile, &keys[prefix.len()..]),
+                false => interpolater(profile, keys),
+            }
+        });
+
+        self
+    }
+
+    /// Returns the name of the environment variable the value at the key path
+    /// `keys` in `profile` was read from, if one was recorded. Keys are
+    /// compared case-insensitively.
+    pub(crate) fn variable<K: AsRef<str>>(&self, profile: &Profile, keys: &[K]) -> Option<&str> {
+        let is_key = |path: &Vec<String>| path.len() == keys.len()
+            && path.iter().zip(keys).all(|(a, b)| uncased::eq(a, b.as_ref()));
+
+        let variables = &self.extra()?.variables;
+        variables.iter()
+            .find(|(p, path, _)| p == profile && is_key(path))
+            .or_else(|| variables.iter().find(|(_, path, _)| is_key(path)))
+            .map(|(_, _, name)| name.as_str())
+    }
+}
+
+/// Returns `true` if `prefix` is a case-insensitive prefix of `path`.
+fn is_prefix(prefix: &[String], path: &[String]) -> bool {
+    path.len() >= prefix.len() && prefix.iter().zip(path).all(|(a, b)| uncased::eq(a, b))
 }
 
 impl PartialEq for Metadata {
@@ -201,6 +413,7 @@ impl Default for Metadata {
             source: None,
             provide_location: None,
             interpolater: Box::new(default_interpolater),
+            extra: None,
         }
     }
 }
@@ -220,6 +433,8 @@ impl Default for Metadata {
 #[derive(PartialEq, Debug, Clone)]
 pub enum Source {
     /// A file: the path to the file.
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
     File(PathBuf),
     /// Some programatic value: the source location.
     Code(&'static Location<'static>),
@@ -239,6 +454,8 @@ impl Source {
     /// let source = Source::from(Path::new("a/b/c.txt"));
     /// assert_eq!(source.file_path(), Some(Path::new("a/b/c.txt")));
     /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
     pub fn file_path(&self) -> Option<&Path> {
         match self {
             Source::File(ref p) => Some(p),
@@ -289,6 +506,7 @@ impl Source {
 impl fmt::Display for Source {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            #[cfg(feature = "std")]
             Source::File(p) => {
                 use {std::env::current_dir, crate::util::diff_paths};
 
@@ -303,6 +521,7 @@ impl fmt::Display for Source {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&Path> for Source {
     fn from(path: &Path) -> Source {
         Source::File(path.into())
@@ -332,5 +551,6 @@ crate::util::cloneable_fn_trait!(
 );
 
 fn default_interpolater(profile: &Profile, keys: &[&str]) -> String {
+    let keys: Vec<_> = keys.iter().map(|k| crate::util::escape_key(k)).collect();
     format!("{}.{}", profile, keys.join("."))
 }
@@ -0,0 +1,247 @@
+//! JSON Patch (RFC 6902) and JSON Merge Patch (RFC 7386) over [`Value`]s.
+
+use crate::prelude::*;
+use crate::error::Error;
+use crate::value::{Value, Dict, Empty, array_index, dict_remove};
+
+/// Returns `true` if `value` is JSON's `null`.
+fn is_null(value: &Value) -> bool {
+    matches!(value, Value::Empty(..))
+}
+
+/// Applies the merge patch `patch` to `target` as specified by RFC 7386.
+fn merge_patch(target: &mut Value, patch: Value) {
+    let patch = match patch {
+        Value::Dict(_, patch) => patch,
+        patch => return *target = patch,
+    };
+
+    if target.as_dict().is_none() {
+        *target = Value::from(Dict::new());
+    }
+
+    if let Value::Dict(_, dict) = target {
+        for (key, value) in patch {
+            match is_null(&value) {
+                true => { dict_remove(dict, &key); }
+                false => merge_patch(dict.entry(key).or_insert(Empty::None.into()), value),
+            }
+        }
+    }
+}
+
+/// Applies the merge patch `patch`, which must be a dictionary, to the
+/// dictionary `doc`.
+pub(crate) fn apply_merge_patch(doc: &mut Value, patch: Value) -> Result<(), Error> {
+    if patch.as_dict().is_none() {
+        let actual = patch.to_actual();
+        return Err(Error::from(format!("a merge patch must be a dictionary, found {}", actual)));
+    }
+
+    merge_patch(doc, patch);
+    Ok(())
+}
+
+/// Parses the JSON Pointer `pointer` into its components.
+fn pointer(pointer: &str) -> Result<Vec<String>, Error> {
+    match pointer {
+        "" => Ok(vec![]),
+        p if p.starts_with('/') => Ok(crate::util::split_key(p)),
+        p => Err(Error::from(format!("`{}` is not a JSON Pointer", p))),
+    }
+}
+
+fn get<'a>(doc: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(doc, |value, key| match value {
+        Value::Dict(_, dict) => dict.get(key),
+        Value::Array(_, vec) => vec.get(array_index(key)?),
+        _ => None,
+    })
+}
+
+fn get_mut<'a>(doc: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    path.iter().try_fold(doc, |value, key| match value {
+        Value::Dict(_, dict) => dict.get_mut(key),
+        Value::Array(_, vec) => vec.get_mut(array_index(key)?),
+        _ => None,
+    })
+}
+
+fn add(doc: &mut Value, path: &[String], value: Value) -> Result<(), &'static str> {
+    let (last, parent) = match path.split_last() {
+        Some(split) => split,
+        None if value.as_dict().is_some() => {
+            *doc = value;
+            return Ok(());
+        }
+        None => return Err("the root must be a dictionary"),
+    };
+
+    match get_mut(doc, parent).ok_or("the parent does not exist")? {
+        Value::Dict(_, dict) => { dict.insert(last.clone(), value); }
+        Value::Array(_, vec) => {
+            let i = match last.as_str() {
+                "-" => vec.len(),
+                i => array_index(i).filter(|i| *i <= vec.len()).ok_or("the index is out of bounds")?,
+            };
+
+            vec.insert(i, value);
+        }
+        _ => return Err("the parent is not a dictionary or array"),
+    }
+
+    Ok(())
+}
+
+fn remove(doc: &mut Value, path: &[String]) -> Result<Value, &'static str> {
+    let (last, parent) = path.split_last().ok_or("the root cannot be removed")?;
+    let removed = match get_mut(doc, parent) {
+        Some(Value::Dict(_, dict)) => dict_remove(dict, last),
+        Some(Value::Array(_, vec)) => array_index(last)
+            .filter(|i| *i < vec.len())
+            .map(|i| vec.remove(i)),
+        _ => None,
+    };
+
+    removed.ok_or("the value does not exist")
+}
+
+/// Returns `true` if `a` and `b` are equal as JSON values: numbers are
+/// compared by value, regardless of their width or signedness.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Num(_, a), Value::Num(_, b)) => a.compare(b) == Some(core::cmp::Ordering::Equal),
+        (Value::Empty(..), Value::Empty(..)) => true,
+        (Value::Dict(_, a), Value::Dict(_, b)) => a.len() == b.len()
+            && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| json_eq(v, w))),
+        (Value::Array(_, a), Value::Array(_, b)) => a.len() == b.len()
+            && a.iter().zip(b).all(|(v, w)| json_eq(v, w)),
+        (a, b) => a == b,
+    }
+}
+
+/// Applies the JSON Patch `patch`, an array of operations, to `doc` as
+/// specified by RFC 6902. If any operation fails, an error naming it is
+/// returned, and `doc` is left partially patched.
+pub(crate) fn apply_patch(doc: &mut Value, patch: Value) -> Result<(), Error> {
+    let operations = match patch {
+        Value::Array(_, operations) => operations,
+        patch => {
+            let actual = patch.to_actual();
+            return Err(Error::from(format!("a JSON Patch must be an array, found {}", actual)));
+        }
+    };
+
+    for (i, operation) in operations.into_iter().enumerate() {
+        let fail = |op: &str, reason: &str| Error::from(format!(
+            "JSON Patch operation {} (`{}`) failed: {}", i, op, reason
+        ));
+
+        let mut operation = operation.into_dict().ok_or_else(|| fail("?", "not a dictionary"))?;
+        let string = |key: &str| match operation.get(key) {
+            Some(Value::String(_, s)) => Some(s.clone()),
+            _ => None,
+        };
+
+        let op = string("op").ok_or_else(|| fail("?", "missing `op`"))?;
+        let path = string("path").ok_or_else(|| fail(&op, "missing `path`"))?;
+        let from = string("from");
+        let path = pointer(&path).map_err(|e| fail(&op, &e.to_string()))?;
+        let from = match (from, op.as_str()) {
+            (Some(from), _) => pointer(&from).map_err(|e| fail(&op, &e.to_string()))?,
+            (None, "move" | "copy") => return Err(fail(&op, "missing `from`")),
+            (None, _) => vec![],
+        };
+
+        let mut value = || operation.remove("value").ok_or_else(|| fail(&op, "missing `value`"));
+        let result = match op.as_str() {
+            "add" => add(doc, &path, value()?),
+            "remove" => remove(doc, &path).map(|_| ()),
+            "replace" => {
+                let value = value()?;
+                match get_mut(doc, &path) {
+                    Some(_) if path.is_empty() && value.as_dict().is_none() => {
+                        Err("the root must be a dictionary")
+                    }
+                    Some(target) => {
+                        *target = value;
+                        Ok(())
+                    }
+                    None => Err("the value does not exist"),
+                }
+            }
+            "move" if path.starts_with(&from) && path != from => {
+                Err("a value cannot be moved into one of its children")
+            }
+            "move" => remove(doc, &from).and_then(|value| add(doc, &path, value)),
+            "copy" => match get(doc, &from).cloned() {
+                Some(value) => add(doc, &path, value),
+                None => Err("the value does not exist"),
+            },
+            "test" => {
+                let value = value()?;
+                match get(doc, &path) {
+                    Some(actual) if json_eq(actual, &value) => Ok(()),
+                    Some(_) => Err("the value is not equal"),
+                    None => Err("the value does not exist"),
+                }
+            }
+            _ => Err("unknown operation"),
+        };
+
+        result.map_err(|reason| fail(&op, reason))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the override that, merged over the global profile's value, turns
+/// the merged value `old` into `new`, or `None` if `old` and `new` are equal.
+/// `lower` is the merged value of the profiles below the global profile. Keys
+/// removed from a dictionary are [`Empty::Unset`].
+pub(crate) fn overriding(old: &Value, new: &Value, lower: Option<&Value>) -> Option<Value> {
+    match (old, new) {
+        (Value::Dict(_, old), Value::Dict(tag, new)) => {
+            let lower = lower.and_then(|v| v.as_dict());
+            let mut dict: Dict = new.iter()
+                .filter_map(|(k, v)| {
+                    let lower = lower.and_then(|d| d.get(k));
+                    let value = match old.get(k) {
+                        Some(old) => overriding(old, v, lower)?,
+                        None => replacing(v, lower),
+                    };
+
+                    Some((k.clone(), value))
+                })
+                .collect();
+
+            for key in old.keys().filter(|k| !new.contains_key(*k)) {
+                dict.insert(key.clone(), Empty::Unset.into());
+            }
+
+            (!dict.is_empty()).then_some(Value::Dict(*tag, dict))
+        }
+        (old, new) if old == new => None,
+        (_, new) => Some(replacing(new, lower)),
+    }
+}
+
+/// Returns the override that, replacing the global profile's value and merged
+/// over `lower`, results in `new`: dictionaries in `new` unset the keys of
+/// those in `lower` that they lack.
+fn replacing(new: &Value, lower: Option<&Value>) -> Value {
+    match (new, lower) {
+        (Value::Dict(tag, new), Some(Value::Dict(_, lower))) => {
+            let mut dict: Dict = new.iter()
+                .map(|(k, v)| (k.clone(), replacing(v, lower.get(k))))
+                .collect();
+
+            for key in lower.keys().filter(|k| !new.contains_key(*k)) {
+                dict.insert(key.clone(), Empty::Unset.into());
+            }
+
+            Value::Dict(*tag, dict)
+        }
+        (new, _) => new.clone(),
+    }
+}
@@ -0,0 +1,294 @@
+use core::cell::RefCell;
+use core::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Kind};
+
+/// Restrictions on what providers may access while providing data.
+///
+/// An application that loads configuration sources it doesn't fully control,
+/// such as a file whose path is given on the command line, can bound what
+/// those sources may read by setting a `Policy` on a `Figment` via
+/// [`Figment::policy()`](crate::Figment::policy()). The policy is in effect
+/// while every provider subsequently merged or joined reads its data, and it
+/// is enforced by the built-in providers:
+///
+///   * [`Data`](crate::providers::Data) refuses to read files outside of the
+///     [file root](Policy::file_root()), after resolving symbolic links.
+///   * [`Env`](crate::providers::Env) refuses to read or
+///     [capture](crate::providers::Env::capture()) the environment if
+///     [environment access](Policy::allow_env()) is denied.
+///   * [`FileContents`](crate::value::magic::FileContents) refuses to read
+///     files outside of the file root with the policy in effect when its
+///     [`Deferred`](crate::value::magic::Deferred) value was extracted.
+///
+/// The policy is also in effect while `Figment::persist()` rewrites a file,
+/// which it likewise refuses to do outside of the file root.
+///
+/// A denied access results in an error of kind [`Kind::Denied`]. Custom
+/// providers can enforce the policy in effect, retrieved via
+/// [`Policy::current()`], by calling [`Policy::check()`] before any access.
+/// No built-in provider accesses the network.
+///
+/// By default, nothing is restricted.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, Policy, providers::{Env, Format, Toml}};
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_dir_all("config")?;
+///     jail.create_file("config/App.toml", "name = \"app\"")?;
+///     jail.create_file("Secrets.toml", "token = \"hunter2\"")?;
+///     jail.set_env("APP_PORT", 8000);
+///
+///     let policy = Policy::unrestricted().allow_env(false).file_root("config");
+///     let figment = Figment::new()
+///         .policy(policy)
+///         .merge(Toml::file("config/App.toml"));
+///
+///     assert_eq!(figment.extract_inner::<String>("name")?, "app");
+///
+///     let error = figment.clone().merge(Toml::file("Secrets.toml")).find_value("name").unwrap_err();
+///     assert_eq!(error.code(), "denied");
+///
+///     let error = figment.merge(Env::prefixed("APP_")).find_value("name").unwrap_err();
+///     assert_eq!(error.to_string(), "policy denies reading environment variables \
+///         in `APP_` environment variable(s)");
+///
+///     Ok(())
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Policy {
+    deny_network: bool,
+    deny_env: bool,
+    file_root: Option<PathBuf>,
+}
+
+/// An access a provider may make, checked against a [`Policy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Capability {
+    /// Reading the process's environment variables.
+    Env,
+    /// Accessing the network at a location, such as a URL.
+    Network(String),
+    /// Reading the file at a path.
+    File(PathBuf),
+}
+
+thread_local! {
+    /// The policy of the figment whose provider is currently providing data.
+    static CURRENT: RefCell<Option<Policy>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously current policy when dropped.
+pub(crate) struct Entered(Option<Policy>);
+
+impl Drop for Entered {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+    }
+}
+
+impl Policy {
+    /// A policy that restricts nothing. This is the default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Policy;
+    ///
+    /// assert_eq!(Policy::unrestricted(), Policy::default());
+    /// ```
+    pub const fn unrestricted() -> Self {
+        Policy { deny_network: false, deny_env: false, file_root: None }
+    }
+
+    /// Sets whether providers may access the network.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Policy, Capability};
+    ///
+    /// let policy = Policy::unrestricted().allow_network(false);
+    /// let error = policy.check(&Capability::Network("https://example.com".into())).unwrap_err();
+    /// assert_eq!(error.to_string(), "policy denies accessing `https://example.com`");
+    /// ```
+    pub fn allow_network(mut self, allow: bool) -> Self {
+        self.deny_network = !allow;
+        self
+    }
+
+    /// Sets whether providers may read environment variables.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Policy, Capability};
+    ///
+    /// let policy = Policy::unrestricted().allow_env(false);
+    /// assert!(policy.check(&Capability::Env).is_err());
+    /// ```
+    pub fn allow_env(mut self, allow: bool) -> Self {
+        self.deny_env = !allow;
+        self
+    }
+
+    /// Restricts the files providers may read to those in the directory
+    /// `root` or its subdirectories. A relative `root` is resolved against
+    /// the current working directory when a file is checked. Paths are
+    /// compared after resolving symbolic links, so a link inside of `root` to
+    /// a file outside of it is denied.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Policy, Capability};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_dir_all("config")?;
+    ///     jail.create_file("config/App.toml", "")?;
+    ///     jail.create_file("App.toml", "")?;
+    ///
+    ///     let policy = Policy::unrestricted().file_root("config");
+    ///     assert!(policy.check(&Capability::File("config/App.toml".into())).is_ok());
+    ///     assert!(policy.check(&Capability::File("config/../App.toml".into())).is_err());
+    ///     assert!(policy.check(&Capability::File("App.toml".into())).is_err());
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn file_root<P: Into<PathBuf>>(mut self, root: P) -> Self {
+        self.file_root = Some(root.into());
+        self
+    }
+
+    /// Returns the policy in effect: that of the figment currently merging or
+    /// joining a provider on this thread, or an unrestricted policy if there
+    /// is no such figment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, Provider, Policy, Capability, Metadata, Profile, Error};
+    /// use figment::value::{Map, Dict};
+    ///
+    /// struct NetProvider(String);
+    ///
+    /// impl Provider for NetProvider {
+    ///     fn metadata(&self) -> Metadata {
+    ///         Metadata::named("Network").source(self.0.as_str())
+    ///     }
+    ///
+    ///     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+    ///         Policy::current().check(&Capability::Network(self.0.clone()))?;
+    ///         /* fetch from the network */
+    ///         # Ok(Map::new())
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Policy::current(), Policy::unrestricted());
+    ///
+    /// let provider = NetProvider("https://example.com/config".into());
+    /// let figment = Figment::new()
+    ///     .policy(Policy::unrestricted().allow_network(false))
+    ///     .merge(provider);
+    ///
+    /// let error = figment.find_value("port").unwrap_err();
+    /// assert_eq!(error.code(), "denied");
+    /// ```
+    pub fn current() -> Policy {
+        CURRENT.with(|current| current.borrow().clone()).unwrap_or_default()
+    }
+
+    /// Returns an error of kind [`Kind::Denied`] if `self` denies
+    /// `capability`. File paths are resolved before they are checked: a file
+    /// that does not exist or whose path cannot be resolved is denied if
+    /// there is a [file root](Policy::file_root()).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Policy, Capability};
+    ///
+    /// let policy = Policy::unrestricted();
+    /// assert!(policy.check(&Capability::Env).is_ok());
+    /// assert!(policy.check(&Capability::File("/etc/passwd".into())).is_ok());
+    ///
+    /// let policy = policy.file_root("/srv/app");
+    /// assert!(policy.check(&Capability::File("/etc/passwd".into())).is_err());
+    /// ```
+    pub fn check(&self, capability: &Capability) -> Result<(), Error> {
+        let allowed = match capability {
+            Capability::Env => !self.deny_env,
+            Capability::Network(_) => !self.deny_network,
+            Capability::File(path) => match path.canonicalize() {
+                Ok(path) => self.allows_file(&path),
+                Err(_) => self.file_root.is_none(),
+            },
+        };
+
+        match allowed {
+            true => Ok(()),
+            false => Err(Kind::Denied(capability.clone()).into()),
+        }
+    }
+
+    /// Makes `self` the current policy until the returned guard is dropped.
+    pub(crate) fn enter(&self) -> Entered {
+        Entered(CURRENT.with(|current| current.replace(Some(self.clone()))))
+    }
+
+    /// Returns `true` if `self` allows reading the file at the resolved path
+    /// `path`.
+    fn allows_file(&self, path: &Path) -> bool {
+        match &self.file_root {
+            Some(root) => root.canonicalize().is_ok_and(|root| path.starts_with(root)),
+            None => true,
+        }
+    }
+
+    /// Resolves `path` and checks the resolved path against the current
+    /// policy. Every built-in file access goes through this method: the
+    /// resolved path that is checked is the one that is then accessed, so a
+    /// symbolic link in `path` changed after the check can't redirect it.
+    pub(crate) fn resolve(path: &Path) -> Result<PathBuf, Error> {
+        let resolved = path.canonicalize().map_err(|e| Kind::Io(e.kind(), path.to_path_buf()))?;
+        match Policy::current().allows_file(&resolved) {
+            true => Ok(resolved),
+            false => Err(Kind::Denied(Capability::File(path.to_path_buf())).into()),
+        }
+    }
+
+    /// Opens the file at `path` for reading if the current policy allows it.
+    /// See [`Policy::resolve()`].
+    pub(crate) fn open(path: &Path) -> Result<std::fs::File, Error> {
+        std::fs::File::open(Policy::resolve(path)?)
+            .map_err(|e| Kind::Io(e.kind(), path.to_path_buf()).into())
+    }
+
+    /// Reads the file at `path` into a string if the current policy allows
+    /// it. See [`Policy::resolve()`].
+    pub(crate) fn read_to_string(path: &Path) -> Result<String, Error> {
+        use std::io::Read;
+
+        let mut string = String::new();
+        Policy::open(path)?.read_to_string(&mut string)
+            .map_err(|e| Kind::Io(e.kind(), path.to_path_buf()))?;
+
+        Ok(string)
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Capability::Env => write!(f, "reading environment variables"),
+            Capability::Network(location) => write!(f, "accessing `{}`", location),
+            Capability::File(path) => write!(f, "reading `{}`", path.display()),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{de, ser};
 use uncased::{Uncased, UncasedStr};
 
+use crate::prelude::*;
 use crate::value::{Dict, Map};
 
 /// A configuration profile: effectively a case-insensitive string.
@@ -115,6 +116,8 @@ impl Profile {
     ///     Ok(())
     /// });
     /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
     pub fn from_env(key: &str) -> Option<Self> {
         for (env_key, val) in std::env::vars_os() {
             let env_key = env_key.to_string_lossy();
@@ -144,6 +147,8 @@ impl Profile {
     ///     Ok(())
     /// });
     /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(nightly, doc(cfg(feature = "std")))]
     pub fn from_env_or<P: Into<Profile>>(var: &str, default: P) -> Self {
         Profile::from_env(var).unwrap_or_else(|| default.into())
     }
@@ -200,10 +205,13 @@ impl Profile {
     /// # Example
     ///
     /// ```rust
-    /// use figment::{Profile, util::map};
+    /// use figment::{Profile, value::Dict};
+    ///
+    /// let mut dict = Dict::new();
+    /// dict.insert("hi".into(), 123.into());
     ///
     /// let profile = Profile::new("static");
-    /// let map = profile.collect(map!["hi".into() => 123.into()]);
+    /// let map = profile.collect(dict);
     /// ```
     pub fn collect(&self, dict: Dict) -> Map<Profile, Dict> {
         let mut map = Map::new();
@@ -224,7 +232,7 @@ impl From<Profile> for String {
     }
 }
 
-impl std::ops::Deref for Profile {
+impl core::ops::Deref for Profile {
     type Target = UncasedStr;
 
     fn deref(&self) -> &UncasedStr {
@@ -232,8 +240,8 @@ impl std::ops::Deref for Profile {
     }
 }
 
-impl std::fmt::Display for Profile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Profile {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.as_str().fmt(f)
     }
 }
@@ -1,5 +1,8 @@
 use crate::{Profile, Error, Metadata};
+use crate::prelude::*;
 use crate::value::{Tag, Map, Dict};
+use crate::warning::Warning;
+use crate::providers::Snapshot;
 
 /// Trait implemented by configuration source providers.
 ///
@@ -23,6 +26,59 @@ use crate::value::{Tag, Map, Dict};
 /// as profiles. If one _is_ specified, read the data as `Dict` and
 /// [`Profile::collect()`] into the specified profile.
 ///
+/// # Per-Key Sources
+///
+/// A provider whose data comes from more than one source, such as one that
+/// reads a file per key, can report the source of each key via
+/// [`Metadata::key_source()`]. As [`Provider::metadata()`] is called before
+/// [`Provider::data()`], per-key sources must be known when the metadata is
+/// requested. Values at such keys are tagged with metadata whose `source` is
+/// the per-key source.
+///
+/// # Tagging Values
+///
+/// Values a provider emits are tagged with its metadata, or with the metadata
+/// of their [per-key source](#per-key-sources). To attribute individual values
+/// to other metadata, a provider tags them with a [`Tag::unique()`] via
+/// [`Value::tagged()`](crate::value::Value::tagged()) and returns the metadata
+/// for each tag from [`Provider::tags()`]. Such values then report their own
+/// metadata via [`Tagged`](crate::value::magic::Tagged),
+/// [`Figment::find_metadata()`](crate::Figment::find_metadata()), and errors,
+/// exactly like values from built-in providers. A tag without metadata in
+/// [`Provider::tags()`] is ignored.
+///
+/// ```rust
+/// use figment::{Figment, Provider, Metadata, Profile, Error};
+/// use figment::value::{Map, Dict, Tag, Value, magic::Tagged};
+///
+/// /// Reads secrets from a vault, attributing each to its vault path.
+/// struct Vault { password: Tag }
+///
+/// impl Provider for Vault {
+///     fn metadata(&self) -> Metadata {
+///         Metadata::named("Vault")
+///     }
+///
+///     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+///         let mut dict = Dict::new();
+///         dict.insert("password".into(), Value::from("hunter2").tagged(self.password));
+///         dict.insert("user".into(), Value::from("admin"));
+///         Ok(Profile::Default.collect(dict))
+///     }
+///
+///     fn tags(&self) -> Map<Tag, Metadata> {
+///         let metadata = Metadata::named("Vault").source("secret/db/password");
+///         Map::from([(self.password, metadata)])
+///     }
+/// }
+///
+/// let figment = Figment::from(Vault { password: Tag::unique() });
+/// let password: Tagged<String> = figment.extract_inner("password").unwrap();
+/// let metadata = figment.get_metadata(password.tag()).unwrap();
+/// assert_eq!(metadata.source.as_ref().unwrap().custom(), Some("secret/db/password"));
+/// assert!(figment.find_metadata("user").unwrap().source.is_none());
+/// ```
+///
 /// # Example
 ///
 /// Implementing a `Provider` requires implementing methods that provide both of
@@ -82,7 +138,8 @@ use crate::value::{Tag, Map, Dict};
 /// [`Figment`]: crate::Figment
 pub trait Provider {
     /// Returns the [`Metadata`] for this provider, identifying itself and its
-    /// configuration sources.
+    /// configuration sources, including any [per-key
+    /// sources](#per-key-sources).
     fn metadata(&self) -> Metadata;
 
     /// Returns the configuration data.
@@ -95,6 +152,52 @@ pub trait Provider {
         None
     }
 
+    /// Returns non-fatal [`Warning`]s about the configuration data, such as
+    /// input that was ignored. Called after [`Provider::data()`]. Warnings
+    /// without metadata are attributed to this provider's metadata. See
+    /// [`Figment::warnings()`](crate::Figment::warnings()).
+    ///
+    /// The default implementation returns no warnings.
+    fn warnings(&self) -> Vec<Warning> {
+        Vec::new()
+    }
+
+    /// Returns the [`Metadata`] of the tags applied to values in the
+    /// configuration data. Called after [`Provider::data()`]. See [tagging
+    /// values](#tagging-values).
+    ///
+    /// The default implementation returns no tags.
+    fn tags(&self) -> Map<Tag, Metadata> {
+        Map::new()
+    }
+
+    /// Returns a [`Snapshot`] of this provider: its metadata, data, profile,
+    /// warnings, and tags, all read at once. A [`Figment`](crate::Figment)
+    /// reads every provider it is given exactly once, via this method.
+    ///
+    /// The default implementation calls [`Snapshot::resolve()`], which calls
+    /// each method once. A provider that reads external state in more than one
+    /// method, like [`Env`](crate::providers::Env), should override it to read
+    /// the state once so that the methods agree with one another even if the
+    /// state changes in the meantime.
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, Provider, providers::Env};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_PORT", 8000);
+    ///     let snapshot = Env::prefixed("APP_").snapshot();
+    ///     jail.set_env("APP_PORT", 9000);
+    ///
+    ///     let figment = Figment::from(&snapshot);
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8000);
+    ///     Ok(())
+    /// });
+    /// ```
+    fn snapshot(&self) -> Snapshot {
+        Snapshot::resolve(self)
+    }
+
     /// This is used internally! Please, please don't use this externally. If
     /// you have a good usecase for this, let me know!
     #[doc(hidden)]
@@ -111,6 +214,18 @@ impl<T: Provider> Provider for &T {
         T::profile(self)
     }
 
+    fn warnings(&self) -> Vec<Warning> {
+        T::warnings(self)
+    }
+
+    fn tags(&self) -> Map<Tag, Metadata> {
+        T::tags(self)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        T::snapshot(self)
+    }
+
     #[doc(hidden)]
     fn __metadata_map(&self) -> Option<Map<Tag, Metadata>> {
         T::__metadata_map(self)
@@ -120,7 +235,7 @@ impl<T: Provider> Provider for &T {
 /// This is exactly `Serialized::global(K, V)`.
 impl<K: AsRef<str>, V: serde::Serialize> Provider for (K, V) {
     fn metadata(&self) -> Metadata {
-        use std::any::type_name;
+        use core::any::type_name;
         Metadata::named(format!("({}, {})", type_name::<K>(), type_name::<V>()))
     }
 
@@ -0,0 +1,185 @@
+use std::panic::Location;
+
+use crate::{Profile, Provider, Metadata};
+use crate::coalesce::Coalescible;
+use crate::error::Error;
+use crate::value::{Map, Dict, ParsePolicy};
+use crate::util::{nest, split_key};
+
+/// A `Provider` that sources values from `--key=value` command-line arguments
+/// without depending on an argument parser.
+///
+/// `Args` is meant for small tools that want command-line overrides but don't
+/// otherwise parse their arguments. Each argument of the form `--key=value`
+/// sets the value at the [key path] `key`, so `--server.port=8080` sets
+/// `server.port`. Values are parsed as [`Env`](crate::providers::Env) values
+/// are, so `--workers=4` is an integer and `--tags=[a, b]` is an array. For
+/// anything more involved, use a real argument parser; with `clap`, see
+/// [`Clap`](crate::providers::Clap).
+///
+/// Every other argument is ignored: positional arguments, short flags like
+/// `-v`, and long flags without an `=`, like `--verbose`. A lone `--` ends the
+/// scan so that the arguments following it are never read as overrides.
+///
+/// [key path]: crate::Figment#extraction
+///
+/// # Provider Details
+///
+///   * **Profile**
+///
+///     This provider does not set a profile.
+///
+///   * **Metadata**
+///
+///     This provider is named `command-line argument(s)`. The source location
+///     is set to the call site of the constructor. Paths are interpolated as
+///     the argument that sets them, for example `--server.port`.
+///
+///   * **Data**
+///
+///     Each `--key=value` argument is emitted at the key path `key`, as
+///     written, with its parsed value. Arguments with an empty key or key
+///     component, like `--=1` or `--a..b=1`, are skipped. When arguments set
+///     the same key, the last one wins; dictionaries are merged. Values are
+///     emitted to the configured profile, which defaults to
+///     [`Profile::Default`].
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use figment::{Figment, providers::{Args, Format, Toml}};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Config {
+///     name: String,
+///     port: u16,
+///     debug: bool,
+/// }
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("App.toml", "name = \"app\"\nport = 80\ndebug = false")?;
+///
+///     // In a real program, use `Args::parse()` to read `std::env::args()`.
+///     let args = ["input.txt", "--port=8080", "-v", "--debug=true"];
+///     let config: Config = Figment::new()
+///         .merge(Toml::file("App.toml"))
+///         .merge(Args::new(args))
+///         .extract()?;
+///
+///     assert_eq!(config, Config { name: "app".into(), port: 8080, debug: true });
+///     Ok(())
+/// });
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(nightly, doc(cfg(feature = "parse-value")))]
+pub struct Args {
+    dict: Dict,
+    /// The profile to emit values to. Defaults to [`Profile::Default`].
+    pub profile: Profile,
+    loc: &'static Location<'static>,
+}
+
+impl Args {
+    /// Constructs a provider from the arguments the process was started with,
+    /// [`std::env::args_os()`], skipping the program name. Non-Unicode
+    /// sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::Args};
+    ///
+    /// let figment = Figment::from(Args::parse());
+    /// ```
+    #[track_caller]
+    pub fn parse() -> Args {
+        let args = std::env::args_os().skip(1).map(|arg| arg.to_string_lossy().into_owned());
+        Args::new(args)
+    }
+
+    /// Constructs a provider from the arguments `args`. Unlike with
+    /// [`Args::parse()`], the first argument is not skipped.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::Args};
+    ///
+    /// let args = ["--name=app", "--server.port=8080", "--", "--name=ignored"];
+    /// let figment = Figment::from(Args::new(args));
+    /// assert_eq!(figment.extract_inner::<String>("name").unwrap(), "app");
+    /// assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+    /// ```
+    #[track_caller]
+    pub fn new<I>(args: I) -> Args
+        where I: IntoIterator, I::Item: AsRef<str>
+    {
+        let policy = ParsePolicy::default();
+        let mut dict = Dict::new();
+        for arg in args {
+            let arg = arg.as_ref();
+            if arg == "--" {
+                break;
+            }
+
+            let (key, value) = match arg.strip_prefix("--").and_then(|a| a.split_once('=')) {
+                Some((key, value)) => (key, value),
+                None => continue,
+            };
+
+            let key = key.trim();
+            if key.is_empty() || split_key(key).iter().any(|k| k.is_empty()) {
+                continue;
+            }
+
+            let nested_dict = nest(key, policy.parse(value))
+                .into_dict()
+                .expect("key is non-empty: must have dict");
+
+            dict = dict.merge(nested_dict);
+        }
+
+        Args { dict, profile: Profile::Default, loc: Location::caller() }
+    }
+
+    /// Sets the profile to emit values to.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::Args};
+    ///
+    /// let figment = Figment::from(Args::new(["--port=8000"]).profile("debug"));
+    /// assert!(figment.extract_inner::<u16>("port").is_err());
+    ///
+    /// let figment = figment.select("debug");
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    pub fn profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Sets the profile to emit values to to [`Profile::Global`], so that
+    /// command-line arguments take precedence over the values of every other
+    /// profile. Equivalent to `self.profile(Profile::Global)`.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::{Args, Serialized}};
+    ///
+    /// let figment = Figment::new()
+    ///     .merge(Args::new(["--port=8000"]).global())
+    ///     .merge(Serialized::default("port", 80).profile("debug"));
+    ///
+    /// assert_eq!(figment.select("debug").extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    pub fn global(self) -> Self {
+        self.profile(Profile::Global)
+    }
+}
+
+impl Provider for Args {
+    fn metadata(&self) -> Metadata {
+        Metadata::from("command-line argument(s)", self.loc)
+            .interpolater(|_, keys| format!("--{}", keys.join(".")))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        Ok(self.profile.collect(self.dict.clone()))
+    }
+}
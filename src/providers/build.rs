@@ -0,0 +1,231 @@
+use core::panic::Location;
+
+use crate::prelude::*;
+use crate::{Profile, Provider, Metadata};
+use crate::coalesce::Coalescible;
+use crate::error::Error;
+use crate::value::{Map, Dict, Value};
+use crate::util::nest;
+
+/// A `Provider` that sources values from the environment the binary was
+/// _compiled_ in, such as the package version, under a key prefix.
+///
+/// A `BuildEnv` is typically constructed with [`build_env!`], which reads the
+/// compile-time environment variables of the crate that invokes it. Because
+/// the values are baked into the binary, they are the same on every run and
+/// can't be overridden by the environment the binary later runs in. Exposing
+/// them as configuration makes build information available through the same
+/// extraction and introspection surface as every other value.
+///
+/// [`build_env!`]: crate::providers::build_env!
+///
+/// # Provider Details
+///
+///   * **Profile**
+///
+///     This provider does not set a profile.
+///
+///   * **Metadata**
+///
+///     This provider is named `compile-time environment`. The source location
+///     is set to the call site of the constructor, which for [`build_env!`] is
+///     the macro invocation. Paths to values read from variables are
+///     interpolated as the variable's name, as in `CARGO_PKG_VERSION`.
+///
+///   * **Data**
+///
+///     Each value is emitted as a string, without parsing, at its key nested
+///     under the prefix. Variables that were not set at compile time are not
+///     emitted. Values are emitted to the configured profile, which defaults
+///     to [`Profile::Default`].
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, providers::{BuildEnv, Serialized}};
+///
+/// let figment = Figment::new()
+///     .merge(Serialized::default("build.version", "unknown"))
+///     .merge(BuildEnv::new("build")
+///         .var("CARGO_PKG_VERSION", option_env!("CARGO_PKG_VERSION"))
+///         .var("GIT_SHA", option_env!("FIGMENT_UNSET_GIT_SHA"))
+///         .value("channel", "beta"));
+///
+/// let version: String = figment.extract_inner("build.version").unwrap();
+/// assert_eq!(version, env!("CARGO_PKG_VERSION"));
+/// assert_eq!(figment.extract_inner::<String>("build.channel").unwrap(), "beta");
+/// assert!(figment.find_value("build.git_sha").is_err());
+///
+/// let metadata = figment.find_metadata("build.version").unwrap();
+/// assert_eq!(metadata.interpolate(&"default".into(), &["build", "version"]), "CARGO_PKG_VERSION");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BuildEnv {
+    prefix: String,
+    dict: Dict,
+    names: Map<String, String>,
+    /// The profile to emit values to. Defaults to [`Profile::Default`].
+    pub profile: Profile,
+    loc: &'static Location<'static>,
+}
+
+impl BuildEnv {
+    /// Constructs a provider that emits no values, yet, to the key path
+    /// `prefix` in the default profile. An empty `prefix` emits values at the
+    /// root.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::BuildEnv};
+    ///
+    /// let figment = Figment::from(BuildEnv::new("").value("sha", "abc123"));
+    /// assert_eq!(figment.extract_inner::<String>("sha").unwrap(), "abc123");
+    /// ```
+    #[track_caller]
+    pub fn new(prefix: &str) -> BuildEnv {
+        BuildEnv {
+            prefix: prefix.into(),
+            dict: Dict::new(),
+            names: Map::new(),
+            profile: Profile::Default,
+            loc: Location::caller(),
+        }
+    }
+
+    /// Emits `value`, if it is `Some`, as the value of the variable `name`.
+    /// The key is `name`, lowercased, with a leading `CARGO_PKG_` removed:
+    /// `CARGO_PKG_VERSION` is emitted as `version` and `GIT_SHA` as `git_sha`.
+    ///
+    /// `value` is usually `option_env!(name)`, which must be invoked in the
+    /// crate whose compile-time environment is to be read.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::BuildEnv};
+    ///
+    /// let provider = BuildEnv::new("build")
+    ///     .var("CARGO_PKG_NAME", option_env!("CARGO_PKG_NAME"))
+    ///     .var("NOT_SET_AT_BUILD_TIME", None);
+    ///
+    /// let figment = Figment::from(provider);
+    /// assert_eq!(figment.extract_inner::<String>("build.name").unwrap(), env!("CARGO_PKG_NAME"));
+    /// assert!(figment.find_value("build.not_set_at_build_time").is_err());
+    /// ```
+    pub fn var(mut self, name: &str, value: Option<&str>) -> Self {
+        if let Some(value) = value {
+            let lower = name.trim().to_ascii_lowercase();
+            let key = lower.strip_prefix("cargo_pkg_").unwrap_or(&lower).to_string();
+            let path = match self.prefix.is_empty() {
+                true => key.clone(),
+                false => format!("{}.{}", self.prefix, key),
+            };
+
+            self.names.insert(path, name.into());
+            self = self.value(&key, value);
+        }
+
+        self
+    }
+
+    /// Emits `value` at the key `key`, nested under the prefix. An empty `key`
+    /// emits nothing.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::BuildEnv};
+    ///
+    /// let figment = Figment::from(BuildEnv::new("build").value("target.os", "linux"));
+    /// assert_eq!(figment.extract_inner::<String>("build.target.os").unwrap(), "linux");
+    /// ```
+    pub fn value(mut self, key: &str, value: &str) -> Self {
+        if let Some(nested_dict) = nest(key, Value::from(value)).into_dict() {
+            self.dict = core::mem::take(&mut self.dict).merge(nested_dict);
+        }
+
+        self
+    }
+
+    /// Sets the profile to emit values to.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::BuildEnv};
+    ///
+    /// let figment = Figment::from(BuildEnv::new("build").value("sha", "abc").profile("debug"));
+    /// assert!(figment.find_value("build.sha").is_err());
+    /// assert!(figment.select("debug").find_value("build.sha").is_ok());
+    /// ```
+    pub fn profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = profile.into();
+        self
+    }
+}
+
+impl Provider for BuildEnv {
+    fn metadata(&self) -> Metadata {
+        let names = self.names.clone();
+        Metadata::from("compile-time environment", self.loc)
+            .interpolater(move |_, keys| {
+                let key = keys.join(".");
+                names.get(&key).cloned().unwrap_or(key)
+            })
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let dict = match nest(&self.prefix, self.dict.clone().into()) {
+            Value::Dict(_, dict) => dict,
+            _ => unreachable!("nesting a dictionary yields a dictionary"),
+        };
+
+        Ok(self.profile.collect(dict))
+    }
+}
+
+/// Constructs a [`BuildEnv`] from the compile-time environment of the
+/// invoking crate, emitting build information under the key path `prefix`.
+///
+/// The provider emits the following values, each as a string, when available:
+///
+///   * `name`, `version`: `CARGO_PKG_NAME` and `CARGO_PKG_VERSION`.
+///   * `profile`: `"debug"` if the invoking crate was compiled with debug
+///     assertions and `"release"` otherwise.
+///
+/// Any additional arguments name variables to read as well, as with
+/// [`BuildEnv::var()`]. Variables that aren't set at compile time are skipped.
+/// Variables like a git commit hash can be set by a build script with
+/// `cargo:rustc-env`:
+///
+/// ```rust,no_run
+/// // build.rs
+/// use std::process::Command;
+///
+/// let output = Command::new("git").args(["rev-parse", "HEAD"]).output().unwrap();
+/// println!("cargo:rustc-env=GIT_SHA={}", String::from_utf8(output.stdout).unwrap());
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, providers::build_env};
+///
+/// let figment = Figment::from(build_env!("build", "GIT_SHA"));
+///
+/// let version: String = figment.extract_inner("build.version").unwrap();
+/// assert_eq!(version, env!("CARGO_PKG_VERSION"));
+///
+/// let profile: String = figment.extract_inner("build.profile").unwrap();
+/// assert_eq!(profile, if cfg!(debug_assertions) { "debug" } else { "release" });
+///
+/// // This crate doesn't set `GIT_SHA`.
+/// assert!(figment.find_value("build.git_sha").is_err());
+/// ```
+#[doc(inline)]
+pub use crate::__figment_build_env as build_env;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __figment_build_env {
+    ($prefix:expr $(, $var:literal)* $(,)?) => (
+        $crate::providers::BuildEnv::new($prefix)
+            .var("CARGO_PKG_NAME", option_env!("CARGO_PKG_NAME"))
+            .var("CARGO_PKG_VERSION", option_env!("CARGO_PKG_VERSION"))
+            .value("profile", if cfg!(debug_assertions) { "debug" } else { "release" })
+            $(.var($var, option_env!($var)))*
+    )
+}
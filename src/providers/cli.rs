@@ -0,0 +1,237 @@
+use std::panic::Location;
+
+use clap::{ArgAction, ArgMatches, Command, parser::ValueSource};
+
+use crate::{Profile, Provider, Metadata};
+use crate::coalesce::Coalescible;
+use crate::error::Error;
+use crate::value::{Value, Map, Dict, ParsePolicy};
+use crate::util::nest;
+
+/// A `Provider` that sources values from command-line arguments parsed by
+/// [`clap`], emitting only the values the user actually supplied.
+///
+/// Serializing a parsed `clap` struct wholesale with
+/// [`Serialized`](crate::providers::Serialized) emits every argument,
+/// including those filled in by `clap`'s
+/// [`default_value`](clap::Arg::default_value), and thus replaces values from
+/// previously merged files and environment variables with command-line
+/// defaults. `Clap` instead inspects each argument's [`ValueSource`] and skips
+/// any argument whose value is a default, so merging `Clap` last lets explicit
+/// arguments take precedence while defaults fall through to other sources.
+///
+/// # Provider Details
+///
+///   * **Profile**
+///
+///     This provider does not set a profile.
+///
+///   * **Metadata**
+///
+///     This provider is named `command-line argument(s)`. The source location
+///     is set to the call site of the constructor. Paths to arguments with a
+///     long flag are interpolated as the flag, for example `--log-level`.
+///
+///   * **Data**
+///
+///     Every argument present in the matches whose value was supplied on the
+///     command line or, with `clap`'s `env` feature, from an environment
+///     variable, is emitted at the [key path] of its [id](clap::Arg::get_id()).
+///     Raw values are parsed as [`Env`](crate::providers::Env) values are.
+///     Arguments that accept more than one value, or that use
+///     [`ArgAction::Append`], are emitted as arrays. The arguments of the
+///     selected subcommand, if any, are emitted in a dictionary keyed by the
+///     subcommand's name. Values are emitted to the configured profile, which
+///     defaults to [`Profile::Default`].
+///
+/// [key path]: crate::Figment#extraction
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use clap::{Arg, ArgAction, Command};
+/// use figment::{Figment, providers::{Clap, Format, Toml}};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Config {
+///     port: u16,
+///     log_level: String,
+///     verbose: bool,
+/// }
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("App.toml", r#"
+///         port = 8000
+///         log_level = "debug"
+///         verbose = false
+///     "#)?;
+///
+///     let command = Command::new("app")
+///         .arg(Arg::new("port").long("port").default_value("80"))
+///         .arg(Arg::new("log_level").long("log-level").default_value("info"))
+///         .arg(Arg::new("verbose").long("verbose").action(ArgAction::SetTrue));
+///
+///     let matches = command.clone().get_matches_from(["app", "--log-level", "warn"]);
+///     let config: Config = Figment::new()
+///         .merge(Toml::file("App.toml"))
+///         .merge(Clap::new(&command, &matches))
+///         .extract()?;
+///
+///     // `port` and `verbose` fall through to the file; `log_level` is overridden.
+///     assert_eq!(config, Config {
+///         port: 8000,
+///         log_level: "warn".into(),
+///         verbose: false,
+///     });
+///
+///     Ok(())
+/// });
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(nightly, doc(cfg(feature = "clap")))]
+pub struct Clap {
+    dict: Dict,
+    flags: Map<String, String>,
+    /// The profile to emit values to. Defaults to [`Profile::Default`].
+    pub profile: Profile,
+    loc: &'static Location<'static>,
+}
+
+impl Clap {
+    /// Constructs a provider that emits the user-supplied values in `matches`,
+    /// which must have been parsed by `command`, to the default profile.
+    ///
+    /// ```rust
+    /// use clap::{Arg, Command};
+    /// use figment::{Figment, providers::Clap};
+    ///
+    /// let command = Command::new("app")
+    ///     .arg(Arg::new("name").long("name").default_value("app"))
+    ///     .arg(Arg::new("tags").long("tag").num_args(1..));
+    ///
+    /// let matches = command.clone().get_matches_from(["app", "--tag", "a", "b"]);
+    /// let figment = Figment::from(Clap::new(&command, &matches));
+    /// assert!(figment.find_value("name").is_err());
+    /// assert_eq!(figment.extract_inner::<Vec<String>>("tags").unwrap(), ["a", "b"]);
+    /// ```
+    #[track_caller]
+    pub fn new(command: &Command, matches: &ArgMatches) -> Clap {
+        let mut command = command.clone();
+        command.build();
+
+        let mut flags = Map::new();
+        let dict = collect(&command, matches, "", &mut flags);
+        Clap { dict, flags, profile: Profile::Default, loc: Location::caller() }
+    }
+
+    /// Sets the profile to emit values to.
+    ///
+    /// ```rust
+    /// use clap::{Arg, Command};
+    /// use figment::{Figment, providers::Clap};
+    ///
+    /// let command = Command::new("app").arg(Arg::new("port").long("port"));
+    /// let matches = command.clone().get_matches_from(["app", "--port", "8000"]);
+    ///
+    /// let figment = Figment::from(Clap::new(&command, &matches).profile("debug"));
+    /// assert!(figment.extract_inner::<u16>("port").is_err());
+    ///
+    /// let figment = figment.select("debug");
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    pub fn profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Sets the profile to emit values to to [`Profile::Global`], so that
+    /// command-line arguments take precedence over the values of every other
+    /// profile. Equivalent to `self.profile(Profile::Global)`.
+    ///
+    /// ```rust
+    /// use clap::{Arg, Command};
+    /// use figment::{Figment, providers::{Clap, Serialized}};
+    ///
+    /// let command = Command::new("app").arg(Arg::new("port").long("port"));
+    /// let matches = command.clone().get_matches_from(["app", "--port", "8000"]);
+    ///
+    /// let figment = Figment::new()
+    ///     .merge(Clap::new(&command, &matches).global())
+    ///     .merge(Serialized::default("port", 80).profile("debug"));
+    ///
+    /// assert_eq!(figment.select("debug").extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    pub fn global(self) -> Self {
+        self.profile(Profile::Global)
+    }
+}
+
+/// Collects the user-supplied values of `command`'s arguments in `matches`,
+/// recording the long flag of each emitted argument at its key path in `flags`.
+fn collect(
+    command: &Command,
+    matches: &ArgMatches,
+    prefix: &str,
+    flags: &mut Map<String, String>,
+) -> Dict {
+    let policy = ParsePolicy::default();
+    let mut dict = Dict::new();
+    for arg in command.get_arguments() {
+        let id = arg.get_id().as_str();
+        match matches.value_source(id) {
+            Some(ValueSource::DefaultValue) | None => continue,
+            Some(_) => {}
+        }
+
+        let mut values: Vec<Value> = match matches.get_raw(id) {
+            Some(raw) => raw.map(|v| policy.parse(&v.to_string_lossy())).collect(),
+            None => continue,
+        };
+
+        let multiple = matches!(arg.get_action(), ArgAction::Append)
+            || arg.get_num_args().is_some_and(|n| n.max_values() > 1);
+
+        let value = match values.len() {
+            0 => continue,
+            1 if !multiple => values.remove(0),
+            _ => Value::from(values),
+        };
+
+        let key = format!("{}{}", prefix, id);
+        if let Some(long) = arg.get_long() {
+            flags.insert(key.clone(), format!("--{}", long));
+        }
+
+        let nested_dict = nest(id, value)
+            .into_dict()
+            .expect("key is non-empty: must have dict");
+
+        dict = dict.merge(nested_dict);
+    }
+
+    if let Some((name, sub_matches)) = matches.subcommand() {
+        if let Some(subcommand) = command.find_subcommand(name) {
+            let prefix = format!("{}{}.", prefix, name);
+            let sub_dict = collect(subcommand, sub_matches, &prefix, flags);
+            dict.insert(name.into(), sub_dict.into());
+        }
+    }
+
+    dict
+}
+
+impl Provider for Clap {
+    fn metadata(&self) -> Metadata {
+        let flags = self.flags.clone();
+        Metadata::from("command-line argument(s)", self.loc)
+            .interpolater(move |_, keys| {
+                let key = keys.join(".");
+                flags.get(&key).cloned().unwrap_or(key)
+            })
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        Ok(self.profile.collect(self.dict.clone()))
+    }
+}
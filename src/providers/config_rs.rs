@@ -0,0 +1,220 @@
+use std::panic::Location;
+
+use config::{Source as ConfigSource, Value as ConfigValue, ValueKind, ConfigError};
+
+use crate::{Figment, Profile, Provider, Metadata};
+use crate::error::Error;
+use crate::value::{Value, Map, Dict, Num, Empty, Tag};
+
+/// A `Provider` that sources values from a [`config`] crate (config-rs)
+/// [`Source`](config::Source), including a built [`config::Config`].
+///
+/// `ConfigRs` eases incremental migration from config-rs: existing sources can
+/// be merged into a [`Figment`] as-is. The reverse is also possible: a
+/// `Figment` is itself a config-rs [`Source`](config::Source) whose values are
+/// those of its selected profile, so it can be added to a
+/// [`config::ConfigBuilder`].
+///
+/// # Provider Details
+///
+///   * **Profile**
+///
+///     This provider does not set a profile.
+///
+///   * **Metadata**
+///
+///     This provider is named `config-rs source`. The source location is set
+///     to the call site of the constructor.
+///
+///   * **Data**
+///
+///     The source's values are collected as config-rs would collect them,
+///     with keys such as `a.b` or `a[0]` expanded into nested tables and
+///     arrays, and emitted to the configured profile, which defaults to
+///     [`Profile::Default`]. Integers and floats are emitted as their
+///     corresponding [`Num`], and config-rs's `Nil` as [`Empty::None`].
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use figment::{Figment, providers::{ConfigRs, Serialized}};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Config {
+///     name: String,
+///     ports: Vec<u16>,
+///     debug: bool,
+/// }
+///
+/// let legacy = config::Config::builder()
+///     .set_default("name", "app").unwrap()
+///     .set_default("ports", vec![80, 443]).unwrap()
+///     .build().unwrap();
+///
+/// let figment = Figment::new()
+///     .merge(ConfigRs::new(legacy))
+///     .join(Serialized::default("debug", true));
+///
+/// let config: Config = figment.extract().unwrap();
+/// assert_eq!(config, Config { name: "app".into(), ports: vec![80, 443], debug: true });
+///
+/// // And a `Figment` is a config-rs `Source`.
+/// let config: Config = config::Config::builder()
+///     .add_source(figment)
+///     .set_override("name", "override").unwrap()
+///     .build().unwrap()
+///     .try_deserialize().unwrap();
+///
+/// assert_eq!(config.name, "override");
+/// assert_eq!(config.ports, vec![80, 443]);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(nightly, doc(cfg(feature = "config")))]
+pub struct ConfigRs<S> {
+    /// The config-rs source to collect values from.
+    pub source: S,
+    /// The profile to emit values to. Defaults to [`Profile::Default`].
+    pub profile: Profile,
+    loc: &'static Location<'static>,
+}
+
+impl<S: ConfigSource> ConfigRs<S> {
+    /// Constructs a provider that emits the values in `source` to the default
+    /// profile.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::ConfigRs};
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_PORT", 8000);
+    ///
+    ///     let source = config::Environment::with_prefix("APP").try_parsing(true);
+    ///     let figment = Figment::from(ConfigRs::new(source));
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8000);
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn new(source: S) -> ConfigRs<S> {
+        ConfigRs { source, profile: Profile::Default, loc: Location::caller() }
+    }
+
+    /// Sets the profile to emit values to.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::ConfigRs};
+    ///
+    /// let source = config::Config::builder()
+    ///     .set_default("port", 8000).unwrap()
+    ///     .build().unwrap();
+    ///
+    /// let figment = Figment::from(ConfigRs::new(source).profile("debug"));
+    /// assert!(figment.extract_inner::<u16>("port").is_err());
+    ///
+    /// let figment = figment.select("debug");
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    pub fn profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Sets the profile to emit values to to [`Profile::Global`].
+    /// Equivalent to `self.profile(Profile::Global)`.
+    ///
+    /// ```rust
+    /// use figment::{Profile, providers::ConfigRs};
+    ///
+    /// let source = config::Config::builder().build().unwrap();
+    /// let provider = ConfigRs::new(source).global();
+    /// assert_eq!(provider.profile, Profile::Global);
+    /// ```
+    pub fn global(self) -> Self {
+        self.profile(Profile::Global)
+    }
+}
+
+impl<S: ConfigSource> Provider for ConfigRs<S> {
+    fn metadata(&self) -> Metadata {
+        Metadata::from("config-rs source", self.loc)
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut root = ConfigValue::new(None, config::Map::<String, ConfigValue>::new());
+        self.source.collect_to(&mut root).map_err(|e| e.to_string())?;
+        match from_config(root) {
+            Value::Dict(_, dict) => Ok(self.profile.collect(dict)),
+            _ => Ok(Map::new()),
+        }
+    }
+}
+
+impl ConfigSource for Figment {
+    fn clone_into_box(&self) -> Box<dyn ConfigSource + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<config::Map<String, ConfigValue>, ConfigError> {
+        let value = self.merged().map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        match into_config(self, value).kind {
+            ValueKind::Table(table) => Ok(table),
+            _ => Ok(config::Map::new()),
+        }
+    }
+}
+
+fn from_config(value: ConfigValue) -> Value {
+    let t = Tag::Default;
+    match value.kind {
+        ValueKind::Nil => Value::Empty(t, Empty::None),
+        ValueKind::Boolean(v) => Value::Bool(t, v),
+        ValueKind::I64(v) => Value::Num(t, Num::I64(v)),
+        ValueKind::I128(v) => Value::Num(t, Num::I128(v)),
+        ValueKind::U64(v) => Value::Num(t, Num::U64(v)),
+        ValueKind::U128(v) => Value::Num(t, Num::U128(v)),
+        ValueKind::Float(v) => Value::Num(t, Num::F64(v)),
+        ValueKind::String(v) => Value::String(t, v),
+        ValueKind::Table(table) => Value::Dict(t, table.into_iter()
+            .map(|(k, v)| (k, from_config(v)))
+            .collect()),
+        ValueKind::Array(vec) => Value::Array(t, vec.into_iter().map(from_config).collect()),
+    }
+}
+
+/// Converts `value` into a config-rs value whose origin is the name of the
+/// value's provider.
+fn into_config(figment: &Figment, value: Value) -> ConfigValue {
+    let origin = figment.get_metadata(value.tag()).map(|md| md.name.to_string());
+    let kind = match value {
+        Value::String(_, v) => ValueKind::String(v),
+        Value::Char(_, v) => ValueKind::String(v.to_string()),
+        Value::Bool(_, v) => ValueKind::Boolean(v),
+        Value::Num(_, v) => match v {
+            Num::U8(v) => ValueKind::U64(v.into()),
+            Num::U16(v) => ValueKind::U64(v.into()),
+            Num::U32(v) => ValueKind::U64(v.into()),
+            Num::U64(v) => ValueKind::U64(v),
+            Num::U128(v) => ValueKind::U128(v),
+            Num::USize(v) => ValueKind::U64(v as u64),
+            Num::I8(v) => ValueKind::I64(v.into()),
+            Num::I16(v) => ValueKind::I64(v.into()),
+            Num::I32(v) => ValueKind::I64(v.into()),
+            Num::I64(v) => ValueKind::I64(v),
+            Num::I128(v) => ValueKind::I128(v),
+            Num::ISize(v) => ValueKind::I64(v as i64),
+            Num::F32(v) => ValueKind::Float(v.into()),
+            Num::F64(v) => ValueKind::Float(v),
+        },
+        Value::Empty(..) => ValueKind::Nil,
+        Value::Dict(_, dict) => ValueKind::Table(dict.into_iter()
+            .map(|(k, v)| (k, into_config(figment, v)))
+            .collect()),
+        Value::Array(_, vec) => ValueKind::Array(vec.into_iter()
+            .map(|v| into_config(figment, v))
+            .collect()),
+    };
+
+    ConfigValue::new(origin.as_ref(), kind)
+}
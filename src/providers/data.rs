@@ -1,9 +1,12 @@
+use std::io::Read;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 
 use serde::de::{self, DeserializeOwned};
 
-use crate::value::{Map, Dict};
+use crate::value::{Map, Dict, Value};
+use crate::coalesce::Coalescible;
+use crate::error::Kind;
 use crate::{Error, Profile, Provider, Metadata};
 
 #[derive(Debug, Clone)]
@@ -52,25 +55,55 @@ enum Source {
 ///     parsed, and the parsed dictionary is emitted into the profile
 ///     configurable via [`Data::profile()`], which defaults to
 ///     [`Profile::Default`]. If the source is a file and the file is not
-///     present, an empty dictionary is emitted.
+///     present, an empty dictionary is emitted. If the file is present but
+///     cannot be opened, an error of kind [`Kind::Io`] carrying the
+///     [`std::io::ErrorKind`] and the file's path is returned. If the
+///     [`Policy`](crate::Policy) in effect denies reading the file, an error
+///     of kind [`Kind::Denied`] is returned. If the file is larger than the
+///     size set via [`Data::max_size()`], an error is returned without
+///     reading the file in full.
 ///
 ///   * **Data (Nested)**
 ///
 ///     When nesting is specified, the source value is exepcted to be a
 ///     dictionary. It's top-level keys are emitted as profiles, and the value
 ///     corresponding to each key as the profile data.
+///
+///   * **Data (Nested under a key)**
+///
+///     When nesting under a key is specified via [`Data::nested_in()`], the
+///     keys of the dictionary at that key path are emitted as profiles, and
+///     all other values as in the unnested case.
 #[derive(Debug, Clone)]
 pub struct Data<F: Format> {
     source: Source,
     /// The profile data will be emitted to if nesting is disabled. Defaults to
     /// [`Profile::Default`].
     pub profile: Option<Profile>,
+    profiles_key: Option<String>,
+    /// The maximum size of the file in bytes, if any.
+    max_size: Option<u64>,
+    /// Whether the file is memory-mapped instead of read.
+    #[cfg(feature = "mmap")]
+    mmap: bool,
+    /// Whether changes can be persisted to the file.
+    #[cfg(feature = "toml-edit")]
+    editable: bool,
     _format: PhantomData<F>,
 }
 
 impl<F: Format> Data<F> {
     fn new(source: Source, profile: Option<Profile>) -> Self {
-        Data { source, profile, _format: PhantomData }
+        Data {
+            source, profile,
+            profiles_key: None,
+            max_size: None,
+            #[cfg(feature = "mmap")]
+            mmap: false,
+            #[cfg(feature = "toml-edit")]
+            editable: false,
+            _format: PhantomData
+        }
     }
 
     /// Returns a `Data` provider that sources its values by parsing the file at
@@ -110,27 +143,41 @@ impl<F: Format> Data<F> {
     /// });
     /// ```
     pub fn file<P: AsRef<Path>>(path: P) -> Self {
-        fn find(path: &Path) -> Option<PathBuf> {
-            if path.is_absolute() {
-                match path.is_file() {
-                    true => return Some(path.to_path_buf()),
-                    false => return None
-                }
-            }
-
-            let cwd = std::env::current_dir().ok()?;
-            let mut cwd = cwd.as_path();
-            loop {
-                let file_path = cwd.join(path);
-                if file_path.is_file() {
-                    return Some(file_path);
-                }
+        let cwd = std::env::current_dir().unwrap_or_default();
+        Data::new(Source::File(find(&cwd, path.as_ref())), Some(Profile::Default))
+    }
 
-                cwd = cwd.parent()?;
-            }
-        }
+    /// Returns a `Data` provider that sources its values by parsing the file at
+    /// `path` as format `F`. Like [`Data::file()`] except that if `path` is
+    /// relative, the search begins in `dir` instead of the current working
+    /// directory. If `dir` is itself relative, it is relative to the current
+    /// working directory.
+    ///
+    /// Because the current working directory is process-global, a test that
+    /// relies on it can be disrupted by a test running in parallel that
+    /// changes it. With this method, a test, for instance one in a [`Jail`]
+    /// that does not change directories, locates files without relying on it.
+    ///
+    /// [`Jail`]: crate::Jail
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::{Format, Toml}};
+    ///
+    /// Jail::expect_with_no_chdir(|jail| {
+    ///     jail.create_file("Config.toml", "port = 8080")?;
+    ///
+    ///     let provider = Toml::file_in(jail.directory(), "Config.toml");
+    ///     assert_eq!(Figment::from(provider).extract_inner::<u16>("port")?, 8080);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn file_in<D: AsRef<Path>, P: AsRef<Path>>(dir: D, path: P) -> Self {
+        let dir = match std::env::current_dir() {
+            Ok(cwd) => cwd.join(dir),
+            Err(_) => dir.as_ref().to_path_buf(),
+        };
 
-        Data::new(Source::File(find(path.as_ref())), Some(Profile::Default))
+        Data::new(Source::File(find(&dir, path.as_ref())), Some(Profile::Default))
     }
 
     /// Returns a `Data` provider that sources its values by parsing the string
@@ -213,6 +260,54 @@ impl<F: Format> Data<F> {
     /// ```
     pub fn nested(mut self) -> Self {
         self.profile = None;
+        self.profiles_key = None;
+        self
+    }
+
+    /// Enables nesting under the key path `key` on `self`: the keys of the
+    /// dictionary at `key` are treated as profiles, while all other values are
+    /// emitted to the profile set via [`Data::profile()`], [`Profile::Default`]
+    /// by default. Values in a profile at `key` take precedence over other
+    /// values emitted to the same profile.
+    ///
+    /// This allows existing layouts with profile tables nested under a key,
+    /// like `[env.production]`, to be used as-is. If there is no value at
+    /// `key`, all values are emitted to the profile. If the value at `key`
+    /// or any profile's value isn't a dictionary, an error is returned.
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, Jail, providers::{Format, Toml}};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Config {
+    ///     name: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", r#"
+    ///         name = "app"
+    ///         port = 8000
+    ///
+    ///         [env.production]
+    ///         port = 80
+    ///     "#)?;
+    ///
+    ///     let figment = Figment::from(Toml::file("Config.toml").nested_in("env"));
+    ///
+    ///     let config: Config = figment.extract()?;
+    ///     assert_eq!(config, Config { name: "app".into(), port: 8000 });
+    ///
+    ///     let config: Config = figment.select("production").extract()?;
+    ///     assert_eq!(config, Config { name: "app".into(), port: 80 });
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn nested_in(mut self, key: &str) -> Self {
+        self.profile = Some(self.profile.unwrap_or_default());
+        self.profiles_key = Some(key.into());
         self
     }
 
@@ -237,29 +332,229 @@ impl<F: Format> Data<F> {
         self.profile = Some(profile.into());
         self
     }
+
+    /// Sets the profile to emit data to when nesting is disabled to
+    /// [`Profile::Global`], so that the values take precedence over the values
+    /// of every other profile. Equivalent to `self.profile(Profile::Global)`.
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::{Format, Toml}};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     let figment = Figment::new()
+    ///         .merge(Toml::string("port = 80").global())
+    ///         .merge(Toml::string("[debug]\nport = 8000").nested());
+    ///
+    ///     assert_eq!(figment.select("debug").extract_inner::<u16>("port")?, 80);
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn global(self) -> Self {
+        self.profile(Profile::Global)
+    }
+
+    /// Sets the maximum size, in bytes, of the file to be read. If the file is
+    /// larger, reading stops one byte past the maximum and an error naming the
+    /// file and the maximum is returned instead. This guards against
+    /// exhausting memory when a path mistakenly refers to a huge file. Has no
+    /// effect on string sources.
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::{Format, Toml}};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", "name = \"a rather long name\"")?;
+    ///
+    ///     let figment = Figment::from(Toml::file("Config.toml").max_size(1024));
+    ///     assert!(figment.extract_inner::<String>("name").is_ok());
+    ///
+    ///     let figment = Figment::from(Toml::file("Config.toml").max_size(16));
+    ///     let error = figment.extract_inner::<String>("name").unwrap_err();
+    ///     assert!(error.to_string().contains("exceeds the maximum of 16 bytes"));
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Memory-maps the file instead of reading it into a buffer, so that the
+    /// file's contents are parsed in place. This avoids copying large files
+    /// into memory before parsing them. Has no effect on string sources.
+    ///
+    /// A memory-mapped file is parsed with [`Format::from_str()`], bypassing
+    /// [`Format::from_path()`], and must contain valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The file is mapped, and parsed in place, every time the provider's
+    /// data is read, as by [`Figment::merge()`](crate::Figment::merge()). The
+    /// caller must ensure that the file is not modified or truncated, by this
+    /// or any other process, while it is mapped. Doing so is undefined
+    /// behavior.
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::{Format, Toml}};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.create_file("Config.toml", "port = 8080")?;
+    ///
+    ///     // SAFETY: Nothing modifies `Config.toml` while the figment exists.
+    ///     let figment = Figment::from(unsafe { Toml::file("Config.toml").mmap() });
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8080);
+    ///     Ok(())
+    /// });
+    /// ```
+    #[cfg(feature = "mmap")]
+    #[cfg_attr(nightly, doc(cfg(feature = "mmap")))]
+    pub unsafe fn mmap(mut self) -> Self {
+        self.mmap = true;
+        self
+    }
+
+    /// Reads and parses the source as a `T`. Returns `None` if the source is a
+    /// file that wasn't found.
+    fn parse<T: DeserializeOwned>(&self) -> Result<Option<T>, Error> {
+        // Profiles are the keys of a nested source or the entries of the
+        // dictionary at the profiles key, one level deeper than its path.
+        let _parse = crate::value::Limits::parse(match (&self.profiles_key, &self.profile) {
+            (Some(key), Some(_)) => Some(crate::util::split_key(key).len() + 1),
+            (_, Some(_)) => None,
+            (_, None) => Some(0),
+        });
+
+        let path = match &self.source {
+            Source::File(Some(path)) => path,
+            Source::File(None) => return Ok(None),
+            Source::String(s) => return self.from_str(s).map(Some),
+        };
+
+        // Read the file exactly once, here, so that I/O failures can be
+        // reported structurally; `F::from_path()` can't.
+        let io_error = |e: std::io::Error| Error::from(Kind::Io(e.kind(), path.clone()));
+        let file = crate::Policy::open(path)?;
+        let too_large = |max: u64| Error::from(format!("`{}` exceeds the maximum of {} bytes",
+            path.display(), max));
+
+        #[cfg(feature = "mmap")]
+        if self.mmap {
+            // SAFETY: The map is only read while it's alive, here, and the
+            // caller of the `unsafe` `mmap()` ensures the file isn't modified
+            // in the meantime.
+            let map = unsafe { memmap2::Mmap::map(&file) }.map_err(io_error)?;
+            if let Some(max) = self.max_size.filter(|&max| map.len() as u64 > max) {
+                return Err(too_large(max));
+            }
+
+            let string = std::str::from_utf8(&map).map_err(|e| e.to_string())?;
+            return self.from_str(string).map(Some);
+        }
+
+        // Read at most one byte more than the maximum, so that an oversized
+        // file is detected by the read itself, never read in full.
+        let mut bytes = Vec::new();
+        let limit = self.max_size.map_or(u64::MAX, |max| max.saturating_add(1));
+        file.take(limit).read_to_end(&mut bytes).map_err(io_error)?;
+        if let Some(max) = self.max_size.filter(|&max| bytes.len() as u64 > max) {
+            return Err(too_large(max));
+        }
+
+        let source = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+        self.from_str(&source).map(Some)
+    }
+
+    /// Parses `string` as a `T` with [`Format::from_str()`].
+    fn from_str<T: DeserializeOwned>(&self, string: &str) -> Result<T, Error> {
+        F::from_str(string).map_err(|e| e.to_string().into())
+    }
 }
 
 impl<F: Format> Provider for Data<F> {
     fn metadata(&self) -> Metadata {
         use Source::*;
-        match &self.source {
+        let mut metadata = match &self.source {
             String(_) => Metadata::named(format!("{} source string", F::NAME)),
             File(None) => Metadata::named(format!("{} file", F::NAME)),
-            File(Some(p)) => Metadata::from(format!("{} file", F::NAME), &**p)
-        }
+            File(Some(p)) => {
+                #[allow(unused_mut)]
+                let mut metadata = Metadata::from(format!("{} file", F::NAME), &**p);
+
+                #[cfg(feature = "toml-edit")]
+                if self.editable {
+                    let (profile, key) = (self.profile.clone(), self.profiles_key.clone());
+                    metadata.extra_mut().editable = Some(crate::providers::Editable::new(p.clone(), profile, key));
+                }
+
+                metadata
+            }
+        };
+
+        metadata.extra_mut().external = true;
+        metadata
     }
 
     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
-        use Source::*;
-        let map: Result<Map<Profile, Dict>, _> = match (&self.source, &self.profile) {
-            (File(None), _) => return Ok(Map::new()),
-            (File(Some(path)), None) => F::from_path(&path),
-            (String(s), None) => F::from_str(&s),
-            (File(Some(path)), Some(prof)) => F::from_path(&path).map(|v| prof.collect(v)),
-            (String(s), Some(prof)) => F::from_str(&s).map(|v| prof.collect(v)),
-        };
+        match (&self.profiles_key, &self.profile) {
+            (Some(key), Some(profile)) => match self.parse()? {
+                Some(dict) => split_profiles(dict, key, profile),
+                None => Ok(Map::new()),
+            },
+            (_, Some(profile)) => Ok(self.parse()?.map(|dict| profile.collect(dict)).unwrap_or_default()),
+            (_, None) => Ok(self.parse()?.unwrap_or_default()),
+        }
+    }
+}
+
+/// Emits the entries of the dictionary at the key path `key` in `dict` as
+/// profiles and the remainder of `dict` to `profile`.
+fn split_profiles(mut dict: Dict, key: &str, profile: &Profile) -> Result<Map<Profile, Dict>, Error> {
+    let path = crate::util::split_key(key);
+    let profiles = path.split_last().and_then(|(last, parents)| {
+        let parent = parents.iter().try_fold(&mut dict, |dict, k| match dict.get_mut(k)? {
+            Value::Dict(_, dict) => Some(dict),
+            _ => None,
+        })?;
+
+        crate::value::dict_remove(parent, last)
+    });
 
-        Ok(map.map_err(|e| e.to_string())?)
+    let mut map = profile.collect(dict);
+    let profiles = match profiles {
+        Some(Value::Dict(_, profiles)) => profiles,
+        Some(_) => return Err(format!("expected a dictionary of profiles at `{}`", key).into()),
+        None => return Ok(map),
+    };
+
+    for (name, value) in profiles {
+        let data = value.into_dict()
+            .ok_or_else(|| format!("expected profile `{}` at `{}` to be a dictionary", name, key))?;
+
+        let dict = map.entry(Profile::new(&name)).or_default();
+        *dict = std::mem::take(dict).merge(data);
+    }
+
+    Ok(map)
+}
+
+/// Searches for `path` in `dir` and its parents if `path` is relative.
+fn find(dir: &Path, path: &Path) -> Option<PathBuf> {
+    if path.is_absolute() {
+        match path.is_file() {
+            true => return Some(path.to_path_buf()),
+            false => return None
+        }
+    }
+
+    let mut cwd = dir;
+    loop {
+        let file_path = cwd.join(path);
+        if file_path.is_file() {
+            return Some(file_path);
+        }
+
+        cwd = cwd.parent()?;
     }
 }
 
@@ -322,8 +617,12 @@ pub trait Format: Sized {
     /// Parses the file at `path` as the data format `Self` as a `T` or returns
     /// an error if the `string` is an invalid `T`. The default implementation
     /// calls [`Format::from_str()`] with the contents of the file.
+    ///
+    /// [`Data`] does not call this method: it reads files itself, exactly
+    /// once, so that it can report I/O failures as [`Kind::Io`] errors, and
+    /// parses their contents with [`Format::from_str()`].
     fn from_path<T: DeserializeOwned>(path: &Path) -> Result<T, Self::Error> {
-        let source = std::fs::read_to_string(path).map_err(de::Error::custom)?;
+        let source = crate::Policy::read_to_string(path).map_err(de::Error::custom)?;
         Self::from_str(&source)
     }
 
@@ -334,6 +633,14 @@ pub trait Format: Sized {
         Data::file(path)
     }
 
+    /// Returns a `Data` provider that sources its values by parsing the file at
+    /// `path`, searched for starting in `dir`, as format `Self`. See
+    /// [`Data::file_in()`] for more details. The default implementation calls
+    /// `Data::file_in(dir, path)`.
+    fn file_in<D: AsRef<Path>, P: AsRef<Path>>(dir: D, path: P) -> Data<Self> {
+        Data::file_in(dir, path)
+    }
+
     /// Returns a `Data` provider that sources its values by parsing `string` as
     /// format `Self`. See [`Data::string()`] for more details. The default
     /// implementation calls `Data::string(string)`.
@@ -380,3 +687,34 @@ macro_rules! impl_format {
 impl_format!(Toml "TOML"/"toml": toml::from_str, toml::de::Error);
 impl_format!(Yaml "YAML"/"yaml": serde_yaml::from_str, serde_yaml::Error);
 impl_format!(Json "JSON"/"json": serde_json::from_str, serde_json::error::Error);
+
+#[cfg(feature = "toml-edit")]
+impl Data<Toml> {
+    /// Makes the file editable: changes can be written back to it with
+    /// [`Figment::persist()`](crate::Figment::persist()), preserving the
+    /// file's comments, ordering, and formatting. Has no effect on string
+    /// sources or if the file does not exist.
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::{Format, Toml}};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", "# The port to listen on.\nport = 80 # default\n")?;
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml").editable());
+    ///     figment.persist("port", 8080)?;
+    ///
+    ///     let contents = std::fs::read_to_string("App.toml").unwrap();
+    ///     assert_eq!(contents, "# The port to listen on.\nport = 8080 # default\n");
+    ///
+    ///     let figment = Figment::from(Toml::file("App.toml"));
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8080);
+    ///     Ok(())
+    /// });
+    /// ```
+    #[cfg_attr(nightly, doc(cfg(feature = "toml-edit")))]
+    pub fn editable(mut self) -> Self {
+        self.editable = true;
+        self
+    }
+}
@@ -0,0 +1,174 @@
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+
+use toml_edit::{DocumentMut, Item, Table, TableLike, InlineTable, Array};
+
+use crate::{Error, Profile};
+use crate::error::{Actual, Kind};
+use crate::value::{Value, Dict};
+
+/// A TOML file that [`Figment::persist()`](crate::Figment::persist()) can
+/// write changes back to. Recorded in the metadata of an
+/// [editable](crate::providers::Data::editable()) `Toml` provider.
+#[derive(Debug, Clone)]
+pub(crate) struct Editable {
+    /// The path to the file.
+    path: PathBuf,
+    /// The profile the file's values are emitted to if it isn't nested.
+    profile: Option<Profile>,
+    /// The key path of the file's profile tables if nested under a key.
+    profiles_key: Option<String>,
+    /// The key path of the file's values in the figment, if it was focused.
+    prefix: Vec<String>,
+}
+
+impl Editable {
+    pub(crate) fn new(path: PathBuf, profile: Option<Profile>, profiles_key: Option<String>) -> Self {
+        Editable { path, profile, profiles_key, prefix: vec![] }
+    }
+
+    /// Appends `prefix` to the key path of the file's values in the figment.
+    pub(crate) fn rebase(&mut self, prefix: &[String]) {
+        self.prefix.extend_from_slice(prefix);
+    }
+
+    /// Writes `value` at the key path `key` in `profile` to the file,
+    /// preserving the file's comments, ordering, and formatting.
+    pub(crate) fn persist(&self, profile: &Profile, key: &str, value: Value) -> Result<(), Error> {
+        let resolved = crate::Policy::resolve(&self.path)?;
+        let io_error = |e: std::io::Error| Kind::Io(e.kind(), self.path.clone());
+        let source = std::fs::read_to_string(&resolved).map_err(io_error)?;
+        let mut doc: DocumentMut = source.parse()
+            .map_err(|e| format!("failed to parse `{}`: {}", self.path.display(), e))?;
+
+        let mut path: Vec<String> = match (&self.profile, &self.profiles_key) {
+            (None, _) => vec![profile.as_str().as_str().into()],
+            (Some(p), Some(key)) if p != profile => {
+                let mut path = crate::util::split_key(key);
+                path.push(profile.as_str().as_str().into());
+                path
+            }
+            (Some(_), _) => vec![],
+        };
+
+        path.extend(self.prefix.iter().cloned());
+        path.extend(crate::util::split_key(key));
+        let value = path.into_iter().rev().fold(value, |value, key| {
+            let mut dict = Dict::new();
+            dict.insert(key, value);
+            dict.into()
+        });
+
+        let root = doc.as_table_mut();
+        for (key, value) in value.into_dict().unwrap_or_default() {
+            set(root, &key, &value, false).map_err(|e| e.prefixed(&key))?;
+        }
+
+        write_atomic(&resolved, doc.to_string().as_bytes()).map_err(io_error)?;
+        Ok(())
+    }
+}
+
+/// Replaces the contents of the file at `path` with `contents` by writing to a
+/// temporary file in the same directory and renaming it over `path`, so that
+/// readers observe either the old or the new contents, never a partial write.
+/// `path` must already be resolved: renaming over a symbolic link would replace
+/// the link, not the file it points to. The file's permissions are kept.
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let permissions = std::fs::metadata(path)?.permissions();
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()));
+
+    let result = std::fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.set_permissions(permissions)?;
+        file.sync_all()
+    });
+
+    match result.and_then(|_| std::fs::rename(&tmp, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// Sets `key` in `table` to `value`, merging dictionaries into existing tables
+/// and keeping the decoration, such as a trailing comment, of replaced values.
+fn set(table: &mut dyn TableLike, key: &str, value: &Value, inline: bool) -> Result<(), Error> {
+    if let Value::Dict(_, dict) = value {
+        if !table.get(key).is_some_and(Item::is_table_like) {
+            let item = match inline {
+                true => Item::Value(InlineTable::new().into()),
+                false => {
+                    let mut table = Table::new();
+                    table.set_implicit(true);
+                    Item::Table(table)
+                }
+            };
+
+            table.insert(key, item);
+        }
+
+        let item = table.get_mut(key).expect("inserted table");
+        let inline = item.is_inline_table();
+        let table = item.as_table_like_mut().expect("table-like item");
+        for (k, v) in dict {
+            set(table, k, v, inline).map_err(|e| e.prefixed(k))?;
+        }
+
+        return Ok(());
+    }
+
+    let mut new = to_toml(value)?;
+    match table.get_mut(key) {
+        Some(item) => {
+            if let Some(old) = item.as_value() {
+                *new.decor_mut() = old.decor().clone();
+            }
+
+            *item = Item::Value(new);
+        }
+        None => {
+            table.insert(key, Item::Value(new));
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts `value` into a TOML value. Dictionaries become inline tables.
+fn to_toml(value: &Value) -> Result<toml_edit::Value, Error> {
+    let out_of_range = |actual| Kind::InvalidValue(actual, "a 64-bit TOML integer".into());
+    Ok(match value {
+        Value::String(_, s) => s.as_str().into(),
+        Value::Char(_, c) => c.to_string().into(),
+        Value::Bool(_, b) => (*b).into(),
+        Value::Num(_, n) => match n.to_actual() {
+            Actual::Unsigned(v) => i64::try_from(v).map_err(|_| out_of_range(n.to_actual()))?.into(),
+            Actual::Signed(v) => i64::try_from(v).map_err(|_| out_of_range(n.to_actual()))?.into(),
+            Actual::Float(v) => v.into(),
+            actual => return Err(Kind::Unsupported(actual).into()),
+        },
+        Value::Empty(_, e) => return Err(Kind::Unsupported(e.to_actual()).into()),
+        Value::Dict(_, dict) => {
+            let mut table = InlineTable::new();
+            for (k, v) in dict {
+                table.insert(k, to_toml(v).map_err(|e| e.prefixed(k))?);
+            }
+
+            table.into()
+        }
+        Value::Array(_, array) => {
+            let mut items = Array::new();
+            for (i, v) in array.iter().enumerate() {
+                items.push(to_toml(v).map_err(|e| e.prefixed_index(i))?);
+            }
+
+            items.into()
+        }
+    })
+}
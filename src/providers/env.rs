@@ -1,10 +1,13 @@
 use std::fmt;
+use std::ffi::OsString;
 
 use crate::{Profile, Provider, Metadata};
+use crate::providers::Snapshot;
 use crate::coalesce::Coalescible;
-use crate::value::{Map, Dict};
+use crate::value::{Map, Dict, ParsePolicy};
 use crate::error::Error;
-use crate::util::nest;
+use crate::util::{nest, split_key};
+use crate::warning::{Warning, Kind};
 
 use uncased::{Uncased, UncasedStr};
 
@@ -55,6 +58,13 @@ crate::util::cloneable_fn_trait!(
 /// Undelimited strings, or strings with invalid escape sequences, are
 /// interpreted exactly as written without any escaping.
 ///
+/// The inference of booleans, floats, and integers can be adjusted with a
+/// [`ParsePolicy`](crate::value::ParsePolicy) via [`Env::parse_policy()`].
+/// [`Env::lenient_bools()`] additionally reads `yes`/`no` and `on`/`off` as
+/// booleans.
+/// To generate values in this syntax programmatically, see the functions in
+/// [`value::escape`](crate::value::escape).
+///
 /// [key paths]: crate::Figment#extraction
 /// [`Value`]: crate::value::Value
 /// [`Bool`]: crate::value::Value::Bool
@@ -77,6 +87,28 @@ crate::util::cloneable_fn_trait!(
 /// the name with `.` with [`Env::map()`]. The [`Env::split()`] method is a
 /// convenience method that does exactly this.
 ///
+/// # Profile Annotations
+///
+/// When enabled via [`Env::profile_annotations()`], a name ending in
+/// `@profile` emits its value to `profile` instead of the [configured
+/// profile](Env::profile()). For example, with `Env::prefixed("APP_")`, the
+/// variable `APP_PORT@RELEASE=9999` sets `port` in the `release` profile only.
+/// Like `.`, an `@` can be substituted into names with [`Env::map()`].
+///
+/// ```rust
+/// use figment::{Figment, Jail, providers::Env};
+///
+/// Jail::expect_with(|jail| {
+///     jail.set_env("APP_PORT", 8000);
+///     jail.set_env("APP_PORT@RELEASE", 9999);
+///
+///     let figment = Figment::from(Env::prefixed("APP_").profile_annotations());
+///     assert_eq!(figment.extract_inner::<u16>("port")?, 8000);
+///     assert_eq!(figment.clone().select("release").extract_inner::<u16>("port")?, 9999);
+///     Ok(())
+/// });
+/// ```
+///
 /// # Provider Details
 ///
 ///   * **Profile**
@@ -93,9 +125,13 @@ crate::util::cloneable_fn_trait!(
 ///
 ///     The data emitted by this provider is single-level dictionary with the
 ///     keys and values returned by [`Env::iter()`], which reads from the
-///     currently set environment variables and is customizable via the various
-///     inherent methods. The dictionary is emitted to the profile
+///     currently set environment variables, or from those captured by
+///     [`Env::capture()`], and is customizable via the various inherent
+///     methods. The dictionary is emitted to the profile
 ///     [`profile`](#structfield.profile), configurable via [`Env::profile()`].
+///     If the [`Policy`](crate::Policy) in effect denies reading the
+///     environment, an error of kind [`Kind::Denied`](crate::error::Kind::Denied) is
+///     returned instead.
 #[derive(Clone)]
 #[cfg_attr(nightly, doc(cfg(feature = "env")))]
 pub struct Env {
@@ -105,6 +141,16 @@ pub struct Env {
     pub profile: Profile,
     /// We use this to generate better metadata when available.
     prefix: Option<String>,
+    /// The pattern keys are split at, also for better metadata.
+    split: Option<String>,
+    /// The policy used to parse values.
+    policy: ParsePolicy,
+    /// A snapshot of the variables, if captured. Otherwise, read live.
+    captured: Option<Vec<(OsString, OsString)>>,
+    /// Whether variables that set the same key are an error.
+    deny_duplicates: bool,
+    /// Whether a trailing `@profile` in a key selects the profile.
+    profile_annotations: bool,
 }
 
 impl fmt::Debug for Env {
@@ -117,7 +163,16 @@ impl Env {
     fn new<F: Clone + 'static>(f: F) -> Self
         where F: Fn(&UncasedStr) -> Option<Uncased>
     {
-        Env { filter_map: Box::new(f), profile: Profile::Default, prefix: None }
+        Env {
+            filter_map: Box::new(f),
+            profile: Profile::Default,
+            prefix: None,
+            split: None,
+            policy: ParsePolicy::default(),
+            captured: None,
+            deny_duplicates: false,
+            profile_annotations: false,
+        }
     }
 
     fn chain<F: Clone + 'static>(self, f: F) -> Self
@@ -126,7 +181,9 @@ impl Env {
         let filter_map = self.filter_map;
         Env {
             filter_map: Box::new(move |key| f(filter_map(key))), profile: self.profile,
-            prefix: self.prefix
+            prefix: self.prefix, split: self.split, policy: self.policy,
+            captured: self.captured, deny_duplicates: self.deny_duplicates,
+            profile_annotations: self.profile_annotations,
         }
     }
 
@@ -271,7 +328,7 @@ impl Env {
     ///
     /// ```rust
     /// use serde::Deserialize;
-    /// use figment::{Figment, Jail, util::map, value::Dict, providers::Env};
+    /// use figment::{Figment, Jail, value::Dict, providers::Env};
     ///
     /// #[derive(Debug, PartialEq, Deserialize)]
     /// struct Foo {
@@ -292,7 +349,7 @@ impl Env {
     ///     let config: Config = Figment::from(Env::prefixed("APP_")).extract()?;
     ///     assert_eq!(config, Config {
     ///         foo: Foo { key: 10 },
-    ///         map: map!["one".into() => 1u8.into(), "two".into() => 2.0.into()],
+    ///         map: vec![("one".into(), 1u8.into()), ("two".into(), 2.0.into())].into_iter().collect(),
     ///     });
     ///
     ///     // With splitting.
@@ -306,7 +363,7 @@ impl Env {
     ///
     ///     assert_eq!(config, Config {
     ///         foo: Foo { key: 20 },
-    ///         map: map!["one".into() => 1.0.into(), "two".into() => "dos".into()],
+    ///         map: vec![("one".into(), 1.0.into()), ("two".into(), "dos".into())].into_iter().collect(),
     ///     });
     ///
     ///     Ok(())
@@ -314,7 +371,13 @@ impl Env {
     /// ```
     pub fn split<P: Into<String>>(self, pattern: P) -> Self {
         let pattern = pattern.into();
-        self.map(move |key| key.as_str().replace(&pattern, ".").into())
+        let mut env = self.map({
+            let pattern = pattern.clone();
+            move |key| key.as_str().replace(&pattern, ".").into()
+        });
+
+        env.split = Some(pattern);
+        env
     }
 
     /// Filters out all environment variable keys contained in `keys`.
@@ -408,17 +471,100 @@ impl Env {
     /// });
     /// ```
     pub fn iter<'a>(&'a self) -> impl Iterator<Item=(Uncased, String)> + 'a {
-        std::env::vars_os()
+        self.vars().filter_map(|(_, key, value)| Some((key.ok()?, value)))
+    }
+
+    /// Returns an iterator over the name, key, and value of every environment
+    /// variable that passes the filter. The key is `Err` if the variable is
+    /// ignored because the key has an empty component.
+    fn vars<'a>(&'a self) -> impl Iterator<Item=(String, Result<Uncased<'static>, ()>, String)> + 'a {
+        let allowed = crate::Policy::current().check(&crate::Capability::Env).is_ok();
+        let live = self.captured.is_none().then(std::env::vars_os).into_iter().flatten();
+        live.chain(self.captured.iter().flatten().cloned())
+            .filter(move |_| allowed)
             .filter(|(k, _)| !k.is_empty())
             .filter_map(move |(k, v)| {
+                let name = k.to_string_lossy().to_string();
                 let key = Uncased::from(k.to_string_lossy());
                 let key = (self.filter_map)(&key)?;
                 let key = key.as_str().trim().to_ascii_lowercase();
-                if key.split('.').any(|s| s.is_empty()) { return None }
-                Some((key.into(), v.to_string_lossy().to_string()))
+                let (path, _) = self.split_profile(&key);
+                let key = match split_key(path).iter().any(|s| s.is_empty()) {
+                    true => Err(()),
+                    false => Ok(key.into()),
+                };
+
+                Some((name, key, v.to_string_lossy().to_string()))
             })
     }
 
+    /// Snapshots the environment variables that pass the filter now so that
+    /// later reads, such as on every extraction or reload of a figment, see
+    /// the same variables even if the environment changes in the meantime.
+    /// Filters and maps applied after capturing apply to the snapshot.
+    ///
+    /// By default, an `Env` reads the environment live, on every use. Use
+    /// [`Env::live()`] to revert to reading live. If the
+    /// [`Policy`](crate::Policy) in effect denies reading the environment,
+    /// nothing is captured. The policy in effect is checked again on every
+    /// read of the captured variables.
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::Env};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_PORT", 8000);
+    ///
+    ///     let env = Env::prefixed("APP_").capture();
+    ///     jail.set_env("APP_PORT", 9999);
+    ///     jail.set_env("APP_NAME", "app");
+    ///
+    ///     let figment = Figment::from(env.clone());
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 8000);
+    ///     assert!(figment.find_value("name").is_err());
+    ///
+    ///     let figment = Figment::from(env.live());
+    ///     assert_eq!(figment.extract_inner::<u16>("port")?, 9999);
+    ///     assert_eq!(figment.extract_inner::<String>("name")?, "app");
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn capture(mut self) -> Self {
+        if crate::Policy::current().check(&crate::Capability::Env).is_err() {
+            self.captured = Some(vec![]);
+            return self;
+        }
+
+        let vars = std::env::vars_os()
+            .filter(|(k, _)| !k.is_empty())
+            .filter(|(k, _)| (self.filter_map)(&Uncased::from(k.to_string_lossy())).is_some())
+            .collect();
+
+        self.captured = Some(vars);
+        self
+    }
+
+    /// Discards any snapshot taken by [`Env::capture()`] so that environment
+    /// variables are read live, on every use. This is the default.
+    ///
+    /// ```rust
+    /// use figment::{Jail, providers::Env};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     let env = Env::prefixed("APP_").capture();
+    ///     jail.set_env("APP_PORT", 8000);
+    ///     assert_eq!(env.iter().count(), 0);
+    ///     assert_eq!(env.live().iter().count(), 1);
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn live(mut self) -> Self {
+        self.captured = None;
+        self
+    }
+
     /// Sets the profile config data will be emitted to.
     ///
     /// ```rust
@@ -451,6 +597,197 @@ impl Env {
         self
     }
 
+    /// Sets the [`ParsePolicy`] used to parse environment variable values.
+    /// Defaults to [`ParsePolicy::default()`].
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::Env, value::ParsePolicy};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_VERSION", "1.10");
+    ///     jail.set_env("APP_ZIP", "02134");
+    ///     jail.set_env("APP_MODE", "0o644");
+    ///
+    ///     let policy = ParsePolicy::new()
+    ///         .floats(false)
+    ///         .radix_ints(true)
+    ///         .parser(|s| {
+    ///             let zip = s.len() == 5 && s.bytes().all(|b| b.is_ascii_digit());
+    ///             zip.then(|| s.into())
+    ///         });
+    ///
+    ///     let figment = Figment::from(Env::prefixed("APP_").parse_policy(policy));
+    ///     assert_eq!(figment.extract_inner::<String>("version")?, "1.10");
+    ///     assert_eq!(figment.extract_inner::<String>("zip")?, "02134");
+    ///     assert_eq!(figment.extract_inner::<u32>("mode")?, 0o644);
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn parse_policy(mut self, policy: ParsePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Parses `yes`, `on`, `no`, and `off`, in any case, as booleans, in
+    /// addition to `true` and `false`. Equivalent to setting
+    /// [`ParsePolicy::lenient_bools()`] on the current parse policy.
+    ///
+    /// Without this, `APP_DEBUG=yes` is read as the string `"yes"`, which
+    /// fails to extract as a `bool`. The values `1` and `0` are always read
+    /// as `true` and `false` when a boolean is expected, with or without this
+    /// option, and remain integers otherwise.
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, Jail, providers::Env};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Config {
+    ///     debug: bool,
+    ///     color: bool,
+    ///     cache: bool,
+    ///     workers: u8,
+    /// }
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_DEBUG", "yes");
+    ///     jail.set_env("APP_COLOR", "Off");
+    ///     jail.set_env("APP_CACHE", "1");
+    ///     jail.set_env("APP_WORKERS", "1");
+    ///
+    ///     let figment = Figment::from(Env::prefixed("APP_"));
+    ///     assert!(figment.extract::<Config>().is_err());
+    ///
+    ///     let figment = Figment::from(Env::prefixed("APP_").lenient_bools());
+    ///     assert_eq!(figment.extract::<Config>()?, Config {
+    ///         debug: true,
+    ///         color: false,
+    ///         cache: true,
+    ///         workers: 1,
+    ///     });
+    ///
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn lenient_bools(mut self) -> Self {
+        self.policy = self.policy.lenient_bools(true);
+        self
+    }
+
+    /// Makes variables that set the same key an error instead of a warning.
+    ///
+    /// Several variables can set the same key after their names are mapped
+    /// to keys: `APP_PORT` and `app_port` both set `port`, as can variables
+    /// renamed by [`Env::map()`]. A variable also collides with one that sets
+    /// a key nested under its key, as `APP_DB=x` does with `APP_DB_URL=y`
+    /// when split at `_`. Only one value can be used, so such collisions are
+    /// almost always mistakes. By default, the value read last is used, and a
+    /// [`DuplicateKey`](Kind::DuplicateKey) warning naming the variables is
+    /// emitted. With this option, reading the variables fails instead.
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::Env, warning::Kind};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_PORT", 8000);
+    ///     jail.set_env("APP_PROXY", 8001);
+    ///
+    ///     let env = Env::prefixed("APP_").map(|key| match key == "proxy" {
+    ///         true => "port".into(),
+    ///         false => key.into(),
+    ///     });
+    ///
+    ///     let figment = Figment::from(env.clone());
+    ///     let warning = &figment.warnings().iter().next().unwrap();
+    ///     assert!(matches!(&warning.kind, Kind::DuplicateKey(names) if names.len() == 2));
+    ///     assert_eq!(warning.key.as_deref(), Some("port"));
+    ///
+    ///     let error = Figment::from(env.deny_duplicates()).extract_inner::<u16>("port");
+    ///     assert!(error.unwrap_err().to_string().contains("set the same key `port`"));
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn deny_duplicates(mut self) -> Self {
+        self.deny_duplicates = true;
+        self
+    }
+
+    /// Enables `@profile` annotations: a variable whose key ends in
+    /// `@profile` emits its value to `profile` instead of the [configured
+    /// profile](Env::profile()). See [profile
+    /// annotations](#profile-annotations). Disabled by default, so that an
+    /// `@` in a key is part of the key.
+    ///
+    /// ```rust
+    /// use figment::{Figment, Jail, providers::Env};
+    ///
+    /// Jail::expect_with(|jail| {
+    ///     jail.set_env("APP_ADMIN@LOCALHOST", "root");
+    ///
+    ///     let figment = Figment::from(Env::prefixed("APP_"));
+    ///     assert_eq!(figment.extract_inner::<String>("admin@localhost")?, "root");
+    ///
+    ///     let figment = Figment::from(Env::prefixed("APP_").profile_annotations());
+    ///     let figment = figment.select("localhost");
+    ///     assert_eq!(figment.extract_inner::<String>("admin")?, "root");
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn profile_annotations(mut self) -> Self {
+        self.profile_annotations = true;
+        self
+    }
+
+    /// Splits a trailing `@profile` annotation off of `key` if annotations
+    /// are enabled.
+    fn split_profile<'k>(&self, key: &'k str) -> (&'k str, Option<Profile>) {
+        match self.profile_annotations {
+            true => crate::util::split_profile(key),
+            false => (key, None),
+        }
+    }
+
+    /// Returns the profile, the key, and the names in the order read of every
+    /// group of variables that set the same key or a key and one nested under
+    /// it, keyed by the shortest key.
+    fn duplicates(&self) -> Vec<(Profile, String, Vec<String>)> {
+        let vars: Vec<(Profile, Vec<String>, String, String)> = self.vars()
+            .filter_map(|(name, key, _)| {
+                let key = key.ok()?;
+                let (path, profile) = self.split_profile(key.as_str());
+                let profile = profile.unwrap_or_else(|| self.profile.clone());
+                Some((profile, split_key(path), path.to_string(), name))
+            })
+            .collect();
+
+        let mut groups: Vec<(Profile, String, Vec<usize>)> = vec![];
+        for (i, (p1, path1, key1, _)) in vars.iter().enumerate() {
+            for (j, (p2, path2, key2, _)) in vars.iter().enumerate().skip(i + 1) {
+                let key = match () {
+                    _ if p1 != p2 => continue,
+                    _ if path2.starts_with(path1) => key1,
+                    _ if path1.starts_with(path2) => key2,
+                    _ => continue,
+                };
+
+                match groups.iter_mut().find(|(p, k, _)| p == p1 && k == key) {
+                    Some((_, _, indices)) => indices.extend([i, j]),
+                    None => groups.push((p1.clone(), key.clone(), vec![i, j])),
+                }
+            }
+        }
+
+        groups.into_iter()
+            .map(|(profile, key, mut indices)| {
+                indices.sort_unstable();
+                indices.dedup();
+                let names = indices.into_iter().map(|i| vars[i].3.clone()).collect();
+                (profile, key, names)
+            })
+            .collect()
+    }
+
     /// A convenience method to retrieve the value for an environment variable
     /// with name `name`. Retrieval is case-insensitive.
     ///
@@ -495,14 +832,37 @@ impl Env {
 
 impl Provider for Env {
     fn metadata(&self) -> Metadata {
-        let mut md = Metadata::named("environment variable(s)")
-            .interpolater(move |_: &Profile, k: &[&str]| {
-                let keys: Vec<_> = k.iter()
-                    .map(|k| k.to_ascii_uppercase())
-                    .collect();
+        // Map each key path back to the name of the variable it was read from
+        // so that errors name the variable itself, as in `APP_DATABASE_URL`.
+        let variables: Vec<(Profile, Vec<String>, String)> = self.vars()
+            .filter_map(|(name, key, _)| {
+                let key = key.ok()?;
+                let (path, profile) = self.split_profile(key.as_str());
+                let profile = profile.unwrap_or_else(|| self.profile.clone());
+                Some((profile, split_key(path), name))
+            })
+            .collect();
 
-                keys.join(".")
-            });
+        let prefix = self.prefix.as_ref().map(|p| p.to_ascii_uppercase());
+        let split = self.split.clone();
+        let mut md = Metadata::named("environment variable(s)");
+        md.extra_mut().variables = variables;
+        let recorded = md.clone();
+        let mut md = md.interpolater(move |profile: &Profile, k: &[&str]| {
+            if let Some(name) = recorded.variable(profile, k) {
+                return name.to_string();
+            }
+
+            let keys: Vec<_> = k.iter()
+                .map(|k| k.to_ascii_uppercase())
+                .collect();
+
+            match (&prefix, &split) {
+                (_, Some(split)) => format!("{}{}", prefix.as_deref().unwrap_or(""), keys.join(split)),
+                (Some(prefix), None) if keys.len() == 1 => format!("{}{}", prefix, keys[0]),
+                _ => keys.join("."),
+            }
+        });
 
         if let Some(prefix) = &self.prefix {
             md.name = format!("`{}` {}", prefix.to_ascii_uppercase(), md.name).into();
@@ -512,15 +872,84 @@ impl Provider for Env {
     }
 
     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
-        let mut dict = Dict::new();
+        crate::Policy::current().check(&crate::Capability::Env)?;
+
+        if self.deny_duplicates {
+            let mut errors: Option<Error> = None;
+            for (_, key, names) in self.duplicates() {
+                let names: Vec<_> = names.iter().map(|name| format!("`{}`", name)).collect();
+                let error = Error::from(format!("environment variables {} set the same key `{}`",
+                    names.join(" and "), key));
+
+                errors = Some(match errors {
+                    Some(errors) => error.chain(errors),
+                    None => error,
+                });
+            }
+
+            if let Some(errors) = errors {
+                return Err(errors);
+            }
+        }
+
+        let mut map = self.profile.collect(Dict::new());
         for (k, v) in self.iter() {
-            let nested_dict = nest(k.as_str(), v.parse().expect("infallible"))
+            let (key, profile) = self.split_profile(k.as_str());
+            let nested_dict = nest(key, self.policy.parse(&v))
                 .into_dict()
                 .expect("key is non-empty: must have dict");
 
-            dict = dict.merge(nested_dict);
+            let dict = map.entry(profile.unwrap_or_else(|| self.profile.clone())).or_default();
+            *dict = std::mem::take(dict).merge(nested_dict);
         }
 
-        Ok(self.profile.collect(dict))
+        Ok(map)
+    }
+
+    /// Warns about every variable that is ignored because its key has an empty
+    /// component, about every value that looks like an array or dictionary
+    /// but fails to parse as one and is thus read as a string, and, unless
+    /// they're denied, about variables that set the same key.
+    fn warnings(&self) -> Vec<Warning> {
+        let mut warnings = vec![];
+        if !self.deny_duplicates {
+            for (profile, key, names) in self.duplicates() {
+                warnings.push(Warning::new(Kind::DuplicateKey(names))
+                    .with_key(key)
+                    .with_profile(profile));
+            }
+        }
+
+        for (name, key, value) in self.vars() {
+            let key = match key {
+                Ok(key) => key,
+                Err(()) => {
+                    warnings.push(Warning::new(Kind::IgnoredEnvVar(name)));
+                    continue;
+                }
+            };
+
+            let raw = value.trim();
+            let structured = raw.starts_with('[') || raw.starts_with('{');
+            if structured && self.policy.parse(&value).as_str().is_some() {
+                let (path, profile) = self.split_profile(key.as_str());
+                let profile = profile.unwrap_or_else(|| self.profile.clone());
+                warnings.push(Warning::new(Kind::ParseFallback(value.clone()))
+                    .with_key(path)
+                    .with_profile(profile));
+            }
+        }
+
+        warnings
+    }
+
+    /// Reads the environment once, as by [`Env::capture()`], unless it's
+    /// already been captured, so that the metadata, data, and warnings of the
+    /// snapshot all derive from the same variables.
+    fn snapshot(&self) -> Snapshot {
+        match self.captured {
+            Some(_) => Snapshot::resolve(self),
+            None => Snapshot::resolve(&self.clone().capture()),
+        }
     }
 }
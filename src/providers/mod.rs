@@ -4,10 +4,32 @@
 //! description of each provider.
 
 mod serialized;
-mod data;
+mod snapshot;
+mod build;
+#[cfg(feature = "std")] mod data;
+#[cfg(feature = "std")] mod system;
+#[cfg(feature = "toml-edit")] mod edit;
 
-#[cfg(feature = "env")] mod env;
-#[cfg(feature = "env")] pub use self::env::Env;
+#[cfg(all(feature = "env", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+mod env;
+#[cfg(all(feature = "env", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+pub use self::env::Env;
+
+#[cfg(feature = "config")] mod config_rs;
+#[cfg(feature = "config")] pub use self::config_rs::ConfigRs;
+
+#[cfg(feature = "parse-value")] mod args;
+#[cfg(feature = "parse-value")] pub use self::args::Args;
+
+#[cfg(feature = "clap")] mod cli;
+#[cfg(feature = "clap")] pub use self::cli::Clap;
+
+#[cfg(feature = "web")] mod web;
+#[cfg(feature = "web")] pub use self::web::{Js, LocalStorage};
 
 pub use self::serialized::Serialized;
-pub use self::data::*;
+pub use self::snapshot::Snapshot;
+pub use self::build::{BuildEnv, build_env};
+#[cfg(feature = "std")] pub use self::data::*;
+#[cfg(feature = "std")] pub use self::system::SystemInfo;
+#[cfg(feature = "toml-edit")] pub(crate) use self::edit::Editable;
@@ -1,7 +1,8 @@
-use std::panic::Location;
+use core::panic::Location;
 
 use serde::Serialize;
 
+use crate::prelude::*;
 use crate::{Profile, Provider, Metadata};
 use crate::error::{Error, Kind::InvalidType};
 use crate::value::{Value, Map, Dict};
@@ -16,7 +17,7 @@ use crate::value::{Value, Map, Dict};
 ///
 ///   * **Metadata**
 ///
-///     This provider is named `T` (via [`std::any::type_name`]). The source
+///     This provider is named `T` (via [`core::any::type_name`]). The source
 ///     location is set to the call site of the constructor.
 ///
 ///   * **Data (Unkeyed)**
@@ -32,6 +33,18 @@ use crate::value::{Value, Map, Dict};
 ///     dictionaries are created for every path component delimited by `.` in
 ///     the key string (3 in `a.b.c`), each dictionary mapping to its parent,
 ///     and the serialized `T` mapping to the leaf.
+///
+///     When enabled via [`Serialized::profile_annotations()`], a key may end
+///     with a profile annotation of the form `@profile`, as in
+///     `port@release`, in which case the value is emitted to that profile
+///     instead of the configured one. A literal `@` can then be escaped as
+///     `\@`.
+///
+///   * **Data (Per-Profile)**
+///
+///     When constructed with [`Serialized::defaults_map()`], each value in the
+///     map is emitted, unkeyed or keyed as above, to the profile it is mapped
+///     from. The configured profile is ignored.
 #[derive(Debug, Clone)]
 pub struct Serialized<T> {
     /// The value to be serialized and used as the provided data.
@@ -41,6 +54,8 @@ pub struct Serialized<T> {
     /// The profile to emit the value to. Defaults to [`Profile::Default`].
     pub profile: Profile,
     loc: &'static Location<'static>,
+    per_profile: bool,
+    profile_annotations: bool,
 }
 
 impl<T> Serialized<T> {
@@ -78,7 +93,9 @@ impl<T> Serialized<T> {
             value,
             key: None,
             profile: profile.into(),
-            loc: Location::caller()
+            loc: Location::caller(),
+            per_profile: false,
+            profile_annotations: false,
         }
     }
 
@@ -126,7 +143,10 @@ impl<T> Serialized<T> {
         Self::from(value, Profile::Global).key(key)
     }
 
-    /// Sets the profile to emit the serialized value to.
+    /// Sets the profile to emit the serialized value to. Values emitted to
+    /// [`Profile::Global`] take precedence over the values of every other
+    /// profile; see also the [`Serialized::globals()`] and
+    /// [`Serialized::global()`] constructors.
     ///
     /// ```rust
     /// use figment::{Figment, Jail, providers::Serialized};
@@ -175,21 +195,117 @@ impl<T> Serialized<T> {
         self.key = Some(key.into());
         self
     }
+
+    /// Enables `@profile` annotations in the key: a key ending in
+    /// `@profile`, as in `port@release`, emits the value to `profile` instead
+    /// of the configured one. Disabled by default, so that an `@` in a key,
+    /// as in `admin@example.com`, is part of the key.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::Serialized};
+    ///
+    /// let figment = Figment::new()
+    ///     .merge(Serialized::default("port", 8000))
+    ///     .merge(Serialized::default("port@release", 9999).profile_annotations());
+    ///
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+    ///
+    /// let figment = figment.select("release");
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 9999);
+    ///
+    /// let figment = Figment::from(Serialized::default("admin@localhost", "root"));
+    /// assert_eq!(figment.extract_inner::<String>("admin@localhost").unwrap(), "root");
+    /// ```
+    pub fn profile_annotations(mut self) -> Self {
+        self.profile_annotations = true;
+        self
+    }
+}
+
+impl<T> Serialized<Map<Profile, T>> {
+    /// Constructs an (unkeyed) provider that emits each value in `values`,
+    /// which must serialize to a [`Dict`], to the profile it is paired with.
+    /// Keying the provider with [`Serialized::key()`] keys every value.
+    ///
+    /// This is a single-provider alternative to merging one `Serialized` per
+    /// profile, as when defaults differ between development and production.
+    ///
+    /// ```rust
+    /// use serde::{Deserialize, Serialize};
+    /// use figment::{Figment, Profile, providers::Serialized};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    /// struct Config {
+    ///     workers: usize,
+    ///     log_level: String,
+    /// }
+    ///
+    /// let figment = Figment::from(Serialized::defaults_map([
+    ///     (Profile::Default, Config { workers: 1, log_level: "debug".into() }),
+    ///     (Profile::new("release"), Config { workers: 16, log_level: "warn".into() }),
+    /// ]));
+    ///
+    /// let config: Config = figment.extract().unwrap();
+    /// assert_eq!(config, Config { workers: 1, log_level: "debug".into() });
+    ///
+    /// let config: Config = figment.select("release").extract().unwrap();
+    /// assert_eq!(config, Config { workers: 16, log_level: "warn".into() });
+    ///
+    /// let figment = Figment::from(Serialized::defaults_map([
+    ///     ("default", 8000),
+    ///     ("release", 80),
+    /// ]).key("port"));
+    ///
+    /// assert_eq!(figment.select("release").extract_inner::<u16>("port").unwrap(), 80);
+    /// ```
+    #[track_caller]
+    pub fn defaults_map<I, P>(values: I) -> Self
+        where I: IntoIterator<Item = (P, T)>, P: Into<Profile>
+    {
+        let values = values.into_iter().map(|(p, v)| (p.into(), v)).collect();
+        Serialized { per_profile: true, ..Self::from(values, Profile::Default) }
+    }
+}
+
+impl<T> Serialized<T> {
+    /// Emits `value` to `profile`, keyed by the configured key, if any.
+    fn emit(&self, value: Value, profile: Profile) -> Result<Map<Profile, Dict>, Error> {
+        let error = InvalidType(value.to_actual(), "map".into());
+        let (dict, profile) = match &self.key {
+            Some(key) => {
+                let (key, key_profile) = match self.profile_annotations {
+                    true => crate::util::split_profile(key),
+                    false => (&**key, None),
+                };
+
+                let dict = crate::util::nest(key, value).into_dict().ok_or(error)?;
+                (dict, key_profile.unwrap_or(profile))
+            }
+            None => (value.into_dict().ok_or(error)?, profile),
+        };
+
+        Ok(profile.collect(dict))
+    }
 }
 
 impl<T: Serialize> Provider for Serialized<T> {
     fn metadata(&self) -> Metadata {
-        Metadata::from(std::any::type_name::<T>(), self.loc)
+        Metadata::from(core::any::type_name::<T>(), self.loc)
     }
 
     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
         let value = Value::serialize(&self.value)?;
-        let error = InvalidType(value.to_actual(), "map".into());
-        let dict = match &self.key {
-            Some(key) => crate::util::nest(key, value).into_dict().ok_or(error)?,
-            None => value.into_dict().ok_or(error)?,
-        };
+        if !self.per_profile {
+            return self.emit(value, self.profile.clone());
+        }
+
+        // A `Map<Profile, _>` serializes to a dictionary keyed by profile.
+        let mut map = Map::new();
+        for (profile, value) in value.into_dict().expect("map serializes to dict") {
+            let data = self.emit(value, Profile::from(&*profile))?;
+            map = crate::coalesce::Coalescible::merge(map, data);
+        }
 
-        Ok(self.profile.clone().collect(dict))
+        Ok(map)
     }
 }
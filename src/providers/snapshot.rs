@@ -0,0 +1,130 @@
+use crate::prelude::*;
+use crate::{Profile, Provider, Metadata, Error};
+use crate::value::{Map, Dict, Tag};
+use crate::warning::Warning;
+
+/// A `Provider` whose metadata, data, and warnings were all read at once, when
+/// it was created, from another provider.
+///
+/// A provider that reads external state, such as files or the environment,
+/// may observe different state in each of its methods if that state changes
+/// in the meantime: the variables [`Env`](crate::providers::Env) names in its
+/// metadata may not be those whose values it returns as data. A `Snapshot`,
+/// returned by [`Provider::snapshot()`], resolves every method of a provider
+/// exactly once, in the order a figment calls them, and thereafter returns
+/// clones of the results, however often and wherever it is merged.
+///
+/// A [`Figment`](crate::Figment) resolves every provider it is given via
+/// `Provider::snapshot()`, so each provider is read exactly once, as a unit,
+/// when it is merged or joined. Every extraction reads only from the data
+/// resolved then, never from the sources themselves. See [consistency] for
+/// details.
+///
+/// [consistency]: crate::Figment#consistency
+///
+/// # Provider Details
+///
+///   * **Profile**
+///
+///     This provider sets the profile the snapshotted provider sets, if any.
+///
+///   * **Metadata**
+///
+///     This provider's metadata is that of the snapshotted provider.
+///
+///   * **Data**
+///
+///     This provider emits the data, or the error, the snapshotted provider
+///     returned when the snapshot was taken.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, Jail, Provider, providers::{Format, Toml}};
+///
+/// Jail::expect_with(|jail| {
+///     jail.create_file("App.toml", "workers = 4")?;
+///     let snapshot = Toml::file("App.toml").snapshot();
+///
+///     jail.create_file("App.toml", "workers = 8")?;
+///     let figment = Figment::from(&snapshot);
+///     assert_eq!(figment.extract_inner::<usize>("workers")?, 4);
+///
+///     let figment = Figment::from(Toml::file("App.toml"));
+///     assert_eq!(figment.extract_inner::<usize>("workers")?, 8);
+///
+///     Ok(())
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    metadata: Metadata,
+    data: Result<Map<Profile, Dict>, Error>,
+    metadata_map: Option<Map<Tag, Metadata>>,
+    tags: Map<Tag, Metadata>,
+    profile: Option<Profile>,
+    warnings: Vec<Warning>,
+}
+
+impl Snapshot {
+    /// Resolves every method of `provider` once, in the order a figment calls
+    /// them: [`Provider::metadata()`], [`Provider::data()`],
+    /// [`Provider::tags()`], [`Provider::profile()`], then
+    /// [`Provider::warnings()`].
+    ///
+    /// This is the default implementation of [`Provider::snapshot()`]. A
+    /// provider that overrides `snapshot()` to read its source once, and not
+    /// once per method, typically calls `Snapshot::resolve()` on a version of
+    /// itself that reads from a copy of its source.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, Provider, providers::Snapshot};
+    ///
+    /// let snapshot = Snapshot::resolve(&("port", 8000));
+    /// assert_eq!(snapshot.metadata().name, "(&str, i32)");
+    /// assert_eq!(Figment::from(snapshot).extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    pub fn resolve<T: Provider + ?Sized>(provider: &T) -> Snapshot {
+        Snapshot {
+            metadata: provider.metadata(),
+            data: provider.data(),
+            metadata_map: provider.__metadata_map(),
+            tags: provider.tags(),
+            profile: provider.profile(),
+            warnings: provider.warnings(),
+        }
+    }
+}
+
+impl Provider for Snapshot {
+    fn metadata(&self) -> Metadata {
+        self.metadata.clone()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        self.data.clone()
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.profile.clone()
+    }
+
+    fn warnings(&self) -> Vec<Warning> {
+        self.warnings.clone()
+    }
+
+    fn tags(&self) -> Map<Tag, Metadata> {
+        self.tags.clone()
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        self.clone()
+    }
+
+    #[doc(hidden)]
+    fn __metadata_map(&self) -> Option<Map<Tag, Metadata>> {
+        self.metadata_map.clone()
+    }
+}
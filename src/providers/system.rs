@@ -0,0 +1,195 @@
+use std::panic::Location;
+
+use crate::{Profile, Provider, Metadata};
+use crate::error::Error;
+use crate::value::{Map, Dict, Value};
+use crate::util::nest;
+
+/// A `Provider` that sources facts about the host system: its hostname, CPU
+/// count, total memory, operating system, and architecture.
+///
+/// `SystemInfo` is meant to be [joined](crate::Figment::join()) so that it
+/// fills in facts without overriding any configured value of the same key.
+/// Configuration can then read the host's facts through the same interface as
+/// every other value, and defaults can scale with the hardware.
+///
+/// # Provider Details
+///
+///   * **Profile**
+///
+///     This provider does not set a profile.
+///
+///   * **Metadata**
+///
+///     This provider is named `system information`. The source location is
+///     set to the call site of the constructor.
+///
+///   * **Data**
+///
+///     The following keys are emitted, nested under the prefix, which
+///     defaults to `system`. Facts that can't be determined on the host are
+///     not emitted.
+///
+///     | key        | value                                                    |
+///     |------------|----------------------------------------------------------|
+///     | `hostname` | the host's name, as a string                             |
+///     | `cpus`     | the [available parallelism], as an integer               |
+///     | `memory`   | the total physical memory in bytes, as an integer        |
+///     | `os`       | [`std::env::consts::OS`], e.g, `linux`                   |
+///     | `family`   | [`std::env::consts::FAMILY`], e.g, `unix`                |
+///     | `arch`     | [`std::env::consts::ARCH`], e.g, `x86_64`                |
+///
+///     The hostname is read from the kernel on Linux and otherwise from the
+///     `HOSTNAME` or `COMPUTERNAME` environment variables, unless the
+///     [`Policy`](crate::Policy) in effect denies reading the environment.
+///     The total memory is only determined on Linux. Facts are read when the
+///     provider is merged or joined. Values are emitted to the configured
+///     profile, which defaults to [`Profile::Default`].
+///
+/// [available parallelism]: std::thread::available_parallelism
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use figment::{Figment, providers::{Serialized, SystemInfo}};
+///
+/// #[derive(Deserialize)]
+/// struct System {
+///     cpus: usize,
+///     os: String,
+/// }
+///
+/// let figment = Figment::from(Serialized::default("system.os", "configured"))
+///     .join(SystemInfo::new());
+///
+/// let system: System = figment.extract_inner("system").unwrap();
+/// assert!(system.cpus >= 1);
+/// assert_eq!(system.os, "configured");
+///
+/// // Scale a default with the hardware.
+/// let workers = figment.extract_inner::<usize>("workers")
+///     .unwrap_or(system.cpus * 2);
+///
+/// assert!(workers >= 2);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(nightly, doc(cfg(feature = "std")))]
+pub struct SystemInfo {
+    prefix: String,
+    /// The profile to emit values to. Defaults to [`Profile::Default`].
+    pub profile: Profile,
+    loc: &'static Location<'static>,
+}
+
+impl SystemInfo {
+    /// Constructs a provider that emits the host's facts under `system` in
+    /// the default profile.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::SystemInfo};
+    ///
+    /// let figment = Figment::from(SystemInfo::new());
+    /// let arch: String = figment.extract_inner("system.arch").unwrap();
+    /// assert_eq!(arch, std::env::consts::ARCH);
+    /// ```
+    #[track_caller]
+    pub fn new() -> SystemInfo {
+        SystemInfo {
+            prefix: "system".into(),
+            profile: Profile::Default,
+            loc: Location::caller(),
+        }
+    }
+
+    /// Sets the key path facts are emitted under. An empty `prefix` emits
+    /// facts at the root.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::SystemInfo};
+    ///
+    /// let figment = Figment::from(SystemInfo::new().prefix("host.facts"));
+    /// assert!(figment.find_value("host.facts.os").is_ok());
+    /// assert!(figment.find_value("system").is_err());
+    /// ```
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Sets the profile to emit values to.
+    ///
+    /// ```rust
+    /// use figment::{Figment, providers::SystemInfo};
+    ///
+    /// let figment = Figment::from(SystemInfo::new().profile("debug"));
+    /// assert!(figment.find_value("system.os").is_err());
+    /// assert!(figment.select("debug").find_value("system.os").is_ok());
+    /// ```
+    pub fn profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = profile.into();
+        self
+    }
+}
+
+impl Default for SystemInfo {
+    #[track_caller]
+    fn default() -> Self {
+        SystemInfo::new()
+    }
+}
+
+/// Returns the host's name, if it can be determined. Environment variables
+/// are only consulted if the policy in effect allows reading them.
+fn hostname() -> Option<String> {
+    let env_allowed = crate::Policy::current().check(&crate::Capability::Env).is_ok();
+    let from_file = |path: &str| crate::Policy::read_to_string(path.as_ref()).ok();
+    let from_env = |name| std::env::var(name).ok().filter(|_| env_allowed);
+    let hostname = from_file("/proc/sys/kernel/hostname")
+        .or_else(|| from_file("/etc/hostname"))
+        .or_else(|| from_env("HOSTNAME"))
+        .or_else(|| from_env("COMPUTERNAME"))?;
+
+    let hostname = hostname.trim();
+    (!hostname.is_empty()).then(|| hostname.to_string())
+}
+
+/// Returns the total physical memory in bytes, if it can be determined.
+fn memory() -> Option<u64> {
+    let meminfo = crate::Policy::read_to_string("/proc/meminfo".as_ref()).ok()?;
+    let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+    let kib = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    kib.checked_mul(1024)
+}
+
+impl Provider for SystemInfo {
+    fn metadata(&self) -> Metadata {
+        Metadata::from("system information", self.loc)
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut dict = Dict::new();
+        if let Some(hostname) = hostname() {
+            dict.insert("hostname".into(), hostname.into());
+        }
+
+        if let Ok(cpus) = std::thread::available_parallelism() {
+            dict.insert("cpus".into(), cpus.get().into());
+        }
+
+        if let Some(memory) = memory() {
+            dict.insert("memory".into(), memory.into());
+        }
+
+        dict.insert("os".into(), std::env::consts::OS.into());
+        dict.insert("family".into(), std::env::consts::FAMILY.into());
+        dict.insert("arch".into(), std::env::consts::ARCH.into());
+
+        let dict = match nest(&self.prefix, dict.into()) {
+            Value::Dict(_, dict) => dict,
+            _ => unreachable!("nesting a dictionary yields a dictionary"),
+        };
+
+        Ok(self.profile.collect(dict))
+    }
+}
@@ -0,0 +1,296 @@
+use wasm_bindgen::{JsCast, JsValue};
+use js_sys::{Array, Object, JSON};
+
+use crate::{Profile, Provider, Metadata};
+use crate::coalesce::Coalescible;
+use crate::error::Error;
+use crate::value::{Value, Map, Dict, Num, Empty};
+use crate::util::nest;
+
+/// A `Provider` that sources values from a JavaScript value, typically an
+/// object handed to Rust from JavaScript via `wasm-bindgen`.
+///
+/// # Provider Details
+///
+///   * **Profile**
+///
+///     This provider does not set a profile.
+///
+///   * **Metadata**
+///
+///     This provider is named `JavaScript value`. The source location is set
+///     to the call site of the constructor.
+///
+///   * **Data**
+///
+///     The value, which must be a JavaScript object, is converted as
+///     `JSON.stringify` would see it and emitted to the configured profile,
+///     which defaults to [`Profile::Default`]: strings, booleans, arrays, and
+///     objects map to their [`Value`] counterparts; `null` and `undefined` map
+///     to [`Empty::None`]; integral numbers map to [`Num::U64`] or
+///     [`Num::I64`] and all other numbers to [`Num::F64`]. Any other value,
+///     such as a function or symbol, is an error.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use serde::Deserialize;
+/// use wasm_bindgen::prelude::*;
+/// use figment::{Figment, providers::Js};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     api: String,
+///     retries: u8,
+/// }
+///
+/// // From JavaScript: `configure({ api: "https://example.com", retries: 3 })`.
+/// #[wasm_bindgen]
+/// pub fn configure(config: JsValue) -> Result<(), JsValue> {
+///     let config: Config = Figment::from(Js::value(config))
+///         .extract()
+///         .map_err(|e| e.to_string())?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(nightly, doc(cfg(feature = "web")))]
+pub struct Js {
+    value: JsValue,
+    /// The profile to emit values to. Defaults to [`Profile::Default`].
+    pub profile: Profile,
+    loc: &'static core::panic::Location<'static>,
+}
+
+impl Js {
+    /// Constructs a provider that emits the entries of the JavaScript object
+    /// `value` to the default profile.
+    ///
+    /// ```rust,no_run
+    /// use figment::{Figment, providers::Js};
+    ///
+    /// let value = js_sys::JSON::parse(r#"{ "port": 8000 }"#).unwrap();
+    /// let figment = Figment::from(Js::value(value));
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    #[track_caller]
+    pub fn value(value: JsValue) -> Js {
+        Js { value, profile: Profile::Default, loc: core::panic::Location::caller() }
+    }
+
+    /// Sets the profile to emit values to.
+    ///
+    /// ```rust,no_run
+    /// use figment::{Figment, providers::Js};
+    ///
+    /// let value = js_sys::JSON::parse(r#"{ "port": 8000 }"#).unwrap();
+    /// let figment = Figment::from(Js::value(value).profile("debug"));
+    /// assert!(figment.extract_inner::<u16>("port").is_err());
+    /// ```
+    pub fn profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Sets the profile to emit values to to [`Profile::Global`].
+    /// Equivalent to `self.profile(Profile::Global)`.
+    ///
+    /// ```rust,no_run
+    /// use figment::{Figment, providers::Js};
+    ///
+    /// let value = js_sys::JSON::parse(r#"{ "port": 8000 }"#).unwrap();
+    /// let figment = Figment::from(Js::value(value).global());
+    /// ```
+    pub fn global(self) -> Self {
+        self.profile(Profile::Global)
+    }
+}
+
+impl Provider for Js {
+    fn metadata(&self) -> Metadata {
+        Metadata::from("JavaScript value", self.loc)
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        match from_js(&self.value)? {
+            Value::Dict(_, dict) => Ok(self.profile.collect(dict)),
+            _ => Err(Error::from("JavaScript value is not an object".to_string())),
+        }
+    }
+}
+
+/// A `Provider` that sources values from the browser's
+/// [`localStorage`](https://developer.mozilla.org/en-US/docs/Web/API/Window/localStorage).
+///
+/// # Provider Details
+///
+///   * **Profile**
+///
+///     This provider does not set a profile.
+///
+///   * **Metadata**
+///
+///     This provider is named `localStorage`. Paths are interpolated as the
+///     prefix followed by the `.`-joined key path, which is the storage key the
+///     value was read from.
+///
+///   * **Data**
+///
+///     Every storage item whose key starts with the configured prefix is read.
+///     The prefix is stripped, and the remainder is emitted as a [key path],
+///     case-sensitively, into the configured profile, which defaults to
+///     [`Profile::Default`]. Each item is parsed as JSON and converted as in
+///     [`Js`]; items that aren't valid JSON are emitted as strings. If
+///     `localStorage` is unavailable, as outside of a browser window, an error
+///     is returned.
+///
+/// [key path]: crate::Figment#extraction
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use serde::Deserialize;
+/// use figment::{Figment, providers::{Format, Json, LocalStorage}};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     theme: String,
+///     volume: u8,
+/// }
+///
+/// // With `localStorage.setItem("app.theme", "dark")` in JavaScript.
+/// let config: Config = Figment::new()
+///     .merge(Json::string(r#"{ "theme": "light", "volume": 5 }"#))
+///     .merge(LocalStorage::prefixed("app."))
+///     .extract()
+///     .unwrap();
+///
+/// assert_eq!(config.theme, "dark");
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(nightly, doc(cfg(feature = "web")))]
+pub struct LocalStorage {
+    prefix: String,
+    /// The profile to emit values to. Defaults to [`Profile::Default`].
+    pub profile: Profile,
+}
+
+impl LocalStorage {
+    /// Constructs a provider that emits every `localStorage` item whose key
+    /// starts with `prefix`, with the prefix removed, to the default profile.
+    ///
+    /// ```rust,no_run
+    /// use figment::{Figment, providers::LocalStorage};
+    ///
+    /// // `localStorage.setItem("app.net.port", "8000")`
+    /// let figment = Figment::from(LocalStorage::prefixed("app."));
+    /// assert_eq!(figment.extract_inner::<u16>("net.port").unwrap(), 8000);
+    /// ```
+    pub fn prefixed(prefix: &str) -> LocalStorage {
+        LocalStorage { prefix: prefix.into(), profile: Profile::Default }
+    }
+
+    /// Sets the profile to emit values to.
+    ///
+    /// ```rust,no_run
+    /// use figment::{Figment, providers::LocalStorage};
+    ///
+    /// let figment = Figment::from(LocalStorage::prefixed("app.").profile("debug"));
+    /// ```
+    pub fn profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Sets the profile to emit values to to [`Profile::Global`].
+    /// Equivalent to `self.profile(Profile::Global)`.
+    ///
+    /// ```rust,no_run
+    /// use figment::{Figment, providers::LocalStorage};
+    ///
+    /// let figment = Figment::from(LocalStorage::prefixed("app.").global());
+    /// ```
+    pub fn global(self) -> Self {
+        self.profile(Profile::Global)
+    }
+}
+
+impl Provider for LocalStorage {
+    fn metadata(&self) -> Metadata {
+        let prefix = self.prefix.clone();
+        Metadata::named("localStorage")
+            .interpolater(move |_, keys| format!("{}{}", prefix, keys.join(".")))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let unavailable = || Error::from("`localStorage` is unavailable".to_string());
+        let storage = web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(unavailable)?;
+
+        let len = storage.length().map_err(|_| unavailable())?;
+        let mut dict = Dict::new();
+        for i in 0..len {
+            let key = match storage.key(i).ok().flatten() {
+                Some(key) => key,
+                None => continue,
+            };
+
+            let item = match key.strip_prefix(&*self.prefix) {
+                Some(item) if !item.is_empty() => item,
+                _ => continue,
+            };
+
+            let string = storage.get_item(&key).ok().flatten().unwrap_or_default();
+            let value = match JSON::parse(&string) {
+                Ok(json) => from_js(&json).map_err(|e| e.prefixed(item))?,
+                Err(_) => Value::from(string),
+            };
+
+            let nested_dict = nest(item, value)
+                .into_dict()
+                .expect("key is non-empty: must have dict");
+
+            dict = dict.merge(nested_dict);
+        }
+
+        Ok(self.profile.collect(dict))
+    }
+}
+
+/// Converts the JavaScript value `value` into a [`Value`].
+fn from_js(value: &JsValue) -> Result<Value, Error> {
+    if value.is_null() || value.is_undefined() {
+        return Ok(Empty::None.into());
+    } else if let Some(b) = value.as_bool() {
+        return Ok(b.into());
+    } else if let Some(s) = value.as_string() {
+        return Ok(s.into());
+    } else if let Some(n) = value.as_f64() {
+        const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+        return Ok(match n {
+            n if n % 1.0 != 0.0 || n.abs() > MAX_SAFE_INTEGER => Num::F64(n).into(),
+            n if n < 0.0 => Num::I64(n as i64).into(),
+            n => Num::U64(n as u64).into(),
+        });
+    } else if Array::is_array(value) {
+        return value.unchecked_ref::<Array>().iter().enumerate()
+            .map(|(i, v)| from_js(&v).map_err(|e| e.prefixed_index(i)))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::from);
+    } else if value.is_object() && !value.is_function() {
+        let mut dict = Dict::new();
+        for entry in Object::entries(value.unchecked_ref::<Object>()).iter() {
+            let entry: Array = entry.unchecked_into();
+            let key = entry.get(0).as_string().unwrap_or_default();
+            let value = from_js(&entry.get(1)).map_err(|e| e.prefixed(&key))?;
+            dict.insert(key, value);
+        }
+
+        return Ok(dict.into());
+    }
+
+    let kind = value.js_typeof().as_string().unwrap_or_default();
+    Err(Error::from(format!("unsupported JavaScript value of type `{}`", kind)))
+}
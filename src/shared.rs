@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use serde::de::DeserializeOwned;
+
+use crate::{Figment, Error};
+
+/// A thread-safe handle to the "current" [`Figment`] that can be atomically
+/// replaced, as on a configuration reload.
+///
+/// A `SharedFigment` is cheap to clone: clones share the same current figment.
+/// [`load()`](SharedFigment::load()) returns the current figment, and
+/// [`store()`](SharedFigment::store()) atomically replaces it. Neither blocks
+/// readers: a thread holding a figment returned by `load()` continues to see
+/// that figment, unchanged, until it calls `load()` again.
+///
+/// [`extract()`](SharedFigment::extract()) deserializes the current figment
+/// into a `T` at most once per stored figment, caching and returning the value
+/// in an `Arc`, as by [`Figment::extract_cached()`]. Storing a new figment
+/// invalidates the cache.
+///
+/// Every extraction reads from exactly one figment, loaded when the extraction
+/// starts, and thus observes a single, consistent [snapshot] of the providers
+/// even if a new figment is stored in the meantime. Separate extractions may
+/// observe different figments, however. To extract several values from the
+/// same figment, extract them from the figment returned by a single call to
+/// `load()`.
+///
+/// [snapshot]: Figment#consistency
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use figment::{Figment, SharedFigment, providers::{Format, Toml}};
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Config {
+///     workers: usize,
+/// }
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("App.toml", "workers = 4")?;
+///
+///     let shared = SharedFigment::new(Figment::from(Toml::file("App.toml")));
+///     let config = shared.extract::<Config>()?;
+///     assert_eq!(config.workers, 4);
+///
+///     // A handle for another thread.
+///     let handle = shared.clone();
+///     std::thread::spawn(move || {
+///         let config = handle.extract::<Config>().unwrap();
+///         assert_eq!(config.workers, 4);
+///     }).join().unwrap();
+///
+///     // On reload, swap in the new figment.
+///     jail.create_file("App.toml", "workers = 8")?;
+///     shared.store(Figment::from(Toml::file("App.toml")));
+///     assert_eq!(shared.extract::<Config>()?.workers, 8);
+///
+///     // Previously extracted values are unaffected.
+///     assert_eq!(config.workers, 4);
+///
+///     Ok(())
+/// });
+/// ```
+#[derive(Clone)]
+#[cfg_attr(nightly, doc(cfg(feature = "shared")))]
+pub struct SharedFigment {
+    current: Arc<ArcSwap<Figment>>,
+}
+
+impl SharedFigment {
+    /// Creates a new `SharedFigment` whose current figment is `figment`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, SharedFigment};
+    ///
+    /// let shared = SharedFigment::new(Figment::from(("port", 8000)));
+    /// assert_eq!(shared.load().extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    pub fn new(figment: Figment) -> Self {
+        SharedFigment { current: Arc::new(ArcSwap::from_pointee(figment)) }
+    }
+
+    /// Returns the current figment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, SharedFigment};
+    ///
+    /// let shared = SharedFigment::new(Figment::from(("port", 8000)));
+    /// let figment = shared.load();
+    /// shared.store(Figment::from(("port", 9000)));
+    ///
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+    /// assert_eq!(shared.load().extract_inner::<u16>("port").unwrap(), 9000);
+    /// ```
+    pub fn load(&self) -> Arc<Figment> {
+        self.current.load_full()
+    }
+
+    /// Atomically replaces the current figment with `figment`, invalidating
+    /// all cached extracted values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, SharedFigment};
+    ///
+    /// let shared = SharedFigment::new(Figment::new());
+    /// shared.store(Figment::from(("port", 9000)));
+    /// assert_eq!(shared.extract_inner::<u16>("port").unwrap(), 9000);
+    /// ```
+    pub fn store(&self, figment: Figment) {
+        self.current.store(Arc::new(figment));
+    }
+
+    /// Deserializes the current figment into `T`, as by
+    /// [`Figment::extract_cached()`]: returns the value previously
+    /// deserialized from the current figment if there is one. Errors are not
+    /// cached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use figment::{Figment, SharedFigment, value::Dict};
+    ///
+    /// let shared = SharedFigment::new(Figment::from(("port", 8000)));
+    /// let a = shared.extract::<Dict>().unwrap();
+    /// let b = shared.extract::<Dict>().unwrap();
+    /// assert!(Arc::ptr_eq(&a, &b));
+    /// ```
+    pub fn extract<T>(&self) -> Result<Arc<T>, Error>
+        where T: DeserializeOwned + Send + Sync + 'static
+    {
+        self.load().extract_cached()
+    }
+
+    /// Deserializes the value at the `key` path in the current figment into
+    /// `T`, as by [`Figment::extract_inner()`]. Unlike
+    /// [`SharedFigment::extract()`], the value is not cached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, SharedFigment};
+    ///
+    /// let shared = SharedFigment::new(Figment::from(("app.port", 8000)));
+    /// assert_eq!(shared.extract_inner::<u16>("app.port").unwrap(), 8000);
+    /// ```
+    pub fn extract_inner<T: DeserializeOwned>(&self, key: &str) -> Result<T, Error> {
+        self.load().extract_inner(key)
+    }
+}
+
+impl From<Figment> for SharedFigment {
+    fn from(figment: Figment) -> Self {
+        SharedFigment::new(figment)
+    }
+}
+
+impl std::fmt::Debug for SharedFigment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SharedFigment").field(&*self.load()).finish()
+    }
+}
@@ -0,0 +1,238 @@
+//! Utilities for testing code that consumes configurations.
+//!
+//! See also [`Jail`](crate::Jail) for testing with files and environment
+//! variables.
+
+use std::collections::BTreeSet;
+use std::panic::Location;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use crate::{Profile, Provider, Metadata, Error};
+use crate::metadata::ReadHook;
+use crate::value::{Map, Dict, Tag};
+use crate::providers::{Serialized, Snapshot};
+
+/// Constructs a [`Figment`](crate::Figment) from key/value pairs.
+///
+/// Each entry is of the form `key => value` where `key` is a key path, as in
+/// [`Serialized::default()`], and `value` is any [`Serialize`] expression.
+/// Entries are emitted to the default profile unless they appear in a block
+/// of the form `@profile name { entries }`, in which case they are emitted to
+/// the profile `name`. Entries are merged in order.
+///
+/// Each entry is provided by its own provider whose metadata names the entry's
+/// key, as in ``mock value `a.b` ``, and whose source is the location of the
+/// macro invocation.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::test::figment;
+///
+/// let figment = figment! {
+///     "a.b" => 1,
+///     "list" => [1, 2, 3],
+///     @profile "release" {
+///         "x" => true,
+///         "a.b" => 2,
+///     }
+/// };
+///
+/// assert_eq!(figment.extract_inner::<u8>("a.b").unwrap(), 1);
+/// assert_eq!(figment.extract_inner::<Vec<u8>>("list").unwrap(), vec![1, 2, 3]);
+/// assert!(figment.extract_inner::<bool>("x").is_err());
+///
+/// let metadata = figment.find_metadata("list").unwrap();
+/// assert_eq!(metadata.name, "mock value `list`");
+/// assert!(metadata.source.as_ref().unwrap().code_location().is_some());
+///
+/// let release = figment.select("release");
+/// assert_eq!(release.extract_inner::<u8>("a.b").unwrap(), 2);
+/// assert_eq!(release.extract_inner::<bool>("x").unwrap(), true);
+/// ```
+#[doc(inline)]
+pub use crate::__figment_test_figment as figment;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __figment_test_figment {
+    (@entries $figment:expr, $profile:expr;) => ($figment);
+    (@entries $figment:expr, $profile:expr; , $($rest:tt)*) => (
+        $crate::__figment_test_figment!(@entries $figment, $profile; $($rest)*)
+    );
+    (@entries $figment:expr, $profile:expr; @profile $name:tt { $($inner:tt)* } $($rest:tt)*) => (
+        $crate::__figment_test_figment!(@entries
+            $crate::__figment_test_figment!(@entries $figment, $crate::Profile::new($name); $($inner)*),
+            $profile; $($rest)*)
+    );
+    (@entries $figment:expr, $profile:expr; $key:expr => $value:expr $(, $($rest:tt)*)?) => (
+        $crate::__figment_test_figment!(@entries
+            $figment.merge($crate::test::Entry::new($profile, $key, $value)),
+            $profile; $($($rest)*)?)
+    );
+    ($($entries:tt)*) => (
+        $crate::__figment_test_figment!(@entries
+            $crate::Figment::new(), $crate::Profile::Default; $($entries)*)
+    );
+}
+
+/// The provider for a single entry in [`figment!`].
+#[doc(hidden)]
+pub struct Entry<T> {
+    key: String,
+    inner: Serialized<T>,
+    loc: &'static Location<'static>,
+}
+
+impl<T> Entry<T> {
+    #[track_caller]
+    pub fn new(profile: Profile, key: &str, value: T) -> Self {
+        Entry {
+            key: key.into(),
+            inner: Serialized::default(key, value).profile(profile),
+            loc: Location::caller(),
+        }
+    }
+}
+
+impl<T: Serialize> Provider for Entry<T> {
+    fn metadata(&self) -> Metadata {
+        Metadata::from(format!("mock value `{}`", self.key), self.loc)
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        self.inner.data()
+    }
+}
+
+/// A provider wrapper that records which of its values are read.
+///
+/// A `Recorder` wraps a provider `P` and records the key path of every value
+/// provided by `P` that is read during an extraction, as well as how many
+/// times `P`'s [`Provider::data()`] was called. A `Recorder` is added to a
+/// figment by reference so that it can be queried afterwards.
+///
+/// A value is _read_ when it is deserialized into some type. Dictionaries are
+/// not recorded; their leaves are. An array that is read is recorded along
+/// with its elements. Reads are recorded even if the extraction fails.
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use figment::{Figment, providers::Serialized, test::{figment, Recorder}};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     name: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let recorder = Recorder::new(figment! {
+///     "name" => "app",
+///     "ports" => [80, 443],
+///     "unused" => true,
+/// });
+///
+/// let figment = Figment::new()
+///     .merge(&recorder)
+///     .merge(Serialized::default("extra", 1));
+///
+/// assert_eq!(recorder.evaluations(), 1);
+/// let config: Config = figment.extract().unwrap();
+/// assert_eq!(recorder.reads(), vec!["name", "ports", "ports.0", "ports.1"]);
+/// assert!(recorder.was_read("ports"));
+/// assert!(!recorder.was_read("unused"));
+///
+/// recorder.clear();
+/// let name: String = figment.extract_inner("name").unwrap();
+/// assert_eq!(recorder.reads(), vec!["name"]);
+/// ```
+pub struct Recorder<P> {
+    provider: P,
+    log: Arc<Mutex<Log>>,
+}
+
+#[derive(Default)]
+struct Log {
+    reads: BTreeSet<String>,
+    evaluations: usize,
+}
+
+impl<P: Provider> Recorder<P> {
+    /// Wraps `provider` in a new `Recorder` with no recorded reads.
+    pub fn new(provider: P) -> Self {
+        Recorder { provider, log: Arc::default() }
+    }
+
+    /// Returns the key paths of the values read so far, sorted and without
+    /// duplicates.
+    pub fn reads(&self) -> Vec<String> {
+        self.log.lock().unwrap().reads.iter().cloned().collect()
+    }
+
+    /// Returns `true` if the value at `key`, or any value nested in it, was
+    /// read.
+    pub fn was_read(&self, key: &str) -> bool {
+        let prefix = format!("{}.", key);
+        self.log.lock().unwrap().reads.iter()
+            .any(|read| read == key || read.starts_with(&prefix))
+    }
+
+    /// Returns the number of times the wrapped provider's data was requested.
+    pub fn evaluations(&self) -> usize {
+        self.log.lock().unwrap().evaluations
+    }
+
+    /// Forgets all recorded reads. The evaluation count is not reset.
+    pub fn clear(&self) {
+        self.log.lock().unwrap().reads.clear();
+    }
+}
+
+impl<P> Recorder<P> {
+    fn hooked(&self, mut metadata: Metadata) -> Metadata {
+        let log = self.log.clone();
+        metadata.extra_mut().read_hook = Some(ReadHook(Arc::new(move |key| {
+            log.lock().unwrap().reads.insert(key.into());
+        })));
+
+        metadata
+    }
+}
+
+impl<P: Provider> Provider for Recorder<P> {
+    fn metadata(&self) -> Metadata {
+        self.hooked(self.provider.metadata())
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        self.log.lock().unwrap().evaluations += 1;
+        self.provider.data()
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.provider.profile()
+    }
+
+    fn warnings(&self) -> Vec<crate::warning::Warning> {
+        self.provider.warnings()
+    }
+
+    fn tags(&self) -> Map<Tag, Metadata> {
+        let tags = self.provider.tags();
+        tags.into_iter().map(|(tag, md)| (tag, self.hooked(md))).collect()
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let provider = self.provider.snapshot();
+        Snapshot::resolve(&Recorder { provider, log: self.log.clone() })
+    }
+
+    fn __metadata_map(&self) -> Option<Map<Tag, Metadata>> {
+        let map = self.provider.__metadata_map()?;
+        Some(map.into_iter().map(|(tag, md)| (tag, self.hooked(md))).collect())
+    }
+}
@@ -25,11 +25,14 @@
 //! assert_eq!(map.get(&23), Some(&"twenty-three"));
 //!
 //! ```
-use std::fmt;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::path::{Path, PathBuf, Component};
 
 use serde::de::{self, Unexpected, Deserializer};
 
+use crate::prelude::*;
+
 /// A helper function to determine the relative path to `path` from `base`.
 ///
 /// Returns `None` if there is no relative path from `base` to `path`, that is,
@@ -63,6 +66,8 @@ use serde::de::{self, Unexpected, Deserializer};
 // Copyright 2012-2015 The Rust Project Developers.
 // Copyright 2017 The Rust Project Developers.
 // Adapted from `pathdiff`, which itself adapted from rustc's path_relative_from.
+#[cfg(feature = "std")]
+#[cfg_attr(nightly, doc(cfg(feature = "std")))]
 pub fn diff_paths<P, B>(path: P, base: B) -> Option<PathBuf>
      where P: AsRef<Path>, B: AsRef<Path>
 {
@@ -198,9 +203,11 @@ pub fn bool_from_str_or_int<'de, D: Deserializer<'de>>(de: D) -> Result<bool, D:
 /// assert_eq!(pairs[2], ("value".into(), 100));
 /// ```
 pub mod vec_tuple_map {
-    use std::fmt;
+    use core::fmt;
     use serde::{de, Deserialize, Serialize, Deserializer, Serializer};
 
+    use crate::prelude::*;
+
     /// The serializer half.
     pub fn serialize<S, K, V>(vec: &[(K, V)], se: S) -> Result<S::Ok, S::Error>
         where S: Serializer, K: Serialize, V: Serialize
@@ -212,7 +219,7 @@ pub mod vec_tuple_map {
     pub fn deserialize<'de, K, V, D>(de: D) -> Result<Vec<(K, V)>, D::Error>
         where D: Deserializer<'de>, K: Deserialize<'de>, V: Deserialize<'de>
     {
-        struct Visitor<K, V>(std::marker::PhantomData<Vec<(K, V)>>);
+        struct Visitor<K, V>(core::marker::PhantomData<Vec<(K, V)>>);
 
         impl<'de, K, V> de::Visitor<'de> for Visitor<K, V>
             where K: Deserialize<'de>, V: Deserialize<'de>,
@@ -235,16 +242,237 @@ pub mod vec_tuple_map {
             }
         }
 
-        de.deserialize_map(Visitor(std::marker::PhantomData))
+        de.deserialize_map(Visitor(core::marker::PhantomData))
     }
 }
 
 use crate::value::{Value, Dict};
 
+/// Splits a key path of the form `a.b.c` into its components.
+///
+/// Components are delimited by `.`. A component containing a literal `.` can
+/// be written either by escaping the dot with a backslash, as in `a\.b`, or
+/// by quoting the component in brackets, as in `map["a.b"]`. Within brackets,
+/// `\"` and `\\` escape a quote and a backslash, respectively. Outside of
+/// brackets, a backslash escapes any following character.
+///
+/// A key path starting with `/` is instead read as an [RFC 6901] JSON Pointer:
+/// components are delimited by `/`, and `~1` and `~0` escape a `/` and a `~`,
+/// respectively. Thus `/servers/0/port` is equivalent to `servers.0.port`. A
+/// component that is an index, like `0`, selects an element of an array
+/// wherever values are looked up.
+///
+/// This is the syntax understood by all key paths in figment, including those
+/// passed to [`Figment::extract_inner()`](crate::Figment::extract_inner()),
+/// [`Figment::find_value()`](crate::Figment::find_value()),
+/// [`Value::find()`], [`nest()`], and keys emitted by the
+/// [`Env`](crate::providers::Env) provider. See [`escape_key()`] for the
+/// inverse operation on a single component.
+///
+/// # Compatibility
+///
+/// In figment 0.10 and earlier, only `.` was special in key paths. As of
+/// figment 0.11, key paths with a component that contains a `\` or a quoted
+/// bracket such as `["`, or that start with a `/`, such as `C:\config` or
+/// `/etc`, have a different meaning: their components must be escaped with
+/// [`escape_key()`] to keep the old one.
+///
+/// ```rust
+/// use figment::util::{escape_key, split_key};
+///
+/// assert_eq!(split_key(r"paths.C:\config"), vec!["paths", "C:config"]);
+/// assert_eq!(split_key(&format!("paths.{}", escape_key(r"C:\config"))), vec!["paths", r"C:\config"]);
+/// assert_eq!(split_key("/etc"), vec!["etc"]);
+/// assert_eq!(split_key(&escape_key("/etc")), vec!["/etc"]);
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use figment::util::split_key;
+///
+/// assert_eq!(split_key("a.b.c"), vec!["a", "b", "c"]);
+/// assert_eq!(split_key(r"hosts.example\.com.port"), vec!["hosts", "example.com", "port"]);
+/// assert_eq!(split_key(r#"hosts["example.com"].port"#), vec!["hosts", "example.com", "port"]);
+/// assert_eq!(split_key(r#"["a.b"]["c"]"#), vec!["a.b", "c"]);
+/// assert_eq!(split_key(r"back\\slash"), vec![r"back\slash"]);
+///
+/// // Brackets without a quoted string are not special.
+/// assert_eq!(split_key("list[0].name"), vec!["list[0]", "name"]);
+///
+/// // JSON Pointers.
+/// assert_eq!(split_key("/servers/0/port"), vec!["servers", "0", "port"]);
+/// assert_eq!(split_key("/hosts/example.com/a~1b~0c"), vec!["hosts", "example.com", "a/b~c"]);
+/// assert_eq!(split_key(r"\/etc.hosts"), vec!["/etc", "hosts"]);
+/// ```
+///
+/// [RFC 6901]: https://www.rfc-editor.org/rfc/rfc6901
+pub fn split_key(key: &str) -> Vec<String> {
+    /// Parses `"..."]` at the start of `string`, returning the unescaped
+    /// contents and the remainder of `string`.
+    fn bracketed(string: &str) -> Option<(String, &str)> {
+        let (mut component, mut chars) = (String::new(), string.strip_prefix('"')?.chars());
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => component.push(chars.next()?),
+                '"' => return Some((component, chars.as_str().strip_prefix(']')?)),
+                c => component.push(c),
+            }
+        }
+
+        None
+    }
+
+    if let Some(pointer) = key.strip_prefix('/') {
+        return pointer.split('/')
+            .map(|component| component.replace("~1", "/").replace("~0", "~"))
+            .collect();
+    }
+
+    let (mut components, mut current, mut pending) = (vec![], String::new(), true);
+    let mut chars = key.chars();
+    while let Some(c) = chars.next() {
+        pending = true;
+        match c {
+            '\\' => current.push(chars.next().unwrap_or('\\')),
+            '.' => components.push(core::mem::take(&mut current)),
+            '[' => match bracketed(chars.as_str()) {
+                Some((component, rest)) => {
+                    if !current.is_empty() {
+                        components.push(core::mem::take(&mut current));
+                    }
+
+                    components.push(component);
+                    pending = rest.starts_with('.');
+                    chars = rest.strip_prefix('.').unwrap_or(rest).chars();
+                }
+                None => current.push(c),
+            },
+            c => current.push(c),
+        }
+    }
+
+    if pending {
+        components.push(current);
+    }
+
+    components
+}
+
+/// Escapes a single key path component so that [`split_key()`] returns it
+/// verbatim as one component. Dots, backslashes, brackets, and a leading
+/// slash are prefixed with a backslash.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::util::{escape_key, split_key};
+///
+/// assert_eq!(escape_key("name"), "name");
+/// assert_eq!(escape_key("example.com"), r"example\.com");
+///
+/// let key = format!("hosts.{}.port", escape_key("example.com"));
+/// assert_eq!(split_key(&key), vec!["hosts", "example.com", "port"]);
+///
+/// assert_eq!(escape_key("/etc"), r"\/etc");
+/// assert_eq!(split_key(&escape_key("/etc")), vec!["/etc"]);
+/// ```
+pub fn escape_key(key: &str) -> alloc::borrow::Cow<'_, str> {
+    if !key.contains(['.', '\\', '[']) && !key.starts_with('/') {
+        return key.into();
+    }
+
+    let mut escaped = String::with_capacity(key.len() + 2);
+    for (i, c) in key.char_indices() {
+        if matches!(c, '.' | '\\' | '[') || (i == 0 && c == '/') {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped.into()
+}
+
+/// Splits a trailing, unescaped `@profile` annotation off of the key path
+/// `key`, returning the key path without the annotation and the annotated
+/// profile, if any. An `@` escaped with a backslash or within a bracketed
+/// component (see [`split_key()`]) is not an annotation, nor is an `@` with
+/// nothing after it.
+pub(crate) fn split_profile(key: &str) -> (&str, Option<crate::Profile>) {
+    let (mut escaped, mut quoted, mut at) = (false, false, None);
+    for (i, c) in key.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            '@' if !quoted => at = Some(i),
+            _ => {}
+        }
+    }
+
+    match at {
+        Some(i) if i + 1 < key.len() => (&key[..i], Some(key[i + 1..].into())),
+        _ => (key, None),
+    }
+}
+
+/// Returns the edit distance between `a` and `b`, counting insertions,
+/// deletions, substitutions, and transpositions of adjacent characters.
+pub(crate) fn distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    (0..=a.len()).for_each(|i| d[i][0] = i);
+    (0..=b.len()).for_each(|j| d[0][j] = j);
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+/// Decodes `string` as base64 in the standard or URL-safe alphabet, with or
+/// without padding. Returns `None` if `string` isn't valid base64.
+pub(crate) fn decode_base64(string: &str) -> Option<Vec<u8>> {
+    fn sextet(c: u8) -> Option<u32> {
+        Some(match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        } as u32)
+    }
+
+    let bytes = string.trim_end_matches('=').as_bytes();
+    if bytes.len() % 4 == 1 || string.len() - bytes.len() > 2 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() * 3 / 4);
+    for chunk in bytes.chunks(4) {
+        let mut bits = 0;
+        for (i, c) in chunk.iter().enumerate() {
+            bits |= sextet(*c)? << (18 - 6 * i);
+        }
+
+        out.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+    }
+
+    Some(out)
+}
+
 /// Given a key path `key` of the form `a.b.c`, creates nested dictionaries for
-/// for every path component delimited by `.` in the path string (3 in `a.b.c`),
-/// each a parent of the next, and the leaf mapping to `value` (`a` -> `b` ->
-/// `c` -> `value`).
+/// for every path component in the path string (3 in `a.b.c`), as split by
+/// [`split_key()`], each a parent of the next, and the leaf mapping to `value`
+/// (`a` -> `b` -> `c` -> `value`).
 ///
 /// If `key` is empty, simply returns `value`. Otherwise, `Value` will be a
 /// dictionary with the nested mappings.
@@ -269,18 +497,18 @@ use crate::value::{Value, Dict};
 /// assert_eq!(just_leaf, leaf);
 /// ```
 pub fn nest(key: &str, value: Value) -> Value {
-    fn value_from(mut keys: std::str::Split<'_, char>, value: Value) -> Value {
+    fn value_from(mut keys: alloc::vec::IntoIter<String>, value: Value) -> Value {
         match keys.next() {
             Some(k) if !k.is_empty() => {
                 let mut dict = Dict::new();
-                dict.insert(k.into(), value_from(keys, value));
+                dict.insert(k, value_from(keys, value));
                 dict.into()
             }
             Some(_) | None => value
         }
     }
 
-    value_from(key.split('.'), value)
+    value_from(split_key(key).into_iter(), value)
 }
 
 #[doc(hidden)]
@@ -304,13 +532,13 @@ macro_rules! make_cloneable {
             fn box_clone(&self) -> Box<dyn $Trait>;
         }
 
-        impl std::clone::Clone for Box<dyn $Trait> {
+        impl core::clone::Clone for Box<dyn $Trait> {
             fn clone(&self) -> Box<dyn $Trait> {
                 (&**self).box_clone()
             }
         }
 
-        impl std::fmt::Debug for Box<dyn $Trait> {
+        impl core::fmt::Debug for Box<dyn $Trait> {
             fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
                 Ok(())
             }
@@ -0,0 +1,110 @@
+use crate::prelude::*;
+
+/// A naming convention for keys, used to canonicalize keys across providers.
+///
+/// A key is split into words at `-`, `_`, and whitespace, and at case
+/// transitions: before an uppercase letter that follows a lowercase letter or
+/// digit, and before the last uppercase letter in a run of uppercase letters
+/// that is followed by a lowercase letter. The words are then joined according
+/// to the convention. As such, `max-connections`, `MAX_CONNECTIONS`, and
+/// `maxConnections` are all the same key in every convention.
+///
+/// Set on a `Figment` via
+/// [`Figment::canonicalize_keys()`](crate::Figment::canonicalize_keys()).
+///
+/// # Example
+///
+/// ```rust
+/// use figment::value::KeyCase;
+///
+/// for key in ["max-connections", "MAX_CONNECTIONS", "maxConnections"] {
+///     assert_eq!(KeyCase::Snake.apply(key), "max_connections");
+///     assert_eq!(KeyCase::Kebab.apply(key), "max-connections");
+///     assert_eq!(KeyCase::Camel.apply(key), "maxConnections");
+///     assert_eq!(KeyCase::Pascal.apply(key), "MaxConnections");
+///     assert_eq!(KeyCase::ScreamingSnake.apply(key), "MAX_CONNECTIONS");
+/// }
+///
+/// assert_eq!(KeyCase::Snake.apply("HTTPServer2Port"), "http_server2_port");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyCase {
+    /// `snake_case`: lowercase words joined by `_`.
+    Snake,
+    /// `kebab-case`: lowercase words joined by `-`.
+    Kebab,
+    /// `camelCase`: capitalized words after the first, lowercase, first word.
+    Camel,
+    /// `PascalCase`: capitalized words.
+    Pascal,
+    /// `SCREAMING_SNAKE_CASE`: uppercase words joined by `_`.
+    ScreamingSnake,
+}
+
+impl KeyCase {
+    /// Converts `key` to this naming convention.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::KeyCase;
+    ///
+    /// assert_eq!(KeyCase::Camel.apply("log_level"), "logLevel");
+    /// assert_eq!(KeyCase::Kebab.apply("LogLevel"), "log-level");
+    /// ```
+    pub fn apply(self, key: &str) -> String {
+        let mut string = String::with_capacity(key.len());
+        for (i, word) in words(key).into_iter().enumerate() {
+            match self {
+                KeyCase::Snake | KeyCase::ScreamingSnake if i > 0 => string.push('_'),
+                KeyCase::Kebab if i > 0 => string.push('-'),
+                _ => {}
+            }
+
+            match self {
+                KeyCase::Snake | KeyCase::Kebab => string.push_str(&word),
+                KeyCase::ScreamingSnake => string.push_str(&word.to_ascii_uppercase()),
+                KeyCase::Camel if i == 0 => string.push_str(&word),
+                KeyCase::Camel | KeyCase::Pascal => {
+                    let mut chars = word.chars();
+                    string.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+                    string.extend(chars);
+                }
+            }
+        }
+
+        string
+    }
+}
+
+/// Splits `key` into lowercase words. See [`KeyCase`].
+fn words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = vec![];
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' || c == '_' || c.is_whitespace() {
+            if !word.is_empty() {
+                words.push(core::mem::take(&mut word));
+            }
+
+            continue;
+        }
+
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                words.push(core::mem::take(&mut word));
+            }
+        }
+
+        word.extend(c.to_lowercase());
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
@@ -0,0 +1,268 @@
+use core::convert::TryFrom;
+use core::cell::Cell;
+use core::fmt;
+use alloc::sync::Arc;
+
+use serde::Serialize;
+use serde::de::{Deserializer, DeserializeOwned, Visitor};
+
+use crate::prelude::*;
+use crate::error::{Actual, Error};
+use crate::value::{Num, Value};
+
+/// A policy controlling lossy numeric conversions during extraction.
+///
+/// By default, a `Figment` is _strict_: a float like `1.0` does not satisfy an
+/// integer field, and an integer that is out of range for the requested type
+/// results in an error. A `Coercion` relaxes these rules and is set on a
+/// `Figment` via [`Figment::coerce()`](crate::Figment::coerce()).
+///
+/// Coercion only applies to values that would otherwise fail to deserialize.
+/// In-range integers are always extracted as-is.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, value::Coercion};
+///
+/// let figment = Figment::from(("float", 1.0)).merge(("big", 300));
+/// assert!(figment.extract_inner::<u32>("float").is_err());
+/// assert!(figment.extract_inner::<u8>("big").is_err());
+///
+/// let figment = figment.coerce(Coercion::lenient());
+/// assert_eq!(figment.extract_inner::<u32>("float").unwrap(), 1);
+/// assert_eq!(figment.extract_inner::<u8>("big").unwrap(), 255);
+///
+/// // Non-integral floats are never coerced into integers.
+/// let figment = figment.merge(("half", 2.5));
+/// assert!(figment.extract_inner::<u32>("half").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coercion {
+    integral_floats: bool,
+    saturating: bool,
+}
+
+impl Coercion {
+    /// The default policy: no lossy conversions are performed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::Coercion;
+    ///
+    /// assert_eq!(Coercion::strict(), Coercion::default());
+    /// ```
+    pub const fn strict() -> Self {
+        Coercion { integral_floats: false, saturating: false }
+    }
+
+    /// A policy allowing integral floats to be extracted as integers and
+    /// saturating out-of-range integers to the bounds of the requested type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::Coercion;
+    ///
+    /// let lenient = Coercion::strict().integral_floats(true).saturating(true);
+    /// assert_eq!(Coercion::lenient(), lenient);
+    /// ```
+    pub const fn lenient() -> Self {
+        Coercion { integral_floats: true, saturating: true }
+    }
+
+    /// Sets whether floats with no fractional part, like `1.0`, can be
+    /// extracted as integers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::Coercion};
+    ///
+    /// let figment = Figment::from(("port", 8000.0))
+    ///     .coerce(Coercion::strict().integral_floats(true));
+    ///
+    /// assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+    /// ```
+    pub const fn integral_floats(mut self, enabled: bool) -> Self {
+        self.integral_floats = enabled;
+        self
+    }
+
+    /// Sets whether integers that are out of range for the requested type are
+    /// clamped to the type's bounds instead of resulting in an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::Coercion};
+    ///
+    /// let figment = Figment::from(("small", -10))
+    ///     .coerce(Coercion::strict().saturating(true));
+    ///
+    /// assert_eq!(figment.extract_inner::<u8>("small").unwrap(), 0);
+    /// assert_eq!(figment.extract_inner::<i64>("small").unwrap(), -10);
+    /// ```
+    pub const fn saturating(mut self, enabled: bool) -> Self {
+        self.saturating = enabled;
+        self
+    }
+}
+
+impl Default for Coercion {
+    fn default() -> Self {
+        Coercion::strict()
+    }
+}
+
+macro_rules! coerce_fn {
+    ($($name:ident: $T:ty),*) => ($(
+        /// Returns `Some` only if `num` cannot be extracted as-is but can be
+        /// coerced under this policy.
+        pub(crate) fn $name(self, num: Num) -> Option<$T> {
+            let (min, max) = (<$T>::MIN, <$T>::MAX);
+            let (int, from_float) = match num.to_actual() {
+                Actual::Float(v) if self.integral_floats && v.is_finite() && v % 1.0 == 0.0 => {
+                    match v < 0.0 {
+                        true => (Actual::Signed(v as i128), true),
+                        false => (Actual::Unsigned(v as u128), true),
+                    }
+                }
+                Actual::Float(_) => return None,
+                int => (int, false),
+            };
+
+            match int {
+                Actual::Unsigned(v) => match <$T>::try_from(v) {
+                    Ok(v) => from_float.then_some(v),
+                    Err(_) => self.saturating.then_some(max),
+                },
+                Actual::Signed(v) => match <$T>::try_from(v) {
+                    Ok(v) => from_float.then_some(v),
+                    Err(_) => self.saturating.then_some(if v < 0 { min } else { max }),
+                },
+                _ => None,
+            }
+        }
+    )*)
+}
+
+impl Coercion {
+    coerce_fn!(to_u8: u8, to_u16: u16, to_u32: u32, to_u64: u64, to_u128: u128);
+    coerce_fn!(to_i8: i8, to_i16: i16, to_i32: i32, to_i64: i64, to_i128: i128);
+}
+
+/// A string coercer registered via
+/// [`Figment::coerce_str()`](crate::Figment::coerce_str()). Returns `None` if
+/// the string deserializes as-is, and otherwise the coerced value.
+pub(crate) type Coercer = dyn Fn(&str) -> Option<Result<Value, Error>> + Send + Sync;
+
+/// Returns the serde name of `T`, if it is a struct, enum, or primitive other
+/// than a string, along with a [`Coercer`] for `T` that calls `f`.
+pub(crate) fn coercer<T, E, F>(f: F) -> Option<(&'static str, Arc<Coercer>)>
+    where T: Serialize + DeserializeOwned,
+          E: fmt::Display,
+          F: Fn(&str) -> Result<T, E> + Send + Sync + 'static,
+{
+    let name = Cell::new(None);
+    let _ = T::deserialize(TypeName(&name));
+    let coercer = move |string: &str| {
+        if T::deserialize(&Value::from(string)).is_ok() {
+            return None;
+        }
+
+        // The coerced value is checked so that it's never coerced again.
+        let value = f(string).map_err(|e| Error::from(e.to_string()))
+            .and_then(Value::serialize)
+            .and_then(|value| T::deserialize(&value).map(|_| value));
+
+        Some(value)
+    };
+
+    Some((name.get()?, Arc::new(coercer)))
+}
+
+/// A deserializer that records the name of the type deserialized from it.
+struct TypeName<'a>(&'a Cell<Option<&'static str>>);
+
+impl TypeName<'_> {
+    fn named<T>(self, name: &'static str) -> Result<T, Error> {
+        self.0.set(Some(name));
+        Err(Error::from(String::new()))
+    }
+}
+
+macro_rules! named_fn {
+    ($($method:ident: $name:expr),*) => ($(
+        fn $method<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+            self.named($name)
+        }
+    )*)
+}
+
+impl<'de> Deserializer<'de> for TypeName<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::from(String::new()))
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, _: V) -> Result<V::Value, Error>
+        where V: Visitor<'de>
+    {
+        self.named(name)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, _: V) -> Result<V::Value, Error>
+        where V: Visitor<'de>
+    {
+        self.named(name)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        _: usize,
+        _: V
+    ) -> Result<V::Value, Error>
+        where V: Visitor<'de>
+    {
+        self.named(name)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _: &'static [&'static str],
+        _: V
+    ) -> Result<V::Value, Error>
+        where V: Visitor<'de>
+    {
+        self.named(name)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        _: &'static [&'static str],
+        _: V
+    ) -> Result<V::Value, Error>
+        where V: Visitor<'de>
+    {
+        self.named(name)
+    }
+
+    named_fn! {
+        deserialize_bool: "bool", deserialize_char: "char",
+        deserialize_f32: "f32", deserialize_f64: "f64",
+        deserialize_u8: "u8", deserialize_u16: "u16", deserialize_u32: "u32",
+        deserialize_u64: "u64", deserialize_u128: "u128",
+        deserialize_i8: "i8", deserialize_i16: "i16", deserialize_i32: "i32",
+        deserialize_i64: "i64", deserialize_i128: "i128"
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf option unit seq tuple map identifier ignored_any
+    }
+}
@@ -1,34 +1,234 @@
-use std::fmt;
-use std::result;
-use std::cell::Cell;
+use core::fmt;
+use core::result;
+use core::cell::{Cell, RefCell};
+use alloc::collections::BTreeSet;
 
 use serde::Deserialize;
 use serde::de::{self, Deserializer, IntoDeserializer};
 use serde::de::{Visitor, SeqAccess, MapAccess};
 
+use crate::prelude::*;
 use crate::Figment;
 use crate::error::{Error, Kind, Result};
 use crate::value::{Value, Num, Empty, Dict, Tag};
 
+/// The addresses of the values read during a deserialization.
+pub type Reads = RefCell<BTreeSet<*const Value>>;
+
 pub struct ConfiguredValueDe<'c> {
     pub config: &'c Figment,
     pub value: &'c Value,
     pub readable: Cell<bool>,
+    pub reads: Option<&'c Reads>,
 }
 
 impl<'c> ConfiguredValueDe<'c> {
     pub fn from(config: &'c Figment, value: &'c Value) -> Self {
-        Self { config, value, readable: Cell::from(true) }
+        Self { config, value, readable: Cell::from(true), reads: None }
+    }
+
+    /// Returns a deserializer for `value`, a child of `self.value`, that
+    /// shares `self`'s configuration.
+    pub fn child<'v>(&self, value: &'v Value) -> ConfiguredValueDe<'v>
+        where 'c: 'v
+    {
+        ConfiguredValueDe { reads: self.reads, ..ConfiguredValueDe::from(self.config, value) }
+    }
+
+    fn record(&self) {
+        if let Some(reads) = self.reads {
+            reads.borrow_mut().insert(self.value as *const Value);
+        }
+    }
+
+    /// Returns the value the [coercer](Figment::coerce_str()) for the type
+    /// named `name` produces from `self`'s value if it is a string that
+    /// doesn't deserialize as the type as-is.
+    fn coerced(&self, name: &str) -> Option<Result<Value>> {
+        let (tag, string) = match self.value {
+            Value::String(tag, string) => (*tag, string),
+            _ => return None,
+        };
+
+        let coercer = self.config.hooks.coercers.get(name)?;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(name, "coercing string");
+
+        let result = coercer(string)?.map(|mut value| {
+            value.map_tags(|t| *t = tag);
+            value
+        });
+
+        Some(result.map_err(|e| e.retagged(tag).resolved(self.config)))
+    }
+}
+
+/// Deserializes the value the coercer for `$name` produces from `$de`'s value,
+/// if any, as `$f` does, returning the result.
+macro_rules! try_coerced {
+    ($de:ident, $name:expr, |$c:ident| $f:expr) => (
+        if let Some(value) = $de.coerced($name) {
+            $de.record();
+            let value = value?;
+            let $c = ConfiguredValueDe::from($de.config, &value);
+            return $f;
+        }
+    )
+}
+
+/// A deserializer that can be the scope of a [`Deserialize`] implementation.
+pub trait ScopedDe: Sized {
+    /// Calls `f` with `self`, making `self`'s value the innermost scope while
+    /// `f` runs. Magic values that serde buffers are looked for in the scope.
+    /// See [`deserialize_buffered()`].
+    fn scoped<T>(self, f: impl FnOnce(Self) -> T) -> T {
+        f(self)
+    }
+}
+
+impl ScopedDe for &Value { }
+
+impl ScopedDe for ConfiguredValueDe<'_> {
+    fn scoped<T>(self, f: impl FnOnce(Self) -> T) -> T {
+        #[cfg(feature = "std")]
+        let _scope = Scope::push(self.config, self.value);
+        f(self)
     }
 }
 
+/// The value of a [`ScopedDe`] [`ConfiguredValueDe`].
+#[cfg(feature = "std")]
+struct Scope {
+    config: *const Figment,
+    value: *const Value,
+    /// The values in `value` magic values have already been deserialized from.
+    claimed: Vec<*const Value>,
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// The values being deserialized, innermost last.
+    static SCOPES: RefCell<Vec<Scope>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the innermost [`Scope`] when dropped.
+#[cfg(feature = "std")]
+struct ScopeGuard;
+
+#[cfg(feature = "std")]
+impl Scope {
+    fn push(config: &Figment, value: &Value) -> ScopeGuard {
+        let scope = Scope { config, value, claimed: vec![] };
+        SCOPES.with(|scopes| scopes.borrow_mut().push(scope));
+        ScopeGuard
+    }
+
+    /// Returns the first value in `self.value`, in visiting order, that is
+    /// equal to `value` and isn't claimed, and claims it.
+    fn claim(&mut self, value: &Value) -> Option<*const Value> {
+        fn find(root: &Value, value: &Value, claimed: &[*const Value]) -> Option<*const Value> {
+            if root == value && !claimed.contains(&(root as *const Value)) {
+                return Some(root);
+            }
+
+            match root {
+                Value::Dict(_, dict) => dict.values().find_map(|v| find(v, value, claimed)),
+                Value::Array(_, array) => array.iter().find_map(|v| find(v, value, claimed)),
+                _ => None,
+            }
+        }
+
+        // SAFETY: A scope is popped before the borrow of its value ends.
+        let found = find(unsafe { &*self.value }, value, &self.claimed)?;
+        self.claimed.push(found);
+        Some(found)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let _ = SCOPES.try_with(|scopes| scopes.borrow_mut().pop());
+    }
+}
+
+/// Deserializes a magic value serde buffered, and which was thus deserialized
+/// as the plain `value`, by calling `f` with a [`ConfiguredValueDe`] for the
+/// value it was buffered from, if that value can be found.
+///
+/// serde buffers the fields of `#[serde(flatten)]` structs and of untagged or
+/// internally tagged enums while deserializing the struct or enum from a
+/// [`ScopedDe`] deserializer, so the original value is the first unclaimed value
+/// equal to `value`, in visiting order, in the innermost scope that has one.
+pub(crate) fn deserialize_buffered<'c, T, F>(value: &Value, f: F) -> Option<Result<T>>
+    where F: FnOnce(ConfiguredValueDe<'c>) -> Result<T>
+{
+    #[cfg(feature = "std")] {
+        let (config, original) = SCOPES.with(|scopes| {
+            scopes.borrow_mut().iter_mut().rev().find_map(|scope| {
+                Some((scope.config, scope.claim(value)?))
+            })
+        })?;
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(kind = kind(value), "found original of buffered magic value");
+
+        // SAFETY: The scope holding these pointers is on the stack: it is
+        // popped after `f` returns, and `ConfiguredValueDe` never lends out
+        // its values, so the result of `f` doesn't borrow from them.
+        let (config, original) = unsafe { (&*config, &*original) };
+        Some(f(ConfiguredValueDe::from(config, original)))
+    }
+
+    #[cfg(not(feature = "std"))] {
+        let _ = (value, f);
+        None
+    }
+}
+
+/// Returns the name of the kind of `value`, but never the value itself, for
+/// use in `tracing` events.
+#[cfg(feature = "tracing")]
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::String(..) => "string",
+        Value::Char(..) => "char",
+        Value::Bool(..) => "bool",
+        Value::Num(..) => "number",
+        Value::Empty(..) => "empty",
+        Value::Dict(..) => "dict",
+        Value::Array(..) => "array",
+    }
+}
+
+macro_rules! coerced_int_fn {
+    ($($name:ident($T:ident): $coerce:ident => $visit:ident),*) => ($(
+        fn $name<V: Visitor<'de>>(self, v: V) -> Result<V::Value> {
+            try_coerced!(self, stringify!($T), |de| de.$name(v));
+            self.record();
+            let (config, tag) = (self.config, self.value.tag());
+            match *self.value {
+                Value::Num(_, n) => match config.coercion.$coerce(n) {
+                    Some(n) => v.$visit(n).map_err(|e: Error| e.retagged(tag).resolved(config)),
+                    None => self.deserialize_any(v),
+                },
+                _ => self.deserialize_any(v),
+            }
+        }
+    )*)
+}
+
 impl<'de: 'c, 'c> Deserializer<'de> for ConfiguredValueDe<'c> {
     type Error = Error;
 
     fn deserialize_any<V>(self, v: V) -> Result<V::Value>
         where V: de::Visitor<'de>
     {
-        let maker = |v| Self::from(self.config, v);
+        self.record();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(kind = kind(self.value), "deserializing value");
+
+        let maker = |v| self.child(v);
         let result = match *self.value {
             Value::String(_, ref s) => v.visit_str(s),
             Value::Char(_, c) => v.visit_char(c),
@@ -45,9 +245,14 @@ impl<'de: 'c, 'c> Deserializer<'de> for ConfiguredValueDe<'c> {
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
         where V: Visitor<'de>
     {
+        self.record();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(present = self.value.to_empty().is_none(), "deserializing option");
+
         let (config, tag) = (self.config, self.value.tag());
         let result = match self.value {
             Value::Empty(_, val) => val.deserialize_any(visitor),
+            Value::String(_, s) if s.is_empty() && config.empty_as_none => visitor.visit_none(),
             _ => visitor.visit_some(self)
         };
 
@@ -57,18 +262,43 @@ impl<'de: 'c, 'c> Deserializer<'de> for ConfiguredValueDe<'c> {
     fn deserialize_struct<V: Visitor<'de>>(
         self,
         name: &'static str,
-        _fields: &'static [&'static str],
+        fields: &'static [&'static str],
         visitor: V
     ) -> Result<V::Value> {
         use crate::value::magic::*;
 
+        try_coerced!(self, name, |de| de.deserialize_struct(name, fields, visitor));
+        self.record();
+        #[cfg(feature = "tracing")]
+        {
+            let strategy = match name {
+                Value::NAME => "magic: Value",
+                #[cfg(feature = "std")]
+                RelativePathBuf::NAME => "magic: RelativePathBuf",
+                Tagged::<()>::NAME => "magic: Tagged",
+                Encrypted::<()>::NAME => "magic: Encrypted",
+                _ if self.config.case_insensitive && self.value.as_dict().is_some() => "uncased fields",
+                _ => "any",
+            };
+
+            tracing::trace!(name, strategy, "deserializing struct");
+        }
+
         let (config, tag) = (self.config, self.value.tag());
         let result = match name {
             Value::NAME => Value::deserialize_from(self, visitor),
+            #[cfg(feature = "std")]
             RelativePathBuf::NAME => RelativePathBuf::deserialize_from(self, visitor),
             Tagged::<()>::NAME => Tagged::<()>::deserialize_from(self, visitor),
+            Encrypted::<()>::NAME => Encrypted::<()>::deserialize_from(self, visitor),
             // SelectedProfile::NAME => SelectedProfile::deserialize_from(self, visitor),
-            _ => self.deserialize_any(visitor)
+            _ => match self.value {
+                Value::Dict(_, map) if config.case_insensitive => {
+                    let map_access = MapDe::new(map, |v| self.child(v)).uncased_fields(fields);
+                    visitor.visit_map(map_access)
+                }
+                _ => self.deserialize_any(visitor)
+            }
         };
 
         result.map_err(|e| e.retagged(tag).resolved(config))
@@ -77,18 +307,25 @@ impl<'de: 'c, 'c> Deserializer<'de> for ConfiguredValueDe<'c> {
 
     fn deserialize_enum<V: Visitor<'de>>(
         self,
-        _: &'static str,
-        _: &'static [&'static str],
+        name: &'static str,
+        variants: &'static [&'static str],
         v: V,
     ) -> Result<V::Value> {
         use serde::de::value::MapAccessDeserializer;
 
+        try_coerced!(self, name, |de| de.deserialize_enum(name, variants, v));
+        self.record();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(name, kind = kind(self.value), "deserializing enum");
+
+        // Providers like `Env` may change the case of keys, so variants are
+        // matched ignoring case if they don't match exactly.
         let (config, tag) = (self.config, self.value.tag());
         let result = match self.value {
-            Value::String(_, s) => v.visit_enum((&**s).into_deserializer()),
+            Value::String(_, s) => v.visit_enum(canonical(variants, s).into_deserializer()),
             Value::Dict(_, ref map) => {
-                let maker = |v| Self::from(self.config, v);
-                let map_access = MapDe::new(map, maker);
+                let maker = |v| self.child(v);
+                let map_access = MapDe::new(map, maker).uncased_fields(variants);
                 v.visit_enum(MapAccessDeserializer::new(map_access))
             }
             Value::Num(_, n) if n.to_u32().is_some() => {
@@ -107,29 +344,120 @@ impl<'de: 'c, 'c> Deserializer<'de> for ConfiguredValueDe<'c> {
         val
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+        where V: Visitor<'de>
+    {
+        // An ignored value isn't read: don't record it.
+        ConfiguredValueDe { reads: None, ..self }.deserialize_any(visitor)
+    }
+
+    coerced_int_fn! {
+        deserialize_u8(u8): to_u8 => visit_u8,
+        deserialize_u16(u16): to_u16 => visit_u16,
+        deserialize_u32(u32): to_u32 => visit_u32,
+        deserialize_u64(u64): to_u64 => visit_u64,
+        deserialize_u128(u128): to_u128 => visit_u128,
+        deserialize_i8(i8): to_i8 => visit_i8,
+        deserialize_i16(i16): to_i16 => visit_i16,
+        deserialize_i32(i32): to_i32 => visit_i32,
+        deserialize_i64(i64): to_i64 => visit_i64,
+        deserialize_i128(i128): to_i128 => visit_i128
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V
+    ) -> Result<V::Value> {
+        try_coerced!(self, name, |de| de.deserialize_newtype_struct(name, visitor));
+        let (config, tag) = (self.config, self.value.tag());
+        visitor.visit_newtype_struct(self).map_err(|e| e.retagged(tag).resolved(config))
+    }
+
+    /// The integers `1` and `0`, as in `APP_DEBUG=1`, are read as `true` and
+    /// `false`. Every other value is deserialized as-is.
+    fn deserialize_bool<V: Visitor<'de>>(self, v: V) -> Result<V::Value> {
+        use crate::error::Actual;
+
+        try_coerced!(self, "bool", |de| de.deserialize_bool(v));
+        self.record();
+        let (config, tag) = (self.config, self.value.tag());
+        let boolean = match *self.value {
+            Value::Num(_, n) => match n.to_actual() {
+                Actual::Unsigned(0) | Actual::Signed(0) => Some(false),
+                Actual::Unsigned(1) | Actual::Signed(1) => Some(true),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match boolean {
+            Some(b) => v.visit_bool(b).map_err(|e: Error| e.retagged(tag).resolved(config)),
+            None => self.deserialize_any(v),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V
+    ) -> Result<V::Value> {
+        try_coerced!(self, name, |de| de.deserialize_unit_struct(name, visitor));
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V
+    ) -> Result<V::Value> {
+        try_coerced!(self, name, |de| de.deserialize_tuple_struct(name, len, visitor));
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        try_coerced!(self, "f32", |de| de.deserialize_f32(visitor));
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        try_coerced!(self, "f64", |de| de.deserialize_f64(visitor));
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        try_coerced!(self, "char", |de| de.deserialize_char(visitor));
+        self.deserialize_any(visitor)
+    }
+
     serde::forward_to_deserialize_any! {
-        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str
-        string seq bytes byte_buf map unit newtype_struct
-        ignored_any unit_struct tuple_struct tuple identifier
+        str string seq bytes byte_buf map unit tuple identifier
     }
 }
 
-use std::collections::btree_map::Iter;
-
 pub struct MapDe<'m, D, F: Fn(&'m Value) -> D> {
-    iter: Iter<'m, String, Value>,
+    iter: <&'m Dict as IntoIterator>::IntoIter,
     pair: Option<(&'m String, &'m Value)>,
     make_deserializer: F,
+    fields: &'static [&'static str],
 }
 
 impl<'m, D, F: Fn(&'m Value) -> D> MapDe<'m, D, F> {
     pub fn new(map: &'m Dict, maker: F) -> Self {
-        MapDe { iter: map.iter(), pair: None, make_deserializer: maker }
+        MapDe { iter: map.iter(), pair: None, make_deserializer: maker, fields: &[] }
+    }
+
+    /// Yields keys that are equal to one of `fields` ignoring ASCII case as
+    /// that field.
+    pub fn uncased_fields(mut self, fields: &'static [&'static str]) -> Self {
+        self.fields = fields;
+        self
     }
 }
 
 impl<'m, 'de, D, F> de::MapAccess<'de> for MapDe<'m, D, F>
-    where D: Deserializer<'de, Error = Error>, F: Fn(&'m Value) -> D,
+    where D: Deserializer<'de, Error = Error> + ScopedDe, F: Fn(&'m Value) -> D,
 {
     type Error = Error;
 
@@ -137,7 +465,12 @@ impl<'m, 'de, D, F> de::MapAccess<'de> for MapDe<'m, D, F>
         where K: de::DeserializeSeed<'de>
     {
         if let Some((k, v)) = self.iter.next() {
-            let result = seed.deserialize(k.as_str().into_deserializer())
+            let key = canonical(self.fields, k);
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(%key, "deserializing field");
+
+            let result = seed.deserialize(key.into_deserializer())
                 .map_err(|e: Error| e.prefixed(k).retagged(v.tag()))
                 .map(Some);
 
@@ -153,13 +486,22 @@ impl<'m, 'de, D, F> de::MapAccess<'de> for MapDe<'m, D, F>
     {
         let (key, value) = self.pair.take().expect("visit_value called before visit_key");
         let tag = value.tag();
-        seed.deserialize((self.make_deserializer)(value))
+        (self.make_deserializer)(value).scoped(|de| seed.deserialize(de))
             .map_err(|e: Error| e.prefixed(key).retagged(tag))
     }
 }
 
+/// Returns the name in `names` equal to `key`, or else the name equal to `key`
+/// ignoring ASCII case, or else `key` itself.
+fn canonical<'a>(names: &'static [&'static str], key: &'a str) -> &'a str {
+    names.iter()
+        .find(|name| **name == key)
+        .or_else(|| names.iter().find(|name| uncased::UncasedStr::new(name) == key))
+        .map_or(key, |name| *name)
+}
+
 pub struct SeqDe<'v, D, F: Fn(&'v Value) -> D> {
-    iter: std::iter::Enumerate<std::slice::Iter<'v, Value>>,
+    iter: core::iter::Enumerate<core::slice::Iter<'v, Value>>,
     len: usize,
     make_deserializer: F,
 }
@@ -171,7 +513,7 @@ impl<'v, D, F: Fn(&'v Value) -> D> SeqDe<'v, D, F> {
 }
 
 impl<'v, 'de, D, F> de::SeqAccess<'de> for SeqDe<'v, D, F>
-    where D: Deserializer<'de, Error = Error>, F: Fn(&'v Value) -> D,
+    where D: Deserializer<'de, Error = Error> + ScopedDe, F: Fn(&'v Value) -> D,
 {
     type Error = Error;
 
@@ -181,8 +523,11 @@ impl<'v, 'de, D, F> de::SeqAccess<'de> for SeqDe<'v, D, F>
         if let Some((i, item)) = self.iter.next() {
             // item.map_tag(|metadata| metadata.path.push(self.count.to_string()));
             self.len -= 1;
-            seed.deserialize((self.make_deserializer)(item))
-                .map_err(|e: Error| e.prefixed(&i.to_string()))
+            #[cfg(feature = "tracing")]
+            tracing::trace!(index = i, "deserializing element");
+
+            (self.make_deserializer)(item).scoped(|de| seed.deserialize(de))
+                .map_err(|e: Error| e.prefixed_index(i).retagged(item.tag()))
                 .map(Some)
         } else {
             Ok(None)
@@ -227,15 +572,15 @@ impl<'de> Deserializer<'de> for &Value {
     fn deserialize_enum<V: Visitor<'de>>(
         self,
         _: &'static str,
-        _: &'static [&'static str],
+        variants: &'static [&'static str],
         v: V,
     ) -> Result<V::Value> {
         use serde::de::value::MapAccessDeserializer;
 
         let result = match self {
-            Value::String(_, s) => v.visit_enum((&**s).into_deserializer()),
+            Value::String(_, s) => v.visit_enum(canonical(variants, s).into_deserializer()),
             Value::Dict(_, ref map) => {
-                let map_access = MapDe::new(map, |v| v);
+                let map_access = MapDe::new(map, |v| v).uncased_fields(variants);
                 v.visit_enum(MapAccessDeserializer::new(map_access))
             }
             Value::Num(_, n) if n.to_u32().is_some() => {
@@ -248,9 +593,17 @@ impl<'de> Deserializer<'de> for &Value {
         result.map_err(|e: Error| e.retagged(self.tag()))
     }
 
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self).map_err(|e: Error| e.retagged(self.tag()))
+    }
+
     serde::forward_to_deserialize_any! {
-        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str
-        string seq bytes byte_buf map unit struct newtype_struct
+        bool u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 char str
+        string seq bytes byte_buf map unit struct
         ignored_any unit_struct tuple_struct tuple identifier
     }
 }
@@ -300,8 +653,8 @@ impl<'de> Deserializer<'de> for Num {
     }
 
     serde::forward_to_deserialize_any! {
-        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq enum
-        bytes byte_buf map struct unit newtype_struct
+        bool u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 char str string
+        seq enum bytes byte_buf map struct unit newtype_struct
         ignored_any unit_struct tuple_struct tuple option identifier
     }
 }
@@ -314,13 +667,13 @@ impl<'de> Deserializer<'de> for Empty {
     {
         match self {
             Empty::Unit => visitor.visit_unit(),
-            Empty::None => visitor.visit_none(),
+            Empty::None | Empty::Unset => visitor.visit_none(),
         }
     }
 
     serde::forward_to_deserialize_any! {
-        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq enum
-        bytes byte_buf map struct unit newtype_struct
+        bool u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 char str string
+        seq enum bytes byte_buf map struct unit newtype_struct
         ignored_any unit_struct tuple_struct tuple option identifier
     }
 }
@@ -341,7 +694,7 @@ impl Value {
         let mut map = Dict::new();
         map.insert(Self::FIELDS[0].into(), de.value.tag().into());
         map.insert(Self::FIELDS[1].into(), de.value.clone());
-        visitor.visit_map(MapDe::new(&map, |v| ConfiguredValueDe::from(de.config, v)))
+        visitor.visit_map(MapDe::new(&map, |v| de.child(v)))
     }
 }
 
@@ -356,10 +709,7 @@ impl<'de> Deserialize<'de> for RawValue {
 
 impl<'de> Deserialize<'de> for Value {
     fn deserialize<D: Deserializer<'de>>(de: D) -> result::Result<Value, D::Error> {
-        // Total hack to "fingerprint" our deserializer by checking if
-        // human_readable changes, which does for ours but shouldn't for others.
-        let (a, b) = (de.is_human_readable(), de.is_human_readable());
-        if a != b {
+        if is_configured(&de) {
             de.deserialize_struct(Value::NAME, Value::FIELDS, ValueVisitor)
         } else {
             de.deserialize_any(ValueVisitor)
@@ -367,12 +717,53 @@ impl<'de> Deserialize<'de> for Value {
     }
 }
 
+/// Returns `true` if `de` is a [`ConfiguredValueDe`], which understands the
+/// struct hints magic values use to request information from the figment.
+pub(crate) fn is_configured<'de, D: Deserializer<'de>>(de: &D) -> bool {
+    // Total hack to "fingerprint" our deserializer by checking if
+    // human_readable changes, which does for ours but shouldn't for others.
+    let (a, b) = (de.is_human_readable(), de.is_human_readable());
+    a != b
+}
+
+/// Returns `true` if a magic value should be deserialized from `de` via its
+/// struct hint: if `de` is ours, or if `de` isn't human-readable and thus
+/// likely doesn't support `deserialize_any()`. Otherwise, as when serde
+/// buffers a value before deserializing it, the magic value is deserialized
+/// from its plain representation.
+pub(crate) fn use_magic_hint<'de, D: Deserializer<'de>>(de: &D, _name: &str) -> bool {
+    let hint = is_configured(de) || !de.is_human_readable();
+    #[cfg(feature = "tracing")]
+    if !hint {
+        tracing::trace!(name = _name, "deserializing magic value from plain representation");
+    }
+
+    hint
+}
+
 pub struct ValueVisitor;
 
+/// Counts a value, nested if `nested`, toward the limits of the parse in
+/// progress, if any. See [`Limits::parse()`](crate::value::Limits::parse()).
+#[inline(always)]
+fn parsing<E: de::Error>(_nested: bool) -> result::Result<(), E> {
+    #[cfg(feature = "std")]
+    crate::value::Limits::parsing(_nested).map_err(E::custom)?;
+    Ok(())
+}
+
+/// Marks the end of a nested value counted by [`parsing()`].
+#[inline(always)]
+fn parsed() {
+    #[cfg(feature = "std")]
+    crate::value::Limits::parsed();
+}
+
 macro_rules! visit_fn {
     ($name:ident: $T:ty => $V:path) => (
         #[inline]
         fn $name<E: de::Error>(self, v: $T) -> result::Result<Self::Value, E> {
+            parsing::<E>(false)?;
             Ok(v.into())
         }
     )
@@ -408,30 +799,34 @@ impl<'de> Visitor<'de> for ValueVisitor {
     fn visit_seq<A>(self, mut seq: A) -> result::Result<Self::Value, A::Error>
         where A: SeqAccess<'de>
     {
+        parsing::<A::Error>(true)?;
         let mut array: Vec<Value> = Vec::with_capacity(seq.size_hint().unwrap_or(0));
         while let Some(elem) = seq.next_element()? {
             array.push(elem);
         }
 
+        parsed();
         Ok(array.into())
     }
 
     fn visit_map<A>(self, mut map: A) -> result::Result<Self::Value, A::Error>
         where A: MapAccess<'de>
     {
+        parsing::<A::Error>(true)?;
         let mut dict = Dict::new();
         let mut id: Option<Tag> = None;
         let mut raw_val: Option<RawValue> = None;
         while let Some(key) = map.next_key()? {
             if key == Value::FIELDS[0] {
-                id = Some(map.next_value().expect("value for key"));
+                id = Some(map.next_value()?);
             } else if key == Value::FIELDS[1] {
-                raw_val = Some(map.next_value().expect("value for key"));
+                raw_val = Some(map.next_value()?);
             }  else {
-                dict.insert(key, map.next_value().expect("value for key"));
+                dict.insert(key, map.next_value()?);
             }
         }
 
+        parsed();
         if let Some(mut value) = raw_val {
             if let Some(id) = id {
                 value.0.map_tag(|t| *t = id);
@@ -444,6 +839,7 @@ impl<'de> Visitor<'de> for ValueVisitor {
     }
 
     fn visit_none<E: de::Error>(self) -> result::Result<Self::Value, E> {
+        parsing::<E>(false)?;
         Ok(Empty::None.into())
     }
 
@@ -454,6 +850,7 @@ impl<'de> Visitor<'de> for ValueVisitor {
     }
 
     fn visit_unit<E: de::Error>(self) -> result::Result<Self::Value, E> {
+        parsing::<E>(false)?;
         Ok(Empty::Unit.into())
     }
 }
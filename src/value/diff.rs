@@ -0,0 +1,158 @@
+use core::fmt;
+
+use crate::prelude::*;
+use crate::value::{Value, Empty};
+use crate::util::escape_key;
+
+/// The structural difference between two [`Value`]s.
+///
+/// A `ValueDiff` is produced by [`Value::diff()`] and lists the key paths
+/// (`a.b.c`, escaped as by [`escape_key()`]) that were added, removed, or
+/// changed between an old and a new value. Dictionaries are compared key by
+/// key and arrays index by index, with array indices appearing as path
+/// components (`a.0.b`). Any other pair of values is compared for equality,
+/// ignoring [tags](crate::value::Tag).
+///
+/// The `Display` implementation renders one line per difference, which is
+/// useful in test assertions:
+///
+/// ```rust
+/// use figment::{value::Value, util::map};
+///
+/// let old = Value::from(map!["name" => Value::from("app"), "port" => 80.into()]);
+/// let new = Value::from(map!["name" => Value::from("app"), "port" => 8080.into()]);
+///
+/// let diff = old.diff(&new);
+/// assert_eq!(diff.to_string(), "~ port: 80 -> 8080");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValueDiff {
+    /// Paths present only in the new value, with the new value.
+    pub added: Vec<(String, Value)>,
+    /// Paths present only in the old value, with the old value.
+    pub removed: Vec<(String, Value)>,
+    /// Paths present in both values with differing values: `(path, old, new)`.
+    pub changed: Vec<(String, Value, Value)>,
+}
+
+impl ValueDiff {
+    pub(crate) fn compute(old: &Value, new: &Value) -> ValueDiff {
+        let mut diff = ValueDiff::default();
+        diff.walk(String::new(), old, new);
+        diff
+    }
+
+    fn walk(&mut self, path: String, old: &Value, new: &Value) {
+        fn join(path: &str, key: &str) -> String {
+            match path.is_empty() {
+                true => escape_key(key).into_owned(),
+                false => format!("{}.{}", path, escape_key(key)),
+            }
+        }
+
+        match (old, new) {
+            (Value::Dict(_, a), Value::Dict(_, b)) => {
+                for (key, a_val) in a {
+                    match b.get(key) {
+                        Some(b_val) => self.walk(join(&path, key), a_val, b_val),
+                        None => self.removed.push((join(&path, key), a_val.clone())),
+                    }
+                }
+
+                for (key, b_val) in b {
+                    if !a.contains_key(key) {
+                        self.added.push((join(&path, key), b_val.clone()));
+                    }
+                }
+            }
+            (Value::Array(_, a), Value::Array(_, b)) => {
+                for (i, a_val) in a.iter().enumerate() {
+                    match b.get(i) {
+                        Some(b_val) => self.walk(join(&path, &i.to_string()), a_val, b_val),
+                        None => self.removed.push((join(&path, &i.to_string()), a_val.clone())),
+                    }
+                }
+
+                for (i, b_val) in b.iter().enumerate().skip(a.len()) {
+                    self.added.push((join(&path, &i.to_string()), b_val.clone()));
+                }
+            }
+            (a, b) if a != b => self.changed.push((path, a.clone(), b.clone())),
+            _ => { /* equal leaves */ }
+        }
+    }
+
+    /// Returns `true` if there are no differences.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::Value;
+    ///
+    /// let value = Value::from(vec![1, 2, 3]);
+    /// assert!(value.diff(&value).is_empty());
+    /// assert!(!value.diff(&Value::from(vec![1, 2])).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// A compact, single-line rendering of a `Value` for diff output.
+pub(crate) struct Compact<'a>(pub(crate) &'a Value);
+
+impl fmt::Display for Compact<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Value::String(_, v) => write!(f, "{:?}", v),
+            Value::Char(_, v) => write!(f, "{:?}", v),
+            Value::Bool(_, v) => write!(f, "{}", v),
+            Value::Num(_, v) => match v.to_actual() {
+                crate::error::Actual::Unsigned(v) => write!(f, "{}", v),
+                crate::error::Actual::Signed(v) => write!(f, "{}", v),
+                crate::error::Actual::Float(v) => write!(f, "{:?}", v),
+                _ => unreachable!("numbers are always ints or floats"),
+            },
+            Value::Empty(_, Empty::None) => write!(f, "none"),
+            Value::Empty(_, Empty::Unit) => write!(f, "()"),
+            Value::Empty(_, Empty::Unset) => write!(f, "unset"),
+            Value::Dict(_, dict) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in dict.iter().enumerate() {
+                    if i > 0 { write!(f, ", ")?; }
+                    write!(f, "{} = {}", k, Compact(v))?;
+                }
+
+                write!(f, "}}")
+            }
+            Value::Array(_, array) => {
+                write!(f, "[")?;
+                for (i, v) in array.iter().enumerate() {
+                    if i > 0 { write!(f, ", ")?; }
+                    write!(f, "{}", Compact(v))?;
+                }
+
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValueDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![];
+        for (path, value) in &self.added {
+            lines.push(format!("+ {}: {}", path, Compact(value)));
+        }
+
+        for (path, value) in &self.removed {
+            lines.push(format!("- {}: {}", path, Compact(value)));
+        }
+
+        for (path, old, new) in &self.changed {
+            lines.push(format!("~ {}: {} -> {}", path, Compact(old), Compact(new)));
+        }
+
+        f.write_str(&lines.join("\n"))
+    }
+}
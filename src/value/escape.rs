@@ -6,19 +6,229 @@
 //
 // See README.md, LICENSE-MIT, LICENSE-APACHE.
 
+//! Escaping and quoting of strings in the syntax understood by [`Value`]
+//! parsing.
+//!
+//! [`Value`]s parsed from strings, as done by the [`Env`] provider and
+//! [`Value::from_str()`](std::str::FromStr), infer types from their input: `1`
+//! is an integer, `true` is a boolean, and `"true"` is a string. The functions
+//! in this module produce strings that parse back into exactly the intended
+//! value, allowing custom providers and tools that generate environment
+//! variables to round-trip data faithfully.
+//!
+//!   * [`quote()`] delimits and escapes a string so it always parses as that
+//!     string.
+//!   * [`quote_key()`] quotes a dictionary key only if necessary.
+//!   * [`escape()`] and [`unescape()`] convert between raw strings and the
+//!     contents of a `"`-delimited string.
+//!   * [`to_literal()`] renders an entire [`Value`] in parseable form.
+//!
+//! Key _paths_, as opposed to individual dictionary keys, use a different
+//! syntax. See [`util::escape_key()`](crate::util::escape_key()).
+//!
+//! # Example
+//!
+//! ```rust
+//! use figment::value::{Value, escape};
+//!
+//! let string = "true";
+//! let literal = escape::quote(string);
+//! assert_eq!(literal, r#""true""#);
+//!
+//! let value: Value = literal.parse().unwrap();
+//! assert_eq!(value.as_str(), Some("true"));
+//! ```
+//!
+//! [`Env`]: crate::providers::Env
+
 use core::fmt;
 use std::borrow::Cow;
 
-#[derive(Eq, PartialEq, Debug)]
+use crate::value::Value;
+use crate::error::Actual;
+
+/// An error that occurs while [unescaping](unescape()) a string. Each variant
+/// carries the character index at which the error occured.
+#[derive(Eq, PartialEq, Debug, Clone)]
 pub enum Error {
+    /// A character that is not allowed in a string, such as a control
+    /// character, was found.
     InvalidCharInString(usize, char),
+    /// An unknown escape sequence, like `\q`, was found.
     InvalidEscape(usize, char),
+    /// A non-hexadecimal digit was found in a `\u` or `\U` escape.
     InvalidHexEscape(usize, char),
+    /// A `\u` or `\U` escape did not name a valid unicode scalar value.
     InvalidEscapeValue(usize, u32),
+    /// The string ended in the middle of an escape sequence.
     UnterminatedString(usize),
 }
 
-pub fn escape(string: &str) -> Result<Cow<'_, str>, Error> {
+/// Escapes `string` so that it can be placed between `"` delimiters and be
+/// [unescaped](unescape()) back into `string`. Backslashes, quotes, and
+/// control characters are escaped. Returns `string` unchanged if nothing needs
+/// escaping.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::value::escape::{escape, unescape};
+///
+/// assert_eq!(escape("hello"), "hello");
+/// assert_eq!(escape("say \"hi\"\n"), r#"say \"hi\"\n"#);
+/// assert_eq!(unescape(&escape("tab\tand \\")).unwrap(), "tab\tand \\");
+/// ```
+pub fn escape(string: &str) -> Cow<'_, str> {
+    let needs_escape = |c: char| matches!(c, '"' | '\\') || c.is_control();
+    if !string.chars().any(needs_escape) {
+        return string.into();
+    }
+
+    let mut output = String::with_capacity(string.len() + 2);
+    for ch in string.chars() {
+        match ch {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{c}' => output.push_str("\\f"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => output.push_str(&format!("\\u{:04X}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output.into()
+}
+
+/// Delimits `string` with `"` after [escaping](escape()) it. The result always
+/// parses as a string value equal to `string`.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::value::{Value, escape::quote};
+///
+/// for string in ["10", "false", "[1, 2]", "with \"quotes\"", ""] {
+///     let value: Value = quote(string).parse().unwrap();
+///     assert_eq!(value.as_str(), Some(string));
+/// }
+/// ```
+pub fn quote(string: &str) -> String {
+    format!("\"{}\"", escape(string))
+}
+
+/// Returns `key` as-is if it can be written unquoted as a dictionary key, that
+/// is, if it is non-empty and consists only of ASCII alphanumerics, `_`, and
+/// `-`. Otherwise, returns the [quoted](quote()) key.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::value::{Value, escape::quote_key};
+///
+/// assert_eq!(quote_key("port"), "port");
+/// assert_eq!(quote_key("example.com"), r#""example.com""#);
+///
+/// let literal = format!("{{{} = 443}}", quote_key("example.com"));
+/// let value: Value = literal.parse().unwrap();
+/// assert_eq!(value.find_ref(r#"["example.com"]"#).unwrap().to_u128(), Some(443));
+/// ```
+pub fn quote_key(key: &str) -> Cow<'_, str> {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    match !key.is_empty() && key.chars().all(is_ident_char) {
+        true => key.into(),
+        false => quote(key).into(),
+    }
+}
+
+/// Renders `value` in the syntax understood by [`Value`] parsing such that
+/// parsing the result yields an equal value.
+///
+/// Strings are always [quoted](quote()), floats always contain a `.`, and
+/// dictionary keys are quoted [as necessary](quote_key()). Because the syntax
+/// has no representation for them, [empty](Value::Empty) values are rendered
+/// as the empty string `""`, and non-finite floats as the strings `"NaN"`,
+/// `"inf"`, and `"-inf"`. Such values do not round-trip.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{util::map, value::{Value, escape::to_literal}};
+///
+/// let value = Value::from(map! {
+///     "name" => Value::from("1.0"),
+///     "ratio" => 1.0.into(),
+///     "hosts" => vec!["a", "b"].into(),
+///     "with space" => map!["enabled" => true].into(),
+/// });
+///
+/// let literal = to_literal(&value);
+/// assert_eq!(literal.parse::<Value>().unwrap(), value);
+/// ```
+pub fn to_literal(value: &Value) -> String {
+    let mut string = String::new();
+    write_literal(&mut string, value);
+    string
+}
+
+fn write_literal(output: &mut String, value: &Value) {
+    match value {
+        Value::String(_, v) => output.push_str(&quote(v)),
+        Value::Char(_, v) => output.push_str(&format!("'{}'", v)),
+        Value::Bool(_, v) => output.push_str(&v.to_string()),
+        Value::Num(_, v) => match v.to_actual() {
+            Actual::Unsigned(v) => output.push_str(&v.to_string()),
+            Actual::Signed(v) => output.push_str(&v.to_string()),
+            Actual::Float(v) if !v.is_finite() => output.push_str(&quote(&v.to_string())),
+            Actual::Float(v) => match format!("{:?}", v) {
+                s if s.contains('.') => output.push_str(&s),
+                s => match s.split_once('e') {
+                    Some((mantissa, exp)) => output.push_str(&format!("{}.0e{}", mantissa, exp)),
+                    None => output.push_str(&format!("{}.0", s)),
+                }
+            },
+            _ => unreachable!("numbers are always ints or floats"),
+        },
+        Value::Empty(..) => output.push_str("\"\""),
+        Value::Dict(_, dict) => {
+            output.push('{');
+            for (i, (k, v)) in dict.iter().enumerate() {
+                if i > 0 { output.push_str(", "); }
+                output.push_str(&quote_key(k));
+                output.push_str(" = ");
+                write_literal(output, v);
+            }
+
+            output.push('}');
+        }
+        Value::Array(_, array) => {
+            output.push('[');
+            for (i, v) in array.iter().enumerate() {
+                if i > 0 { output.push_str(", "); }
+                write_literal(output, v);
+            }
+
+            output.push(']');
+        }
+    }
+}
+
+/// Unescapes the contents of a `"`-delimited string, without the delimiters,
+/// resolving the escape sequences documented in [`Env`](crate::providers::Env).
+/// Returns `string` unchanged if it contains no escapes.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::value::escape::unescape;
+///
+/// assert_eq!(unescape("plain").unwrap(), "plain");
+/// assert_eq!(unescape(r"a\tb\u00e9").unwrap(), "a\tb\u{e9}");
+/// assert!(unescape(r"bad \q escape").is_err());
+/// ```
+pub fn unescape(string: &str) -> Result<Cow<'_, str>, Error> {
     let mut chars = string.chars().enumerate();
     let mut output = Cow::from(string);
     while let Some((i, ch)) = chars.next() {
@@ -88,3 +298,5 @@ impl fmt::Display for Error {
         }
     }
 }
+
+impl std::error::Error for Error {}
@@ -0,0 +1,283 @@
+#[cfg(feature = "std")]
+use core::cell::Cell;
+
+use crate::error::{Error, Result};
+use crate::prelude::*;
+use crate::value::{Value, Dict};
+
+/// Guards on the size and shape of configuration values.
+///
+/// When ingesting untrusted configuration, deeply nested or very large values
+/// can exhaust the stack or memory during extraction. A `Limits` bounds the
+/// nesting depth, the total number of values, and the length of any single
+/// string or key. Limits are set on a `Figment` via
+/// [`Figment::limits()`](crate::Figment::limits()) and are checked against
+/// the data of every provider subsequently merged or joined, before and
+/// again after it is combined with the rest. A violation results in an error
+/// naming the offending key.
+///
+/// File providers like [`Toml`](crate::providers::Toml) additionally enforce
+/// the nesting depth and, for sources with a single profile, the number of
+/// values while parsing, so that an oversized source is rejected before it's
+/// parsed in full. Such an error names the offending location in the source
+/// instead of the offending key.
+///
+/// By default, nothing is limited.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Figment, value::{Limits, Value}, util::map};
+///
+/// let nested = map!["a" => map!["b" => map!["c" => 1]]];
+/// let figment = Figment::new()
+///     .limits(Limits::none().max_depth(2))
+///     .merge(("nested", nested));
+///
+/// let error = figment.extract::<Value>().unwrap_err();
+/// assert_eq!(error.path, vec!["nested", "a", "b"]);
+/// assert!(error.to_string().contains("maximum nesting depth of 2"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Limits {
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+    max_string_len: Option<usize>,
+}
+
+impl Limits {
+    /// Limits that impose no restrictions. This is the default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::Limits;
+    ///
+    /// assert_eq!(Limits::none(), Limits::default());
+    /// ```
+    pub const fn none() -> Self {
+        Limits { max_depth: None, max_nodes: None, max_string_len: None }
+    }
+
+    /// Sets the maximum nesting depth. Top-level keys are at depth `1`, so a
+    /// value at `a.b.c` is at depth `3`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::Limits, util::map};
+    ///
+    /// let figment = Figment::new()
+    ///     .limits(Limits::none().max_depth(2))
+    ///     .merge(("a", map!["b" => 1]));
+    ///
+    /// assert_eq!(figment.extract_inner::<u8>("a.b").unwrap(), 1);
+    /// ```
+    pub const fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Sets the maximum number of values in any one profile, as provided by
+    /// any one provider and as combined, counting every dictionary, array,
+    /// and leaf value across all keys.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::Limits};
+    ///
+    /// let figment = Figment::new()
+    ///     .limits(Limits::none().max_nodes(4))
+    ///     .merge(("list", vec![1, 2, 3, 4]));
+    ///
+    /// let error = figment.extract_inner::<Vec<u8>>("list").unwrap_err();
+    /// assert!(error.to_string().contains("maximum of 4 values"));
+    /// ```
+    pub const fn max_nodes(mut self, nodes: usize) -> Self {
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    /// Sets the maximum length, in bytes, of any string value or key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::Limits};
+    ///
+    /// let figment = Figment::new()
+    ///     .limits(Limits::none().max_string_len(8))
+    ///     .merge(("name", "a very long name"));
+    ///
+    /// let error = figment.extract_inner::<String>("name").unwrap_err();
+    /// assert_eq!(error.path, vec!["name"]);
+    /// ```
+    pub const fn max_string_len(mut self, len: usize) -> Self {
+        self.max_string_len = Some(len);
+        self
+    }
+
+    /// Makes `self` the limits the current thread's parses are bounded by
+    /// until the returned guard is dropped. See [`Limits::parse()`].
+    #[cfg(feature = "std")]
+    pub(crate) fn enter(self) -> Entered {
+        Entered(CURRENT.with(|current| current.replace(self)))
+    }
+
+    /// Bounds the values [`ValueVisitor`](crate::value::ValueVisitor)
+    /// deserializes on the current thread by the current limits until the
+    /// returned guard is dropped. `profiles` is the number of levels of
+    /// nesting above the keys of each profile in the source being parsed,
+    /// which don't count toward the nesting depth, or `None` if the source has
+    /// a single profile. Values are only counted in the latter case.
+    #[cfg(feature = "std")]
+    pub(crate) fn parse(profiles: Option<usize>) -> Parse {
+        let limits = CURRENT.with(|current| current.get());
+        let state = match limits == Limits::none() {
+            true => None,
+            false => Some(Parsing { limits, profiles, depth: 0, nodes: 0 }),
+        };
+
+        Parse(PARSING.with(|parsing| parsing.replace(state)))
+    }
+
+    /// Counts a value being parsed, nested if `nested`, returning an error if
+    /// it exceeds the bounds of the current parse, if any. The contents of a
+    /// nested value are one level deeper until [`Limits::parsed()`].
+    #[cfg(feature = "std")]
+    pub(crate) fn parsing(nested: bool) -> core::result::Result<(), String> {
+        PARSING.with(|parsing| {
+            let mut state = match parsing.get() {
+                Some(state) => state,
+                None => return Ok(()),
+            };
+
+            let depth = (state.depth + 1).saturating_sub(state.profiles.unwrap_or(0));
+            state.depth += nested as usize;
+            state.nodes += state.profiles.is_none() as usize;
+            parsing.set(Some(state));
+
+            if let Some(max) = state.limits.max_depth.filter(|max| depth > *max) {
+                return Err(format!("value exceeds maximum nesting depth of {}", max));
+            }
+
+            match state.limits.max_nodes.filter(|max| state.nodes > *max) {
+                Some(max) => Err(format!("value exceeds maximum of {} values", max)),
+                None => Ok(()),
+            }
+        })
+    }
+
+    /// Marks the end of a nested value counted by [`Limits::parsing()`].
+    #[cfg(feature = "std")]
+    pub(crate) fn parsed() {
+        PARSING.with(|parsing| {
+            if let Some(mut state) = parsing.get() {
+                state.depth -= 1;
+                parsing.set(Some(state));
+            }
+        })
+    }
+
+    /// Checks `dict` against `self`, returning an error with the path of the
+    /// first violating value, if any.
+    pub(crate) fn check(&self, dict: &Dict) -> Result<()> {
+        if *self == Limits::none() {
+            return Ok(());
+        }
+
+        let (mut path, mut nodes) = (vec![], 0);
+        self.check_dict(dict, &mut path, &mut nodes)
+    }
+
+    fn check_dict(&self, dict: &Dict, path: &mut Vec<String>, nodes: &mut usize) -> Result<()> {
+        for (key, value) in dict {
+            path.push(key.clone());
+            self.check_str(key)
+                .and_then(|_| self.check_value(value, path, nodes))
+                .map_err(|e| e.retagged(value.tag()).prefixed(key))?;
+
+            path.pop();
+        }
+
+        Ok(())
+    }
+
+    fn check_value(&self, value: &Value, path: &mut Vec<String>, nodes: &mut usize) -> Result<()> {
+        *nodes += 1;
+        if let Some(max) = self.max_nodes.filter(|max| *nodes > *max) {
+            return Err(format!("value exceeds maximum of {} values", max).into());
+        }
+
+        if let Some(max) = self.max_depth.filter(|max| path.len() > *max) {
+            return Err(format!("value exceeds maximum nesting depth of {}", max).into());
+        }
+
+        match value {
+            Value::String(_, s) => self.check_str(s),
+            Value::Dict(_, dict) => self.check_dict(dict, path, nodes),
+            Value::Array(_, array) => {
+                for (i, value) in array.iter().enumerate() {
+                    path.push(i.to_string());
+                    self.check_value(value, path, nodes).map_err(|e| e.prefixed_index(i))?;
+                    path.pop();
+                }
+
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_str(&self, string: &str) -> Result<()> {
+        match self.max_string_len {
+            Some(max) if string.len() > max => Err(Error::from(format!(
+                "string of length {} exceeds maximum length of {}", string.len(), max
+            ))),
+            _ => Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// The limits of the figment whose provider is currently providing data.
+    static CURRENT: Cell<Limits> = const { Cell::new(Limits::none()) };
+
+    /// The progress of the parse in progress bounded by `CURRENT`, if any.
+    static PARSING: Cell<Option<Parsing>> = const { Cell::new(None) };
+}
+
+/// Restores the previously current limits when dropped.
+#[cfg(feature = "std")]
+pub(crate) struct Entered(Limits);
+
+#[cfg(feature = "std")]
+impl Drop for Entered {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.0));
+    }
+}
+
+/// The progress of a parse bounded by `limits`. See [`Limits::parse()`].
+#[cfg(feature = "std")]
+#[derive(Clone, Copy)]
+struct Parsing {
+    limits: Limits,
+    profiles: Option<usize>,
+    /// The number of nested values being parsed.
+    depth: usize,
+    nodes: usize,
+}
+
+/// Restores the previous parse, if any, when dropped.
+#[cfg(feature = "std")]
+pub(crate) struct Parse(Option<Parsing>);
+
+#[cfg(feature = "std")]
+impl Drop for Parse {
+    fn drop(&mut self) {
+        PARSING.with(|parsing| parsing.set(self.0));
+    }
+}
@@ -1,12 +1,27 @@
 //! (De)serializable values that "magically" use information from the extracing
 //! [`Figment`](crate::Figment).
-
-use std::ops::Deref;
+//!
+//! Magic values work wherever they're nested: in sequences, options, maps,
+//! tuples, and enum variants, as well as in the fields of `#[serde(flatten)]`
+//! structs and of untagged or internally tagged enums. serde buffers the
+//! latter before deserializing them, so there, a magic value is matched to
+//! the configured value it was buffered from by equality: if several values
+//! of the flattened struct or of the enum are equal, the first one is used.
+//! If a buffered magic value isn't extracted from a figment, it is
+//! deserialized from its plain representation: a [`RelativePathBuf`] has no
+//! [`metadata_path()`](RelativePathBuf::metadata_path()), and a [`Tagged`]
+//! value has the default tag.
+
+use core::ops::Deref;
+#[cfg(feature = "std")]
 use std::path::{PathBuf, Path};
 
-use serde::{Deserialize, Serialize, de};
+use serde::{Deserialize, de};
+#[cfg(feature = "std")]
+use serde::Serialize;
 
-use crate::{Error, value::{ConfiguredValueDe, MapDe, Tag}};
+use crate::prelude::*;
+use crate::{Error, value::{ConfiguredValueDe, MapDe, Tag, Value}};
 
 /// Marker trait for "magic" values. Primarily for use with [`Either`].
 pub trait Magic: for<'de> Deserialize<'de> {
@@ -147,6 +162,8 @@ pub trait Magic: for<'de> Deserialize<'de> {
 ///     Ok(())
 /// })
 /// ```
+#[cfg(feature = "std")]
+#[cfg_attr(nightly, doc(cfg(feature = "std")))]
 #[derive(Debug, Clone)]
 // #[derive(Deserialize, Serialize)]
 // #[serde(rename = "___figment_relative_path_buf")]
@@ -157,18 +174,21 @@ pub struct RelativePathBuf {
     path: PathBuf,
 }
 
+#[cfg(feature = "std")]
 impl PartialEq for RelativePathBuf {
     fn eq(&self, other: &Self) -> bool {
         self.relative() == other.relative()
     }
 }
 
+#[cfg(feature = "std")]
 impl<P: AsRef<Path>> From<P> for RelativePathBuf {
     fn from(path: P) -> RelativePathBuf {
         Self { metadata_path: None, path: path.as_ref().into() }
     }
 }
 
+#[cfg(feature = "std")]
 impl Magic for RelativePathBuf {
     const NAME: &'static str = "___figment_relative_path_buf";
 
@@ -186,7 +206,7 @@ impl Magic for RelativePathBuf {
         if let Some(d) = de.value.as_dict() {
             if let Some(mpv) = d.get(Self::FIELDS[0]) {
                 if mpv.to_empty().is_none() {
-                    return visitor.visit_map(MapDe::new(d, |v| ConfiguredValueDe::from(config, v)));
+                    return visitor.visit_map(MapDe::new(d, |v| de.child(v)));
                 }
             }
         }
@@ -196,7 +216,7 @@ impl Magic for RelativePathBuf {
                 .and_then(|s| s.file_path())
                 .map(|path| path.display().to_string()));
 
-        let mut map = crate::value::Map::new();
+        let mut map = crate::value::Dict::new();
         if let Some(path) = metadata_path {
             map.insert(Self::FIELDS[0].into(), path.into());
         }
@@ -204,10 +224,11 @@ impl Magic for RelativePathBuf {
         // If we have this struct with no metadata_path, still use the value.
         let value = de.value.find_ref(Self::FIELDS[1]).unwrap_or(&de.value);
         map.insert(Self::FIELDS[1].into(), value.clone());
-        visitor.visit_map(MapDe::new(&map, |v| ConfiguredValueDe::from(config, v)))
+        visitor.visit_map(MapDe::new(&map, |v| de.child(v)))
     }
 }
 
+#[cfg(feature = "std")]
 impl RelativePathBuf {
     /// Returns the path as it was declared, without modification.
     ///
@@ -376,7 +397,7 @@ impl RelativePathBuf {
 //         de: ConfiguredValueDe<'c>,
 //         visitor: V
 //     ) -> Result<V::Value, Error>{
-//         let mut map = crate::value::Map::new();
+//         let mut map = crate::value::Dict::new();
 //         map.insert(Self::FIELDS[0].into(), de.config.profile().to_string().into());
 //         visitor.visit_map(MapDe::new(&map, |v| ConfiguredValueDe::from(de.config, v)))
 //     }
@@ -449,6 +470,32 @@ impl RelativePathBuf {
 /// assert_eq!(config.int_or_str, Either::Right("hi".into()));
 /// assert_eq!(config.path_or_bytes, Either::Right(vec![3, 7, 13]));
 /// ```
+///
+/// # Errors
+///
+/// When neither `A` nor `B` deserializes, the error contains the errors for
+/// both: first those for `A`, then those for `B`, each with its own kind, full
+/// key path, and metadata.
+///
+/// ```
+/// use figment::{Figment, value::magic::{Either, Tagged}};
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Config {
+///     int_or_bytes: Either<Tagged<usize>, Vec<u8>>,
+/// }
+///
+/// let figment = Figment::from(("int_or_bytes", (1, "two")));
+/// let errors: Vec<_> = figment.extract::<Config>().unwrap_err().into_iter().collect();
+/// assert_eq!(errors.len(), 2);
+///
+/// assert_eq!(errors[0].path, vec!["int_or_bytes"]);
+/// assert_eq!(errors[0].expected().as_deref(), Some("usize"));
+///
+/// assert_eq!(errors[1].path, vec!["int_or_bytes", "1"]);
+/// assert_eq!(errors[1].expected().as_deref(), Some("u8"));
+/// assert!(errors.iter().all(|e| e.metadata.is_some()));
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 // #[derive(Serialize)]
 pub enum Either<A, B> {
@@ -466,8 +513,11 @@ impl<'de: 'b, 'b, A, B> Deserialize<'de> for Either<A, B>
     {
         use crate::value::ValueVisitor;
 
-        // FIXME: propogate the error properly
-        let value = de.deserialize_struct(A::NAME, A::FIELDS, ValueVisitor)?;
+        let value = match crate::value::use_magic_hint(&de, A::NAME) {
+            true => de.deserialize_struct(A::NAME, A::FIELDS, ValueVisitor)?,
+            false => de.deserialize_any(ValueVisitor)?,
+        };
+
         match A::deserialize(&value) {
             Ok(value) => Ok(Either::Left(value)),
             Err(a_err) => {
@@ -477,22 +527,11 @@ impl<'de: 'b, 'b, A, B> Deserialize<'de> for Either<A, B>
 
                 match B::deserialize(value) {
                     Ok(value) => Ok(Either::Right(value)),
-                    Err(b_err) => Err(de::Error::custom(format!("{}; {}", a_err, b_err)))
+                    // Report both: the left branch's errors, then the right's.
+                    Err(b_err) => Err(b_err.chain(a_err.unprefixed(A::FIELDS)).into_generic()),
                 }
             }
         }
-
-        // use crate::error::Kind::*;
-        // result.map_err(|e| match e.kind {
-        //     InvalidType(Actual, String) => de::Error::invalid_type()
-        //     InvalidValue(Actual, String),
-        //     UnknownField(String, &'static [&'static str]),
-        //     MissingField(Cow<'static, str>),
-        //     DuplicateField(&'static str),
-        //     InvalidLength(usize, String),
-        //     UnknownVariant(String, &'static [&'static str]),
-        //     kind => de::Error::custom(kind.to_string()),
-        // })
     }
 }
 
@@ -547,16 +586,13 @@ impl<T: for<'de> Deserialize<'de>> Magic for Tagged<T> {
         de: ConfiguredValueDe<'c>,
         visitor: V
     ) -> Result<V::Value, Error>{
-        let config = de.config;
-        let mut map = crate::value::Map::new();
+        let mut map = crate::value::Dict::new();
 
         // If we have this struct with a non-default tag, use it.
         if let Some(dict) = de.value.as_dict() {
             if let Some(tagv) = dict.get(Self::FIELDS[0]) {
                 if let Ok(false) = tagv.deserialize::<Tag>().map(|t| t.is_default()) {
-                    return visitor.visit_map(MapDe::new(dict, |v| {
-                        ConfiguredValueDe::from(config, v)
-                    }));
+                    return visitor.visit_map(MapDe::new(dict, |v| de.child(v)));
                 }
             }
         }
@@ -565,7 +601,7 @@ impl<T: for<'de> Deserialize<'de>> Magic for Tagged<T> {
         let value = de.value.find_ref(Self::FIELDS[1]).unwrap_or(&de.value);
         map.insert(Self::FIELDS[0].into(), de.value.tag().into());
         map.insert(Self::FIELDS[1].into(), value.clone());
-        visitor.visit_map(MapDe::new(&map, |v| ConfiguredValueDe::from(config, v)))
+        visitor.visit_map(MapDe::new(&map, |v| de.child(v)))
     }
 }
 
@@ -622,6 +658,497 @@ impl<T> From<T> for Tagged<T> {
     }
 }
 
+/// Resolves the raw value of a [`Deferred`] into the value it stands for.
+///
+/// A resolver is a type, selected by a `Deferred`'s second type parameter, so
+/// that it can be named in a configuration structure's field types. The unit
+/// type `()` is the identity resolver. [`FileContents`] resolves a path into
+/// the contents of the file at the path.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Error, value::{Value, magic::Resolver}};
+///
+/// /// Resolves `env:NAME` into the value of the environment variable `NAME`.
+/// struct EnvRef;
+///
+/// impl Resolver for EnvRef {
+///     fn resolve(value: Value) -> Result<Value, Error> {
+///         match value.as_str().and_then(|s| s.strip_prefix("env:")) {
+///             Some(name) => std::env::var(name)
+///                 .map(Value::from)
+///                 .map_err(|e| Error::from(format!("{}: {}", name, e))),
+///             None => Ok(value),
+///         }
+///     }
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(nightly, doc(cfg(feature = "std")))]
+pub trait Resolver {
+    /// Resolves the raw `value` into the value it stands for.
+    fn resolve(value: Value) -> Result<Value, Error>;
+}
+
+#[cfg(feature = "std")]
+impl Resolver for () {
+    fn resolve(value: Value) -> Result<Value, Error> {
+        Ok(value)
+    }
+}
+
+/// A [`Resolver`] that resolves a string path into the contents of the file
+/// at the path, as a string. Relative paths are relative to the current
+/// working directory. The contents are not trimmed.
+#[cfg(feature = "std")]
+#[cfg_attr(nightly, doc(cfg(feature = "std")))]
+#[derive(Debug, Clone, Copy)]
+pub struct FileContents;
+
+#[cfg(feature = "std")]
+impl Resolver for FileContents {
+    fn resolve(value: Value) -> Result<Value, Error> {
+        let path: PathBuf = value.deserialize()?;
+        let mut contents = Value::from(crate::Policy::read_to_string(&path)?);
+        *contents.tag_mut() = value.tag();
+        Ok(contents)
+    }
+}
+
+/// A value that is resolved and deserialized into a `T` only when it is first
+/// accessed, and then cached.
+///
+/// Extracting a `Deferred<T, R>` only captures the raw configured value. The
+/// first call to [`Deferred::get()`] resolves the raw value with the
+/// [`Resolver`] `R`, then deserializes the result into `T`. Subsequent calls
+/// return the cached result. Secrets or file references in rarely used code
+/// paths thus cost nothing at startup. Because resolution can fail on access,
+/// a `Deferred` doesn't dereference to `T`. Note that because `T` is
+/// deserialized outside of the extraction, the figment's
+/// [coercion](crate::Figment::coerce()) options don't apply to it.
+///
+/// A `Deferred` serializes as its raw value.
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use figment::{Figment, providers::{Format, Toml}};
+/// use figment::value::magic::{Deferred, FileContents};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     host: String,
+///     password: Deferred<String, FileContents>,
+///     port: Deferred<u16>,
+/// }
+///
+/// figment::Jail::expect_with(|jail| {
+///     jail.create_file("password.txt", "hunter2")?;
+///     jail.create_file("App.toml", r#"
+///         host = "db.local"
+///         password = "password.txt"
+///         port = "not a port"
+///     "#)?;
+///
+///     let config: Config = Figment::from(Toml::file("App.toml")).extract()?;
+///     assert!(!config.password.is_resolved());
+///     assert_eq!(config.password.raw().as_str(), Some("password.txt"));
+///
+///     // The file is only read when the password is first accessed.
+///     assert_eq!(config.password.get()?, "hunter2");
+///     assert!(config.password.is_resolved());
+///
+///     // Errors are only reported on access.
+///     assert!(config.port.get().is_err());
+///     Ok(())
+/// });
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(nightly, doc(cfg(feature = "std")))]
+pub struct Deferred<T, R = ()> {
+    value: Value,
+    /// The policy in effect when the value was created, in effect again
+    /// while it's resolved.
+    policy: crate::Policy,
+    resolved: std::sync::OnceLock<Result<T, Error>>,
+    _resolver: core::marker::PhantomData<fn() -> R>,
+}
+
+#[cfg(feature = "std")]
+impl<T, R> Deferred<T, R> {
+    /// Returns a `Deferred` that resolves `value` when first accessed with the
+    /// [`Policy`](crate::Policy) currently in effect.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::magic::Deferred;
+    ///
+    /// let deferred: Deferred<u16> = Deferred::new(8000.into());
+    /// assert_eq!(deferred.get().unwrap(), &8000);
+    /// ```
+    pub fn new(value: Value) -> Self {
+        Deferred {
+            value,
+            policy: crate::Policy::current(),
+            resolved: std::sync::OnceLock::new(),
+            _resolver: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns the raw, unresolved value.
+    pub fn raw(&self) -> &Value {
+        &self.value
+    }
+
+    /// Returns `true` if the value has been resolved, successfully or not.
+    pub fn is_resolved(&self) -> bool {
+        self.resolved.get().is_some()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: for<'de> Deserialize<'de>, R: Resolver> Deferred<T, R> {
+    /// Resolves and deserializes the raw value if it hasn't been already and
+    /// returns the cached result.
+    ///
+    /// An error does not carry the metadata of the value's provider. It can be
+    /// retrieved with [`Figment::get_metadata()`](crate::Figment::get_metadata())
+    /// and the tag of the [raw](Deferred::raw()) value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::Figment;
+    /// use figment::value::magic::Deferred;
+    ///
+    /// let figment = Figment::from(("port", "eighty"));
+    /// let port = figment.extract_inner::<Deferred<u16>>("port").unwrap();
+    /// assert!(port.get().is_err());
+    ///
+    /// let metadata = figment.get_metadata(port.raw().tag()).unwrap();
+    /// assert!(metadata.name.contains("&str"));
+    /// ```
+    pub fn get(&self) -> Result<&T, Error> {
+        let resolved = self.resolved.get_or_init(|| {
+            let _policy = self.policy.enter();
+            let tag = self.value.tag();
+            R::resolve(self.value.clone())
+                .and_then(|value| value.deserialize())
+                .map_err(|e| e.retagged(tag))
+        });
+
+        resolved.as_ref().map_err(|e| e.clone())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Clone, R> Clone for Deferred<T, R> {
+    fn clone(&self) -> Self {
+        Deferred {
+            value: self.value.clone(),
+            policy: self.policy.clone(),
+            resolved: self.resolved.clone(),
+            _resolver: core::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: core::fmt::Debug, R> core::fmt::Debug for Deferred<T, R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Deferred")
+            .field("value", &self.value)
+            .field("resolved", &self.resolved.get())
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, T, R> Deserialize<'de> for Deferred<T, R> {
+    fn deserialize<D: de::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        <Value as Deserialize>::deserialize(de).map(Deferred::new)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, R> Serialize for Deferred<T, R> {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(ser)
+    }
+}
+
+/// Decrypts the ciphertext of [`Encrypted`] values.
+///
+/// A decryptor is registered with a [`Figment`](crate::Figment) under an
+/// identifier via [`Figment::decryptor()`](crate::Figment::decryptor()). It
+/// is called with the base64-decoded payload of every value of the form
+/// `enc:{id}:{payload}` extracted as an `Encrypted`, where `{id}` is its
+/// identifier, and returns the plaintext. A decryptor can wrap any scheme:
+/// age, a cloud KMS, or something custom. Any closure of type
+/// `Fn(&[u8]) -> Result<Vec<u8>, Error>` is a `Decryptor`.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{Error, value::magic::Decryptor};
+///
+/// /// A toy "cipher": XORs every byte with a key.
+/// struct Xor(u8);
+///
+/// impl Decryptor for Xor {
+///     fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+///         Ok(ciphertext.iter().map(|b| b ^ self.0).collect())
+///     }
+/// }
+///
+/// assert_eq!(Xor(1).decrypt(&[105, 104]).unwrap(), b"hi");
+/// ```
+pub trait Decryptor: Send + Sync {
+    /// Decrypts `ciphertext` into the plaintext it encrypts.
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+impl<F> Decryptor for F
+    where F: Fn(&[u8]) -> Result<Vec<u8>, Error> + Send + Sync
+{
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        self(ciphertext)
+    }
+}
+
+/// A value that can be configured encrypted, decrypted at extraction.
+///
+/// A string value of the form `enc:{id}:{payload}`, where `{payload}` is
+/// base64-encoded ciphertext, is decrypted by the [`Decryptor`] registered
+/// with [`Figment::decryptor()`](crate::Figment::decryptor()) under `{id}`
+/// when extracted as an `Encrypted<T>`. Ciphertext can thus live in ordinary
+/// configuration files while the plaintext only exists in memory. The
+/// plaintext, which must be UTF-8, is deserialized into `T` as a string: `T`
+/// can be a `String` or any type that deserializes from one, including via a
+/// [coercer](crate::Figment::coerce_str()).
+///
+/// Any other value is deserialized into `T` as-is, so that plaintext values
+/// can be configured in development. Use [`Encrypted::ciphertext()`] to
+/// determine whether a value was encrypted. Extracting an encrypted value
+/// fails if no decryptor is registered under its identifier, if its payload
+/// isn't valid base64, if decryption fails, or if the plaintext isn't UTF-8.
+///
+/// The `Debug` implementation never prints the plaintext, and an `Encrypted`
+/// that was decrypted serializes as its ciphertext.
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use figment::{Figment, providers::{Format, Toml}, value::magic::Encrypted};
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Config {
+///     user: String,
+///     password: Encrypted<String>,
+///     token: Encrypted<String>,
+/// }
+///
+/// figment::Jail::expect_with(|jail| {
+///     // `aHVudGVyMg==` is `hunter2` in base64; `rot` "decrypts" it as-is.
+///     jail.create_file("App.toml", r#"
+///         user = "admin"
+///         password = "enc:rot:aHVudGVyMg=="
+///         token = "plain"
+///     "#)?;
+///
+///     let figment = Figment::from(Toml::file("App.toml"))
+///         .decryptor("rot", |c: &[u8]| Ok(c.to_vec()));
+///
+///     let config: Config = figment.extract()?;
+///     assert_eq!(*config.password, "hunter2");
+///     assert_eq!(config.password.ciphertext(), Some("enc:rot:aHVudGVyMg=="));
+///     assert_eq!(format!("{:?}", config.password), "Encrypted { ciphertext: Some(\"enc:rot:aHVudGVyMg==\"), .. }");
+///     assert_eq!(*config.token, "plain");
+///     assert!(config.token.ciphertext().is_none());
+///
+///     let error = Figment::from(Toml::file("App.toml")).extract::<Config>().unwrap_err();
+///     assert!(error.to_string().starts_with("no decryptor is registered for `enc:rot:` values"));
+///     Ok(())
+/// });
+/// ```
+#[derive(Clone)]
+pub struct Encrypted<T> {
+    ciphertext: Option<String>,
+    value: T,
+}
+
+impl<T: PartialEq> PartialEq for Encrypted<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: for<'de> Deserialize<'de>> Magic for Encrypted<T> {
+    const NAME: &'static str = "___figment_encrypted";
+    const FIELDS: &'static [&'static str] = &[
+        "___figment_encrypted_ciphertext", "___figment_encrypted_value"
+    ];
+
+    fn deserialize_from<'de: 'c, 'c, V: de::Visitor<'de>>(
+        de: ConfiguredValueDe<'c>,
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        // If we have this struct, as serialized by us, use it.
+        if let Some(dict) = de.value.as_dict() {
+            if dict.contains_key(Self::FIELDS[1]) {
+                return visitor.visit_map(MapDe::new(dict, |v| de.child(v)));
+            }
+        }
+
+        let mut map = crate::value::Dict::new();
+        let (id, payload) = match de.value.as_str().and_then(|s| s.strip_prefix("enc:")) {
+            Some(rest) => rest.split_once(':').unwrap_or((rest, "")),
+            None => {
+                map.insert(Self::FIELDS[1].into(), de.value.clone());
+                return visitor.visit_map(MapDe::new(&map, |v| de.child(v)))
+                    .map_err(|e| e.unprefixed(Self::FIELDS));
+            }
+        };
+
+        let decryptor = de.config.hooks.decryptors.get(id).ok_or_else(|| {
+            Error::from(format!("no decryptor is registered for `enc:{}:` values", id))
+        })?;
+
+        let ciphertext = crate::util::decode_base64(payload)
+            .ok_or_else(|| Error::from(format!("`enc:{}:` value is not valid base64", id)))?;
+
+        let plaintext = String::from_utf8(decryptor.decrypt(&ciphertext)?)
+            .map_err(|_| Error::from(format!("decrypted `enc:{}:` value is not UTF-8", id)))?;
+
+        let mut plaintext = Value::from(plaintext);
+        *plaintext.tag_mut() = de.value.tag();
+        map.insert(Self::FIELDS[0].into(), de.value.clone());
+        map.insert(Self::FIELDS[1].into(), plaintext);
+        visitor.visit_map(MapDe::new(&map, |v| de.child(v)))
+            .map_err(|e| e.unprefixed(Self::FIELDS))
+    }
+}
+
+impl<T> Encrypted<T> {
+    /// Returns the value as configured, as in `enc:age:YWdl...`, if it was
+    /// encrypted, and `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{Figment, value::magic::Encrypted};
+    ///
+    /// let figment = Figment::from(("key", "enc:id:a2V5")).decryptor("id", |c: &[u8]| Ok(c.to_vec()));
+    /// let key = figment.extract_inner::<Encrypted<String>>("key").unwrap();
+    /// assert_eq!(key.ciphertext(), Some("enc:id:a2V5"));
+    /// assert_eq!(*key, "key");
+    /// ```
+    pub fn ciphertext(&self) -> Option<&str> {
+        self.ciphertext.as_deref()
+    }
+
+    /// Consumes `self` and returns the decrypted value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::magic::Encrypted;
+    ///
+    /// let encrypted = Encrypted::from(8000);
+    /// assert_eq!(encrypted.into_inner(), 8000);
+    /// ```
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Encrypted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> From<T> for Encrypted<T> {
+    fn from(value: T) -> Self {
+        Encrypted { ciphertext: None, value }
+    }
+}
+
+impl<T> core::fmt::Debug for Encrypted<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Encrypted")
+            .field("ciphertext", &self.ciphertext)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Encrypted<T> {
+    fn deserialize<D: de::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        struct Visitor<T>(core::marker::PhantomData<fn() -> T>);
+
+        impl<'de, T: Deserialize<'de>> de::Visitor<'de> for Visitor<T> {
+            type Value = Encrypted<T>;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("struct Encrypted")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let fields = Encrypted::<()>::FIELDS;
+                let (mut ciphertext, mut value) = (None, None);
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        k if k == fields[0] => ciphertext = map.next_value()?,
+                        k if k == fields[1] => value = Some(map.next_value()?),
+                        _ => { map.next_value::<de::IgnoredAny>()?; }
+                    }
+                }
+
+                let value = value.ok_or_else(|| de::Error::missing_field(fields[1]))?;
+                Ok(Encrypted { ciphertext, value })
+            }
+        }
+
+        let visitor = Visitor(core::marker::PhantomData);
+        let (name, fields) = (Encrypted::<()>::NAME, Encrypted::<()>::FIELDS);
+        if !crate::value::use_magic_hint(&de, name) {
+            // Deserialize from the original value if it can be found. Outside
+            // of a figment, only plain values can be deserialized.
+            let value = <Value as Deserialize>::deserialize(de)?;
+            if let Some(result) = crate::value::deserialize_buffered(&value, Encrypted::<T>::deserialize) {
+                return result.map_err(|e| e.into_generic());
+            }
+
+            let result = match value.as_dict().is_some_and(|d| d.contains_key(fields[1])) {
+                true => de::Deserializer::deserialize_struct(&value, name, fields, visitor),
+                false if value.as_str().is_some_and(|s| s.starts_with("enc:")) => {
+                    Err(Error::from("encrypted values can only be decrypted in a figment".to_string()))
+                }
+                false => T::deserialize(&value).map(Encrypted::from),
+            };
+
+            return result.map_err(|e| e.into_generic());
+        }
+
+        de.deserialize_struct(name, fields, visitor)
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for Encrypted<T> {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        match &self.ciphertext {
+            Some(ciphertext) => ciphertext.serialize(ser),
+            None => self.value.serialize(ser),
+        }
+    }
+}
+
 /// These were generated by serde's derive. We don't want to depend on the
 /// 'derive' feature, so we simply expand it and copy the impls here.
 mod _serde {
@@ -629,13 +1156,13 @@ mod _serde {
 
     pub mod export {
         // These are re-reexports used by serde's codegen.
-        pub use std::clone::Clone;
-        pub use std::convert::{From, Into};
-        pub use std::default::Default;
-        pub use std::fmt::{self, Formatter};
-        pub use std::marker::PhantomData;
-        pub use std::option::Option::{self, None, Some};
-        pub use std::result::Result::{self, Err, Ok};
+        pub use core::clone::Clone;
+        pub use core::convert::{From, Into};
+        pub use core::default::Default;
+        pub use core::fmt::{self, Formatter};
+        pub use core::marker::PhantomData;
+        pub use core::option::Option::{self, None, Some};
+        pub use core::result::Result::{self, Err, Ok};
 
         pub fn missing_field<'de, V, E>(field: &'static str) -> Result<V, E>
             where V: serde::de::Deserialize<'de>,
@@ -672,6 +1199,7 @@ mod _serde {
         }
     }
 
+    #[cfg(feature = "std")]
     #[doc(hidden)]
     #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
     const _: () = {
@@ -777,6 +1305,15 @@ mod _serde {
                             "struct RelativePathBuf",
                         )
                     }
+                    fn visit_str<__E>(
+                        self,
+                        __value: &str,
+                    ) -> export::Result<Self::Value, __E>
+                    where
+                        __E: _serde::de::Error,
+                    {
+                        export::Ok(RelativePathBuf::from(__value))
+                    }
                     #[inline]
                     fn visit_seq<__A>(
                         self,
@@ -928,19 +1465,31 @@ mod _serde {
                     "___figment_relative_metadata_path",
                     "___figment_relative_path",
                 ];
+                let __visitor = __Visitor {
+                    marker: export::PhantomData::<RelativePathBuf>,
+                    lifetime: export::PhantomData,
+                };
+
+                if !crate::value::use_magic_hint(&__deserializer, "___figment_relative_path_buf") {
+                    // Deserialize from the original value if it can be found.
+                    let value = <Value as _serde::Deserialize>::deserialize(__deserializer)?;
+                    let result = crate::value::deserialize_buffered(&value, RelativePathBuf::deserialize)
+                        .unwrap_or_else(|| _serde::Deserializer::deserialize_any(&value, __visitor));
+
+                    return result.map_err(|e| e.into_generic());
+                }
+
                 _serde::Deserializer::deserialize_struct(
                     __deserializer,
                     "___figment_relative_path_buf",
                     FIELDS,
-                    __Visitor {
-                        marker: export::PhantomData::<RelativePathBuf>,
-                        lifetime: export::PhantomData,
-                    },
+                    __visitor,
                 )
             }
         }
     };
 
+    #[cfg(feature = "std")]
     #[doc(hidden)]
     #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
     const _: () = {
@@ -1008,24 +1557,13 @@ mod _serde {
             where
                 __S: _serde::Serializer,
             {
+                // Untagged, like `Either`'s `Deserialize` implementation.
                 match *self {
                     Either::Left(ref __field0) => {
-                        _serde::Serializer::serialize_newtype_variant(
-                            __serializer,
-                            "Either",
-                            0u32,
-                            "Left",
-                            __field0,
-                        )
+                        _serde::Serialize::serialize(__field0, __serializer)
                     }
                     Either::Right(ref __field0) => {
-                        _serde::Serializer::serialize_newtype_variant(
-                            __serializer,
-                            "Either",
-                            1u32,
-                            "Right",
-                            __field0,
-                        )
+                        _serde::Serialize::serialize(__field0, __serializer)
                     }
                 }
             }
@@ -1282,14 +1820,35 @@ mod _serde {
                 }
                 const FIELDS: &[&str] =
                     &["___figment_tagged_tag", "___figment_tagged_value"];
+                let __visitor = __Visitor {
+                    marker: export::PhantomData::<Tagged<T>>,
+                    lifetime: export::PhantomData,
+                };
+
+                if !crate::value::use_magic_hint(&__deserializer, "___figment_tagged_item") {
+                    // Deserialize from the original value if it can be found.
+                    // Otherwise, a plain value has the default tag. Only a
+                    // `Tagged` serialized by us has a `FIELDS[1]` key.
+                    let value = <Value as _serde::Deserialize>::deserialize(__deserializer)?;
+                    if let Some(result) = crate::value::deserialize_buffered(&value, Tagged::<T>::deserialize) {
+                        return result.map_err(|e| e.into_generic());
+                    }
+
+                    let result = match value.as_dict().is_some_and(|d| d.contains_key(FIELDS[1])) {
+                        true => _serde::Deserializer::deserialize_struct(
+                            &value, "___figment_tagged_item", FIELDS, __visitor
+                        ),
+                        false => T::deserialize(&value).map(Tagged::from),
+                    };
+
+                    return result.map_err(|e| e.into_generic());
+                }
+
                 _serde::Deserializer::deserialize_struct(
                     __deserializer,
                     "___figment_tagged_item",
                     FIELDS,
-                    __Visitor {
-                        marker: export::PhantomData::<Tagged<T>>,
-                        lifetime: export::PhantomData,
-                    },
+                    __visitor,
                 )
             }
         }
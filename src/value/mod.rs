@@ -4,16 +4,34 @@ mod value;
 mod ser;
 mod de;
 mod tag;
+mod coerce;
+mod diff;
+mod redact;
+mod limits;
+mod case;
 
 #[cfg(feature = "parse-value")]
 mod parse;
 
 #[cfg(feature = "parse-value")]
-mod escape;
+#[cfg_attr(nightly, doc(cfg(feature = "parse-value")))]
+pub mod escape;
 
 pub mod magic;
 
 pub(crate) use {self::ser::*, self::de::*};
 pub use tag::Tag;
+pub use coerce::Coercion;
+pub(crate) use coerce::{Coercer, coercer};
+pub use diff::ValueDiff;
+pub use redact::Redacted;
+pub use limits::Limits;
+pub use case::KeyCase;
 pub use value::{Value, Map, Num, Dict, Empty};
+pub(crate) use value::{array_index, dict_remove, find_keys_in};
+pub(crate) use diff::Compact;
+
+#[cfg(feature = "parse-value")]
+#[cfg_attr(nightly, doc(cfg(feature = "parse-value")))]
+pub use parse::ParsePolicy;
 pub use uncased::{Uncased, UncasedStr};
@@ -1,9 +1,315 @@
+use std::fmt;
+
 use pear::{parse_error, parsers::*};
 use pear::combinators::*;
 use pear::macros::{parse, parser, switch};
 use pear::input::{Pear, Text};
 
-use crate::value::{Value, Dict, escape::escape};
+use crate::value::{Value, Dict, escape::unescape};
+
+crate::util::cloneable_fn_trait!(
+    LiteralParser: Fn(&str) -> Option<Value> + Send + Sync + 'static
+);
+
+/// A policy controlling how strings are parsed into [`Value`]s.
+///
+/// Parsing infers the type of undelimited literals: by default, `true` and
+/// `false` are booleans, numbers containing a `.` are floats, and decimal
+/// numbers are integers. This inference is not always desirable: a version
+/// string like `1.10` becomes the float `1.1`, and a zip code like `02134`
+/// becomes the integer `2134`. A `ParsePolicy` adjusts the inference and is
+/// applied by [`ParsePolicy::parse()`] and by the [`Env`] provider via
+/// [`Env::parse_policy()`]. Parsing a `Value` via [`str::parse()`] uses the
+/// default policy.
+///
+/// A policy only affects undelimited literals. Strings delimited with `"`,
+/// arrays, and dictionaries are always parsed as such.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::value::{Value, ParsePolicy};
+///
+/// let policy = ParsePolicy::new().floats(false).bools(false);
+/// assert_eq!(policy.parse("1.10"), Value::from("1.10"));
+/// assert_eq!(policy.parse("true"), Value::from("true"));
+/// assert_eq!(policy.parse("[1, 2.5]"), Value::from(vec![Value::from(1usize), "2.5".into()]));
+///
+/// let policy = ParsePolicy::new().radix_ints(true).underscores(true);
+/// assert_eq!(policy.parse("0xff").to_u128(), Some(255));
+/// assert_eq!(policy.parse("-0o17").to_i128(), Some(-15));
+/// assert_eq!(policy.parse("1_000_000").to_u128(), Some(1_000_000));
+/// ```
+///
+/// [`Env`]: crate::providers::Env
+/// [`Env::parse_policy()`]: crate::providers::Env::parse_policy()
+#[derive(Debug, Clone)]
+pub struct ParsePolicy {
+    bools: bool,
+    lenient_bools: bool,
+    floats: bool,
+    radix_ints: bool,
+    underscores: bool,
+    parsers: Vec<Box<dyn LiteralParser>>,
+}
+
+impl ParsePolicy {
+    /// Returns the default policy: `true` and `false` are parsed as booleans,
+    /// numbers containing a `.` as floats, and decimal numbers as integers. No
+    /// custom parsers are registered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::{Value, ParsePolicy};
+    ///
+    /// let policy = ParsePolicy::new();
+    /// assert_eq!(policy.parse("true"), Value::from(true));
+    /// assert_eq!(policy.parse("1.5"), Value::from(1.5));
+    /// assert_eq!(policy.parse("0x10"), Value::from("0x10"));
+    /// ```
+    pub fn new() -> Self {
+        ParsePolicy {
+            bools: true,
+            lenient_bools: false,
+            floats: true,
+            radix_ints: false,
+            underscores: false,
+            parsers: vec![],
+        }
+    }
+
+    /// Sets whether `true` and `false` are parsed as booleans. When disabled,
+    /// they are parsed as strings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::{Value, ParsePolicy};
+    ///
+    /// let policy = ParsePolicy::new().bools(false);
+    /// assert_eq!(policy.parse("false"), Value::from("false"));
+    /// ```
+    pub fn bools(mut self, enabled: bool) -> Self {
+        self.bools = enabled;
+        self
+    }
+
+    /// Sets whether `yes`, `on`, `no`, and `off`, and `true` and `false` in
+    /// any case, are parsed as booleans. Matching ignores ASCII case, so `Yes`
+    /// and `OFF` are booleans, too. Disabled by default.
+    ///
+    /// `1` and `0` are parsed as integers regardless, so that numeric values
+    /// are unaffected; figment extracts them as `true` and `false` wherever a
+    /// boolean is expected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::{Value, ParsePolicy};
+    ///
+    /// let policy = ParsePolicy::new().lenient_bools(true);
+    /// assert_eq!(policy.parse("yes"), Value::from(true));
+    /// assert_eq!(policy.parse("Off"), Value::from(false));
+    /// assert_eq!(policy.parse("TRUE"), Value::from(true));
+    /// assert_eq!(policy.parse("[on, no]"), Value::from(vec![true, false]));
+    /// assert_eq!(policy.parse("1").to_u128(), Some(1));
+    /// assert_eq!(policy.parse("yes please"), Value::from("yes please"));
+    ///
+    /// assert_eq!(ParsePolicy::new().parse("yes"), Value::from("yes"));
+    /// ```
+    pub fn lenient_bools(mut self, enabled: bool) -> Self {
+        self.lenient_bools = enabled;
+        self
+    }
+
+    /// Sets whether numbers containing a `.` are parsed as floats. When
+    /// disabled, they are parsed as strings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::{Value, ParsePolicy};
+    ///
+    /// let policy = ParsePolicy::new().floats(false);
+    /// assert_eq!(policy.parse("3.14"), Value::from("3.14"));
+    /// ```
+    pub fn floats(mut self, enabled: bool) -> Self {
+        self.floats = enabled;
+        self
+    }
+
+    /// Sets whether integers with a `0x` (hexadecimal), `0o` (octal), or `0b`
+    /// (binary) prefix, optionally preceded by a `-`, are parsed as integers.
+    /// Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::{Value, ParsePolicy};
+    ///
+    /// let policy = ParsePolicy::new().radix_ints(true);
+    /// assert_eq!(policy.parse("0o755").to_u128(), Some(0o755));
+    /// assert_eq!(policy.parse("0b101").to_u128(), Some(5));
+    /// assert_eq!(policy.parse("0xZZ"), Value::from("0xZZ"));
+    /// ```
+    pub fn radix_ints(mut self, enabled: bool) -> Self {
+        self.radix_ints = enabled;
+        self
+    }
+
+    /// Sets whether numbers may contain `_` separators between digits, as in
+    /// `1_000`. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::{Value, ParsePolicy};
+    ///
+    /// let policy = ParsePolicy::new().underscores(true);
+    /// assert_eq!(policy.parse("10_000").to_u128(), Some(10_000));
+    /// assert_eq!(policy.parse("_10"), Value::from("_10"));
+    /// assert_eq!(policy.parse("1__0"), Value::from("1__0"));
+    /// ```
+    pub fn underscores(mut self, enabled: bool) -> Self {
+        self.underscores = enabled;
+        self
+    }
+
+    /// Registers a custom literal parser. Custom parsers are invoked, in the
+    /// order they were registered, on every undelimited literal before any
+    /// built-in inference. The first parser to return `Some` determines the
+    /// value. If all parsers return `None`, built-in inference is applied.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::{Value, ParsePolicy};
+    ///
+    /// // Keep numbers with leading zeroes, like zip codes, as strings.
+    /// let policy = ParsePolicy::new().parser(|literal| {
+    ///     let leading_zero = literal.len() > 1 && literal.starts_with('0');
+    ///     leading_zero.then(|| Value::from(literal))
+    /// });
+    ///
+    /// assert_eq!(policy.parse("02134"), Value::from("02134"));
+    /// assert_eq!(policy.parse("[02134, 10]"), Value::from(vec![Value::from("02134"), 10usize.into()]));
+    /// assert_eq!(policy.parse("0").to_u128(), Some(0));
+    /// ```
+    pub fn parser<F>(mut self, f: F) -> Self
+        where F: Fn(&str) -> Option<Value> + Clone + Send + Sync + 'static
+    {
+        self.parsers.push(Box::new(f));
+        self
+    }
+
+    /// Parses `string` into a [`Value`] according to `self`. Parsing never
+    /// fails: a string that cannot be parsed as any other value is returned as
+    /// a string value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{value::{Value, ParsePolicy}, util::map};
+    ///
+    /// let policy = ParsePolicy::new().floats(false);
+    /// let value = policy.parse("{version=1.10,count=3}");
+    /// assert_eq!(value, Value::from(map!["version" => Value::from("1.10"), "count" => 3usize.into()]));
+    /// ```
+    pub fn parse(&self, string: &str) -> Value {
+        parse!(value(self): Text::from(string))
+            .unwrap_or_else(|_| Value::from(string.to_string()))
+    }
+
+    fn literal(&self, literal: &str) -> Value {
+        if let Some(value) = self.parsers.iter().find_map(|f| f(literal)) {
+            return value;
+        }
+
+        match literal {
+            "true" if self.bools => return Value::from(true),
+            "false" if self.bools => return Value::from(false),
+            _ => {}
+        }
+
+        if self.lenient_bools {
+            match literal.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" => return Value::from(true),
+                "false" | "no" | "off" => return Value::from(false),
+                _ => {}
+            }
+        }
+
+        let number = match self.underscores && literal.contains('_') {
+            true => match strip_underscores(literal) {
+                Some(stripped) => std::borrow::Cow::Owned(stripped),
+                None => return Value::from(literal.to_string()),
+            },
+            false => std::borrow::Cow::Borrowed(literal),
+        };
+
+        if self.floats && number.contains('.') {
+            if let Ok(float) = number.parse::<f64>() {
+                return Value::from(float);
+            }
+        }
+
+        if let Ok(int) = number.parse::<usize>() {
+            return Value::from(int);
+        } else if let Ok(int) = number.parse::<isize>() {
+            return Value::from(int);
+        }
+
+        if self.radix_ints {
+            let (negative, unsigned) = match number.strip_prefix('-') {
+                Some(unsigned) => (true, unsigned),
+                None => (false, &*number),
+            };
+
+            let radix = match unsigned.get(..2) {
+                Some("0x") | Some("0X") => Some(16),
+                Some("0o") | Some("0O") => Some(8),
+                Some("0b") | Some("0B") => Some(2),
+                _ => None,
+            };
+
+            if let Some(radix) = radix {
+                let digits = &unsigned[2..];
+                if !negative {
+                    if let Ok(int) = usize::from_str_radix(digits, radix) {
+                        return Value::from(int);
+                    }
+                } else if let Ok(int) = isize::from_str_radix(&format!("-{}", digits), radix) {
+                    return Value::from(int);
+                }
+            }
+        }
+
+        Value::from(literal.to_string())
+    }
+}
+
+impl Default for ParsePolicy {
+    fn default() -> Self {
+        ParsePolicy::new()
+    }
+}
+
+/// Removes `_` separators from `literal` if every `_` is surrounded by
+/// alphanumeric characters. Returns `None` otherwise.
+fn strip_underscores(literal: &str) -> Option<String> {
+    let bytes = literal.as_bytes();
+    for (i, _) in literal.match_indices('_') {
+        let before = i.checked_sub(1).and_then(|j| bytes.get(j));
+        let after = bytes.get(i + 1);
+        match (before, after) {
+            (Some(b), Some(a)) if b.is_ascii_alphanumeric() && a.is_ascii_alphanumeric() => {}
+            _ => return None,
+        }
+    }
+
+    Some(literal.replace('_', ""))
+}
 
 type Input<'a> = Pear<Text<'a>>;
 type Result<'a, T> = pear::input::Result<T, Input<'a>>;
@@ -34,7 +340,7 @@ fn string<'a>(input: &mut Input<'a>) -> Result<'a, String> {
     };
 
     let inner = (eat('"')?, take_while(str_char)?, eat('"')?).1;
-    match escape(inner) {
+    match unescape(inner) {
         Ok(string) => string.into_owned(),
         Err(e) => parse_error!("invalid string: {}", e)?,
     }
@@ -42,51 +348,37 @@ fn string<'a>(input: &mut Input<'a>) -> Result<'a, String> {
 
 #[parser]
 fn key<'a>(input: &mut Input<'a>) -> Result<'a, String> {
-    Ok(take_some_while(is_ident_char)?.to_string())
+    switch! {
+        peek('"') => string()?,
+        _ => take_some_while(is_ident_char)?.to_string()
+    }
 }
 
 #[parser]
-fn key_value<'a>(input: &mut Input<'a>) -> Result<'a, (String, Value)> {
+fn key_value<'a>(input: &mut Input<'a>, policy: &ParsePolicy) -> Result<'a, (String, Value)> {
     let key = (surrounded(key, is_whitespace)?, eat('=')?).0;
-    (key, surrounded(value, is_whitespace)?)
+    (key, surrounded(|i| value(i, policy), is_whitespace)?)
 }
 
 #[parser]
-fn array<'a>(input: &mut Input<'a>) -> Result<'a, Vec<Value>> {
-    Ok(delimited_collect('[', value, ',', ']')?)
+fn array<'a>(input: &mut Input<'a>, policy: &ParsePolicy) -> Result<'a, Vec<Value>> {
+    Ok(delimited_collect('[', |i| value(i, policy), ',', ']')?)
 }
 
 #[parser]
-fn dict<'a>(input: &mut Input<'a>) -> Result<'a, Dict> {
-    Ok(delimited_collect('{', key_value, ',', '}')?)
+fn dict<'a>(input: &mut Input<'a>, policy: &ParsePolicy) -> Result<'a, Dict> {
+    Ok(delimited_collect('{', |i| key_value(i, policy), ',', '}')?)
 }
 
 #[parser]
-fn value<'a>(input: &mut Input<'a>) -> Result<'a, Value> {
+fn value<'a>(input: &mut Input<'a>, policy: &ParsePolicy) -> Result<'a, Value> {
     skip_while(is_whitespace)?;
     let val = switch! {
-        eat_slice("true") => Value::from(true),
-        eat_slice("false") => Value::from(false),
-        peek('{') => Value::from(dict()?),
-        peek('[') => Value::from(array()?),
+        peek('{') => Value::from(dict(policy)?),
+        peek('[') => Value::from(array(policy)?),
         peek('"') => Value::from(string()?),
         peek('\'') => Value::from((eat('\'')?, eat_any()?, eat('\'')?).1),
-        _ => {
-            let value = take_while(is_not_separator)?.trim();
-            if value.contains('.') {
-                if let Ok(float) = value.parse::<f64>() {
-                    return Ok(Value::from(float));
-                }
-            }
-
-            if let Ok(int) = value.parse::<usize>() {
-                Value::from(int)
-            } else if let Ok(int) = value.parse::<isize>() {
-                Value::from(int)
-            } else {
-                Value::from(value.to_string())
-            }
-        }
+        _ => policy.literal(take_while(is_not_separator)?.trim())
     };
 
     skip_while(is_whitespace)?;
@@ -97,8 +389,7 @@ impl std::str::FromStr for Value {
     type Err = std::convert::Infallible;
 
     fn from_str(s: &str) -> std::result::Result<Self, std::convert::Infallible> {
-        Ok(parse!(value: Text::from(s))
-            .unwrap_or_else(|_| Value::from(s.to_string())))
+        Ok(ParsePolicy::default().parse(s))
     }
 }
 
@@ -173,6 +464,7 @@ mod tests {
             "{a=[[-2]],b=\" hi\"}" => map!["a" => v(vec![vec![-2]]), "b" => v(" hi")],
             "[1,true,hi,\"a \"]" => vec![v(1u8), v(true), v("hi"), v("a ")],
             "[1,{a=b},hi]" => vec![v(1u8), v(map!["a" => "b"]), v("hi")],
+            "{\"a.b\"=1, \"\"=2}" => map!["a.b" => 1u8, "" => 2u8],
             "[[ -1], {a=[ b ]},  hi ]" =>
                 vec![v(vec![-1]), v(map!["a" => vec!["b"]]), v("hi")],
         }
@@ -0,0 +1,203 @@
+use core::fmt;
+
+use crate::prelude::*;
+use crate::value::{Value, Dict, Compact};
+use crate::util::split_key;
+
+/// A rendering of a [`Value`] or [`Dict`] that is safe to log.
+///
+/// Logging a configuration tree is a common mistake: the tree may contain
+/// secrets, and a single value may be megabytes long. A `Redacted` renders
+/// the tree on a single line, like `{name = "app", password = <redacted>}`,
+/// with the value at every redacted key path replaced by `<redacted>`.
+/// Strings longer than [`max_len`](Redacted::max_len()) characters and arrays
+/// and dictionaries with more than `max_len` entries are truncated, with a
+/// note of how much was left out. The `Debug` and `Display` implementations
+/// render identically.
+///
+/// A `Redacted` is created via [`Value::redacted()`], from a `&Dict`, or via
+/// [`Figment::redacted()`](crate::Figment::redacted()), which redacts the
+/// keys registered with [`Figment::redact()`](crate::Figment::redact()).
+///
+/// A redacted key path redacts the value at that path, whatever it is, in
+/// every element of an array along the path: `servers.password` redacts the
+/// `password` of every server in `servers = [{ .. }, { .. }]`.
+///
+/// # Example
+///
+/// ```rust
+/// use figment::{util::map, value::{Redacted, Value}};
+///
+/// let value = Value::from(map! {
+///     "blob" => Value::from("x".repeat(1000)),
+///     "database" => map!["password" => "hunter2", "user" => "admin"].into(),
+///     "name" => "app".into(),
+/// });
+///
+/// let redacted = value.redacted().redact(["database.password"]).max_len(3);
+/// assert_eq!(redacted.to_string(), "{blob = \"xxx\"... (+997 chars), \
+///     database = {password = <redacted>, user = \"adm\"... (+2 chars)}, name = \"app\"}");
+///
+/// let dict = value.into_dict().unwrap();
+/// let redacted = Redacted::from(&dict).redact(["database"]).max_len(1);
+/// assert_eq!(format!("{:?}", redacted), "{blob = \"x\"... (+999 chars), ... (+2 more)}");
+/// ```
+#[derive(Clone)]
+pub struct Redacted<'a> {
+    root: Root<'a>,
+    keys: Vec<Vec<String>>,
+    max_len: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Root<'a> {
+    Value(&'a Value),
+    Dict(&'a Dict),
+}
+
+impl<'a> Redacted<'a> {
+    /// The default maximum length of strings, in characters, and of arrays
+    /// and dictionaries, in entries: `128`.
+    pub const DEFAULT_MAX_LEN: usize = 128;
+
+    /// Redacts the value at every key path in `keys`. Keys are split and
+    /// unescaped as by [`Figment::find_value()`](crate::Figment::find_value()).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{util::map, value::Value};
+    ///
+    /// let value = Value::from(map!["tls.key" => "def", "token" => "abc"]);
+    /// let redacted = value.redacted().redact(["token", r"tls\.key"]);
+    /// assert_eq!(redacted.to_string(), "{tls.key = <redacted>, token = <redacted>}");
+    /// ```
+    pub fn redact<I, K>(mut self, keys: I) -> Self
+        where I: IntoIterator<Item = K>, K: AsRef<str>
+    {
+        self.keys.extend(keys.into_iter().map(|key| split_key(key.as_ref())));
+        self
+    }
+
+    /// Sets the maximum length of strings, in characters, and of arrays and
+    /// dictionaries, in entries, beyond which they are truncated. Defaults to
+    /// [`Redacted::DEFAULT_MAX_LEN`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::Value;
+    ///
+    /// let value = Value::from(vec![1, 2, 3, 4]);
+    /// assert_eq!(value.redacted().to_string(), "[1, 2, 3, 4]");
+    /// assert_eq!(value.redacted().max_len(2).to_string(), "[1, 2, ... (+2 more)]");
+    /// ```
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Redacts the value at every already split key path in `keys`. An empty
+    /// key path redacts the entire value.
+    pub(crate) fn redact_paths(mut self, keys: Vec<Vec<String>>) -> Self {
+        self.keys.extend(keys);
+        self
+    }
+
+    fn new(root: Root<'a>) -> Self {
+        Redacted { root, keys: vec![], max_len: Self::DEFAULT_MAX_LEN }
+    }
+}
+
+impl<'a> From<&'a Value> for Redacted<'a> {
+    fn from(value: &'a Value) -> Self {
+        Redacted::new(Root::Value(value))
+    }
+}
+
+impl<'a> From<&'a Dict> for Redacted<'a> {
+    fn from(dict: &'a Dict) -> Self {
+        Redacted::new(Root::Dict(dict))
+    }
+}
+
+/// Returns whether the value at `key` is redacted by one of `keys` and the
+/// remaining paths of those that redact values nested under it.
+fn child<'k>(keys: &[&'k [String]], key: &str) -> (bool, Vec<&'k [String]>) {
+    let mut redacted = false;
+    let mut rest = vec![];
+    for (first, tail) in keys.iter().filter_map(|k| k.split_first()) {
+        if first == key {
+            match tail.is_empty() {
+                true => redacted = true,
+                false => rest.push(tail),
+            }
+        }
+    }
+
+    (redacted, rest)
+}
+
+fn write_dict(f: &mut fmt::Formatter<'_>, dict: &Dict, keys: &[&[String]], max: usize) -> fmt::Result {
+    write!(f, "{{")?;
+    for (i, (k, v)) in dict.iter().take(max).enumerate() {
+        if i > 0 { write!(f, ", ")?; }
+        write!(f, "{} = ", k)?;
+        match child(keys, k) {
+            (true, _) => write!(f, "<redacted>")?,
+            (false, rest) => write_value(f, v, &rest, max)?,
+        }
+    }
+
+    if dict.len() > max {
+        if max > 0 { write!(f, ", ")?; }
+        write!(f, "... (+{} more)", dict.len() - max)?;
+    }
+
+    write!(f, "}}")
+}
+
+fn write_value(f: &mut fmt::Formatter<'_>, value: &Value, keys: &[&[String]], max: usize) -> fmt::Result {
+    match value {
+        Value::String(_, v) => match v.char_indices().nth(max) {
+            Some((i, _)) => write!(f, "{:?}... (+{} chars)", &v[..i], v[i..].chars().count()),
+            None => write!(f, "{:?}", v),
+        },
+        Value::Dict(_, dict) => write_dict(f, dict, keys, max),
+        Value::Array(_, array) => {
+            write!(f, "[")?;
+            for (i, v) in array.iter().take(max).enumerate() {
+                if i > 0 { write!(f, ", ")?; }
+                write_value(f, v, keys, max)?;
+            }
+
+            if array.len() > max {
+                if max > 0 { write!(f, ", ")?; }
+                write!(f, "... (+{} more)", array.len() - max)?;
+            }
+
+            write!(f, "]")
+        }
+        _ => write!(f, "{}", Compact(value)),
+    }
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.keys.iter().any(|k| k.is_empty()) {
+            return write!(f, "<redacted>");
+        }
+
+        let keys: Vec<&[String]> = self.keys.iter().map(|k| k.as_slice()).collect();
+        match self.root {
+            Root::Value(value) => write_value(f, value, &keys, self.max_len),
+            Root::Dict(dict) => write_dict(f, dict, &keys, self.max_len),
+        }
+    }
+}
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -1,15 +1,65 @@
-use serde::{ser, Serialize, Serializer};
+use serde::{ser, Deserialize, Serialize, Serializer};
 
+use crate::prelude::*;
 use crate::error::{Error, Kind};
-use crate::value::{Value, Dict, Num, Empty};
+use crate::value::magic::{Magic, Tagged};
+use crate::value::{Value, Dict, Num, Empty, Tag};
+#[cfg(feature = "std")]
+use core::cell::Cell;
+
+type Result<T> = core::result::Result<T, Error>;
+
+/// The name of the newtype struct a tagged `Value` serializes as while a
+/// [`ValueSerializer`] is serializing, so that the tag is preserved.
+const TAGGED_VALUE: &str = "___figment_value_with_tag";
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// `Some` while [`Value::serialize()`] is serializing, and then the tag of
+    /// the `Value` serialized as a [`TAGGED_VALUE`] newtype struct, if any.
+    static SERIALIZING: Cell<Option<Tag>> = const { Cell::new(None) };
+}
+
+/// Calls `f`, in which `Value`s serialize as [`TAGGED_VALUE`] newtype structs
+/// that a `ValueSerializer` deserializes into values with the same tags.
+pub(crate) fn preserving_tags<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "std")] {
+        struct Restore(Option<Option<Tag>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let _ = SERIALIZING.try_with(|s| s.set(self.0.take().flatten()));
+            }
+        }
+
+        let _restore = Restore(Some(SERIALIZING.with(|s| s.replace(Some(Tag::Default)))));
+        f()
+    }
+
+    #[cfg(not(feature = "std"))]
+    f()
+}
 
-type Result<T> = std::result::Result<T, Error>;
+/// A `Value`, serialized without its tag.
+struct Untagged<'a>(&'a Value);
 
 impl Serialize for Value {
-    fn serialize<S: Serializer>(&self, ser: S) -> std::result::Result<S::Ok, S::Error> {
+    fn serialize<S: Serializer>(&self, ser: S) -> core::result::Result<S::Ok, S::Error> {
+        #[cfg(feature = "std")]
+        if !self.tag().is_default() && SERIALIZING.with(|s| s.get().is_some()) {
+            SERIALIZING.with(|s| s.set(Some(self.tag())));
+            return ser.serialize_newtype_struct(TAGGED_VALUE, &Untagged(self));
+        }
+
+        Untagged(self).serialize(ser)
+    }
+}
+
+impl Serialize for Untagged<'_> {
+    fn serialize<S: Serializer>(&self, ser: S) -> core::result::Result<S::Ok, S::Error> {
         use ser::{SerializeSeq, SerializeMap};
 
-        match self {
+        match self.0 {
             Value::String(_, v) => ser.serialize_str(v),
             Value::Char(_, v) => ser.serialize_char(*v),
             Value::Bool(_, v) => ser.serialize_bool(*v),
@@ -36,7 +86,7 @@ impl Serialize for Value {
 }
 
 impl Serialize for Num {
-    fn serialize<S: Serializer>(&self, ser: S) -> std::result::Result<S::Ok, S::Error> {
+    fn serialize<S: Serializer>(&self, ser: S) -> core::result::Result<S::Ok, S::Error> {
         match *self {
             Num::U8(v) => ser.serialize_u8(v),
             Num::U16(v) => ser.serialize_u16(v),
@@ -57,10 +107,11 @@ impl Serialize for Num {
 }
 
 impl Serialize for Empty {
-    fn serialize<S: Serializer>(&self, ser: S) -> std::result::Result<S::Ok, S::Error> {
+    fn serialize<S: Serializer>(&self, ser: S) -> core::result::Result<S::Ok, S::Error> {
         match self {
             Empty::None => ser.serialize_none(),
             Empty::Unit => ser.serialize_unit(),
+            Empty::Unset => ser.serialize_unit_struct(Empty::UNSET_NAME),
         }
     }
 }
@@ -74,12 +125,23 @@ macro_rules! serialize_fn {
 }
 
 pub struct SeqSerializer {
-    sequence: Vec<Value>
+    sequence: Vec<Value>,
+    variant: Option<&'static str>,
 }
 
 pub struct MapSerializer {
     keys: Vec<String>,
-    values: Vec<Value>
+    values: Vec<Value>,
+    variant: Option<&'static str>,
+    tagged: bool,
+}
+
+/// Returns the externally tagged representation of the enum variant `variant`
+/// with the contents `value`: a dictionary with one entry.
+fn variant(variant: &'static str, value: Value) -> Value {
+    let mut dict = Dict::new();
+    dict.insert(variant.into(), value);
+    dict.into()
 }
 
 impl Serializer for ValueSerializer {
@@ -125,7 +187,8 @@ impl Serializer for ValueSerializer {
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
         Ok(SeqSerializer {
-            sequence: len.map(Vec::with_capacity).unwrap_or_default()
+            sequence: len.map(Vec::with_capacity).unwrap_or_default(),
+            variant: None,
         })
     }
 
@@ -133,25 +196,29 @@ impl Serializer for ValueSerializer {
         Ok(MapSerializer {
             keys: len.map(Vec::with_capacity).unwrap_or_default(),
             values: len.map(Vec::with_capacity).unwrap_or_default(),
+            variant: None,
+            tagged: false,
         })
     }
 
     fn serialize_struct(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct> {
-        self.serialize_map(Some(len))
+        let map = self.serialize_map(Some(len))?;
+        Ok(MapSerializer { tagged: name == Tagged::<()>::NAME, ..map })
     }
 
     fn serialize_struct_variant(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        self.serialize_map(Some(len))
+        let map = self.serialize_map(Some(len))?;
+        Ok(MapSerializer { variant: Some(variant), ..map })
     }
 
     fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok>
@@ -171,11 +238,20 @@ impl Serializer for ValueSerializer {
 
     fn serialize_newtype_struct<T: ?Sized>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok>
         where T: Serialize
     {
+        #[cfg(feature = "std")]
+        if name == TAGGED_VALUE {
+            let tag = SERIALIZING.with(|s| s.replace(Some(Tag::Default))).unwrap_or_default();
+            let mut value = value.serialize(self)?;
+            *value.tag_mut() = tag;
+            return Ok(value);
+        }
+
+        let _ = name;
         value.serialize(self)
     }
 
@@ -195,10 +271,11 @@ impl Serializer for ValueSerializer {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        self.serialize_seq(Some(len))
+        let seq = self.serialize_seq(Some(len))?;
+        Ok(SeqSerializer { variant: Some(variant), ..seq })
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
@@ -209,18 +286,21 @@ impl Serializer for ValueSerializer {
         Ok(Empty::Unit.into())
     }
 
-    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
-        self.serialize_unit()
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok> {
+        match name {
+            Empty::UNSET_NAME => Ok(Empty::Unset.into()),
+            _ => self.serialize_unit(),
+        }
     }
 
     fn serialize_newtype_variant<T: Serialize + ?Sized>(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok> {
-        value.serialize(self)
+        Ok(variant(name, value.serialize(self)?))
     }
 }
 
@@ -282,7 +362,8 @@ impl<'a> ser::SerializeTupleVariant for SeqSerializer {
     }
 
     fn end(self) -> Result<Self::Ok> {
-        ser::SerializeSeq::end(self)
+        let name = self.variant.expect("tuple variant name");
+        Ok(variant(name, self.sequence.into()))
     }
 }
 
@@ -295,6 +376,7 @@ impl<'a> ser::SerializeMap for MapSerializer {
     {
         match key.serialize(ValueSerializer)? {
             Value::String(_, s) => self.keys.push(s),
+            Value::Char(_, c) => self.keys.push(c.to_string()),
             v => return Err(Kind::UnsupportedKey(v.to_actual(), "string".into()).into()),
         };
 
@@ -326,7 +408,20 @@ impl<'a> ser::SerializeStruct for MapSerializer {
     }
 
     fn end(self) -> Result<Self::Ok> {
-        ser::SerializeMap::end(self)
+        if !self.tagged {
+            return ser::SerializeMap::end(self);
+        }
+
+        // A `Tagged` serializes as its value with its tag.
+        let mut fields = self.keys.into_iter().zip(self.values).collect::<Dict>();
+        let mut field = |name: &'static str| fields.remove(name).ok_or_else(|| Kind::MissingField(name.into()));
+        let tag = Tag::deserialize(&field(Tagged::<()>::FIELDS[0])?)?;
+        let mut value = field(Tagged::<()>::FIELDS[1])?;
+        if value.tag().is_default() {
+            *value.tag_mut() = tag;
+        }
+
+        Ok(value)
     }
 }
 
@@ -342,6 +437,7 @@ impl<'a> ser::SerializeStructVariant for MapSerializer {
     }
 
     fn end(self) -> Result<Self::Ok> {
-        ser::SerializeMap::end(self)
+        let name = self.variant.expect("struct variant name");
+        Ok(variant(name, ser::SerializeMap::end(self)?))
     }
 }
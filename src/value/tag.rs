@@ -1,5 +1,5 @@
-use std::fmt;
-use std::sync::atomic::Ordering;
+use core::fmt;
+use core::sync::atomic::Ordering;
 
 use serde::{de, ser};
 use crate::profile::{Profile, ProfileTag};
@@ -20,7 +20,7 @@ pub struct Tag(u64);
 static COUNTER: atomic::Atomic<u64> = atomic::Atomic::new(1);
 
 #[cfg(not(any(target_pointer_width = "8", target_pointer_width = "16", target_pointer_width = "32")))]
-static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+static COUNTER: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(1);
 
 
 impl Tag {
@@ -43,8 +43,26 @@ impl Tag {
         Tag(bits)
     }
 
-    // Returns a tag with a unique metadata id.
-    pub(crate) fn next() -> Tag {
+    /// Returns a new tag with a unique metadata id.
+    ///
+    /// A [`Provider`](crate::Provider) that attributes the values it emits to
+    /// metadata other than its own tags them, via [`Value::tagged()`], with
+    /// unique tags that it associates with the metadata in
+    /// [`Provider::tags()`](crate::Provider::tags()).
+    ///
+    /// [`Value::tagged()`]: crate::value::Value::tagged()
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::value::Tag;
+    ///
+    /// let (a, b) = (Tag::unique(), Tag::unique());
+    /// assert_ne!(a, b);
+    /// assert!(!a.is_default());
+    /// assert_eq!(a.profile(), Some(figment::Profile::Default));
+    /// ```
+    pub fn unique() -> Tag {
         let id = COUNTER.fetch_add(1, Ordering::AcqRel);
         if id > Self::METADATA_ID_MASK {
             panic!("figment: out of unique tag IDs");
@@ -110,19 +128,19 @@ impl PartialEq for Tag {
 impl Eq for Tag {  }
 
 impl PartialOrd for Tag {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         self.metadata_id().partial_cmp(&other.metadata_id())
     }
 }
 
 impl Ord for Tag {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.metadata_id().cmp(&other.metadata_id())
     }
 }
 
-impl std::hash::Hash for Tag {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+impl core::hash::Hash for Tag {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         state.write_u64(self.metadata_id())
     }
 }
@@ -1,16 +1,46 @@
-use std::str::Split;
-use std::collections::BTreeMap;
+use core::cmp::Ordering;
 
 use serde::Serialize;
 
-use crate::value::{Tag, ValueSerializer};
+use crate::prelude::*;
+use crate::value::{Tag, ValueSerializer, ValueDiff, Redacted};
 use crate::error::{Error, Actual};
+use crate::util::split_key;
 
-/// An alias to the type of map used in [`Value::Dict`].
-pub type Map<K, V> = BTreeMap<K, V>;
+#[cfg(not(feature = "preserve-order"))]
+use alloc::collections::BTreeMap as DictMap;
 
-/// An alias to a [`Map`] from `String` to [`Value`]s.
-pub type Dict = Map<String, Value>;
+#[cfg(feature = "preserve-order")]
+use indexmap::IndexMap as DictMap;
+
+/// An alias to the type of map used throughout figment, a
+/// [`BTreeMap`](alloc::collections::BTreeMap).
+pub type Map<K, V> = alloc::collections::BTreeMap<K, V>;
+
+/// An alias to the map from `String` to [`Value`]s used in [`Value::Dict`].
+///
+/// By default, this is a [`Map`], and keys are iterated in sorted order. When
+/// the `preserve-order` feature is enabled, this is an
+/// [`IndexMap`](https://docs.rs/indexmap/1), and keys are iterated in the
+/// order in which they were first provided, through merging, joining, and
+/// serialization.
+///
+/// ```rust
+/// use figment::{Figment, value::Dict, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string("zebra = 1\napple = 2\nmango = 3"))
+///     .merge(("banana", 4));
+///
+/// let dict: Dict = figment.extract().unwrap();
+/// let keys: Vec<&str> = dict.keys().map(|k| k.as_str()).collect();
+///
+/// #[cfg(feature = "preserve-order")]
+/// assert_eq!(keys, ["zebra", "apple", "mango", "banana"]);
+///
+/// #[cfg(not(feature = "preserve-order"))]
+/// assert_eq!(keys, ["apple", "banana", "mango", "zebra"]);
+/// ```
+pub type Dict = DictMap<String, Value>;
 
 /// An enum representing all possible figment value variants.
 ///
@@ -40,6 +70,43 @@ pub enum Value {
     Array(Tag, Vec<Value>),
 }
 
+/// Returns the value at `key` in `dict`, matching ignoring ASCII case if
+/// `uncased`.
+fn dict_get<'a>(dict: &'a Dict, key: &str, uncased: bool) -> Option<&'a Value> {
+    match uncased {
+        true => dict.iter().find(|(k, _)| uncased::UncasedStr::new(k) == key).map(|(_, v)| v),
+        false => dict.get(key),
+    }
+}
+
+/// Returns a reference to the value at the split key path `keys` in `dict`,
+/// as [`Value::find_keys()`] does, or `None` if `keys` is empty.
+pub(crate) fn find_keys_in<'a>(dict: &'a Dict, keys: &[String], uncased: bool) -> Option<&'a Value> {
+    let (first, rest) = keys.split_first()?;
+    dict_get(dict, first, uncased)?.find_keys(rest, uncased)
+}
+
+/// Removes `key` from `dict`, keeping the order of the remaining keys.
+pub(crate) fn dict_remove(dict: &mut Dict, key: &str) -> Option<Value> {
+    #[cfg(feature = "preserve-order")] {
+        dict.shift_remove(key)
+    }
+
+    #[cfg(not(feature = "preserve-order"))] {
+        dict.remove(key)
+    }
+}
+
+/// Parses the key path component `key` as an array index: a decimal integer
+/// without leading zeroes, as in JSON Pointer.
+pub(crate) fn array_index(key: &str) -> Option<usize> {
+    let digits = !key.is_empty() && key.bytes().all(|b| b.is_ascii_digit());
+    match digits && (key == "0" || !key.starts_with('0')) {
+        true => key.parse().ok(),
+        false => None,
+    }
+}
+
 macro_rules! conversion_fn {
     ($RT:ty, $([$star:tt])? $Variant:ident => $T:ty, $fn_name:ident) => {
         conversion_fn!(
@@ -83,8 +150,22 @@ impl Value {
     /// let value = Value::serialize(vec![4, 5, 6]).unwrap();
     /// assert_eq!(value, vec![4, 5, 6].into());
     /// ```
+    ///
+    /// The tags of `Value`s and [`Tagged`](crate::value::magic::Tagged)
+    /// values in `value` are preserved, so re-providing an extracted value
+    /// retains its provenance in a figment that knows the tags' metadata:
+    ///
+    /// ```
+    /// use figment::{Figment, value::{Value, magic::Tagged}};
+    ///
+    /// let figment = Figment::from(("port", 8080));
+    /// let port: Tagged<u16> = figment.extract_inner("port").unwrap();
+    /// let value = Value::serialize(&port).unwrap();
+    /// assert_eq!(value.tag(), port.tag());
+    /// assert_eq!(value.to_u128(), Some(8080));
+    /// ```
     pub fn serialize<T: Serialize>(value: T) -> Result<Self, Error> {
-        value.serialize(ValueSerializer)
+        crate::value::ser::preserving_tags(|| value.serialize(ValueSerializer))
     }
 
     /// Deserialize `self` into any deserializable `T`.
@@ -100,12 +181,92 @@ impl Value {
         T::deserialize(self)
     }
 
+    /// Consumes `self` and deserializes it into any owned, deserializable `T`,
+    /// returning a figment [`Error`] tagged with `self`'s [`Tag`] on failure.
+    ///
+    /// Unlike [`Value::deserialize()`], the target type can usually be inferred
+    /// without a turbofish, and the returned value does not borrow from
+    /// `self`.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use figment::{value::Value, util::map};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Server {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let value = Value::from(map!["host" => Value::from("localhost"), "port" => 8080.into()]);
+    /// let server: Server = value.deserialize_into().unwrap();
+    /// assert_eq!(server, Server { host: "localhost".into(), port: 8080 });
+    ///
+    /// let value = Value::from("not a number");
+    /// let error = value.deserialize_into::<u16>().unwrap_err();
+    /// assert!(error.to_string().contains("invalid type"));
+    /// ```
+    pub fn deserialize_into<T: serde::de::DeserializeOwned>(self) -> Result<T, Error> {
+        T::deserialize(&self).map_err(|e| e.retagged(self.tag()))
+    }
+
+    /// Computes the structural difference from `self` to `other`, returning
+    /// the key paths that were added, removed, or changed. See [`ValueDiff`]
+    /// for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{value::Value, util::map};
+    ///
+    /// let old = Value::from(map! {
+    ///     "server" => map!["port" => Value::from(80), "host" => "localhost".into()],
+    ///     "debug" => map!["level" => Value::from(1)],
+    /// });
+    ///
+    /// let new = Value::from(map! {
+    ///     "server" => map!["port" => Value::from(8080), "host" => "localhost".into()],
+    ///     "workers" => map!["count" => Value::from(4)],
+    /// });
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.added[0].0, "workers");
+    /// assert_eq!(diff.removed[0].0, "debug");
+    /// assert_eq!(diff.changed[0].0, "server.port");
+    /// assert_eq!(diff.changed[0].2, 8080.into());
+    /// ```
+    pub fn diff(&self, other: &Value) -> ValueDiff {
+        ValueDiff::compute(self, other)
+    }
+
+    /// Returns a rendering of `self` that is safe to log, with the values at
+    /// chosen keys redacted and huge values truncated. See [`Redacted`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{value::Value, util::map};
+    ///
+    /// let value = Value::from(map!["password" => "hunter2", "user" => "admin"]);
+    /// let redacted = value.redacted().redact(["password"]);
+    /// assert_eq!(redacted.to_string(), "{password = <redacted>, user = \"admin\"}");
+    /// ```
+    pub fn redacted(&self) -> Redacted<'_> {
+        Redacted::from(self)
+    }
+
     /// Looks up and returns the value at path `path`, where `path` is of the
     /// form `a.b.c` where `a`, `b`, and `c` are keys to dictionaries. If the
     /// key is empty, simply returns `self`. If the key is not empty and `self`
     /// or any of the values for non-leaf keys in the path are not dictionaries,
     /// returns `None`.
     ///
+    /// Keys that themselves contain a `.` can be addressed by escaping the dot,
+    /// as in `a\\.b`, or by quoting the key in brackets, as in `a["b.c"]`. A
+    /// key that is an index, as in `a.0`, selects an element of an array. The
+    /// path may also be a JSON Pointer, as in `/a/0`. See
+    /// [`split_key()`](crate::util::split_key()) for the full syntax.
+    ///
     /// This method consumes `self`. See [`Value::find_ref()`] for a
     /// non-consuming variant.
     ///
@@ -134,16 +295,65 @@ impl Value {
     ///
     /// assert!(value.clone().find("apple.pie").is_none());
     /// assert!(value.clone().find("pineapple").is_none());
+    ///
+    /// let value = Value::from(map! {
+    ///     "hosts" => map! {
+    ///         "example.com" => 443usize,
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(value.clone().find(r"hosts.example\.com").unwrap().to_u128(), Some(443));
+    /// assert_eq!(value.clone().find(r#"hosts["example.com"]"#).unwrap().to_u128(), Some(443));
+    /// assert!(value.clone().find("hosts.example.com").is_none());
+    ///
+    /// let value = Value::from(map! {
+    ///     "servers" => vec![map!["port" => 80usize], map!["port" => 443usize]],
+    /// });
+    ///
+    /// assert_eq!(value.clone().find("servers.1.port").unwrap().to_u128(), Some(443));
+    /// assert_eq!(value.clone().find("/servers/0/port").unwrap().to_u128(), Some(80));
+    /// assert!(value.clone().find("/servers/2/port").is_none());
+    /// assert!(value.clone().find("/servers/01/port").is_none());
     /// ```
     pub fn find(self, path: &str) -> Option<Value> {
-        fn find(mut keys: Split<char>, value: Value) -> Option<Value> {
+        fn find(mut keys: alloc::vec::IntoIter<String>, value: Value) -> Option<Value> {
             match keys.next() {
-                Some(k) if !k.is_empty() => find(keys, value.into_dict()?.remove(k)?),
+                Some(k) if !k.is_empty() => match value {
+                    Value::Array(_, mut vec) => {
+                        let i = array_index(&k).filter(|i| *i < vec.len())?;
+                        find(keys, vec.swap_remove(i))
+                    }
+                    value => find(keys, value.into_dict()?.remove(&k)?),
+                },
                 Some(_) | None => Some(value)
             }
         }
 
-        find(path.split('.'), self)
+        find(split_key(path).into_iter(), self)
+    }
+
+    /// Like [`Value::find()`] but matches each key in `path` ignoring ASCII
+    /// case.
+    pub(crate) fn find_uncased(self, path: &str) -> Option<Value> {
+        self.find_keys(&split_key(path), true).cloned()
+    }
+
+    /// Returns a reference to the value at the split key path `keys`, as
+    /// [`Value::find_ref()`] does, matching keys ignoring ASCII case if
+    /// `uncased`. This is the lookup behind every key path search.
+    pub(crate) fn find_keys(&self, keys: &[String], uncased: bool) -> Option<&Value> {
+        let (k, rest) = match keys.split_first() {
+            Some((k, rest)) if !k.is_empty() => (k, rest),
+            _ => return Some(self),
+        };
+
+        let value = match self {
+            Value::Array(_, vec) => vec.get(array_index(k)?)?,
+            Value::Dict(_, dict) => dict_get(dict, k, uncased)?,
+            _ => return None,
+        };
+
+        value.find_keys(rest, uncased)
     }
 
     /// Exactly like [`Value::find()`] but does not consume `self`,
@@ -176,14 +386,90 @@ impl Value {
     /// assert!(value.find_ref("pineapple").is_none());
     /// ```
     pub fn find_ref<'a>(&'a self, path: &str) -> Option<&'a Value> {
-        fn find<'a, 'v>(mut keys: Split<'a, char>, value: &'v Value) -> Option<&'v Value> {
-            match keys.next() {
-                Some(k) if !k.is_empty() => find(keys, value.as_dict()?.get(k)?),
-                Some(_) | None => Some(value)
+        self.find_keys(&split_key(path), false)
+    }
+
+    /// Returns the key path and a reference to every value in `self` at a key
+    /// path matching `pattern`, in map order. `pattern` is a key path, as in
+    /// [`Value::find()`], in which a component of exactly `*` matches any key
+    /// of a dictionary and any index of an array. The returned key paths are
+    /// [escaped](crate::util::escape_key()) so that they can be used with
+    /// [`Value::find()`] and friends.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::{value::Value, util::map};
+    ///
+    /// let value = Value::from(map! {
+    ///     "services" => Value::from(map! {
+    ///         "api" => map!["port" => Value::from(80)],
+    ///         "db" => map!["host" => Value::from("localhost")],
+    ///         "web.v2" => map!["port" => Value::from(443)],
+    ///     }),
+    ///     "ports" => Value::from(vec![1, 2]),
+    /// });
+    ///
+    /// let found = value.find_all("services.*.port");
+    /// let paths: Vec<_> = found.iter().map(|(path, _)| path.as_str()).collect();
+    /// assert_eq!(paths, ["services.api.port", r"services.web\.v2.port"]);
+    /// assert_eq!(found[1].1.to_i128(), Some(443));
+    ///
+    /// let found = value.find_all("ports.*");
+    /// assert_eq!(found[1], ("ports.1".to_string(), &Value::from(2)));
+    ///
+    /// assert!(value.find_all("*.*.host").len() == 1);
+    /// assert!(value.find_all("*.missing").is_empty());
+    /// ```
+    pub fn find_all<'a>(&'a self, pattern: &str) -> Vec<(String, &'a Value)> {
+        self.find_all_with(pattern, false)
+    }
+
+    /// Like [`Value::find_all()`] but matches each key that isn't `*`
+    /// ignoring ASCII case if `uncased` is `true`.
+    pub(crate) fn find_all_with<'a>(&'a self, pattern: &str, uncased: bool) -> Vec<(String, &'a Value)> {
+        fn find<'a>(
+            keys: &[String],
+            value: &'a Value,
+            uncased: bool,
+            path: &mut Vec<String>,
+            found: &mut Vec<(String, &'a Value)>,
+        ) {
+            let (key, rest) = match keys.split_first() {
+                Some((key, rest)) if !key.is_empty() => (key, rest),
+                _ => return found.push((path.join("."), value)),
+            };
+
+            let mut visit = |k: &str, v: &'a Value, path: &mut Vec<String>| {
+                path.push(crate::util::escape_key(k).into_owned());
+                find(rest, v, uncased, path, found);
+                path.pop();
+            };
+
+            match value {
+                Value::Dict(_, dict) => for (k, v) in dict {
+                    let matches = key == "*" || match uncased {
+                        true => uncased::UncasedStr::new(k) == key.as_str(),
+                        false => k == key,
+                    };
+
+                    if matches {
+                        visit(k, v, path);
+                    }
+                },
+                Value::Array(_, vec) if key == "*" => for (i, v) in vec.iter().enumerate() {
+                    visit(&i.to_string(), v, path);
+                },
+                Value::Array(_, vec) => if let Some(v) = array_index(key).and_then(|i| vec.get(i)) {
+                    visit(key, v, path);
+                },
+                _ => {}
             }
         }
 
-        find(path.split('.'), self)
+        let mut found = vec![];
+        find(&split_key(pattern), self, uncased, &mut vec![], &mut found);
+        found
     }
 
     /// Returns the [`Tag`] applied to this value.
@@ -215,6 +501,28 @@ impl Value {
         }
     }
 
+    /// Returns `self` with the tag `tag`, which is also applied to the
+    /// descendants of `self` that aren't already tagged.
+    ///
+    /// A [`Provider`](crate::Provider) can tag the values it emits with tags
+    /// from [`Tag::unique()`] to attribute them to metadata other than its
+    /// own. See [`Provider::tags()`](crate::Provider::tags()).
+    ///
+    /// ```
+    /// use figment::value::{Value, Tag};
+    ///
+    /// let tag = Tag::unique();
+    /// let value = Value::from(vec![1, 2]).tagged(tag);
+    /// assert_eq!(value.tag(), tag);
+    /// assert_eq!(value.find_ref("1").unwrap().tag(), tag);
+    /// assert!(value.as_array().unwrap().iter().all(|v| v.tag() == tag));
+    /// ```
+    pub fn tagged(mut self, tag: Tag) -> Self {
+        *self.tag_mut() = tag;
+        self.map_tag(|t| *t = tag);
+        self
+    }
+
     conversion_fn!(&Value, String => &str, as_str);
     conversion_fn!(Value, String => String, into_string);
     conversion_fn!(&Value, [*]Char => char, to_char);
@@ -226,6 +534,174 @@ impl Value {
     conversion_fn!(&Value, Array => &[Value], as_array);
     conversion_fn!(Value, Array => Vec<Value>, into_array);
 
+    /// Returns the elements of the array `self` as string slices if every
+    /// element is a [`Value::String`].
+    ///
+    /// Returns an error if `self` is not an array or, for every element that
+    /// is not a string, an error whose path is the element's index, in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::Value;
+    ///
+    /// let value = Value::from(vec!["a", "b"]);
+    /// assert_eq!(value.as_str_array().unwrap(), ["a", "b"]);
+    ///
+    /// let value = Value::from(vec![Value::from("a"), 1.into(), true.into()]);
+    /// let error = value.as_str_array().unwrap_err();
+    /// assert_eq!(error.count(), 2);
+    /// assert_eq!(error.path, ["1"]);
+    /// assert_eq!(error.to_string(), "invalid type: found signed int `1`, \
+    ///     expected a string for key \"[1]\"\ninvalid type: found bool true, \
+    ///     expected a string for key \"[2]\"");
+    ///
+    /// assert!(Value::from("a").as_str_array().is_err());
+    /// ```
+    pub fn as_str_array(&self) -> Result<Vec<&str>, Error> {
+        self.array_elements("an array of strings")?
+            .iter()
+            .map(|v| v.as_str().ok_or_else(|| v.invalid_type("a string")))
+            .collect_elements()
+    }
+
+    /// Returns the elements of the array `self` as [`Num`]s if every element
+    /// is a [`Value::Num`].
+    ///
+    /// Returns an error if `self` is not an array or, for every element that
+    /// is not a number, an error whose path is the element's index, in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::{Value, Num};
+    ///
+    /// let value = Value::from(vec![1u8, 2u8]);
+    /// assert_eq!(value.as_num_array().unwrap(), [Num::U8(1), Num::U8(2)]);
+    ///
+    /// let value = Value::from(vec![Value::from(1), "two".into()]);
+    /// let error = value.as_num_array().unwrap_err();
+    /// assert_eq!(error.path, ["1"]);
+    /// assert_eq!(error.display_path().unwrap(), "[1]");
+    /// ```
+    pub fn as_num_array(&self) -> Result<Vec<Num>, Error> {
+        self.array_elements("an array of numbers")?
+            .iter()
+            .map(|v| v.to_num().ok_or_else(|| v.invalid_type("a number")))
+            .collect_elements()
+    }
+
+    /// Consumes the array `self` and deserializes each of its elements into
+    /// a `T`, as by [`Value::deserialize_into()`].
+    ///
+    /// Returns an error if `self` is not an array or, for every element that
+    /// fails to deserialize, an error whose path is prefixed with the
+    /// element's index, in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::{value::Value, util::map};
+    ///
+    /// let value = Value::from(vec![80, 443]);
+    /// assert_eq!(value.into_vec::<u16>().unwrap(), [80, 443]);
+    ///
+    /// let value = Value::from(vec![
+    ///     Value::from(map!["port" => 80]),
+    ///     Value::from(map!["port" => -1]),
+    /// ]);
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Server { port: u16 }
+    ///
+    /// let error = value.into_vec::<Server>().err().unwrap();
+    /// assert_eq!(error.path, ["1", "port"]);
+    /// assert_eq!(error.display_path().unwrap(), "[1].port");
+    /// ```
+    pub fn into_vec<T: serde::de::DeserializeOwned>(self) -> Result<Vec<T>, Error> {
+        self.array_elements("an array")?;
+        self.into_array()
+            .expect("checked array")
+            .into_iter()
+            .map(Value::deserialize_into)
+            .collect_elements()
+    }
+
+    /// Returns the elements of `self` if it is an array or an invalid type
+    /// error, expecting `expected`, otherwise.
+    fn array_elements(&self, expected: &str) -> Result<&[Value], Error> {
+        self.as_array().ok_or_else(|| self.invalid_type(expected))
+    }
+
+    fn invalid_type(&self, expected: &str) -> Error {
+        Error::from(crate::error::Kind::InvalidType(self.to_actual(), expected.into()))
+            .retagged(self.tag())
+    }
+
+    /// Returns `true` if `self` is [`Empty::None`], an explicitly empty value
+    /// like `Option::None` or a JSON `null`.
+    ///
+    /// An explicitly empty value is distinct from an absent one: it replaces
+    /// the value it is merged over and is extracted as `None` into an
+    /// `Option`, while an absent key leaves the value it would be merged over
+    /// as is. A custom provider expresses the former by emitting the key with
+    /// the value `Value::from(None::<T>)` and the latter by not emitting the
+    /// key at all.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::{Figment, value::{Value, Empty}};
+    ///
+    /// assert!(Value::from(None::<u8>).is_none());
+    /// assert!(Value::from(Empty::None).is_none());
+    /// assert!(!Value::from(()).is_none());
+    /// assert!(!Value::from(Some(1)).is_none());
+    ///
+    /// let figment = Figment::from(("proxy", "http://proxy.local"));
+    /// let absent = figment.clone().merge(("port", 80));
+    /// assert!(absent.extract_inner::<Option<String>>("proxy").unwrap().is_some());
+    ///
+    /// let empty = figment.merge(("proxy", Value::from(None::<String>)));
+    /// assert!(empty.find_value("proxy").unwrap().is_none());
+    /// assert_eq!(empty.extract_inner::<Option<String>>("proxy").unwrap(), None);
+    /// ```
+    pub fn is_none(&self) -> bool {
+        matches!(self, Value::Empty(_, Empty::None))
+    }
+
+    /// Returns `true` if `self` is [`Empty::Unit`], an explicitly empty value
+    /// like `()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::{Value, Empty};
+    ///
+    /// assert!(Value::from(()).is_empty_unit());
+    /// assert!(Value::from(Empty::Unit).is_empty_unit());
+    /// assert!(!Value::from(None::<u8>).is_empty_unit());
+    /// assert!(!Value::from("").is_empty_unit());
+    /// ```
+    pub fn is_empty_unit(&self) -> bool {
+        matches!(self, Value::Empty(_, Empty::Unit))
+    }
+
+    /// Returns `true` if `self` is [`Empty::Unset`], a value that removes the
+    /// key it is at.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::{Value, Empty};
+    ///
+    /// assert!(Value::from(Empty::Unset).is_unset());
+    /// assert!(!Value::from(None::<u8>).is_unset());
+    /// ```
+    pub fn is_unset(&self) -> bool {
+        matches!(self, Value::Empty(_, Empty::Unset))
+    }
+
     /// Converts `self` into a `u128` if `self` is an unsigned `Value::Num`
     /// variant.
     ///
@@ -309,6 +785,18 @@ impl Value {
         }
     }
 
+    /// Calls `f` with the tag of `self` and of each of its descendants.
+    pub(crate) fn map_tags<F>(&mut self, f: F)
+        where F: Fn(&mut Tag) + Copy
+    {
+        f(self.tag_mut());
+        match self {
+            Value::Dict(_, v) => v.values_mut().for_each(|v| v.map_tags(f)),
+            Value::Array(_, v) => v.iter_mut().for_each(|v| v.map_tags(f)),
+            _ => { /* already handled */ }
+        }
+    }
+
     pub(crate) fn map_tag<F>(&mut self, mut f: F)
         where F: FnMut(&mut Tag) + Copy
     {
@@ -324,6 +812,33 @@ impl Value {
     }
 }
 
+/// Collects the results of converting the elements of an array, prefixing
+/// each error with the index of its element and chaining them in order.
+trait CollectElements<T> {
+    fn collect_elements(self) -> Result<Vec<T>, Error>;
+}
+
+impl<T, I: Iterator<Item = Result<T, Error>>> CollectElements<T> for I {
+    fn collect_elements(self) -> Result<Vec<T>, Error> {
+        let mut values = vec![];
+        let mut errors: Option<Error> = None;
+        for (i, result) in self.enumerate() {
+            match result {
+                Ok(value) => values.push(value),
+                Err(e) => errors = Some(match errors {
+                    Some(errors) => e.prefixed_index(i).chain(errors),
+                    None => e.prefixed_index(i),
+                }),
+            }
+        }
+
+        match errors {
+            Some(errors) => Err(errors),
+            None => Ok(values),
+        }
+    }
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -365,6 +880,30 @@ impl<'a, T: Into<Value> + Clone> From<&'a [T]> for Value {
     }
 }
 
+/// Converts `()` into [`Empty::Unit`].
+impl From<()> for Value {
+    fn from(_: ()) -> Value {
+        Value::Empty(Tag::Default, Empty::Unit)
+    }
+}
+
+/// Converts `None` into [`Empty::None`] and `Some(value)` into `value`.
+///
+/// ```
+/// use figment::value::{Value, Empty};
+///
+/// assert_eq!(Value::from(None::<u8>), Empty::None.into());
+/// assert_eq!(Value::from(Some("hi")), "hi".into());
+/// ```
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Value {
+        match value {
+            Some(value) => value.into(),
+            None => Value::Empty(Tag::Default, Empty::None),
+        }
+    }
+}
+
 impl<'a, T: Into<Value>> From<Vec<T>> for Value {
     fn from(vec: Vec<T>) -> Value {
         let vector = vec.into_iter().map(|v| v.into()).collect();
@@ -382,6 +921,17 @@ impl<K: AsRef<str>, V: Into<Value>> From<Map<K, V>> for Value {
     }
 }
 
+#[cfg(feature = "preserve-order")]
+impl<K: AsRef<str>, V: Into<Value>> From<indexmap::IndexMap<K, V>> for Value {
+    fn from(map: indexmap::IndexMap<K, V>) -> Value {
+        let dict: Dict = map.into_iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.into()))
+            .collect();
+
+        Value::Dict(Tag::Default, dict)
+    }
+}
+
 macro_rules! impl_from_for_value {
     ($($T:ty: $V:ident),*) => ($(
         impl From<$T> for Value {
@@ -497,6 +1047,158 @@ impl Num {
         })
     }
 
+    /// Converts `self` into a `u32` if `self` is an integer variant, signed or
+    /// unsigned, whose value fits in a `u32`. Unlike [`Num::to_u32()`], the
+    /// value, not the variant, determines whether the conversion succeeds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::Num;
+    ///
+    /// assert_eq!(Num::I64(8080).to_u32_checked(), Some(8080));
+    /// assert_eq!(Num::U128(u32::MAX as u128).to_u32_checked(), Some(u32::MAX));
+    /// assert_eq!(Num::U64(u32::MAX as u64 + 1).to_u32_checked(), None);
+    /// assert_eq!(Num::I8(-1).to_u32_checked(), None);
+    /// assert_eq!(Num::F64(1.0).to_u32_checked(), None);
+    /// ```
+    pub fn to_u32_checked(self) -> Option<u32> {
+        use core::convert::TryFrom;
+
+        match self.to_actual() {
+            Actual::Unsigned(v) => u32::try_from(v).ok(),
+            Actual::Signed(v) => u32::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Converts `self` into a `u64` if `self` is an integer variant, signed or
+    /// unsigned, whose value fits in a `u64`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::Num;
+    ///
+    /// assert_eq!(Num::U64(u64::MAX).to_u64_checked(), Some(u64::MAX));
+    /// assert_eq!(Num::I32(7).to_u64_checked(), Some(7));
+    /// assert_eq!(Num::I32(-7).to_u64_checked(), None);
+    /// assert_eq!(Num::U128(u128::MAX).to_u64_checked(), None);
+    /// ```
+    pub fn to_u64_checked(self) -> Option<u64> {
+        use core::convert::TryFrom;
+
+        match self.to_actual() {
+            Actual::Unsigned(v) => u64::try_from(v).ok(),
+            Actual::Signed(v) => u64::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Converts `self` into an `i64` if `self` is an integer variant, signed
+    /// or unsigned, whose value fits in an `i64`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::Num;
+    ///
+    /// assert_eq!(Num::I8(-7).to_i64_checked(), Some(-7));
+    /// assert_eq!(Num::U64(7).to_i64_checked(), Some(7));
+    /// assert_eq!(Num::U64(u64::MAX).to_i64_checked(), None);
+    /// assert_eq!(Num::F32(7.0).to_i64_checked(), None);
+    /// ```
+    pub fn to_i64_checked(self) -> Option<i64> {
+        use core::convert::TryFrom;
+
+        match self.to_actual() {
+            Actual::Unsigned(v) => i64::try_from(v).ok(),
+            Actual::Signed(v) => i64::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Converts `self` into an `f64`, rounding to the nearest representable
+    /// value if necessary. Integers with more than 53 significant bits may
+    /// lose precision.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::Num;
+    ///
+    /// assert_eq!(Num::U8(3).to_f64_lossy(), 3.0);
+    /// assert_eq!(Num::I32(-3).to_f64_lossy(), -3.0);
+    /// assert_eq!(Num::F32(0.5).to_f64_lossy(), 0.5);
+    /// assert_eq!(Num::U64(u64::MAX).to_f64_lossy(), 18446744073709551615.0);
+    /// ```
+    pub fn to_f64_lossy(self) -> f64 {
+        match self.to_actual() {
+            Actual::Unsigned(v) => v as f64,
+            Actual::Signed(v) => v as f64,
+            Actual::Float(v) => v,
+            _ => unreachable!("a number is unsigned, signed, or a float"),
+        }
+    }
+
+    /// Returns `true` if `self` is an integer variant, signed or unsigned, and
+    /// `false` if it is a float variant, even one with an integral value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use figment::value::Num;
+    ///
+    /// assert!(Num::U8(1).is_integer());
+    /// assert!(Num::ISize(-1).is_integer());
+    /// assert!(!Num::F64(1.0).is_integer());
+    /// ```
+    pub fn is_integer(&self) -> bool {
+        !matches!(self, Num::F32(_) | Num::F64(_))
+    }
+
+    /// Compares the values of `self` and `other`, regardless of their
+    /// variants. Returns `None` if either is NaN.
+    ///
+    /// The comparison is exact: no value is converted to a type that cannot
+    /// represent it, so `u64::MAX` compares greater than `u64::MAX - 1` even
+    /// though both convert to the same `f64`. Note that unlike this method,
+    /// `==` on `Num`s compares signed and unsigned integers and floats as
+    /// distinct: `Num::U8(1) != Num::I8(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use figment::value::Num;
+    ///
+    /// assert_eq!(Num::U8(1).compare(&Num::I64(1)), Some(Ordering::Equal));
+    /// assert_eq!(Num::I8(-1).compare(&Num::U128(0)), Some(Ordering::Less));
+    /// assert_eq!(Num::F32(2.5).compare(&Num::U16(2)), Some(Ordering::Greater));
+    /// assert_eq!(Num::F64(-2.5).compare(&Num::I16(-2)), Some(Ordering::Less));
+    /// assert_eq!(Num::F64(f64::NAN).compare(&Num::U8(0)), None);
+    ///
+    /// let max = Num::U64(u64::MAX);
+    /// assert_eq!(max.compare(&Num::U64(u64::MAX - 1)), Some(Ordering::Greater));
+    /// assert_eq!(max.compare(&Num::F64(u64::MAX as f64)), Some(Ordering::Less));
+    /// ```
+    pub fn compare(&self, other: &Num) -> Option<Ordering> {
+        use Actual::*;
+
+        match (self.to_actual(), other.to_actual()) {
+            (Unsigned(a), Unsigned(b)) => Some(a.cmp(&b)),
+            (Signed(a), Signed(b)) => Some(a.cmp(&b)),
+            (Float(a), Float(b)) => a.partial_cmp(&b),
+            (Unsigned(a), Signed(b)) => Some(cmp_unsigned_signed(a, b)),
+            (Signed(a), Unsigned(b)) => Some(cmp_unsigned_signed(b, a).reverse()),
+            (Float(a), Unsigned(b)) => cmp_float_unsigned(a, b),
+            (Unsigned(a), Float(b)) => cmp_float_unsigned(b, a).map(Ordering::reverse),
+            (Float(a), Signed(b)) => cmp_float_signed(a, b),
+            (Signed(a), Float(b)) => cmp_float_signed(b, a).map(Ordering::reverse),
+            _ => unreachable!("a number is unsigned, signed, or a float"),
+        }
+    }
+
     /// Converts `self` into an [`Actual`]. All unsigned variants return
     /// [`Actual::Unsigned`], signed variants [`Actual::Signed`], and float
     /// variants [`Actual::Float`]. Values exceeding the bit-width of the target
@@ -536,6 +1238,49 @@ impl Num {
     }
 }
 
+/// Compares `a` to `b`. Any negative `b` is less than every `a`.
+fn cmp_unsigned_signed(a: u128, b: i128) -> Ordering {
+    match b < 0 {
+        true => Ordering::Greater,
+        false => a.cmp(&(b as u128)),
+    }
+}
+
+/// Compares `a` to `b` without rounding: the integral part of `a`, which is
+/// exactly representable as a `u128` when in range, is compared first, then
+/// its fractional part, if any.
+fn cmp_float_unsigned(a: f64, b: u128) -> Option<Ordering> {
+    if a.is_nan() {
+        None
+    } else if a < 0.0 {
+        Some(Ordering::Less)
+    } else if a >= u128::MAX as f64 {
+        Some(Ordering::Greater)
+    } else {
+        let int = a as u128;
+        let fract = match a > int as f64 {
+            true => Ordering::Greater,
+            false => Ordering::Equal,
+        };
+
+        Some(int.cmp(&b).then(fract))
+    }
+}
+
+/// Like [`cmp_float_unsigned()`], but for a signed `b`.
+fn cmp_float_signed(a: f64, b: i128) -> Option<Ordering> {
+    if a.is_nan() {
+        None
+    } else if a >= -(i128::MIN as f64) {
+        Some(Ordering::Greater)
+    } else if a < i128::MIN as f64 {
+        Some(Ordering::Less)
+    } else {
+        let int = a as i128;
+        Some(int.cmp(&b).then(a.partial_cmp(&(int as f64))?))
+    }
+}
+
 impl PartialEq for Num {
     fn eq(&self, other: &Self) -> bool {
         self.to_actual() == other.to_actual()
@@ -558,16 +1303,75 @@ impl_from_for_num_value! {
     f32: F32, f64: F64
 }
 
-/// A value with no value: `None` or `Unit`.
+/// A value with no value: `None`, `Unit`, or `Unset`.
+///
+/// `Value::from(None::<T>)` and `Value::from(())` are `None` and `Unit`,
+/// respectively, and [`Value::is_none()`], [`Value::is_empty_unit()`], and
+/// [`Value::is_unset()`] check for each variant.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Empty {
     /// Like `Option::None`.
     None,
     /// Like `()`.
-    Unit
+    Unit,
+    /// Removes the key it is at.
+    ///
+    /// Like any other value, `Unset` replaces the value it is merged over, but
+    /// when a figment's values are combined for extraction, every key whose
+    /// value is `Unset` is removed, as if it had never been provided. Because
+    /// profiles are combined at extraction, an `Unset` in a custom profile
+    /// also removes a key from the default profile. A later provider can thus
+    /// unset a key set by an earlier one, resetting an `Option` to `None` or a
+    /// field with a serde default to its default. `Unset` is serialized by
+    /// [`Serialized`](crate::providers::Serialized) as itself and by any other
+    /// serializer as `None`.
+    ///
+    /// Formats without `null`, like TOML, and environment variables cannot
+    /// express `Unset` directly, but a [`ParsePolicy`] can map a sentinel to
+    /// it:
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use figment::{Figment, providers::{Env, Format, Toml}};
+    /// use figment::value::{Empty, Value, ParsePolicy};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Config {
+    ///     proxy: Option<String>,
+    ///     #[serde(default)]
+    ///     retries: u8,
+    /// }
+    ///
+    /// figment::Jail::expect_with(|jail| {
+    ///     jail.create_file("App.toml", r#"
+    ///         proxy = "http://proxy.local"
+    ///         retries = 3
+    ///     "#)?;
+    ///
+    ///     jail.set_env("APP_PROXY", "null");
+    ///     jail.set_env("APP_RETRIES", "null");
+    ///
+    ///     let policy = ParsePolicy::new()
+    ///         .parser(|s| (s == "null").then(|| Value::from(Empty::Unset)));
+    ///
+    ///     let config: Config = Figment::new()
+    ///         .merge(Toml::file("App.toml"))
+    ///         .merge(Env::prefixed("APP_").parse_policy(policy))
+    ///         .extract()?;
+    ///
+    ///     assert_eq!(config, Config { proxy: None, retries: 0 });
+    ///     Ok(())
+    /// });
+    /// ```
+    ///
+    /// [`ParsePolicy`]: crate::value::ParsePolicy
+    Unset,
 }
 
 impl Empty {
+    /// The name `Unset` is serialized as a unit struct with.
+    pub(crate) const UNSET_NAME: &'static str = "___figment_unset";
+
     /// Converts `self` into an [`Actual`].
     ///
     /// # Example
@@ -577,10 +1381,11 @@ impl Empty {
     ///
     /// assert_eq!(Empty::None.to_actual(), Actual::Option);
     /// assert_eq!(Empty::Unit.to_actual(), Actual::Unit);
+    /// assert_eq!(Empty::Unset.to_actual(), Actual::Option);
     /// ```
     pub fn to_actual(&self) -> Actual {
         match self {
-            Empty::None => Actual::Option,
+            Empty::None | Empty::Unset => Actual::Option,
             Empty::Unit => Actual::Unit,
         }
     }
@@ -0,0 +1,230 @@
+//! Non-fatal diagnostics produced while collecting configuration data.
+//!
+//! Providers and the merging of their data can observe conditions that don't
+//! prevent extraction but likely indicate a mistake: an environment variable
+//! that was ignored, a key that is shadowed by another spelling of itself, or
+//! a value that failed to parse as intended. These are collected as
+//! [`Warning`]s and retrieved via [`Figment::warnings()`](crate::Figment::warnings()).
+//!
+//! # Example
+//!
+//! ```rust
+//! use figment::{Figment, providers::Env, warning::Kind};
+//!
+//! figment::Jail::expect_with(|jail| {
+//!     jail.set_env("APP_PORTS", "[80, 443");
+//!
+//!     let figment = Figment::from(Env::prefixed("APP_"));
+//!     let warnings = figment.warnings();
+//!     assert_eq!(warnings.len(), 1);
+//!
+//!     let warning = warnings.iter().next().unwrap();
+//!     assert_eq!(warning.key.as_deref(), Some("ports"));
+//!     assert!(matches!(warning.kind, Kind::ParseFallback(_)));
+//!
+//!     for warning in warnings {
+//!         println!("warning: {}", warning);
+//!     }
+//!
+//!     Ok(())
+//! });
+//! ```
+
+use core::fmt::{self, Display};
+
+use crate::prelude::*;
+use crate::{Profile, Metadata};
+
+/// A non-fatal diagnostic. See the [module docs](crate::warning).
+///
+/// # Display
+///
+/// A `Warning` displays as its kind followed by the name of its provider, if
+/// known, as in:
+///
+/// ```text
+/// `ports`: `[80, 443` failed to parse and was read as a string in `APP_` environment variable(s)
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    /// The warning kind.
+    pub kind: Kind,
+    /// The key path the warning concerns, if any.
+    pub key: Option<String>,
+    /// The profile the warning concerns, if any.
+    pub profile: Option<Profile>,
+    /// The metadata of the provider the warning concerns, if known. Set by
+    /// [`Figment`](crate::Figment) to the metadata of the provider that
+    /// emitted the warning if it is unset.
+    pub metadata: Option<Metadata>,
+}
+
+/// A warning kind.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kind {
+    /// A custom warning message.
+    Message(String),
+    /// The key is deprecated, with an optional replacement key.
+    Deprecated(Option<String>),
+    /// The value of the key is discarded in favor of the value of another key
+    /// in the same provider that is the same key after key unification, as by
+    /// [`Figment::case_insensitive()`](crate::Figment::case_insensitive()) or
+    /// [`Figment::canonicalize_keys()`](crate::Figment::canonicalize_keys()).
+    Shadowed,
+    /// The raw string, which appeared to be a structured value, failed to
+    /// parse and was read as a string instead.
+    ParseFallback(String),
+    /// The environment variable with the given name was ignored because its
+    /// key has an empty component, as in `APP_A__B` when split at `_`.
+    IgnoredEnvVar(String),
+    /// The key is set more than once within the provider, by each of the
+    /// given sources in order, such as the names of environment variables,
+    /// and the value set last is used. See
+    /// [`Env::deny_duplicates()`](crate::providers::Env::deny_duplicates()).
+    DuplicateKey(Vec<String>),
+    /// The provider was skipped because an identical provider was previously
+    /// added. See
+    /// [`Figment::dedup_providers()`](crate::Figment::dedup_providers()).
+    DuplicateProvider,
+    /// The provider emitted data for the warning's profile, which isn't one of
+    /// the [known profiles](crate::Figment::known_profiles()), with the known
+    /// profile it is likely a misspelling of, if any.
+    UnknownProfile(Option<Profile>),
+}
+
+impl Warning {
+    /// Returns a new warning of the given `kind` with no key, profile, or
+    /// metadata.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use figment::warning::{Warning, Kind};
+    ///
+    /// let warning = Warning::new(Kind::Deprecated(Some("server.port".into())))
+    ///     .with_key("port");
+    ///
+    /// assert_eq!(warning.to_string(), "`port` is deprecated: use `server.port` instead");
+    /// ```
+    pub fn new(kind: Kind) -> Self {
+        Warning { kind, key: None, profile: None, metadata: None }
+    }
+
+    /// Sets the key path the warning concerns.
+    pub fn with_key<K: Into<String>>(mut self, key: K) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Sets the profile the warning concerns.
+    pub fn with_profile<P: Into<Profile>>(mut self, profile: P) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+}
+
+impl From<String> for Warning {
+    fn from(message: String) -> Self {
+        Warning::new(Kind::Message(message))
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(key) = &self.key {
+            match &self.kind {
+                Kind::Deprecated(_) | Kind::Shadowed | Kind::DuplicateKey(_) => {
+                    write!(f, "`{}` ", key)?
+                }
+                _ => write!(f, "`{}`: ", key)?,
+            }
+        }
+
+        match &self.kind {
+            Kind::Message(msg) => f.write_str(msg)?,
+            Kind::Deprecated(None) => f.write_str("is deprecated")?,
+            Kind::Deprecated(Some(key)) => write!(f, "is deprecated: use `{}` instead", key)?,
+            Kind::Shadowed => f.write_str("is shadowed by another spelling of the same key")?,
+            Kind::ParseFallback(raw) => {
+                write!(f, "`{}` failed to parse and was read as a string", raw)?
+            }
+            Kind::IgnoredEnvVar(name) => {
+                write!(f, "environment variable `{}` was ignored: key has an empty component", name)?
+            }
+            Kind::DuplicateKey(sources) => {
+                let sources: Vec<_> = sources.iter().map(|s| format!("`{}`", s)).collect();
+                write!(f, "is set more than once, by {}; the last is used", sources.join(" and "))?
+            }
+            Kind::DuplicateProvider => f.write_str("duplicate provider was skipped")?,
+            Kind::UnknownProfile(suggestion) => {
+                match &self.profile {
+                    Some(profile) => write!(f, "profile `{}` is unknown", profile)?,
+                    None => f.write_str("profile is unknown")?,
+                }
+
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean `{}`?)", suggestion)?;
+                }
+            }
+        }
+
+        if let Some(md) = &self.metadata {
+            match &md.source {
+                Some(source) => write!(f, " in {} {}", source, md.name)?,
+                None => write!(f, " in {}", md.name)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A collection of [`Warning`]s, in the order they were emitted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Warnings(pub(crate) Vec<Warning>);
+
+impl Warnings {
+    /// Returns an iterator over the warnings.
+    pub fn iter(&self) -> core::slice::Iter<'_, Warning> {
+        self.0.iter()
+    }
+
+    /// Returns the number of warnings.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no warnings.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Warnings {
+    type Item = &'a Warning;
+    type IntoIter = core::slice::Iter<'a, Warning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Warnings {
+    type Item = Warning;
+    type IntoIter = alloc::vec::IntoIter<Warning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Display for Warnings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, warning) in self.iter().enumerate() {
+            if i > 0 { writeln!(f)?; }
+            warning.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
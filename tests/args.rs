@@ -0,0 +1,52 @@
+#![cfg(feature = "parse-value")]
+
+use figment::{Figment, providers::{Args, Serialized}};
+
+fn figment(args: &[&str]) -> Figment {
+    Figment::from(Serialized::default("port", 8000))
+        .merge(Serialized::default("server.workers", 16))
+        .merge(Args::new(args))
+}
+
+#[test]
+fn key_value_pairs_override() {
+    let figment = figment(&["--port=9000", "--server.host=localhost", "--tags=[a, b]"]);
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 9000);
+    assert_eq!(figment.extract_inner::<String>("server.host").unwrap(), "localhost");
+    assert_eq!(figment.extract_inner::<u16>("server.workers").unwrap(), 16);
+    assert_eq!(figment.extract_inner::<Vec<String>>("tags").unwrap(), ["a", "b"]);
+}
+
+#[test]
+fn values_are_parsed() {
+    let figment = figment(&["--a=true", "--b=-3", "--c=1.5", "--d=\"1\"", "--e=", "--f=x=y"]);
+    assert!(figment.extract_inner::<bool>("a").unwrap());
+    assert_eq!(figment.extract_inner::<i8>("b").unwrap(), -3);
+    assert_eq!(figment.extract_inner::<f64>("c").unwrap(), 1.5);
+    assert_eq!(figment.extract_inner::<String>("d").unwrap(), "1");
+    assert_eq!(figment.extract_inner::<String>("e").unwrap(), "");
+    assert_eq!(figment.extract_inner::<String>("f").unwrap(), "x=y");
+}
+
+#[test]
+fn other_arguments_are_ignored() {
+    let figment = figment(&[
+        "input.txt", "-v", "--verbose", "-p=1", "--=1", "--a..b=1", "--.c=1",
+        "--port=1", "--port=2", "--", "--port=3", "--after=1",
+    ]);
+
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 2);
+    for key in ["verbose", "v", "p", "a", "c", "after"] {
+        assert!(figment.find_value(key).is_err(), "{}", key);
+    }
+}
+
+#[test]
+fn errors_name_arguments() {
+    let figment = figment(&["--server.workers=many"]);
+    let err = figment.extract_inner::<u16>("server.workers").unwrap_err();
+    let metadata = err.metadata.as_ref().unwrap();
+    assert_eq!(metadata.name, "command-line argument(s)");
+    assert_eq!(metadata.interpolate(&err.profile.unwrap(), &err.path), "--server.workers");
+    assert!(metadata.source.is_some());
+}
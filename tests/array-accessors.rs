@@ -0,0 +1,51 @@
+use figment::{Figment, util::map};
+use figment::value::{Num, Value};
+
+#[test]
+fn array_accessors_convert_every_element() {
+    let figment = Figment::from(("hosts", ["a.local", "b.local"]))
+        .merge(("ports", [80, 443]))
+        .merge(("matrix", vec![vec![1, 2], vec![3]]))
+        .merge(("empty", Vec::<String>::new()));
+
+    let hosts = figment.find_value("hosts").unwrap();
+    assert_eq!(hosts.as_str_array().unwrap(), ["a.local", "b.local"]);
+
+    let ports = figment.find_value("ports").unwrap();
+    assert_eq!(ports.as_num_array().unwrap(), [Num::I32(80), Num::I32(443)]);
+    assert_eq!(ports.into_vec::<u16>().unwrap(), [80, 443]);
+
+    let matrix = figment.find_value("matrix").unwrap();
+    assert_eq!(matrix.into_vec::<Vec<u8>>().unwrap(), [vec![1, 2], vec![3]]);
+
+    let empty = figment.find_value("empty").unwrap();
+    assert!(empty.as_str_array().unwrap().is_empty());
+    assert!(empty.as_num_array().unwrap().is_empty());
+    assert!(empty.into_vec::<bool>().unwrap().is_empty());
+}
+
+#[test]
+fn array_accessor_errors_are_indexed() {
+    let value = Value::from(vec![Value::from(1), "two".into(), 3.into(), 'x'.into()]);
+
+    let error = value.as_num_array().unwrap_err();
+    let paths: Vec<_> = error.clone().into_iter().map(|e| e.path).collect();
+    assert_eq!(paths, [vec!["1"], vec!["3"]]);
+
+    let error = value.as_str_array().unwrap_err();
+    assert_eq!(error.count(), 3);
+
+    let matrix = Value::from(vec![vec![Value::from(1)], vec![2.into(), "three".into()]]);
+    let error = matrix.into_vec::<Vec<u8>>().unwrap_err();
+    assert_eq!(error.path, ["1", "1"]);
+    assert_eq!(error.display_path().unwrap(), "[1][1]");
+
+    let dict = Value::from(map!["a" => 1]);
+    for error in [dict.as_str_array().unwrap_err(), dict.as_num_array().unwrap_err()] {
+        assert!(error.path.is_empty());
+        assert!(error.to_string().starts_with("invalid type: found map, expected an array"));
+    }
+
+    let error = dict.into_vec::<u8>().unwrap_err();
+    assert_eq!(error.to_string(), "invalid type: found map, expected an array");
+}
@@ -0,0 +1,73 @@
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use figment::{Figment, SecretRead, util::map, value::KeyCase};
+
+fn audited(figment: Figment) -> (Figment, Arc<Mutex<Vec<SecretRead>>>) {
+    let log: Arc<Mutex<Vec<SecretRead>>> = Arc::default();
+    let sink = log.clone();
+    (figment.audit_secrets(move |read| sink.lock().unwrap().push(read.clone())), log)
+}
+
+fn keys(log: &Mutex<Vec<SecretRead>>) -> Vec<(String, String)> {
+    log.lock().unwrap().drain(..).map(|r| (r.key, r.requested)).collect()
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    password: Option<String>,
+}
+
+#[test]
+fn only_secret_values_that_are_read_are_reported() {
+    let servers = vec![
+        map!["host" => "a", "password" => "one"],
+        map!["host" => "b", "password" => "two"],
+    ];
+
+    let (figment, log) = audited(Figment::from(("servers", servers))
+        .merge(("apiToken", "abc"))
+        .canonicalize_keys(KeyCase::Snake)
+        .redact(["servers.password", "apiToken"]));
+
+    let _: Vec<Server> = figment.extract_inner("servers").unwrap();
+    assert_eq!(keys(&log), [
+        ("servers.0.password".to_string(), "servers".to_string()),
+        ("servers.1.password".to_string(), "servers".to_string()),
+    ]);
+
+    let _: String = figment.extract_inner("servers.0.host").unwrap();
+    let _: String = figment.extract_inner("apiToken").unwrap();
+    assert_eq!(keys(&log), [("api_token".to_string(), "apiToken".to_string())]);
+
+    // Failed extractions still report what was read.
+    assert!(figment.extract_inner::<u32>("api_token").is_err());
+    assert_eq!(keys(&log).len(), 1);
+
+    // Without secrets, nothing is tracked or reported.
+    let (figment, log) = audited(Figment::from(("token", "abc")));
+    let _: String = figment.extract_inner("token").unwrap();
+    assert!(keys(&log).is_empty());
+}
+
+#[test]
+fn focused_figments_report_relative_keys() {
+    let (figment, log) = audited(Figment::from(("database", map!["password" => "abc", "user" => "admin"]))
+        .redact(["database"]));
+
+    let focused = figment.focus("database");
+    let _: String = focused.extract_inner("user").unwrap();
+    let _: String = focused.extract_inner("password").unwrap();
+    assert_eq!(keys(&log), [
+        ("user".to_string(), "user".to_string()),
+        ("password".to_string(), "password".to_string()),
+    ]);
+
+    let read_at = figment.extract_inner::<String>("database.user")
+        .map(|_| log.lock().unwrap()[0].time)
+        .unwrap();
+
+    assert!(read_at <= std::time::SystemTime::now());
+}
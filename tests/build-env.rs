@@ -0,0 +1,38 @@
+use figment::{Figment, providers::{BuildEnv, Serialized, build_env}};
+
+#[test]
+fn build_env_captures_package_and_profile() {
+    let figment = Figment::from(build_env!("build"));
+    assert_eq!(figment.extract_inner::<String>("build.name").unwrap(), env!("CARGO_PKG_NAME"));
+    assert_eq!(figment.extract_inner::<String>("build.version").unwrap(), env!("CARGO_PKG_VERSION"));
+
+    let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+    assert_eq!(figment.extract_inner::<String>("build.profile").unwrap(), profile);
+
+    let metadata = figment.find_metadata("build.version").unwrap();
+    assert_eq!(metadata.name, "compile-time environment");
+    assert!(metadata.source.as_ref().unwrap().code_location().unwrap().file().ends_with("build-env.rs"));
+}
+
+#[test]
+fn build_env_reads_extra_variables() {
+    let figment = Figment::from(Serialized::default("info.git_sha", "unknown"))
+        .merge(build_env!("info", "CARGO_CRATE_NAME", "CARGO_PKG_AUTHORS", "GIT_SHA"));
+
+    assert_eq!(figment.extract_inner::<String>("info.cargo_crate_name").unwrap(), env!("CARGO_CRATE_NAME"));
+    assert_eq!(figment.extract_inner::<String>("info.authors").unwrap(), env!("CARGO_PKG_AUTHORS"));
+    assert_eq!(figment.extract_inner::<String>("info.git_sha").unwrap(), "unknown");
+}
+
+#[test]
+fn values_are_strings_and_errors_name_variables() {
+    let figment = Figment::from(BuildEnv::new("build.info")
+        .var("BUILD_NUMBER", Some("0123"))
+        .var("CARGO_PKG_VERSION_MAJOR", Some("x")));
+
+    assert_eq!(figment.extract_inner::<String>("build.info.build_number").unwrap(), "0123");
+
+    let err = figment.extract_inner::<u8>("build.info.version_major").unwrap_err();
+    let metadata = err.metadata.as_ref().unwrap();
+    assert_eq!(metadata.interpolate(&err.profile.unwrap(), &err.path), "CARGO_PKG_VERSION_MAJOR");
+}
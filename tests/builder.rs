@@ -0,0 +1,68 @@
+#![cfg(all(feature = "env", feature = "toml"))]
+
+use serde::{Serialize, Deserialize};
+use figment::{FigmentBuilder, Jail, util::map};
+
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    port: u16,
+    server: Server,
+}
+
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+struct Server {
+    workers: usize,
+    tls: bool,
+}
+
+#[test]
+fn standard_layers_defaults_file_and_env() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "name = \"app\"\nport = 80\n[server]\nworkers = 2")?;
+        jail.set_env("APP_PORT", 8080);
+        jail.set_env("APP_SERVER__TLS", true);
+        jail.set_env("OTHER_PORT", 1);
+
+        let config: Config = figment::extract("APP", "App.toml")?;
+        assert_eq!(config, Config {
+            name: "app".into(),
+            port: 8080,
+            server: Server { workers: 2, tls: true },
+        });
+
+        let figment = FigmentBuilder::standard("APP", "App.toml").build();
+        assert_eq!(figment.metadata().count(), 2);
+        assert!(figment.extract::<Config>().is_ok());
+
+        let figment = FigmentBuilder::standard("APP", "Missing.toml")
+            .defaults(Config::default())
+            .build();
+
+        assert_eq!(figment.extract_inner::<u16>("port")?, 8080);
+        assert_eq!(figment.extract_inner::<String>("name")?, "");
+        Ok(())
+    });
+}
+
+#[test]
+fn builder_layers_can_be_replaced() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "port = 80")?;
+        jail.create_file("Other.toml", "port = 81")?;
+        jail.set_env("APP_PORT", 8080);
+        jail.set_env("OTHER_PORT", 8081);
+
+        let builder = FigmentBuilder::standard("APP", "App.toml").defaults(map!["port" => 1]);
+        assert!(builder.clone().extract::<Config>().is_err());
+        assert_eq!(builder.clone().build().extract_inner::<u16>("port")?, 8080);
+        assert_eq!(builder.clone().env("OTHER").build().extract_inner::<u16>("port")?, 8081);
+
+        let figment = FigmentBuilder::new().defaults(map!["port" => 1]).file("Other.toml").build();
+        assert_eq!(figment.extract_inner::<u16>("port")?, 81);
+
+        let figment = FigmentBuilder::new().defaults(map!["port" => 1]).build();
+        assert_eq!(figment.extract_inner::<u16>("port")?, 1);
+        Ok(())
+    });
+}
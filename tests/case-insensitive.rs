@@ -0,0 +1,64 @@
+use serde::Deserialize;
+use figment::{Figment, Jail, providers::{Env, Format, Toml}};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    host: String,
+    port: u16,
+    log_level: Option<String>,
+}
+
+#[test]
+fn keys_unify_across_providers_and_profiles() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", r#"
+            [default]
+            Host = "localhost"
+            Port = 80
+
+            [debug]
+            HOST = "debug.local"
+            Log_Level = "trace"
+            log_level = "debug"
+        "#)?;
+
+        jail.set_env("APP_PORT", 8000);
+
+        let figment = Figment::new()
+            .case_insensitive(true)
+            .merge(Toml::file("App.toml").nested())
+            .merge(Env::prefixed("APP_"));
+
+        let config: Config = figment.extract()?;
+        assert_eq!(config, Config { host: "localhost".into(), port: 8000, log_level: None });
+
+        let config: Config = figment.clone().select("debug").extract()?;
+        assert_eq!(config.host, "debug.local");
+        assert_eq!(config.port, 8000);
+        assert!(config.log_level.is_some());
+
+        let dict = figment.select("debug").extract::<figment::value::Dict>()?;
+        assert_eq!(dict.len(), 3);
+        Ok(())
+    });
+}
+
+#[test]
+fn case_sensitive_by_default() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", r#"
+            Host = "localhost"
+            port = 80
+        "#)?;
+
+        let figment = Figment::from(Toml::file("App.toml"));
+        assert!(figment.extract::<Config>().is_err());
+        assert!(figment.find_value("host").is_err());
+
+        let figment = figment.case_insensitive(true);
+        assert_eq!(figment.extract::<Config>()?.host, "localhost");
+        assert_eq!(figment.find_value("HOST")?.as_str(), Some("localhost"));
+        assert_eq!(figment.focus("").extract_inner::<u16>("PORT")?, 80);
+        Ok(())
+    });
+}
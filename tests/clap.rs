@@ -0,0 +1,55 @@
+#![cfg(feature = "clap")]
+
+use clap::{Arg, ArgAction, Command};
+use figment::{Figment, providers::{Clap, Serialized}};
+
+fn command() -> Command {
+    Command::new("app")
+        .arg(Arg::new("port").long("port").default_value("80"))
+        .arg(Arg::new("verbose").short('v').action(ArgAction::Count))
+        .arg(Arg::new("include").long("include").action(ArgAction::Append))
+        .subcommand(Command::new("serve")
+            .arg(Arg::new("workers").long("workers").default_value("4"))
+            .arg(Arg::new("tls").long("tls").action(ArgAction::SetTrue)))
+}
+
+fn figment(args: &[&str]) -> Figment {
+    let command = command();
+    let matches = command.clone().get_matches_from(args);
+    Figment::from(Serialized::default("port", 8000))
+        .merge(Serialized::default("serve.workers", 16))
+        .merge(Clap::new(&command, &matches))
+}
+
+#[test]
+fn defaults_fall_through() {
+    let figment = figment(&["app", "serve"]);
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+    assert_eq!(figment.extract_inner::<u16>("serve.workers").unwrap(), 16);
+    assert!(figment.find_value("verbose").is_err());
+    assert!(figment.find_value("serve.tls").is_err());
+}
+
+#[test]
+fn explicit_values_override() {
+    let figment = figment(&[
+        "app", "--port", "9000", "-vvv", "--include", "a",
+        "serve", "--workers", "2", "--tls"
+    ]);
+
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 9000);
+    assert_eq!(figment.extract_inner::<u8>("verbose").unwrap(), 3);
+    assert_eq!(figment.extract_inner::<Vec<String>>("include").unwrap(), ["a"]);
+    assert_eq!(figment.extract_inner::<u16>("serve.workers").unwrap(), 2);
+    assert!(figment.extract_inner::<bool>("serve.tls").unwrap());
+}
+
+#[test]
+fn errors_name_flags() {
+    let figment = figment(&["app", "--port", "big", "serve", "--workers", "many"]);
+    let err = figment.extract_inner::<u16>("port").unwrap_err();
+    assert_eq!(err.metadata.as_ref().unwrap().interpolate(&err.profile.unwrap(), &err.path), "--port");
+
+    let err = figment.extract_inner::<u16>("serve.workers").unwrap_err();
+    assert_eq!(err.metadata.as_ref().unwrap().interpolate(&err.profile.unwrap(), &err.path), "--workers");
+}
@@ -0,0 +1,16 @@
+#![cfg(all(unix, feature = "env"))]
+
+use figment::Figment;
+
+#[test]
+fn spawned_command_sees_config() {
+    let figment = Figment::from(("app.name", "with \"quotes\"")).merge(("app.port", 80));
+    let output = figment.focus("app")
+        .command("sh", "APP_")
+        .unwrap()
+        .args(["-c", r#"printf '%s|%s' "$APP_NAME" "$APP_PORT""#])
+        .output()
+        .unwrap();
+
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), r#"with "quotes"|80"#);
+}
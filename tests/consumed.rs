@@ -0,0 +1,67 @@
+use serde::Deserialize;
+use figment::{Figment, Jail, providers::{Env, Format, Toml}};
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    name: String,
+    proxy: Option<String>,
+    servers: Vec<Server>,
+    log: Log,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Log {
+    level: String,
+}
+
+#[test]
+fn consumed_keys_and_changes() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", r#"
+            name = "app"
+            servers = [{ host = "a" }, { host = "b", port = 1 }]
+            empty = {}
+
+            [log]
+            level = "info"
+            format = "json"
+        "#)?;
+
+        jail.set_env("APP_LOG.LEVEL", "debug");
+
+        let figment = Figment::new()
+            .merge(Toml::file("App.toml"))
+            .merge(Env::prefixed("APP_"));
+
+        let (config, consumed) = figment.extract_consumed::<Config>()?;
+        assert_eq!(config.log.level, "debug");
+        assert_eq!(consumed.keys().collect::<Vec<_>>(), ["log.level", "name", "servers"]);
+        assert_eq!(consumed.unused().collect::<Vec<_>>(), ["empty", "log.format"]);
+        assert!(consumed.metadata("log.level").unwrap().name.contains("APP_"));
+        assert!(consumed.metadata("name").unwrap().name.contains("TOML"));
+
+        assert!(!consumed.changed(&figment));
+        assert!(!consumed.changed(&figment.clone().merge(("log.format", "text"))));
+        assert!(consumed.changed(&figment.clone().merge(("servers", ["c"]))));
+        assert!(consumed.changed(&figment.clone().merge(("log", 1))));
+
+        // An added key that was absent isn't a change.
+        assert!(!consumed.changed(&figment.clone().merge(("proxy", "p"))));
+
+        jail.set_env("APP_LOG.LEVEL", "warn");
+        let figment = Figment::new()
+            .merge(Toml::file("App.toml"))
+            .merge(Env::prefixed("APP_"));
+
+        assert!(consumed.changed(&figment));
+        Ok(())
+    });
+}
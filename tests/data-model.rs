@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use figment::{Figment, providers::Serialized, value::{Coercion, Value}};
+
+/// Asserts that `value` round-trips through `Value` and through a figment.
+fn roundtrip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T) {
+    let serialized = Value::serialize(&value).unwrap();
+    assert_eq!(serialized.deserialize::<T>().unwrap(), value, "{:?}", serialized);
+
+    let figment = Figment::from(Serialized::default("value", &value));
+    assert_eq!(figment.extract_inner::<T>("value").unwrap(), value);
+
+    let figment = Figment::from(Serialized::defaults(Wrapper { value: &value }));
+    assert_eq!(figment.extract::<Wrapper<T>>().unwrap().value, value);
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Wrapper<T> {
+    value: T,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Newtype(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Tuple(u8, char, String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Enum {
+    Unit,
+    Newtype(u128),
+    Empty(Unit),
+    Nested(Box<Enum>),
+    Tuple(i128, char),
+    Struct { a: u8, b: Option<Newtype> },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Bytes(#[serde(with = "bytes")] Vec<u8>);
+
+mod bytes {
+    use serde::{Serializer, Deserializer, de};
+
+    pub fn serialize<S: Serializer>(v: &[u8], ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_bytes(v)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<u8>, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = vec![];
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+
+                Ok(bytes)
+            }
+        }
+
+        de.deserialize_byte_buf(Visitor)
+    }
+}
+
+#[test]
+fn integers_roundtrip() {
+    roundtrip(u128::MAX);
+    roundtrip(u128::MIN);
+    roundtrip(i128::MIN);
+    roundtrip(i128::MAX);
+    roundtrip(u64::MAX as u128 + 1);
+    roundtrip(i64::MIN as i128 - 1);
+    roundtrip(usize::MAX);
+    roundtrip(isize::MIN);
+    roundtrip(vec![u8::MAX, 0, 7]);
+    roundtrip((i8::MIN, i16::MIN, i32::MIN, i64::MIN, u16::MAX, u32::MAX, u64::MAX));
+    roundtrip(BTreeMap::from([("a".to_string(), u128::MAX), ("b".to_string(), 1)]));
+}
+
+#[test]
+fn random_values_roundtrip() {
+    // A xorshift generator: deterministic, but covers every bit width.
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..256 {
+        let (a, b) = (next(), next());
+        let wide = (a as u128) << 64 | b as u128;
+        roundtrip(wide);
+        roundtrip(wide as i128);
+        roundtrip((a as u8, a as i8, a as u16, a as i16, a as u32, a as i32, a, a as i64));
+        roundtrip(f64::from_bits(a & !(0x7ff << 52)));
+        roundtrip(char::from_u32(b as u32 % 0x11_0000).unwrap_or('?'));
+        roundtrip(Enum::Tuple(wide as i128, 'x'));
+        roundtrip(Bytes(a.to_le_bytes().to_vec()));
+    }
+}
+
+#[test]
+fn floats_and_chars_roundtrip() {
+    roundtrip(1.5f32);
+    roundtrip(-0.25f64);
+    roundtrip(f64::MAX);
+    roundtrip('a');
+    roundtrip('🦀');
+    roundtrip(vec!['x', '\n']);
+    roundtrip(String::from("hello"));
+    roundtrip(true);
+    roundtrip(BTreeMap::from([('a', 1), ('b', 2)]));
+}
+
+#[test]
+fn units_and_options_roundtrip() {
+    roundtrip(());
+    roundtrip(Unit);
+    roundtrip(vec![Unit, Unit]);
+    roundtrip(Some(5u8));
+    roundtrip(Some(Some(5u8)));
+    roundtrip(Newtype(7));
+    roundtrip(Tuple(1, 'c', "s".into()));
+    roundtrip((1u8, 'c', Unit, ()));
+}
+
+#[test]
+fn enums_roundtrip() {
+    roundtrip(Enum::Unit);
+    roundtrip(Enum::Newtype(u128::MAX));
+    roundtrip(Enum::Empty(Unit));
+    roundtrip(Enum::Nested(Box::new(Enum::Tuple(i128::MIN, 'z'))));
+    roundtrip(Enum::Tuple(-1, '🦀'));
+    roundtrip(Enum::Struct { a: 1, b: None });
+    roundtrip(Enum::Struct { a: 1, b: Some(Newtype(2)) });
+    roundtrip(vec![Enum::Unit, Enum::Newtype(1), Enum::Struct { a: 3, b: None }]);
+    roundtrip(Some(Enum::Newtype(0)));
+}
+
+#[test]
+fn bytes_roundtrip() {
+    roundtrip(Bytes(vec![]));
+    roundtrip(Bytes(vec![0, 1, 255]));
+    roundtrip(vec![Bytes(b"abc".to_vec())]);
+}
+
+#[test]
+fn values_deserialize_into_wider_types() {
+    let figment = Figment::from(("a", 1u8)).merge(("b", -1i8)).merge(("c", 'c'));
+    assert_eq!(figment.extract_inner::<u128>("a").unwrap(), 1);
+    assert_eq!(figment.extract_inner::<i128>("a").unwrap(), 1);
+    assert_eq!(figment.extract_inner::<i128>("b").unwrap(), -1);
+    assert!(figment.extract_inner::<u128>("b").is_err());
+    assert_eq!(figment.extract_inner::<String>("c").unwrap(), "c");
+    assert_eq!(figment.extract_inner::<char>("c").unwrap(), 'c');
+
+    let figment = Figment::from(("s", "x")).merge(("big", u128::MAX)).merge(("f", 2.0));
+    assert_eq!(figment.extract_inner::<char>("s").unwrap(), 'x');
+    assert!(figment.extract_inner::<u64>("big").is_err());
+    assert!(figment.extract_inner::<i128>("big").is_err());
+    assert!(figment.extract_inner::<u128>("f").is_err());
+
+    let figment = figment.merge(("small", i128::MIN)).coerce(Coercion::lenient());
+    assert_eq!(figment.extract_inner::<i128>("big").unwrap(), i128::MAX);
+    assert_eq!(figment.extract_inner::<u128>("small").unwrap(), 0);
+    assert_eq!(figment.extract_inner::<u128>("f").unwrap(), 2);
+    assert_eq!(figment.extract_inner::<i128>("f").unwrap(), 2);
+}
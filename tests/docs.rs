@@ -0,0 +1,102 @@
+use serde::Serialize;
+use figment::{Figment, Jail, providers::{Env, Format, Toml, Serialized}};
+use figment::value::{KeyCase, Value};
+
+#[derive(Default, Serialize)]
+struct Server {
+    port: u16,
+    proxy: Option<String>,
+    workers: usize,
+}
+
+#[test]
+fn docs_report_values_sources_and_descriptions() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "[server]\nhosts = [\"a\", \"b\"]\nport = 80")?;
+        jail.set_env("APP_SERVER_PORT", 8080);
+
+        let figment = Figment::from(Toml::file("App.toml"))
+            .merge(Env::prefixed("APP_").split("_"))
+            .describe("server.port", "The port to listen on.")
+            .describe("server.tls", "TLS settings.")
+            .require(["server.tls"]);
+
+        let docs = figment.docs()?;
+        let keys: Vec<_> = docs.keys.iter().map(|doc| doc.key.as_str()).collect();
+        assert_eq!(keys, ["server.hosts", "server.port", "server.tls"]);
+
+        let hosts = docs.get("server.hosts").unwrap();
+        assert_eq!(hosts.kind, Some("array"));
+        assert_eq!(hosts.metadata.unwrap().name, "TOML file");
+        assert_eq!(hosts.source.as_deref(), Some("`default.server.hosts` in TOML file `App.toml`"));
+        assert_eq!(hosts.description, None);
+
+        let port = docs.get("server.port").unwrap();
+        assert_eq!(port.value, Some(Value::from(8080u16)));
+        assert_eq!(port.source.as_deref(), Some("`APP_SERVER_PORT` in `APP_` environment variable(s)"));
+        assert_eq!(port.description, Some("The port to listen on."));
+
+        let tls = docs.get("server.tls").unwrap();
+        assert_eq!((tls.kind, &tls.value, &tls.source), (None, &None, &None));
+        assert_eq!(tls.description, Some("TLS settings."));
+        Ok(())
+    });
+}
+
+#[test]
+fn docs_defaults() {
+    let figment = Figment::from(Serialized::default("server.port", 8080u16));
+    let defaults = figment::util::map!["server" => Server { workers: 4, ..Server::default() }];
+    let docs = figment.docs().unwrap().defaults(defaults).unwrap();
+    let keys: Vec<_> = docs.keys.iter().map(|doc| doc.key.as_str()).collect();
+    assert_eq!(keys, ["server.port", "server.proxy", "server.workers"]);
+    assert_eq!(docs.get("server.port").unwrap().default, Some(Value::from(0u16)));
+    assert_eq!(docs.get("server.workers").unwrap().kind, Some("integer"));
+    assert_eq!(docs.get("server.workers").unwrap().default, Some(Value::from(4usize)));
+    assert_eq!(docs.get("server.workers").unwrap().value, None);
+    assert_eq!(docs.get("server.proxy").unwrap().kind, Some("empty"));
+
+    assert!(figment.docs().unwrap().defaults(vec![1, 2]).is_err());
+}
+
+#[test]
+fn docs_follow_focus_and_key_case() {
+    let figment = Figment::from(("database", figment::util::map!["maxConnections" => 8]))
+        .canonicalize_keys(KeyCase::Snake)
+        .describe("database.maxConnections", "Pool size.")
+        .describe("cache.ttl", "Cache lifetime.");
+
+    let docs = figment.docs().unwrap();
+    assert_eq!(docs.get("database.max_connections").unwrap().description, Some("Pool size."));
+
+    let focused = figment.focus("database");
+    let docs = focused.docs().unwrap();
+    let keys: Vec<_> = docs.keys.iter().map(|doc| doc.key.as_str()).collect();
+    assert_eq!(keys, ["max_connections"]);
+    assert_eq!(docs.keys[0].description, Some("Pool size."));
+}
+
+#[test]
+fn docs_render_escaped_tables() {
+    let figment = Figment::from(("a.b", "x|y"))
+        .merge(("html", "<b>&</b>"))
+        .describe("a.b", "line one\nline | two");
+
+    let markdown = figment.docs().unwrap().to_markdown();
+    assert!(markdown.contains("| `a.b` | string |  | `\"x\\|y\"` |"), "{}", markdown);
+    assert!(markdown.contains("| line one line \\| two |\n"), "{}", markdown);
+
+    let html = figment.docs().unwrap().to_html();
+    assert!(html.contains("<td><code>&quot;&lt;b&gt;&amp;&lt;/b&gt;&quot;</code></td>"), "{}", html);
+    assert!(html.ends_with("</tbody>\n</table>\n"));
+}
+
+#[test]
+fn docs_fail_only_if_merging_failed() {
+    let figment = Figment::from(("port", 80)).require(["missing"]);
+    assert!(figment.extract_inner::<u16>("port").is_err());
+    assert!(figment.docs().is_ok());
+
+    let figment = Figment::from(Toml::string("invalid = "));
+    assert!(figment.docs().is_err());
+}
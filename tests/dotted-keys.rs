@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use figment::{Figment, providers::{Format, Toml, Env, Serialized}};
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    #[allow(dead_code)]
+    hosts: BTreeMap<String, Host>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Host {
+    #[allow(dead_code)]
+    port: u16,
+}
+
+#[test]
+fn dotted_keys() {
+    figment::Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", r#"
+            [hosts."example.com"]
+            port = 443
+
+            [hosts."example.org"]
+            port = "not a port"
+        "#)?;
+
+        jail.set_env("APP_HOSTS__LOCALHOST_DOT_LAN__PORT", 8080);
+
+        let figment = Figment::new()
+            .merge(Toml::file("Config.toml"))
+            .merge(Serialized::default(r#"hosts["a.b"].port"#, 1))
+            .merge(Env::prefixed("APP_").split("__").map(|k| k.as_str().replace("_DOT_", "\\.").into()));
+
+        assert_eq!(figment.extract_inner::<u16>(r"hosts.example\.com.port")?, 443);
+        assert_eq!(figment.extract_inner::<u16>(r#"hosts["example.com"].port"#)?, 443);
+        assert_eq!(figment.extract_inner::<u16>(r#"hosts["a.b"].port"#)?, 1);
+        assert_eq!(figment.extract_inner::<u16>(r"hosts.localhost\.lan.port")?, 8080);
+        assert!(figment.find_value("hosts.example.com.port").is_err());
+
+        let focused = figment.focus(r"hosts.example\.com");
+        assert_eq!(focused.extract_inner::<u16>("port")?, 443);
+
+        let error = figment.extract::<Config>().unwrap_err();
+        assert_eq!(error.path, vec!["hosts", "example.org", "port"]);
+        assert!(error.to_string().contains(r#""default.hosts.example\\.org.port""#));
+        Ok(())
+    });
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "env")]
+
+use figment::{Figment, Jail, providers::Env, warning::Kind};
+
+fn duplicates(figment: &Figment) -> Vec<(String, Vec<String>)> {
+    let mut duplicates: Vec<_> = figment.warnings().iter()
+        .filter_map(|w| match &w.kind {
+            Kind::DuplicateKey(names) => {
+                let mut names = names.clone();
+                names.sort();
+                Some((w.key.clone().unwrap(), names))
+            }
+            _ => None,
+        })
+        .collect();
+
+    duplicates.sort();
+    duplicates
+}
+
+#[test]
+fn env_vars_setting_the_same_key_are_flagged() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", 8000);
+        jail.set_env("app_port", 9000);
+        jail.set_env("APP_DB", "x");
+        jail.set_env("APP_DB_URL", "postgres://");
+        jail.set_env("APP_LOG_LEVEL", "debug");
+        jail.set_env("APP_LOG_FILE", "app.log");
+
+        let figment = Figment::from(Env::prefixed("APP_").split("_"));
+        assert_eq!(duplicates(&figment), [
+            ("db".to_string(), vec!["APP_DB".to_string(), "APP_DB_URL".to_string()]),
+            ("port".to_string(), vec!["APP_PORT".to_string(), "app_port".to_string()]),
+        ]);
+
+        let warning = figment.warnings().iter()
+            .find(|w| w.key.as_deref() == Some("db"))
+            .unwrap();
+
+        let message = warning.to_string();
+        assert!(message.starts_with("`db` is set more than once, by `APP_DB"), "{}", message);
+        assert!(message.contains("; the last is used in `APP_` environment variable(s)"), "{}", message);
+
+        // Without splitting, `db` and `db_url` are distinct keys.
+        let figment = Figment::from(Env::prefixed("APP_"));
+        assert_eq!(duplicates(&figment).len(), 1);
+        Ok(())
+    });
+}
+
+#[test]
+fn denied_duplicates_are_errors() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", 8000);
+        jail.set_env("app_port", 9000);
+        jail.set_env("APP_NAME", "app");
+
+        let env = Env::prefixed("APP_").deny_duplicates();
+        let figment = Figment::from(env.clone());
+        assert!(duplicates(&figment).is_empty());
+
+        let error = figment.extract_inner::<String>("name").unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("set the same key `port`"), "{}", message);
+        assert!(message.contains("`APP_PORT`") && message.contains("`app_port`"), "{}", message);
+
+        jail.clear_env();
+        jail.set_env("APP_NAME", "app");
+        assert_eq!(Figment::from(env).extract_inner::<String>("name")?, "app");
+        Ok(())
+    });
+}
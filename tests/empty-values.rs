@@ -0,0 +1,129 @@
+#![cfg(all(feature = "env", feature = "toml", feature = "json", feature = "yaml"))]
+
+use serde::Deserialize;
+use figment::{Figment, Jail, Provider, Metadata, Profile, Error};
+use figment::providers::{Env, Format, Toml, Json, Yaml, Serialized};
+use figment::value::{Map, Dict, Value, Empty};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    missing: Option<String>,
+    empty: Option<String>,
+    blank: Option<String>,
+    string: Option<String>,
+    #[serde(default)]
+    null: Option<u16>,
+}
+
+fn providers(jail: &mut Jail) -> figment::error::Result<Vec<Figment>> {
+    jail.create_file("App.toml", "empty = ''\nblank = ' '\nstring = 'a'")?;
+    jail.create_file("App.yaml", "empty: ''\nblank: ' '\nstring: a\nnull: ~")?;
+    jail.create_file("App.json", r#"{"empty": "", "blank": " ", "string": "a", "null": null}"#)?;
+    jail.set_env("APP_EMPTY", "");
+    jail.set_env("APP_BLANK", "\" \"");
+    jail.set_env("APP_STRING", "a");
+
+    let serialized = Serialized::defaults(figment::util::map![
+        "blank" => Some(" "),
+        "empty" => Some(""),
+        "null" => None,
+        "string" => Some("a"),
+    ]);
+
+    Ok(vec![
+        Figment::from(Toml::file("App.toml")),
+        Figment::from(Yaml::file("App.yaml")),
+        Figment::from(Json::file("App.json")),
+        Figment::from(Env::prefixed("APP_")),
+        Figment::from(serialized),
+    ])
+}
+
+#[test]
+fn empty_strings_are_values_by_default() {
+    Jail::expect_with(|jail| {
+        for figment in providers(jail)? {
+            let config: Config = figment.extract()?;
+            assert_eq!(config, Config {
+                missing: None,
+                empty: Some("".into()),
+                blank: Some(" ".into()),
+                string: Some("a".into()),
+                null: None,
+            });
+
+            assert!(figment.extract_inner::<Option<u16>>("empty").is_err());
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn empty_strings_are_none_if_enabled() {
+    Jail::expect_with(|jail| {
+        for figment in providers(jail)? {
+            let figment = figment.empty_as_none(true);
+            let config: Config = figment.extract()?;
+            assert_eq!(config, Config {
+                missing: None,
+                empty: None,
+                blank: Some(" ".into()),
+                string: Some("a".into()),
+                null: None,
+            });
+
+            assert_eq!(figment.extract_inner::<Option<u16>>("empty")?, None);
+            assert_eq!(figment.extract_inner::<String>("empty")?, "");
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn empty_as_none_applies_at_any_depth() {
+    let figment = Figment::from(Json::string(r#"{"a": {"b": ["", "x"]}}"#)).empty_as_none(true);
+    let values: Vec<Option<String>> = figment.extract_inner("a.b").unwrap();
+    assert_eq!(values, [None, Some("x".into())]);
+
+    let figment = figment.empty_as_none(false);
+    let values: Vec<Option<String>> = figment.extract_inner("a.b").unwrap();
+    assert_eq!(values, [Some("".into()), Some("x".into())]);
+}
+
+/// Emits `proxy` as explicitly empty, `retries` as `()`, and no `port`.
+struct Cleared;
+
+impl Provider for Cleared {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("Cleared")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut dict = Dict::new();
+        dict.insert("proxy".into(), Value::from(None::<String>));
+        dict.insert("retries".into(), Value::from(()));
+        Ok(Profile::Default.collect(dict))
+    }
+}
+
+#[test]
+fn explicitly_empty_values_differ_from_absent_ones() {
+    let figment = Figment::from(Serialized::defaults(figment::util::map![
+        "port" => Value::from(8080),
+        "proxy" => Value::from("http://proxy.local"),
+        "retries" => Value::from(3),
+    ]));
+
+    let figment = figment.merge(Cleared);
+    assert!(figment.find_value("proxy").unwrap().is_none());
+    assert!(figment.find_value("retries").unwrap().is_empty_unit());
+    assert_eq!(figment.extract_inner::<Option<String>>("proxy").unwrap(), None);
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8080);
+    assert!(figment.extract_inner::<u8>("retries").is_err());
+
+    let unset = figment.merge(("proxy", Empty::Unset));
+    assert!(unset.find_value("proxy").is_err());
+    assert!(Value::from(Empty::Unset).is_unset());
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use figment::{Figment, Error, providers::Serialized, util::map};
+use figment::value::{Value, magic::Encrypted};
+
+/// "Decrypts" by reversing the bytes.
+fn reverse(ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(ciphertext.iter().rev().copied().collect())
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Database {
+    url: Encrypted<String>,
+    #[serde(flatten)]
+    auth: Auth,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Auth {
+    passwords: Vec<Encrypted<String>>,
+    port: Option<Encrypted<u16>>,
+}
+
+#[test]
+fn encrypted_values_decrypt_wherever_nested() {
+    // `Ym9u` is `bon`, `b2xsZWg` is `olleh`, and `MDg` is `08`.
+    let figment = Figment::from(("database", map![
+        "url" => Value::from("enc:rev:Ym9u"),
+        "passwords" => vec!["enc:rev:b2xsZWg", "plain"].into(),
+        "port" => "enc:rev:MDg".into(),
+    ]))
+    .decryptor("rev", reverse)
+    .coerce_str(|s: &str| s.parse::<u16>());
+
+    let db: Database = figment.extract_inner("database").unwrap();
+    assert_eq!(*db.url, "nob");
+    assert_eq!(*db.auth.passwords[0], "hello");
+    assert_eq!(*db.auth.passwords[1], "plain");
+    assert_eq!(db.auth.passwords[0].ciphertext(), Some("enc:rev:b2xsZWg"));
+    assert_eq!(**db.auth.port.as_ref().unwrap(), 80);
+
+    // Serializing re-encrypts; only ciphertext leaves memory.
+    let figment = Figment::from(Serialized::defaults(&db)).decryptor("rev", reverse);
+    assert_eq!(figment.extract_inner::<String>("url").unwrap(), "enc:rev:Ym9u");
+    let error = figment.extract::<Database>().unwrap_err();
+    assert!(error.to_string().starts_with("invalid type: found string \"80\", expected u16"));
+
+    let error = figment.extract_inner::<Encrypted<u16>>("port").unwrap_err();
+    assert_eq!(error.path, ["port"]);
+    assert!(error.to_string().contains("expected u16 for key \"default.port\""), "{}", error);
+
+    let db: Database = figment.coerce_str(|s: &str| s.parse::<u16>()).extract().unwrap();
+    assert_eq!(*db.url, "nob");
+    assert_eq!(**db.auth.port.as_ref().unwrap(), 80);
+}
+
+#[test]
+fn decryption_failures_are_errors() {
+    let figment = Figment::from(("a", "enc:rev:not base64!"))
+        .merge(("b", "enc:rev:_w"))
+        .merge(("c", "enc:other:YQ"))
+        .merge(("d", "enc:fail:YQ"))
+        .decryptor("rev", reverse)
+        .decryptor("fail", |_: &[u8]| Err(Error::from("wrong key".to_string())));
+
+    let error = |key| figment.extract_inner::<Encrypted<String>>(key).unwrap_err().to_string();
+    assert!(error("a").starts_with("`enc:rev:` value is not valid base64 for key \"global.a\""));
+    assert!(error("b").starts_with("decrypted `enc:rev:` value is not UTF-8"));
+    assert!(error("c").starts_with("no decryptor is registered for `enc:other:` values"));
+    assert!(error("d").starts_with("wrong key"));
+}
+
+#[test]
+fn encrypted_values_only_decrypt_in_figments() {
+    let plain = Encrypted::<String>::deserialize(&Value::from("plain")).unwrap();
+    assert_eq!(*plain, "plain");
+
+    let error = Encrypted::<String>::deserialize(&Value::from("enc:rev:YQ")).unwrap_err();
+    assert!(error.to_string().contains("only be decrypted in a figment"));
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use figment::{Figment, Jail, providers::{Format, Toml, Serialized, Env}};
+
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+enum External {
+    Unit,
+    Newtype(u16),
+    Tuple(u16, String),
+    Struct { port: u16 },
+}
+
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+enum Internal {
+    Unit,
+    Newtype(Server),
+    Struct { port: u16 },
+}
+
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "value")]
+enum Adjacent {
+    Unit,
+    Newtype(u16),
+    Tuple(u16, String),
+    Struct { port: u16 },
+}
+
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum Untagged {
+    Port(u16),
+    Flag(bool),
+    Name(String),
+    Server(Server),
+    Ports(Vec<u16>),
+}
+
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+struct Server {
+    port: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Config<T> {
+    service: T,
+}
+
+/// Extracts `service` from `Test.toml`, merged with `TEST_` environment
+/// variables, over defaults of `default`.
+fn extract<T>(default: T) -> figment::error::Result<T>
+    where T: Serialize + for<'de> Deserialize<'de>
+{
+    Figment::new()
+        .merge(Serialized::defaults(Config { service: default }))
+        .merge(Toml::file("Test.toml"))
+        .merge(Env::prefixed("TEST_").split("__"))
+        .extract::<Config<T>>()
+        .map(|config| config.service)
+}
+
+#[test]
+fn test_externally_tagged() {
+    Jail::expect_with(|jail| {
+        assert_eq!(extract(External::Unit)?, External::Unit);
+
+        jail.create_file("Test.toml", "service = { Newtype = 1 }")?;
+        assert_eq!(extract(External::Unit)?, External::Newtype(1));
+
+        jail.create_file("Test.toml", "service = { Tuple = [1, \"a\"] }")?;
+        assert_eq!(extract(External::Unit)?, External::Tuple(1, "a".into()));
+
+        jail.create_file("Test.toml", "[service.Struct]\nport = 1")?;
+        assert_eq!(extract(External::Unit)?, External::Struct { port: 1 });
+
+        jail.set_env("TEST_SERVICE", "Unit");
+        assert_eq!(extract(External::Unit)?, External::Unit);
+
+        jail.create_file("Test.toml", "")?;
+        jail.set_env("TEST_SERVICE", "{Tuple=[2,b]}");
+        assert_eq!(extract(External::Unit)?, External::Tuple(2, "b".into()));
+
+        // Environment variable names are lowercased: `struct`, `newtype`.
+        jail.clear_env();
+        jail.set_env("TEST_SERVICE__STRUCT__PORT", 2);
+        assert_eq!(extract(External::Unit)?, External::Struct { port: 2 });
+
+        jail.clear_env();
+        jail.set_env("TEST_SERVICE__NEWTYPE", 3);
+        assert_eq!(extract(External::Unit)?, External::Newtype(3));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn test_internally_tagged() {
+    Jail::expect_with(|jail| {
+        assert_eq!(extract(Internal::Unit)?, Internal::Unit);
+
+        jail.create_file("Test.toml", "[service]\ntype = \"Struct\"\nport = 1")?;
+        assert_eq!(extract(Internal::Unit)?, Internal::Struct { port: 1 });
+
+        jail.create_file("Test.toml", "service = { type = \"Newtype\", port = 2 }")?;
+        assert_eq!(extract(Internal::Unit)?, Internal::Newtype(Server { port: 2 }));
+
+        jail.create_file("Test.toml", "")?;
+        jail.set_env("TEST_SERVICE", "{type=Struct,port=3}");
+        assert_eq!(extract(Internal::Unit)?, Internal::Struct { port: 3 });
+
+        jail.clear_env();
+        jail.set_env("TEST_SERVICE__TYPE", "Newtype");
+        jail.set_env("TEST_SERVICE__PORT", 4);
+        assert_eq!(extract(Internal::Unit)?, Internal::Newtype(Server { port: 4 }));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn test_adjacently_tagged() {
+    Jail::expect_with(|jail| {
+        assert_eq!(extract(Adjacent::Unit)?, Adjacent::Unit);
+
+        jail.create_file("Test.toml", "service = { type = \"Newtype\", value = 1 }")?;
+        assert_eq!(extract(Adjacent::Unit)?, Adjacent::Newtype(1));
+
+        jail.create_file("Test.toml", "service = { type = \"Tuple\", value = [1, \"a\"] }")?;
+        assert_eq!(extract(Adjacent::Unit)?, Adjacent::Tuple(1, "a".into()));
+
+        jail.create_file("Test.toml", "[service]\ntype = \"Struct\"\nvalue = { port = 1 }")?;
+        assert_eq!(extract(Adjacent::Unit)?, Adjacent::Struct { port: 1 });
+
+        jail.create_file("Test.toml", "")?;
+        jail.set_env("TEST_SERVICE__TYPE", "Struct");
+        jail.set_env("TEST_SERVICE__VALUE__PORT", 2);
+        assert_eq!(extract(Adjacent::Unit)?, Adjacent::Struct { port: 2 });
+
+        jail.clear_env();
+        jail.set_env("TEST_SERVICE", "{type=Unit}");
+        assert_eq!(extract(Adjacent::Unit)?, Adjacent::Unit);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn test_untagged() {
+    Jail::expect_with(|jail| {
+        assert_eq!(extract(Untagged::Port(0))?, Untagged::Port(0));
+
+        jail.create_file("Test.toml", "service = true")?;
+        assert_eq!(extract(Untagged::Port(0))?, Untagged::Flag(true));
+
+        jail.create_file("Test.toml", "service = { port = 1 }")?;
+        assert_eq!(extract(Untagged::Port(0))?, Untagged::Server(Server { port: 1 }));
+
+        jail.create_file("Test.toml", "service = [1, 2]")?;
+        assert_eq!(extract(Untagged::Port(0))?, Untagged::Ports(vec![1, 2]));
+
+        jail.set_env("TEST_SERVICE", 8080);
+        assert_eq!(extract(Untagged::Port(0))?, Untagged::Port(8080));
+
+        jail.set_env("TEST_SERVICE", "example.com");
+        assert_eq!(extract(Untagged::Port(0))?, Untagged::Name("example.com".into()));
+
+        jail.clear_env();
+        jail.set_env("TEST_SERVICE__PORT", 2);
+        assert_eq!(extract(Untagged::Port(0))?, Untagged::Server(Server { port: 2 }));
+
+        Ok(())
+    })
+}
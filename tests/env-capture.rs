@@ -0,0 +1,72 @@
+use figment::{Figment, Jail, Policy, providers::Env};
+
+#[test]
+fn captured_env_is_stable_across_reloads() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", 8000);
+        jail.set_env("APP_SERVER_HOST", "localhost");
+        jail.set_env("OTHER", 1);
+
+        let env = Env::prefixed("APP_").capture();
+        let load = || Figment::from(env.clone());
+        let first = load();
+
+        jail.set_env("APP_PORT", 9999);
+        jail.set_env("APP_NEW", "new");
+        let second = load();
+        jail.clear_env();
+        let third = load();
+
+        for figment in [first, second, third] {
+            assert_eq!(figment.extract_inner::<u16>("port")?, 8000);
+            assert_eq!(figment.extract_inner::<String>("server_host")?, "localhost");
+            assert!(figment.find_value("new").is_err());
+            assert!(figment.find_value("other").is_err());
+        }
+
+        // By default, the environment is read anew each time.
+        let env = Env::prefixed("APP_");
+        jail.set_env("APP_PORT", 1);
+        assert_eq!(Figment::from(env.clone()).extract_inner::<u16>("port")?, 1);
+        jail.set_env("APP_PORT", 2);
+        assert_eq!(Figment::from(env).extract_inner::<u16>("port")?, 2);
+        Ok(())
+    });
+}
+
+#[test]
+fn captured_env_applies_later_filters() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", 8000);
+        jail.set_env("APP_SERVER_HOST", "localhost");
+
+        let env = Env::prefixed("APP_").capture();
+        jail.set_env("APP_SERVER_PORT", 80);
+
+        let figment = Figment::from(env.clone().split("_"));
+        assert_eq!(figment.extract_inner::<String>("server.host")?, "localhost");
+        assert!(figment.find_value("server.port").is_err());
+
+        let figment = Figment::from(env.clone().only(&["port"]));
+        assert_eq!(figment.extract_inner::<u16>("port")?, 8000);
+        assert!(figment.find_value("server_host").is_err());
+
+        let figment = Figment::from(env.live().split("_"));
+        assert_eq!(figment.extract_inner::<u16>("server.port")?, 80);
+        Ok(())
+    });
+}
+
+#[test]
+fn captured_env_respects_policy() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", 8000);
+
+        let figment = Figment::new()
+            .policy(Policy::unrestricted().allow_env(false))
+            .merge(Env::prefixed("APP_").capture());
+
+        assert_eq!(figment.extract_inner::<u16>("port").unwrap_err().code(), "denied");
+        Ok(())
+    });
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "env")]
+
+use serde::Deserialize;
+use figment::{Figment, Jail, providers::Env};
+
+#[derive(Debug, Deserialize)]
+struct Database {
+    #[allow(dead_code)]
+    url: String,
+    #[allow(dead_code)]
+    pool_size: u32,
+}
+
+#[test]
+fn errors_name_the_variable_as_set() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_DATABASE__URL", "postgres://");
+        jail.set_env("app_Database__POOL_SIZE", "many");
+
+        let figment = Figment::from(Env::prefixed("APP_").split("__"));
+        let error = figment.extract_inner::<Database>("database").unwrap_err();
+        assert_eq!(error.key().as_deref(), Some("database.pool_size"));
+        assert_eq!(error.interpolated_key().as_deref(), Some("app_Database__POOL_SIZE"));
+        assert!(error.to_string().ends_with("for key \"database.pool_size\" from \
+            `app_Database__POOL_SIZE` in `APP_` environment variable(s)"), "{}", error);
+
+        let focused = figment.focus("database");
+        let error = focused.extract::<Database>().unwrap_err();
+        assert!(error.to_string().contains("for key \"pool_size\" from `app_Database__POOL_SIZE`"), "{}", error);
+
+        let figment = Figment::new().adjoin_library("db", Figment::from(Env::prefixed("APP_DATABASE__")));
+        let error = figment.extract_inner::<Database>("db").unwrap_err();
+        assert!(error.to_string().contains("for key \"db.pool_size\" from `app_Database__POOL_SIZE`"), "{}", error);
+        Ok(())
+    });
+}
+
+#[test]
+fn unread_keys_are_interpolated() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_DATABASE_POOL_SIZE", 8);
+
+        let figment = Figment::from(Env::prefixed("APP_").split("_"));
+        let error = figment.extract_inner::<String>("database.url").unwrap_err();
+        assert!(!error.to_string().contains(" from `"), "{}", error);
+
+        let error = figment.extract_inner::<bool>("database.pool.size").unwrap_err();
+        assert!(error.to_string().contains("for key \"database.pool.size\" from \
+            `APP_DATABASE_POOL_SIZE`"), "{}", error);
+        Ok(())
+    });
+}
@@ -0,0 +1,103 @@
+use serde::Deserialize;
+use figment::{Figment, Error, value::Limits, providers::{Format, Toml}};
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    servers: Vec<Server>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    tls: Option<Tls>,
+    kind: Kind,
+    aliases: Option<Vec<Vec<String>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Tls {
+    cert: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+enum Kind {
+    Tcp { port: u16 },
+    Unix(String),
+}
+
+fn error(toml: &str) -> Error {
+    Figment::from(Toml::string(toml)).extract::<Config>().unwrap_err()
+}
+
+#[test]
+fn paths_include_indices_and_keys() {
+    let e = error(r#"
+        [[servers]]
+        kind = "Unix"
+
+        [[servers]]
+        kind = { Unix = "/tmp/sock" }
+        tls = { cert = 10 }
+    "#);
+
+    assert_eq!(e.display_path().unwrap(), "servers[0].kind");
+
+    let e = error(r#"
+        [[servers]]
+        kind = { Unix = "/tmp/sock" }
+
+        [[servers]]
+        kind = { Unix = "/tmp/sock" }
+        tls = { cert = 10 }
+    "#);
+
+    assert_eq!(e.display_path().unwrap(), "servers[1].tls.cert");
+    assert!(e.to_string().contains("servers.1.tls.cert"));
+
+    let e = error(r#"
+        [[servers]]
+        kind = { Tcp = { port = "eighty" } }
+    "#);
+
+    assert_eq!(e.display_path().unwrap(), "servers[0].kind.Tcp.port");
+
+    let e = error(r#"
+        [[servers]]
+        kind = { Unix = "/tmp/sock" }
+        aliases = [["a", "b"], ["c", 7]]
+    "#);
+
+    assert_eq!(e.display_path().unwrap(), "servers[0].aliases[1][1]");
+}
+
+#[test]
+fn escaped_keys_and_focused_paths() {
+    let figment = Figment::from(Toml::string(r#"
+        [hosts."example.com"]
+        ports = [80, "443"]
+    "#));
+
+    let e = figment.extract_inner::<Vec<u16>>(r#"hosts["example.com"].ports"#).unwrap_err();
+    assert_eq!(e.display_path().unwrap(), r"hosts.example\.com.ports[1]");
+}
+
+#[test]
+fn limit_errors_have_paths() {
+    let figment = Figment::new()
+        .limits(Limits::default().max_string_len(4))
+        .merge(Toml::string(r#"list = [{ a = "ok" }, { a = "too long" }]"#));
+
+    let e = figment.extract::<figment::value::Value>().unwrap_err();
+    assert_eq!(e.display_path().unwrap(), "list[1].a");
+}
+
+#[test]
+fn unresolved_errors_display_their_path() {
+    let value = figment::value::Value::from(vec![1, 2, -3]);
+    let e = value.deserialize::<Vec<u8>>().unwrap_err();
+    assert_eq!(e.display_path().unwrap(), "[2]");
+    assert!(e.to_string().ends_with(r#"for key "[2]""#), "{}", e);
+}
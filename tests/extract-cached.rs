@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use figment::{Figment, providers::Serialized, value::{Dict, Coercion}};
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+struct Log {
+    level: String,
+}
+
+#[test]
+fn extract_cached_memoizes_per_type() {
+    let figment = Figment::from(("port", 8000)).merge(("level", "info"));
+
+    let a = figment.extract_cached::<Server>().unwrap();
+    let b = figment.extract_cached::<Server>().unwrap();
+    assert!(Arc::ptr_eq(&a, &b));
+    assert_eq!(a.port, 8000);
+
+    let log = figment.extract_cached::<Log>().unwrap();
+    assert_eq!(log.level, "info");
+    assert!(Arc::ptr_eq(&log, &figment.extract_cached::<Log>().unwrap()));
+    assert!(Arc::ptr_eq(&a, &figment.extract_cached::<Server>().unwrap()));
+
+    let clone = figment.clone();
+    assert!(!Arc::ptr_eq(&a, &clone.extract_cached::<Server>().unwrap()));
+}
+
+#[test]
+fn extract_cached_is_invalidated_by_changes() {
+    let figment = Figment::from(Serialized::default("port", 8000))
+        .merge(Serialized::default("port", 9000).profile("debug"));
+
+    let cached = figment.extract_cached::<Server>().unwrap();
+    assert_eq!(cached.port, 8000);
+
+    let figment = figment.select("debug");
+    assert_eq!(figment.extract_cached::<Server>().unwrap().port, 9000);
+
+    let figment = figment.set("port", 9001);
+    assert_eq!(figment.extract_cached::<Server>().unwrap().port, 9001);
+
+    let cached = figment.extract_cached::<Dict>().unwrap();
+    let figment = figment.merge(("level", "info"));
+    assert!(figment.extract_cached::<Dict>().unwrap().contains_key("level"));
+    assert!(!cached.contains_key("level"));
+
+    let figment = figment.unset("port");
+    assert!(figment.extract_cached::<Server>().is_err());
+
+    let cached = figment.extract_cached::<Dict>().unwrap();
+    let figment = figment.coerce(Coercion::lenient());
+    assert!(!Arc::ptr_eq(&cached, &figment.extract_cached::<Dict>().unwrap()));
+
+    let figment = figment.require(["level"]).merge(("port", 80));
+    assert!(figment.extract_cached::<Log>().is_ok());
+
+    let figment = figment.require(["host"]);
+    assert!(figment.extract_cached::<Log>().is_err());
+}
+
+#[test]
+fn extract_cached_does_not_cache_errors() {
+    let figment = Figment::from(("port", "eighty"));
+    assert!(figment.extract_cached::<Server>().is_err());
+    assert!(figment.extract_cached::<Server>().is_err());
+
+    let figment = figment.merge(("port", 80));
+    assert_eq!(figment.extract_cached::<Server>().unwrap().port, 80);
+}
+
+#[test]
+fn extract_cached_is_invalidated_by_failed_changes() {
+    let figment = Figment::from(("port", 8000));
+    assert_eq!(figment.extract_cached::<Server>().unwrap().port, 8000);
+
+    // Fails to serialize: maps must have string keys.
+    let bad: std::collections::BTreeMap<u8, u8> = [(1, 1)].into();
+    let broken = figment.set("bad", bad);
+    assert!(broken.extract_cached::<Server>().is_err());
+
+    let figment = Figment::from(("port", 8000)).adjoin_library("lib", Figment::new());
+    assert_eq!(figment.extract_cached::<Server>().unwrap().port, 8000);
+    let figment = figment.adjoin_library("lib.sub", Figment::new());
+    assert!(figment.extract_cached::<Server>().is_err());
+}
@@ -0,0 +1,41 @@
+use serde::Deserialize;
+use figment::{Figment, providers::Serialized};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Region {
+    Us,
+    Eu,
+}
+
+#[test]
+fn extract_map_deserializes_keys_and_values() {
+    let figment = Figment::new()
+        .merge(Serialized::default("us.port", 80))
+        .merge(Serialized::default("eu.port", 8080));
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server { port: u16 }
+
+    let mut entries = figment.extract_map::<Region, Server>().unwrap();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    assert_eq!(entries[0].key, Region::Us);
+    assert_eq!(entries[0].value, Server { port: 80 });
+    assert_eq!(entries[1].key, Region::Eu);
+    assert!(entries[1].metadata.unwrap().source.is_some());
+}
+
+#[test]
+fn extract_map_reports_every_failing_entry() {
+    let figment = Figment::new()
+        .merge(Serialized::default("us", "a"))
+        .merge(Serialized::default("asia", 1))
+        .merge(Serialized::default("eu", 2));
+
+    let error = figment.extract_map::<Region, u8>().unwrap_err();
+    assert_eq!(error.count(), 2);
+
+    let mut paths: Vec<_> = error.into_iter().map(|e| e.path).collect();
+    paths.sort();
+    assert_eq!(paths, vec![vec!["asia".to_string()], vec!["us".to_string()]]);
+}
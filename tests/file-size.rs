@@ -0,0 +1,53 @@
+use figment::{Figment, Jail, providers::{Format, Toml}};
+
+#[test]
+fn max_size_bounds_file_size() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", "port = 80")?;
+
+        let figment = Figment::from(Toml::file("Config.toml").max_size(9));
+        assert_eq!(figment.extract_inner::<u16>("port")?, 80);
+
+        let figment = Figment::from(Toml::file("Config.toml").nested().max_size(8));
+        let error = figment.extract_inner::<u16>("port").unwrap_err();
+        assert!(error.to_string().starts_with("`"));
+        assert!(error.to_string().contains("Config.toml` exceeds the maximum of 8 bytes"));
+        assert!(error.metadata.unwrap().name.contains("TOML"));
+
+        let figment = Figment::from(Toml::string("port = 80").max_size(1));
+        assert_eq!(figment.extract_inner::<u16>("port")?, 80);
+
+        // The limit may fall within a multi-byte character.
+        jail.create_file("Name.toml", "name = \"é\"")?;
+        let figment = Figment::from(Toml::file("Name.toml").max_size(8));
+        let error = figment.extract_inner::<String>("name").unwrap_err();
+        assert!(error.to_string().contains("exceeds the maximum of 8 bytes"));
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_parses_files_in_place() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", "[debug]\nport = 80")?;
+        jail.create_file("Empty.toml", "")?;
+        jail.create_binary_file("Invalid.toml", b"port = \"\xff\"")?;
+
+        // SAFETY: Nothing modifies the jailed files while they're mapped.
+        let figment = Figment::from(unsafe { Toml::file("Config.toml").nested().mmap() });
+        assert_eq!(figment.select("debug").extract_inner::<u16>("port")?, 80);
+
+        let figment = Figment::from(unsafe { Toml::file("Config.toml").mmap() }.max_size(8));
+        assert!(figment.find_value("port").unwrap_err().to_string().contains("maximum of 8 bytes"));
+
+        let figment = Figment::from(unsafe { Toml::file("Empty.toml").mmap() });
+        assert!(figment.find_value("port").unwrap_err().missing());
+
+        let figment = Figment::from(unsafe { Toml::file("Invalid.toml").mmap() });
+        assert!(figment.find_value("port").unwrap_err().to_string().contains("invalid utf-8"));
+
+        Ok(())
+    });
+}
@@ -0,0 +1,37 @@
+use figment::{Figment, providers::{Format, Toml}, value::KeyCase};
+
+const SERVICES: &str = r#"
+    [[services]]
+    Name = "api"
+    listen-port = 80
+
+    [[services]]
+    Name = "web"
+    listen-port = 443
+"#;
+
+#[test]
+fn find_all_matches_array_elements() {
+    let figment = Figment::from(Toml::string(SERVICES));
+    let found = figment.find_all("services.*.Name").unwrap();
+    let paths: Vec<_> = found.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(paths, ["services.0.Name", "services.1.Name"]);
+    assert!(figment.find_all("services.*.name").unwrap().is_empty());
+}
+
+#[test]
+fn find_all_respects_key_configuration() {
+    let figment = Figment::new()
+        .case_insensitive(true)
+        .merge(Toml::string(SERVICES));
+
+    assert_eq!(figment.find_all("SERVICES.*.name").unwrap().len(), 2);
+
+    let figment = Figment::new()
+        .canonicalize_keys(KeyCase::Snake)
+        .merge(Toml::string(SERVICES));
+
+    let ports = figment.find_all("services.*.listenPort").unwrap();
+    assert_eq!(ports[0].0, "services.0.listen_port");
+    assert_eq!(ports[1].1.deserialize::<u16>().unwrap(), 443);
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use figment::{Figment, providers::{Format, Toml}, value::KeyCase};
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Server {
+    name: String,
+    port: u16,
+}
+
+const CONFIG: &str = r#"
+    Max-Connections = 10
+
+    [[servers]]
+    name = "api"
+    port = 8000
+
+    [[servers]]
+    name = "admin"
+    port = 9000
+"#;
+
+#[test]
+fn frozen_overlay_respects_key_configuration() {
+    let frozen = Figment::new()
+        .case_insensitive(true)
+        .merge(Toml::string(CONFIG))
+        .freeze()
+        .unwrap();
+
+    let frozen = frozen.overlay([("max-connections", 20)]).unwrap();
+    assert_eq!(frozen.extract_inner::<u32>("MAX-CONNECTIONS").unwrap(), 20);
+
+    let frozen = Figment::new()
+        .canonicalize_keys(KeyCase::Snake)
+        .merge(Toml::string(CONFIG))
+        .freeze()
+        .unwrap();
+
+    let frozen = frozen.overlay([("maxConnections", 30)]).unwrap();
+    assert_eq!(frozen.extract_inner::<u32>("max_connections").unwrap(), 30);
+}
+
+#[test]
+fn frozen_overlay_merges_keyed_arrays() {
+    let frozen = Figment::new()
+        .merge_arrays_by("name")
+        .merge(Toml::string(CONFIG))
+        .freeze()
+        .unwrap();
+
+    let overrides = vec![Server { name: "admin".into(), port: 80 }];
+    let servers: Vec<Server> = frozen.overlay([("servers", overrides)]).unwrap()
+        .extract_inner("servers")
+        .unwrap();
+
+    assert_eq!(servers, vec![
+        Server { name: "api".into(), port: 8000 },
+        Server { name: "admin".into(), port: 80 },
+    ]);
+
+    let servers: Vec<Server> = frozen.extract_inner("servers").unwrap();
+    assert_eq!(servers[1].port, 9000);
+}
+
+#[test]
+fn frozen_lookups_index_arrays() {
+    use figment::util::map;
+
+    let figment = Figment::from(("servers", [map!["port" => 80], map!["port" => 443]]));
+    let frozen = figment.clone().freeze().unwrap();
+    assert_eq!(figment.extract_inner::<u16>("servers.0.port").unwrap(), 80);
+    assert_eq!(frozen.extract_inner::<u16>("servers.0.port").unwrap(), 80);
+    assert_eq!(frozen.extract_inner::<u16>("servers.1.port").unwrap(), 443);
+    assert!(frozen.extract_inner::<u16>("servers.2.port").is_err());
+
+    let frozen = figment.case_insensitive(true).freeze().unwrap();
+    assert_eq!(frozen.extract_inner::<u16>("SERVERS.1.Port").unwrap(), 443);
+}
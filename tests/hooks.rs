@@ -0,0 +1,64 @@
+use std::sync::{Arc, Mutex};
+
+use figment::{Figment, FigmentExt, Error, Profile, value::Value};
+use figment::providers::{Format, Toml, Serialized};
+
+struct Recorded(Arc<Mutex<Vec<String>>>);
+
+impl FigmentExt for Recorded {
+    fn apply(self, figment: Figment) -> Figment {
+        let (pre, post, extract) = (self.0.clone(), self.0.clone(), self.0);
+        figment
+            .pre_merge(move |md, _| {
+                pre.lock().unwrap().push(format!("pre {}", md.name));
+                Ok(())
+            })
+            .post_merge(move |_| {
+                post.lock().unwrap().push("post".into());
+                Ok(())
+            })
+            .pre_extract(move |_| {
+                extract.lock().unwrap().push("extract".into());
+                Ok(())
+            })
+    }
+}
+
+#[test]
+fn hooks_run_in_order_for_subsequent_providers() {
+    let log = Arc::new(Mutex::new(vec![]));
+    let figment = Figment::new()
+        .merge(Serialized::default("a", 1))
+        .with(Recorded(log.clone()))
+        .merge(("b", 2))
+        .join(("c", 3));
+
+    // Only providers added after the hooks are registered are seen.
+    assert_eq!(log.lock().unwrap().len(), 4);
+    assert!(log.lock().unwrap()[0].starts_with("pre ("));
+    assert_eq!(log.lock().unwrap()[1], "post");
+    assert!(log.lock().unwrap()[2].starts_with("pre ("));
+
+    figment.find_value("a").unwrap();
+    figment.extract_inner::<u8>("b").unwrap();
+    assert_eq!(log.lock().unwrap()[4..], ["extract", "extract"]);
+}
+
+#[test]
+fn hook_errors_fail_the_figment() {
+    figment::Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "port = 0")?;
+
+        let figment = Figment::new()
+            .pre_merge(|_, data| match data.get(&Profile::Default).and_then(|d| d.get("port")) {
+                Some(Value::Num(_, n)) if *n == 0i64.into() => Err(Error::from("bad port".to_string())),
+                _ => Ok(()),
+            })
+            .merge(Toml::file("App.toml"));
+
+        let error = figment.extract_inner::<u16>("port").unwrap_err();
+        assert!(error.to_string().starts_with("bad port"));
+        assert!(error.metadata.unwrap().name.contains("TOML"));
+        Ok(())
+    });
+}
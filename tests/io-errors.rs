@@ -0,0 +1,44 @@
+use std::io::ErrorKind;
+
+use figment::{Figment, error::Kind, providers::{Format, Toml}};
+
+#[test]
+fn file_io_errors_are_structured() {
+    figment::Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", "port = 80")?;
+        let path = jail.directory().join("Config.toml");
+        let provider = Toml::file("Config.toml");
+        std::fs::remove_file(&path).unwrap();
+
+        let error = Figment::from(provider).extract_inner::<u16>("port").unwrap_err();
+        assert_eq!(error.code(), "io");
+        match error.kind {
+            Kind::Io(ErrorKind::NotFound, path) => assert!(path.ends_with("Config.toml")),
+            kind => panic!("unexpected error kind: {:?}", kind),
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn file_permission_denied() {
+    use std::os::unix::fs::PermissionsExt;
+
+    figment::Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", "port = 80")?;
+        let path = jail.directory().join("Config.toml");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+        // Privileged users can read the file regardless of its permissions.
+        if std::fs::File::open(&path).is_ok() {
+            return Ok(());
+        }
+
+        let error = Figment::from(Toml::file("Config.toml")).extract_inner::<u16>("port").unwrap_err();
+        assert!(matches!(error.kind, Kind::Io(ErrorKind::PermissionDenied, _)));
+        assert!(error.to_string().contains("Config.toml"));
+        Ok(())
+    });
+}
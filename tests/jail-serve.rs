@@ -0,0 +1,29 @@
+#![cfg(feature = "test")]
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use figment::Jail;
+
+#[test]
+fn idle_clients_dont_hang_jail() {
+    let start = Instant::now();
+    Jail::expect_with(|jail| {
+        let url = jail.serve([("/a.json", "{}")])?;
+        let addr = url.trim_start_matches("http://");
+
+        // Connects but never sends a request.
+        let _idle = TcpStream::connect(addr).unwrap();
+
+        // A later client is still served once the idle client times out.
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"GET /a.json HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        Ok(())
+    });
+
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
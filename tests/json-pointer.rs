@@ -0,0 +1,60 @@
+use serde::Deserialize;
+use figment::{Figment, providers::Serialized, util::map, value::Value};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+fn figment() -> Figment {
+    let servers = vec![
+        map!["host" => Value::from("a.example.com"), "port" => Value::from(80)],
+        map!["host" => Value::from("b.example.com"), "port" => Value::from(443)],
+    ];
+
+    Figment::from(Serialized::default("servers", servers))
+        .merge(Serialized::default("paths", map!["/api" => "api", "a~b" => "tilde"]))
+        .merge(Serialized::default("ports", vec![8000, 8001]))
+}
+
+#[test]
+fn pointers_find_values() {
+    let figment = figment();
+    assert_eq!(figment.extract_inner::<String>("/servers/1/host").unwrap(), "b.example.com");
+    assert_eq!(figment.extract_inner::<u16>("/ports/0").unwrap(), 8000);
+    assert_eq!(figment.extract_inner::<String>("/paths/~1api").unwrap(), "api");
+    assert_eq!(figment.extract_inner::<String>("/paths/a~0b").unwrap(), "tilde");
+    assert_eq!(figment.find_value("").unwrap(), figment.find_value("/").unwrap());
+    assert!(figment.find_value("/servers/2").is_err());
+    assert!(figment.find_value("/servers/-").is_err());
+    assert!(figment.find_value("/servers/00").is_err());
+    assert!(figment.find_value("/ports/0/x").is_err());
+
+    let server: Server = figment.extract_inner("/servers/0").unwrap();
+    assert_eq!(server, Server { host: "a.example.com".into(), port: 80 });
+    assert_eq!(figment.find_metadata("/servers/0/host"), figment.find_metadata("servers"));
+}
+
+#[test]
+fn dotted_keys_index_arrays() {
+    let figment = figment();
+    assert_eq!(figment.extract_inner::<u16>("servers.1.port").unwrap(), 443);
+    assert_eq!(figment.extract_inner::<u16>("ports.1").unwrap(), 8001);
+    assert_eq!(figment.find_value(r"paths.\/api").unwrap().as_str(), Some("api"));
+
+    for (path, _) in figment.find_all("servers.*.host").unwrap() {
+        assert!(figment.find_value(&path).is_ok());
+    }
+
+    let found = figment.find_all("servers.1.*").unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].0, "servers.1.host");
+}
+
+#[test]
+fn pointer_errors_carry_full_path() {
+    let figment = figment().merge(Serialized::default("servers", vec![map!["port" => "x"]]));
+    let err = figment.extract_inner::<u16>("/servers/0/port").unwrap_err();
+    assert_eq!(err.path, ["servers", "0", "port"]);
+}
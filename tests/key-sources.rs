@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use figment::{Figment, Profile, Provider, Metadata, Error, Jail};
+use figment::value::{Map, Dict, magic::{RelativePathBuf, Tagged}};
+use figment::util::map;
+
+/// Emits one key per file, like a directory of `KEY.toml` files would.
+struct Files;
+
+impl Provider for Files {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("files")
+            .key_source("tls", Path::new("/etc/app/tls/tls.toml"))
+            .key_source("port", Path::new("/etc/app/port.toml"))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut dict = Dict::new();
+        dict.insert("tls".into(), map! { "cert" => "cert.pem" }.into());
+        dict.insert("port".into(), "eighty".into());
+        dict.insert("name".into(), "app".into());
+        Ok(Profile::Default.collect(dict))
+    }
+}
+
+#[derive(Deserialize)]
+struct Tls {
+    cert: RelativePathBuf,
+}
+
+#[test]
+fn per_key_sources_resolve() {
+    Jail::expect_with(|_| {
+        let figment = Figment::from(Files);
+
+        let tls: Tls = figment.extract_inner("tls")?;
+        assert_eq!(tls.cert.relative(), Path::new("/etc/app/tls/cert.pem"));
+
+        let name: Tagged<String> = figment.extract_inner("name")?;
+        let md = figment.get_metadata(name.tag()).unwrap();
+        assert_eq!(md.name, "files");
+        assert!(md.source.is_none());
+
+        let err = figment.extract_inner::<u16>("port").unwrap_err();
+        let source = err.metadata.as_ref().and_then(|md| md.source.as_ref());
+        assert_eq!(source.and_then(|s| s.file_path()), Some(Path::new("/etc/app/port.toml")));
+        assert!(err.to_string().contains("/etc/app/port.toml"));
+        Ok(())
+    });
+}
+
+#[test]
+fn per_key_sources_survive_nesting_figments() {
+    let inner = Figment::from(Files);
+    let figment = Figment::new().merge(&inner).merge(("name", "other"));
+    let md = figment.find_metadata("tls.cert").unwrap();
+    assert_eq!(md.source.as_ref().unwrap().file_path(), Some(Path::new("/etc/app/tls/tls.toml")));
+}
@@ -0,0 +1,115 @@
+use serde::Deserialize;
+use figment::{Figment, Jail, providers::{Format, Toml, Serialized}};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Server {
+    name: String,
+    port: u16,
+}
+
+#[test]
+fn keyed_arrays_merge_and_join() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Base.toml", r#"
+            tags = ["a", "b"]
+
+            [[servers]]
+            name = "api"
+            port = 8000
+
+            [[servers]]
+            name = "admin"
+            port = 9000
+        "#)?;
+
+        jail.create_file("Local.toml", r#"
+            tags = ["c"]
+
+            [[servers]]
+            name = "metrics"
+            port = 9100
+
+            [[servers]]
+            name = "api"
+            port = 80
+        "#)?;
+
+        let figment = Figment::new()
+            .merge_arrays_by("name")
+            .merge(Toml::file("Base.toml"))
+            .merge(Toml::file("Local.toml"));
+
+        let servers: Vec<Server> = figment.extract_inner("servers")?;
+        assert_eq!(servers, vec![
+            Server { name: "api".into(), port: 80 },
+            Server { name: "admin".into(), port: 9000 },
+            Server { name: "metrics".into(), port: 9100 },
+        ]);
+
+        // Arrays that aren't keyed are replaced as usual.
+        assert_eq!(figment.extract_inner::<Vec<String>>("tags")?, ["c"]);
+
+        let figment = Figment::new()
+            .merge_arrays_by("name")
+            .merge(Toml::file("Base.toml"))
+            .join(Toml::file("Local.toml"));
+
+        let servers: Vec<Server> = figment.extract_inner("servers")?;
+        assert_eq!(servers, vec![
+            Server { name: "api".into(), port: 8000 },
+            Server { name: "admin".into(), port: 9000 },
+            Server { name: "metrics".into(), port: 9100 },
+        ]);
+
+        // Without the option, arrays are replaced wholesale.
+        let figment = Figment::new()
+            .merge(Toml::file("Base.toml"))
+            .merge(Toml::file("Local.toml"));
+
+        let servers: Vec<Server> = figment.extract_inner("servers")?;
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "metrics");
+
+        Ok(())
+    });
+}
+
+#[test]
+fn keyed_arrays_require_key_in_every_element() {
+    let figment = Figment::new()
+        .merge_arrays_by("name")
+        .merge(Serialized::default("servers", vec![
+            Server2 { name: Some("api".into()), port: 8000 },
+        ]))
+        .merge(Serialized::default("servers", vec![
+            Server2 { name: None, port: 80 },
+        ]));
+
+    let servers: Vec<Server2> = figment.extract_inner("servers").unwrap();
+    assert_eq!(servers, vec![Server2 { name: None, port: 80 }]);
+}
+
+#[derive(Debug, PartialEq, Deserialize, serde::Serialize)]
+struct Server2 {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    port: u16,
+}
+
+#[test]
+fn keyed_arrays_match_duplicate_and_non_string_ids_in_order() {
+    #[derive(Debug, PartialEq, Deserialize, serde::Serialize)]
+    struct Worker { id: u8, load: u8 }
+
+    let workers = |ids: &[(u8, u8)]| -> Vec<Worker> {
+        ids.iter().map(|&(id, load)| Worker { id, load }).collect()
+    };
+
+    let figment = Figment::new()
+        .merge_arrays_by("id")
+        .merge(Serialized::default("workers", workers(&[(1, 0), (2, 0), (1, 0)])))
+        .merge(Serialized::default("workers", workers(&[(3, 9), (1, 1), (1, 2), (1, 3)])));
+
+    let merged: Vec<Worker> = figment.extract_inner("workers").unwrap();
+    assert_eq!(merged, workers(&[(1, 1), (2, 0), (1, 2), (3, 9), (1, 3)]));
+}
@@ -0,0 +1,82 @@
+use figment::{Figment, Profile, Jail, providers::{Format, Toml, Serialized}};
+use figment::warning::Kind;
+
+fn unknown(figment: &Figment) -> Vec<(String, Option<String>)> {
+    figment.warnings().iter()
+        .filter_map(|w| match &w.kind {
+            Kind::UnknownProfile(suggestion) => Some((
+                w.profile.as_ref().unwrap().to_string(),
+                suggestion.as_ref().map(|p| p.to_string()),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn unknown_profiles_are_flagged_regardless_of_order() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", r#"
+            [default]
+            workers = 4
+
+            [global]
+            debug = false
+
+            [prodction]
+            workers = 16
+
+            [Debug]
+            workers = 1
+        "#)?;
+
+        let before = Figment::new()
+            .known_profiles(["debug", "production"])
+            .merge(Toml::file("App.toml").nested())
+            .merge(Serialized::default("port", 80).profile("qa"));
+
+        let after = Figment::new()
+            .merge(Toml::file("App.toml").nested())
+            .merge(Serialized::default("port", 80).profile("qa"))
+            .known_profiles(["debug"])
+            .known_profiles(["production"]);
+
+        for figment in &[before, after] {
+            let mut warnings = unknown(figment);
+            warnings.sort();
+            assert_eq!(warnings, [
+                ("prodction".to_string(), Some("production".to_string())),
+                ("qa".to_string(), None),
+            ]);
+
+            let toml = figment.warnings().iter()
+                .find(|w| w.profile == Some(Profile::new("prodction")))
+                .unwrap();
+
+            assert_eq!(toml.metadata.as_ref().unwrap().name, "TOML file");
+
+            let error = figment.check_profiles().unwrap_err();
+            assert_eq!(error.count(), 2);
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn undeclared_profiles_are_all_known() {
+    let figment = Figment::new().merge(Serialized::default("port", 80).profile("qa"));
+    assert!(figment.warnings().is_empty());
+    assert!(figment.check_profiles().is_ok());
+
+    let figment = figment.known_profiles(["qa"]);
+    assert!(figment.warnings().is_empty());
+    assert!(figment.check_profiles().is_ok());
+}
+
+#[test]
+fn check_profiles_propagates_merge_errors() {
+    let figment = Figment::from(Toml::string("invalid = ")).known_profiles(["qa"]);
+    let error = figment.check_profiles().unwrap_err();
+    assert!(error.to_string().contains("TOML"), "{}", error);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "env")]
+
+use serde::Deserialize;
+use figment::{Figment, Jail, providers::{Env, Serialized}, value::ParsePolicy};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Flags {
+    a: bool,
+    b: bool,
+    c: Option<bool>,
+    list: Vec<bool>,
+}
+
+#[test]
+fn env_lenient_bools() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_A", "ON");
+        jail.set_env("APP_B", "no");
+        jail.set_env("APP_C", "0");
+        jail.set_env("APP_LIST", "[yes, off, True, 1]");
+        jail.set_env("APP_NAME", "yesterday");
+        jail.set_env("APP_PORT", "1");
+
+        let figment = Figment::from(Env::prefixed("APP_").lenient_bools());
+        let flags: Flags = figment.extract()?;
+        assert_eq!(flags, Flags { a: true, b: false, c: Some(false), list: vec![true, false, true, true] });
+        assert_eq!(figment.extract_inner::<String>("name")?, "yesterday");
+        assert_eq!(figment.extract_inner::<u16>("port")?, 1);
+
+        let err = Figment::from(Env::prefixed("APP_")).extract::<Flags>().unwrap_err();
+        assert_eq!(err.path, ["a"]);
+
+        // The opt-in composes with other policy settings.
+        let policy = ParsePolicy::new().floats(false);
+        jail.set_env("APP_VERSION", "1.10");
+        let figment = Figment::from(Env::prefixed("APP_").parse_policy(policy).lenient_bools());
+        assert!(figment.extract_inner::<bool>("a")?);
+        assert_eq!(figment.extract_inner::<String>("version")?, "1.10");
+        Ok(())
+    });
+}
+
+#[test]
+fn integers_extract_as_bools() {
+    let figment = Figment::from(Serialized::default("one", 1u8))
+        .merge(Serialized::default("zero", 0i64))
+        .merge(Serialized::default("two", 2))
+        .merge(Serialized::default("neg", -1));
+
+    assert!(figment.extract_inner::<bool>("one").unwrap());
+    assert!(!figment.extract_inner::<bool>("zero").unwrap());
+    assert_eq!(figment.extract_inner::<u8>("one").unwrap(), 1);
+    assert!(figment.extract_inner::<bool>("two").is_err());
+    assert!(figment.extract_inner::<bool>("neg").is_err());
+
+    let err = figment.extract_inner::<bool>("two").unwrap_err();
+    assert_eq!(err.path, ["two"]);
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "toml")]
+
+use figment::{Figment, Jail, providers::{Format, Toml, Serialized}};
+use figment::value::Value;
+
+fn library() -> Figment {
+    Figment::from(Serialized::default("pool", 8))
+        .merge(Serialized::default("timeout", 30).profile("debug"))
+        .select("debug")
+}
+
+#[test]
+fn library_values_are_namespaced_defaults() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Lib.toml", "[log]\nlevel = 1")?;
+        jail.create_file("App.toml", "[db]\npool = 16")?;
+
+        let lib = Figment::from(Toml::file("Lib.toml")).merge(("retries", 3));
+        let figment = Figment::from(Toml::file("App.toml"))
+            .adjoin_library("db", library())
+            .adjoin_library("net.http", lib)
+            .merge(Serialized::default("net.http.log.level", 2));
+
+        assert_eq!(figment.profile(), "default");
+        assert_eq!(figment.extract_inner::<u32>("db.pool")?, 16);
+        assert!(figment.extract_inner::<u32>("db.timeout").is_err());
+        assert_eq!(figment.clone().select("debug").extract_inner::<u32>("db.timeout")?, 30);
+        assert_eq!(figment.extract_inner::<u32>("net.http.log.level")?, 2);
+        assert_eq!(figment.extract_inner::<u32>("net.http.retries")?, 3);
+
+        let retries = figment.find_value("net.http.retries")?;
+        let metadata = figment.get_metadata(retries.tag()).unwrap();
+        assert_eq!(metadata.interpolate(&"global".into(), &["net", "http", "retries"]), "global.retries");
+
+        let error = figment.extract_inner::<String>("net.http.retries").unwrap_err();
+        assert_eq!(error.path, ["net", "http", "retries"]);
+        assert!(error.to_string().contains("key \"global.retries\""), "{}", error);
+
+        jail.create_file("Lib.toml", "[log]\nlevel = \"high\"")?;
+        let figment = Figment::new()
+            .adjoin_library("net.http", Figment::from(Toml::file("Lib.toml")));
+
+        let error = figment.extract_inner::<u8>("net.http.log.level").unwrap_err();
+        assert_eq!(error.metadata.unwrap().name, "TOML file");
+        Ok(())
+    });
+}
+
+#[test]
+fn library_namespaces_cannot_collide() {
+    let figment = Figment::new()
+        .adjoin_library("db", library())
+        .adjoin_library("dbx", library())
+        .adjoin_library("net.http", library());
+
+    assert!(figment.extract::<Value>().is_ok());
+
+    for namespace in &["db", "net", "net.http.tls"] {
+        let error = figment.clone()
+            .adjoin_library(namespace, library())
+            .extract::<Value>()
+            .unwrap_err();
+
+        let existing = if *namespace == "db" { "db" } else { "net.http" };
+        let expected = format!("library namespace `{}` collides with library namespace `{}`",
+            namespace, existing);
+
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+#[test]
+fn library_requirements_descriptions_and_errors_carry_over() {
+    let lib = Figment::from(Serialized::default("pool", 8))
+        .require(["url"])
+        .describe("pool", "Connections in the pool.");
+
+    let figment = Figment::new().adjoin_library("db", lib);
+    let error = figment.extract::<Value>().unwrap_err();
+    assert_eq!(error.to_string(), "missing field `url` for key \"db\"");
+
+    let docs = figment.docs().unwrap();
+    assert_eq!(docs.get("db.pool").unwrap().description, Some("Connections in the pool."));
+
+    let figment = figment.merge(Serialized::default("db.url", "postgres://"));
+    assert!(figment.extract::<Value>().is_ok());
+
+    let broken = Figment::from(Toml::string("pool = "));
+    let error = Figment::new().adjoin_library("db", broken).extract::<Value>().unwrap_err();
+    assert!(error.to_string().contains("TOML"), "{}", error);
+}
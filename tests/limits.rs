@@ -0,0 +1,79 @@
+use figment::{Figment, Jail, value::Limits, providers::{Format, Toml, Json}};
+
+#[test]
+fn limits_apply_to_each_provider_and_their_combination() {
+    let limits = Limits::none().max_nodes(3).max_string_len(4);
+    let figment = Figment::new()
+        .limits(limits)
+        .merge(("a", 1))
+        .merge(("b", 2))
+        .merge(("c", 3));
+
+    assert_eq!(figment.extract_inner::<u8>("c").unwrap(), 3);
+
+    let error = figment.clone().merge(("d", [1, 2])).extract_inner::<u8>("c").unwrap_err();
+    assert!(error.to_string().contains("maximum of 3 values"));
+
+    // Each provider is within the limits, but their combination is not.
+    let error = figment.clone().merge(("d", 4)).extract_inner::<u8>("c").unwrap_err();
+    assert!(error.to_string().contains("maximum of 3 values"));
+    assert_eq!(error.path, ["d"]);
+
+    let error = figment.set("name", "robert").extract_inner::<u8>("a").unwrap_err();
+    assert_eq!(error.path, ["name"]);
+    assert_eq!(error.metadata.unwrap().name, "runtime override");
+}
+
+#[test]
+fn limits_apply_while_parsing() {
+    let deep = format!("{}{}", "[".repeat(64), "]".repeat(64));
+    let figment = Figment::new()
+        .limits(Limits::none().max_depth(8))
+        .merge(Json::string(&format!(r#"{{ "deep": {} }}"#, deep)));
+
+    let error = figment.extract::<figment::value::Value>().unwrap_err();
+    assert!(error.to_string().contains("maximum nesting depth of 8"), "{}", error);
+    assert!(error.to_string().contains("line 1"), "{}", error);
+
+    let figment = Figment::new()
+        .limits(Limits::none().max_nodes(4))
+        .merge(Json::string(r#"{ "list": [1, 2, 3, 4, 5, 6] }"#));
+
+    let error = figment.extract::<figment::value::Value>().unwrap_err();
+    assert!(error.to_string().contains("maximum of 4 values"), "{}", error);
+    assert!(error.to_string().contains("line 1"), "{}", error);
+}
+
+#[test]
+fn profiles_do_not_count_toward_parse_limits() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", r#"
+            [default]
+            server = { port = 80, host = "a" }
+
+            [debug]
+            server = { port = 8080, host = "b" }
+        "#)?;
+
+        jail.create_file("Env.toml", r#"
+            [env.default.server]
+            port = 80
+
+            [env.debug.server]
+            port = 8080
+        "#)?;
+
+        let limits = Limits::none().max_depth(2).max_nodes(3);
+        let figment = Figment::new().limits(limits).merge(Toml::file("App.toml").nested());
+        assert_eq!(figment.extract_inner::<u16>("server.port")?, 80);
+        assert_eq!(figment.select("debug").extract_inner::<u16>("server.port")?, 8080);
+
+        let figment = Figment::new().limits(limits).merge(Toml::file("Env.toml").nested_in("env"));
+        assert_eq!(figment.select("debug").extract_inner::<u16>("server.port")?, 8080);
+
+        let limits = Limits::none().max_depth(1);
+        let figment = Figment::new().limits(limits).merge(Toml::file("App.toml").nested());
+        assert!(figment.extract_inner::<u16>("server.port").is_err());
+        Ok(())
+    });
+}
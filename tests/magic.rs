@@ -0,0 +1,252 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+use figment::{Figment, Jail, providers::{Env, Format, Serialized, Toml}};
+use figment::value::{Value, Tag, magic::{RelativePathBuf, Tagged, Either}};
+
+static CONFIG: &str = r#"
+    path = "a"
+    paths = ["a", "b"]
+    nested = [["a"], ["b"]]
+    map = { x = "a", y = "b" }
+    map_of_vecs = { x = ["a"], y = ["b", "c"] }
+    tuple = ["a", 1]
+    port = 1
+    ports = [1, 2]
+    mixed = [1, "x"]
+    newtype = { Newtype = "a" }
+    tuple_variant = { Tuple = ["a", 1] }
+    struct_variant = { Struct = { path = "a", port = 1 } }
+    internal = { kind = "File", path = "a", port = 1 }
+    untagged = { path = "a", port = 1 }
+    flat = { path = "a", port = 1, either = "a" }
+"#;
+
+#[derive(Debug, Deserialize)]
+enum External {
+    Newtype(RelativePathBuf),
+    Tuple(RelativePathBuf, Tagged<u16>),
+    Struct { path: RelativePathBuf, port: Tagged<u16> },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind")]
+enum Internal {
+    File { path: RelativePathBuf, port: Tagged<u16> },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Untagged {
+    File { path: RelativePathBuf, port: Tagged<u16> },
+}
+
+#[derive(Debug, Deserialize)]
+struct Flat {
+    #[serde(flatten)]
+    inner: Inner,
+}
+
+#[derive(Debug, Deserialize)]
+struct Inner {
+    path: RelativePathBuf,
+    port: Tagged<u16>,
+    either: Either<RelativePathBuf, u16>,
+}
+
+#[test]
+fn magic_values_are_hinted_in_containers() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", CONFIG)?;
+
+        let figment = Figment::from(Toml::file("Config.toml"));
+        let file = jail.directory().join("Config.toml");
+        let relative = |path: &RelativePathBuf| {
+            assert_eq!(path.metadata_path(), Some(&*file));
+            assert_eq!(path.relative(), jail.directory().join(path.original()));
+        };
+
+        let tagged = |value: &Tagged<u16>| {
+            let metadata = figment.get_metadata(value.tag()).expect("tagged");
+            assert!(metadata.name.contains("TOML"));
+        };
+
+        figment.extract_inner::<Vec<RelativePathBuf>>("paths")?.iter().for_each(relative);
+        figment.extract_inner::<Vec<Vec<RelativePathBuf>>>("nested")?.iter().flatten().for_each(relative);
+        figment.extract_inner::<Option<RelativePathBuf>>("path")?.iter().for_each(relative);
+        figment.extract_inner::<HashMap<String, RelativePathBuf>>("map")?.values().for_each(relative);
+        figment.extract_inner::<BTreeMap<String, RelativePathBuf>>("map")?.values().for_each(relative);
+        figment.extract_inner::<HashMap<String, Vec<RelativePathBuf>>>("map_of_vecs")?
+            .values()
+            .flatten()
+            .for_each(relative);
+
+        let (path, port) = figment.extract_inner::<(RelativePathBuf, Tagged<u16>)>("tuple")?;
+        relative(&path);
+        tagged(&port);
+
+        figment.extract_inner::<Vec<Tagged<u16>>>("ports")?.iter().for_each(tagged);
+        figment.extract_inner::<Option<Tagged<u16>>>("port")?.iter().for_each(tagged);
+        figment.extract_inner::<Tagged<Vec<RelativePathBuf>>>("paths")?.iter().for_each(relative);
+        figment.extract_inner::<Vec<Option<Tagged<RelativePathBuf>>>>("paths")?
+            .iter()
+            .for_each(|path| relative(path.as_ref().unwrap()));
+
+        match figment.extract_inner::<External>("newtype")? {
+            External::Newtype(path) => relative(&path),
+            v => panic!("unexpected variant: {:?}", v),
+        }
+
+        match figment.extract_inner::<External>("tuple_variant")? {
+            External::Tuple(path, port) => { relative(&path); tagged(&port); }
+            v => panic!("unexpected variant: {:?}", v),
+        }
+
+        match figment.extract_inner::<External>("struct_variant")? {
+            External::Struct { path, port } => { relative(&path); tagged(&port); }
+            v => panic!("unexpected variant: {:?}", v),
+        }
+
+        for either in figment.extract_inner::<Vec<Either<RelativePathBuf, u16>>>("paths")? {
+            match either {
+                Either::Left(path) => relative(&path),
+                Either::Right(v) => panic!("unexpected right: {}", v),
+            }
+        }
+
+        let mixed = figment.extract_inner::<Vec<Either<Tagged<u16>, String>>>("mixed")?;
+        assert!(matches!(&mixed[0], Either::Left(port) if **port == 1 && !port.tag().is_default()));
+        assert!(matches!(&mixed[1], Either::Right(s) if s == "x"));
+
+        match figment.extract_inner::<Option<Either<RelativePathBuf, u16>>>("path")? {
+            Some(Either::Left(path)) => relative(&path),
+            v => panic!("unexpected value: {:?}", v),
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn magic_values_deserialize_when_buffered() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", CONFIG)?;
+
+        // serde buffers the values of flattened structs and untagged or
+        // internally tagged enums, but magic values still find the figment.
+        let figment = Figment::from(Toml::file("Config.toml"));
+        let file = jail.directory().join("Config.toml");
+        let configured = |path: &RelativePathBuf, port: &Tagged<u16>| {
+            assert_eq!(path.original(), std::path::Path::new("a"));
+            assert_eq!(path.metadata_path(), Some(&*file));
+            assert_eq!(**port, 1);
+            assert!(figment.get_metadata(port.tag()).unwrap().name.contains("TOML"));
+        };
+
+        let Internal::File { path, port } = figment.extract_inner("internal")?;
+        configured(&path, &port);
+
+        let Untagged::File { path, port } = figment.extract_inner("untagged")?;
+        configured(&path, &port);
+
+        let flat: Flat = figment.extract_inner("flat")?;
+        configured(&flat.inner.path, &flat.inner.port);
+        assert!(matches!(flat.inner.either, Either::Left(path) if path.metadata_path() == Some(&*file)));
+
+        let flats: Vec<Flat> = figment.clone().merge(("flats", vec![flat_value(), flat_value()]))
+            .extract_inner("flats")?;
+
+        for flat in flats {
+            assert_eq!(flat.inner.path.original(), std::path::Path::new("b"));
+            assert_eq!(flat.inner.path.metadata_path(), None);
+            assert!(!flat.inner.port.tag().is_default());
+        }
+
+        Ok(())
+    });
+}
+
+fn flat_value() -> Value {
+    Value::from(figment::util::map!["path" => Value::from("b"), "port" => 2.into(), "either" => 3.into()])
+}
+
+#[derive(Debug, Deserialize)]
+struct Outer {
+    name: Tagged<String>,
+    #[serde(flatten)]
+    server: Server,
+}
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    host: Tagged<String>,
+    port: Tagged<u16>,
+    root: RelativePathBuf,
+    #[serde(flatten)]
+    tls: Tls,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tls {
+    cert: RelativePathBuf,
+    enabled: Tagged<bool>,
+}
+
+#[test]
+fn flattened_magic_values_resolve_per_key() {
+    Jail::expect_with(|jail| {
+        jail.create_dir_all("conf")?;
+        jail.create_file("conf/App.toml", r#"
+            [default]
+            name = "app"
+            host = "localhost"
+            root = "static"
+            cert = "cert.pem"
+
+            [debug]
+            port = 8000
+        "#)?;
+
+        jail.set_env("APP_HOST", "localhost");
+        jail.set_env("APP_ENABLED", true);
+        jail.set_env("APP_PORT", 80);
+
+        let figment = Figment::from(Toml::file("conf/App.toml").nested())
+            .merge(Env::prefixed("APP_").only(&["host", "enabled"]))
+            .merge(Serialized::default("port", 80));
+
+        let source = |tagged: Tag| figment.get_metadata(tagged).unwrap().name.to_string();
+        let file = jail.directory().join("conf/App.toml");
+
+        let outer: Outer = figment.extract()?;
+        assert!(source(outer.name.tag()).contains("TOML"));
+        assert!(source(outer.server.host.tag()).contains("APP_"));
+        assert!(source(outer.server.tls.enabled.tag()).contains("APP_"));
+        assert!(!source(outer.server.port.tag()).contains("TOML"));
+        assert_eq!(outer.server.root.metadata_path(), Some(&*file));
+        assert_eq!(outer.server.root.relative(), jail.directory().join("conf/static"));
+        assert_eq!(outer.server.tls.cert.relative(), jail.directory().join("conf/cert.pem"));
+
+        let outer: Outer = figment.clone().select("debug").extract()?;
+        assert_eq!(*outer.server.port, 8000);
+        assert!(source(outer.server.port.tag()).contains("TOML"));
+        assert!(source(outer.server.host.tag()).contains("APP_"));
+        Ok(())
+    });
+}
+
+#[test]
+fn magic_values_deserialize_from_values() {
+    let value = Value::from(vec![1u16, 2]);
+    let tagged: Tagged<Vec<u16>> = value.deserialize().unwrap();
+    assert_eq!(*tagged, [1, 2]);
+
+    let paths: Vec<RelativePathBuf> = Value::from(vec!["a", "b"]).deserialize().unwrap();
+    assert_eq!(paths, [RelativePathBuf::from("a"), RelativePathBuf::from("b")]);
+
+    let figment = Figment::from(("port", 1));
+    let tagged: Tagged<u16> = figment.extract_inner("port").unwrap();
+    let value = Value::serialize(Tagged::from(*tagged)).unwrap();
+    let roundtrip: Tagged<u16> = value.deserialize().unwrap();
+    assert_eq!(*roundtrip, 1);
+}
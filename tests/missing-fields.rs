@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+
+use serde::{Serialize, Deserialize};
+use figment::{Figment, providers::{Env, Format, Toml, Serialized}};
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+struct Config {
+    name: String,
+    level: Level,
+    optional: Option<u8>,
+    server: Server,
+    paths: Vec<PathBuf>,
+    debug: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+    tls: Tls,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+struct Tls {
+    cert: PathBuf,
+    key: PathBuf,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+enum Level { Low, High(u8) }
+
+fn missing(figment: &Figment) -> Vec<String> {
+    figment.extract::<Config>().unwrap_err()
+        .into_iter()
+        .inspect(|e| assert!(e.missing()))
+        .map(|e| e.key().map_or_else(|| e.kind.to_string(), |k| format!("{}: {}", k, e.kind)))
+        .collect()
+}
+
+#[test]
+fn the_first_missing_field_is_reported() {
+    figment::Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", r#"
+            debug = true
+
+            [server]
+            port = 80
+
+            [server.tls]
+            key = "key.pem"
+        "#)?;
+
+        let figment = Figment::from(Toml::file("Config.toml"));
+        assert_eq!(missing(&figment), vec!["server.tls: missing field `cert`"]);
+
+        let error = figment.extract::<Config>().unwrap_err();
+        assert!(error.help().is_none());
+        Ok(())
+    });
+
+    let figment = Figment::from(Serialized::global("debug", true));
+    assert_eq!(missing(&figment), vec!["missing field `name`"]);
+}
+
+#[test]
+fn missing_fields_hint_at_defaults() {
+    #[derive(Serialize)]
+    struct Defaults { name: &'static str, debug: bool }
+
+    let figment = Figment::from(Serialized::defaults(Defaults { name: "app", debug: false }));
+    let error = figment.extract::<Config>().unwrap_err();
+    assert_eq!(error.count(), 1);
+    assert!(error.help().unwrap().contains("`level`"));
+    assert!(error.to_string().contains("`level` can be provided by missing_fields::missing_fields_hint_at_defaults::Defaults"), "{}", error);
+    assert!(error.to_string().contains("missing-fields.rs"), "{}", error);
+}
+
+#[test]
+fn missing_fields_are_found_without_deserializing_again() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use figment::{test::Recorder, value::magic::Encrypted};
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Secrets { token: Encrypted<String>, user: String, group: String, role: String }
+
+    static DECRYPTIONS: AtomicUsize = AtomicUsize::new(0);
+    let reverse = |ciphertext: &[u8]| {
+        DECRYPTIONS.fetch_add(1, Ordering::SeqCst);
+        Ok(ciphertext.iter().rev().copied().collect())
+    };
+
+    // `Ym9u` is `bon`.
+    let recorder = Recorder::new(Serialized::default("token", "enc:rev:Ym9u"));
+    let figment = Figment::from(&recorder).decryptor("rev", reverse);
+    let error = figment.extract::<Secrets>().unwrap_err();
+    assert_eq!(error.count(), 1);
+    assert_eq!(DECRYPTIONS.load(Ordering::SeqCst), 1);
+    assert_eq!(recorder.reads().len(), 1);
+}
+
+#[test]
+fn missing_fields_hint_at_misspelled_keys() {
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct App { database: Database, workers: u16 }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Database { url: String, pool_size: u32 }
+
+    fn help(figment: &Figment) -> String {
+        let error = figment.extract::<App>().unwrap_err();
+        assert_eq!(error.count(), 1);
+        error.help().unwrap_or_default().to_string()
+    }
+
+    figment::Jail::expect_with(|jail| {
+        jail.set_env("APP_DATABSE_URL", "postgres://localhost");
+        jail.set_env("APP_DATABASE_POOLSIZE", 8);
+        jail.create_file("App.toml", "wrokers = 4")?;
+
+        let figment = Figment::from(Env::prefixed("APP_").split("_"))
+            .merge(Toml::file("App.toml"));
+
+        assert_eq!(help(&figment), "found `APP_DATABSE_URL` in `APP_` environment variable(s): \
+            did you mean `database.url`?");
+
+        let figment = figment.merge(Serialized::default("database.url", "postgres://localhost"));
+        assert_eq!(help(&figment), "found `APP_DATABASE_POOLSIZE` in `APP_` environment \
+            variable(s): did you mean `database.pool_size`?");
+
+        let figment = figment.merge(Serialized::default("database.pool_size", 8));
+        assert_eq!(help(&figment), "found `default.wrokers` in TOML file: did you mean `workers`?");
+
+        let figment = Figment::from(Env::prefixed("APP_"));
+        let error = figment.extract_inner::<String>("database_url").unwrap_err();
+        assert!(error.help().is_none());
+
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        struct Flat { database_url: String }
+
+        let error = figment.extract::<Flat>().unwrap_err();
+        assert_eq!(error.help(), Some("found `APP_DATABSE_URL` in `APP_` environment \
+            variable(s): did you mean `database_url`?"));
+        Ok(())
+    });
+}
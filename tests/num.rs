@@ -0,0 +1,93 @@
+use std::cmp::Ordering::{self, *};
+
+use figment::Figment;
+use figment::value::Num;
+
+fn all(value: i8) -> Vec<Num> {
+    let mut nums = vec![
+        Num::I8(value), Num::I16(value.into()), Num::I32(value.into()),
+        Num::I64(value.into()), Num::I128(value.into()), Num::ISize(value.into()),
+        Num::F32(value.into()), Num::F64(value.into()),
+    ];
+
+    if value >= 0 {
+        let value = value as u8;
+        nums.extend([
+            Num::U8(value), Num::U16(value.into()), Num::U32(value.into()),
+            Num::U64(value.into()), Num::U128(value.into()), Num::USize(value.into()),
+        ]);
+    }
+
+    nums
+}
+
+#[test]
+fn checked_conversions_depend_on_values() {
+    for num in all(7) {
+        assert_eq!(num.to_u32_checked(), num.is_integer().then_some(7), "{:?}", num);
+        assert_eq!(num.to_u64_checked(), num.is_integer().then_some(7), "{:?}", num);
+        assert_eq!(num.to_i64_checked(), num.is_integer().then_some(7), "{:?}", num);
+        assert_eq!(num.to_f64_lossy(), 7.0);
+    }
+
+    for num in all(-7) {
+        assert_eq!(num.to_u32_checked(), None);
+        assert_eq!(num.to_u64_checked(), None);
+        assert_eq!(num.to_i64_checked(), num.is_integer().then_some(-7), "{:?}", num);
+        assert_eq!(num.to_f64_lossy(), -7.0);
+    }
+
+    assert_eq!(Num::I128(i64::MIN.into()).to_i64_checked(), Some(i64::MIN));
+    assert_eq!(Num::I128(i128::from(i64::MIN) - 1).to_i64_checked(), None);
+    assert_eq!(Num::U128(u64::MAX.into()).to_u64_checked(), Some(u64::MAX));
+
+    // Providers emit whichever variant the source suggests.
+    let figment = Figment::from(("port", 8080u64)).merge(("offset", -1i64));
+    let port = figment.find_value("port").unwrap().to_num().unwrap();
+    assert_eq!(port.to_u32(), None);
+    assert_eq!(port.to_u32_checked(), Some(8080));
+
+    let offset = figment.find_value("offset").unwrap().to_num().unwrap();
+    assert_eq!(offset.to_u32_checked(), None);
+    assert_eq!(offset.to_i64_checked(), Some(-1));
+}
+
+#[test]
+fn comparisons_are_exact_across_variants() {
+    for a in all(3) {
+        for b in all(3) {
+            assert_eq!(a.compare(&b), Some(Equal), "{:?} {:?}", a, b);
+        }
+
+        for b in all(-3) {
+            assert_eq!(a.compare(&b), Some(Greater), "{:?} {:?}", a, b);
+            assert_eq!(b.compare(&a), Some(Less), "{:?} {:?}", b, a);
+        }
+    }
+
+    let cases: &[(Num, Num, Option<Ordering>)] = &[
+        (Num::F64(-0.0), Num::U8(0), Some(Equal)),
+        (Num::F64(-0.5), Num::U8(0), Some(Less)),
+        (Num::F64(-0.5), Num::I8(0), Some(Less)),
+        (Num::F64(0.5), Num::I8(0), Some(Greater)),
+        (Num::F64(-1.5), Num::I8(-1), Some(Less)),
+        (Num::F64(-1.5), Num::I8(-2), Some(Greater)),
+        (Num::F64(2f64.powi(128)), Num::U128(u128::MAX), Some(Greater)),
+        (Num::F64(2f64.powi(127)), Num::I128(i128::MAX), Some(Greater)),
+        (Num::F64(-(2f64.powi(127))), Num::I128(i128::MIN), Some(Equal)),
+        (Num::F64(-(2f64.powi(128))), Num::I128(i128::MIN), Some(Less)),
+        (Num::F64(2f64.powi(64)), Num::U64(u64::MAX), Some(Greater)),
+        (Num::F64(2f64.powi(63)), Num::I64(i64::MAX), Some(Greater)),
+        (Num::F64(f64::INFINITY), Num::U128(u128::MAX), Some(Greater)),
+        (Num::F64(f64::NEG_INFINITY), Num::I128(i128::MIN), Some(Less)),
+        (Num::U128(u128::MAX), Num::I128(i128::MAX), Some(Greater)),
+        (Num::F32(f32::NAN), Num::F32(f32::NAN), None),
+        (Num::F32(f32::NAN), Num::I8(0), None),
+        (Num::U8(0), Num::F64(f64::NAN), None),
+    ];
+
+    for (a, b, expected) in cases {
+        assert_eq!(a.compare(b), *expected, "{:?} {:?}", a, b);
+        assert_eq!(b.compare(a), expected.map(Ordering::reverse), "{:?} {:?}", b, a);
+    }
+}
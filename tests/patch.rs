@@ -0,0 +1,153 @@
+#![cfg(feature = "json")]
+
+use figment::{Figment, providers::{Format, Json, Serialized}};
+use figment::value::Value;
+
+fn figment() -> Figment {
+    Figment::from(Json::string(r#"{
+        "name": "app",
+        "server": { "port": 80, "hosts": ["a", "b"] },
+        "tls": { "key": "key.pem", "cert": "cert.pem" }
+    }"#))
+}
+
+fn json(string: &str) -> Value {
+    Json::from_str(string).unwrap()
+}
+
+#[test]
+fn patch_operations() {
+    let figment = figment().patch(json(r#"[
+        { "op": "add", "path": "/workers", "value": 4 },
+        { "op": "add", "path": "/server/hosts/1", "value": "z" },
+        { "op": "remove", "path": "/tls/cert" },
+        { "op": "replace", "path": "/name", "value": "renamed" },
+        { "op": "move", "from": "/tls/key", "path": "/key" },
+        { "op": "copy", "from": "/server/hosts/0", "path": "/primary" },
+        { "op": "test", "path": "/server/port", "value": 80 }
+    ]"#)).unwrap();
+
+    assert_eq!(figment.extract_inner::<u32>("workers").unwrap(), 4);
+    assert_eq!(figment.extract_inner::<Vec<String>>("server.hosts").unwrap(), ["a", "z", "b"]);
+    assert_eq!(figment.extract_inner::<String>("name").unwrap(), "renamed");
+    assert_eq!(figment.extract_inner::<String>("key").unwrap(), "key.pem");
+    assert_eq!(figment.extract_inner::<String>("primary").unwrap(), "a");
+    assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 80);
+    assert!(figment.find_value("tls.cert").is_err());
+    assert!(figment.find_value("tls.key").is_err());
+
+    let metadata = figment.find_metadata("workers").unwrap();
+    assert_eq!(metadata.name, "runtime override");
+    assert_eq!(figment.find_metadata("server.port").unwrap().name, "JSON source string");
+}
+
+#[test]
+fn patch_is_atomic() {
+    let figment = figment();
+    let bad_patches = [
+        r#"[{ "op": "remove", "path": "/name" }, { "op": "test", "path": "/name", "value": "app" }]"#,
+        r#"[{ "op": "replace", "path": "/missing", "value": 1 }]"#,
+        r#"[{ "op": "remove", "path": "/server/hosts/2" }]"#,
+        r#"[{ "op": "add", "path": "/server/hosts/3", "value": "c" }]"#,
+        r#"[{ "op": "add", "path": "/missing/key", "value": 1 }]"#,
+        r#"[{ "op": "move", "from": "/server", "path": "/server/inner" }]"#,
+        r#"[{ "op": "remove", "path": "" }]"#,
+        r#"[{ "op": "frobnicate", "path": "/name" }]"#,
+        r#"[{ "op": "add", "path": "name", "value": 1 }]"#,
+        r#"[{ "op": "copy", "path": "/name" }]"#,
+        r#"{ "op": "remove", "path": "/name" }"#,
+    ];
+
+    for patch in &bad_patches {
+        assert!(figment.patch(json(patch)).is_err(), "{}", patch);
+    }
+
+    let err = figment.patch(json(r#"[
+        { "op": "remove", "path": "/name" },
+        { "op": "test", "path": "/server/port", "value": 81 }
+    ]"#)).unwrap_err();
+
+    assert!(err.to_string().contains("operation 1 (`test`)"), "{}", err);
+    assert_eq!(figment.extract_inner::<String>("name").unwrap(), "app");
+}
+
+#[test]
+fn patch_test_compares_numbers_by_value() {
+    let figment = Figment::from(Serialized::default("port", 80u8))
+        .merge(("ratio", 1.0));
+
+    let patch = json(r#"[
+        { "op": "test", "path": "/port", "value": 80 },
+        { "op": "test", "path": "/ratio", "value": 1 }
+    ]"#);
+
+    assert!(figment.patch(patch).is_ok());
+
+    // `u64::MAX` rounds to 2^64 as an `f64` but is not equal to it.
+    let figment = Figment::from(Serialized::default("big", u64::MAX));
+    let patch = json(r#"[{ "op": "test", "path": "/big", "value": 18446744073709551616.0 }]"#);
+    assert!(figment.clone().patch(patch).is_err());
+
+    let patch = json(r#"[{ "op": "test", "path": "/big", "value": 18446744073709551615 }]"#);
+    assert!(figment.patch(patch).is_ok());
+}
+
+#[test]
+fn patches_persist_over_later_providers() {
+    let figment = figment()
+        .patch(json(r#"[
+            { "op": "replace", "path": "/server/port", "value": 8080 },
+            { "op": "remove", "path": "/name" }
+        ]"#))
+        .unwrap()
+        .merge(("server.port", 9000))
+        .merge(("name", "other"))
+        .merge(("server.timeout", 5));
+
+    assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+    assert_eq!(figment.extract_inner::<u16>("server.timeout").unwrap(), 5);
+    assert!(figment.find_value("name").is_err());
+
+    let figment = figment
+        .patch(json(r#"[{ "op": "remove", "path": "/tls" }]"#))
+        .unwrap()
+        .patch(json(r#"[{ "op": "add", "path": "/tls", "value": { "key": "new.pem" } }]"#))
+        .unwrap();
+
+    assert_eq!(figment.extract_inner::<String>("tls.key").unwrap(), "new.pem");
+    assert!(figment.find_value("tls.cert").is_err());
+}
+
+#[test]
+fn merge_patch() {
+    let figment = figment().merge_patch(json(r#"{
+        "name": null,
+        "server": { "port": 8080, "hosts": ["c"] },
+        "tls": "off",
+        "workers": { "count": 4 }
+    }"#)).unwrap();
+
+    assert!(figment.find_value("name").is_err());
+    assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+    assert_eq!(figment.extract_inner::<Vec<String>>("server.hosts").unwrap(), ["c"]);
+    assert_eq!(figment.extract_inner::<String>("tls").unwrap(), "off");
+    assert_eq!(figment.extract_inner::<u32>("workers.count").unwrap(), 4);
+
+    let figment = figment.merge_patch(json(r#"{ "tls": { "key": "new.pem" } }"#)).unwrap();
+    assert_eq!(figment.extract_inner::<String>("tls.key").unwrap(), "new.pem");
+    assert!(figment.find_value("tls.cert").is_err());
+
+    assert!(figment.merge_patch(json("[1, 2]")).is_err());
+    assert!(figment.merge_patch(json("null")).is_err());
+}
+
+#[test]
+fn empty_patches_change_nothing() {
+    let figment = figment();
+    let patched = figment.patch(json("[]")).unwrap();
+    assert_eq!(patched.find_metadata("name").unwrap().name, "JSON source string");
+
+    let patched = figment.merge_patch(json(r#"{ "name": "app", "missing": null }"#)).unwrap();
+    assert_eq!(patched.find_metadata("name").unwrap().name, "JSON source string");
+    assert!(patched.find_value("missing").is_err());
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "toml-edit")]
+
+use figment::{Figment, Jail, providers::{Format, Toml}, util::map};
+
+fn read(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn persist_preserves_comments_and_order() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "\
+# Application settings.
+name = \"app\" # the name
+port = 80
+
+[log]
+# One of `info` or `debug`.
+level = \"info\"
+")?;
+
+        let figment = Figment::from(Toml::file("App.toml").editable());
+        figment.persist("port", 8080)?;
+        figment.persist("log", map!["level" => "debug", "format" => "json"])?;
+        figment.persist("tags", vec!["a", "b"])?;
+        figment.persist("server.tls.enabled", true)?;
+
+        assert_eq!(read("App.toml"), "\
+# Application settings.
+name = \"app\" # the name
+port = 8080
+tags = [\"a\", \"b\"]
+
+[log]
+# One of `info` or `debug`.
+level = \"debug\"
+format = \"json\"
+
+[server.tls]
+enabled = true
+");
+
+        Ok(())
+    });
+}
+
+#[test]
+fn persist_writes_to_the_source_of_the_key() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Base.toml", "port = 80\n")?;
+        jail.create_file("User.toml", "name = \"app\"\n")?;
+
+        let figment = Figment::new()
+            .merge(Toml::file("Base.toml").editable())
+            .merge(Toml::file("User.toml").editable());
+
+        figment.persist("port", 8080)?;
+        figment.persist("name", "App")?;
+        figment.persist("workers", 4)?;
+
+        assert_eq!(read("Base.toml"), "port = 8080\n");
+        assert_eq!(read("User.toml"), "name = \"App\"\nworkers = 4\n");
+
+        let figment = Figment::from(Toml::file("Base.toml"));
+        assert!(figment.persist("port", 1).is_err());
+        assert!(Figment::from(Toml::file("Base.toml").editable()).persist("port", ()).is_err());
+        assert_eq!(read("Base.toml"), "port = 8080\n");
+
+        Ok(())
+    });
+}
+
+#[test]
+fn persist_respects_profiles_and_focus() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "[default]\nport = 80\n\n[debug]\nport = 8000\n")?;
+        jail.create_file("Env.toml", "port = 80\n\n[env.debug]\nport = 8000\n")?;
+
+        let figment = Figment::from(Toml::file("App.toml").nested().editable()).select("debug");
+        figment.persist("port", 9000)?;
+        assert_eq!(read("App.toml"), "[default]\nport = 80\n\n[debug]\nport = 9000\n");
+
+        let figment = Figment::from(Toml::file("Env.toml").nested_in("env").editable());
+        figment.persist("port", 81)?;
+        figment.select("debug").persist("port", 8001)?;
+        assert_eq!(read("Env.toml"), "port = 81\n\n[env.debug]\nport = 8001\n");
+
+        jail.create_file("Db.toml", "[database]\nurl = \"postgres://\"\n")?;
+        let figment = Figment::from(Toml::file("Db.toml").editable()).focus("database");
+        figment.persist("pool", 8)?;
+        assert_eq!(read("Db.toml"), "[database]\nurl = \"postgres://\"\npool = 8\n");
+
+        Ok(())
+    });
+}
+
+#[test]
+fn persist_replaces_files_atomically() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "port = 80\n")?;
+        let figment = Figment::from(Toml::file("App.toml").editable());
+
+        // Readers holding the old file still see its old contents.
+        let old = std::fs::File::open("App.toml").unwrap();
+        figment.persist("port", 8080)?;
+        assert_eq!(std::io::read_to_string(old).unwrap(), "port = 80\n");
+        assert_eq!(read("App.toml"), "port = 8080\n");
+
+        let files: Vec<_> = std::fs::read_dir(jail.directory()).unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+
+        assert_eq!(files, ["App.toml"]);
+
+        #[cfg(unix)] {
+            std::os::unix::fs::symlink("App.toml", "Link.toml").unwrap();
+            let figment = Figment::from(Toml::file("Link.toml").editable());
+            figment.persist("port", 9000)?;
+            assert!(std::fs::symlink_metadata("Link.toml").unwrap().file_type().is_symlink());
+            assert_eq!(read("App.toml"), "port = 9000\n");
+        }
+
+        Ok(())
+    });
+}
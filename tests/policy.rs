@@ -0,0 +1,170 @@
+use figment::{Figment, Jail, Policy, Capability, error::Kind};
+use figment::providers::{Env, Format, Toml};
+
+#[test]
+fn file_root_denies_files_outside_root() {
+    Jail::expect_with(|jail| {
+        jail.create_dir_all("config")?;
+        jail.create_file("config/App.toml", "name = \"app\"")?;
+        jail.create_file("Secret.toml", "token = \"hunter2\"")?;
+
+        let figment = Figment::new()
+            .policy(Policy::unrestricted().file_root("config"))
+            .merge(Toml::file("config/App.toml"));
+
+        assert_eq!(figment.extract_inner::<String>("name")?, "app");
+
+        let error = figment.merge(Toml::file("Secret.toml")).find_value("token").unwrap_err();
+        let path = jail.directory().join("Secret.toml");
+        assert_eq!(error.kind, Kind::Denied(Capability::File(path)));
+        assert!(error.metadata.unwrap().name.contains("TOML"));
+
+        Ok(())
+    });
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn file_root_resolves_symlinks() {
+    Jail::expect_with(|jail| {
+        jail.create_dir_all("config")?;
+        jail.create_file("Secret.toml", "token = \"hunter2\"")?;
+        jail.symlink("../Secret.toml", "config/App.toml")?;
+
+        let figment = Figment::new()
+            .policy(Policy::unrestricted().file_root("config"))
+            .merge(Toml::file("config/App.toml"));
+
+        assert_eq!(figment.find_value("token").unwrap_err().code(), "denied");
+        Ok(())
+    });
+}
+
+#[test]
+fn env_access_can_be_denied() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", 8000);
+
+        let figment = Figment::new()
+            .policy(Policy::unrestricted().allow_env(false))
+            .merge(Env::prefixed("APP_"));
+
+        let error = figment.extract_inner::<u16>("port").unwrap_err();
+        assert_eq!(error.kind, Kind::Denied(Capability::Env));
+        assert_eq!(Policy::current(), Policy::unrestricted());
+
+        let figment = Figment::from(Env::prefixed("APP_"));
+        assert_eq!(figment.extract_inner::<u16>("port")?, 8000);
+
+        Ok(())
+    });
+}
+
+#[test]
+fn policy_is_in_effect_only_while_providing() {
+    use figment::{Provider, Metadata, Profile, Error, value::{Map, Dict}};
+
+    struct Probe;
+
+    impl Provider for Probe {
+        fn metadata(&self) -> Metadata {
+            Metadata::named("probe")
+        }
+
+        fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+            let allowed = Policy::current().check(&Capability::Network("probe".into())).is_ok();
+            let mut dict = Dict::new();
+            dict.insert("allowed".into(), allowed.into());
+            Ok(Profile::Default.collect(dict))
+        }
+    }
+
+    let figment = Figment::new()
+        .merge(Probe)
+        .policy(Policy::unrestricted().allow_network(false));
+
+    assert!(figment.extract_inner::<bool>("allowed").unwrap());
+
+    let figment = figment.merge(Probe);
+    assert!(!figment.extract_inner::<bool>("allowed").unwrap());
+    assert_eq!(Policy::current(), Policy::unrestricted());
+}
+
+#[test]
+fn env_capture_respects_policy() {
+    use figment::{Provider, Metadata, Profile, Error, value::{Map, Dict}};
+
+    struct Capturing;
+
+    impl Provider for Capturing {
+        fn metadata(&self) -> Metadata {
+            Metadata::named("capturing")
+        }
+
+        fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+            let captured = Env::prefixed("APP_").capture().iter().count();
+            let mut dict = Dict::new();
+            dict.insert("captured".into(), captured.into());
+            Ok(Profile::Default.collect(dict))
+        }
+    }
+
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", 8000);
+
+        let figment = Figment::new().merge(Capturing);
+        assert_eq!(figment.extract_inner::<usize>("captured")?, 1);
+
+        let figment = Figment::new()
+            .policy(Policy::unrestricted().allow_env(false))
+            .merge(Capturing);
+
+        assert_eq!(figment.extract_inner::<usize>("captured")?, 0);
+        Ok(())
+    });
+}
+
+#[test]
+#[cfg(feature = "toml-edit")]
+fn persist_respects_policy() {
+    Jail::expect_with(|jail| {
+        jail.create_dir_all("config")?;
+        jail.create_file("App.toml", "port = 80")?;
+
+        let figment = Figment::from(Toml::file("App.toml").editable())
+            .policy(Policy::unrestricted().file_root("config"));
+
+        let error = figment.persist("port", 8080).unwrap_err();
+        assert_eq!(error.code(), "denied");
+        assert_eq!(std::fs::read_to_string("App.toml").unwrap(), "port = 80");
+
+        let figment = figment.policy(Policy::unrestricted());
+        figment.persist("port", 8080)?;
+        assert!(std::fs::read_to_string("App.toml").unwrap().contains("8080"));
+        Ok(())
+    });
+}
+
+#[test]
+fn file_contents_respects_policy() {
+    use figment::value::magic::{Deferred, FileContents};
+
+    Jail::expect_with(|jail| {
+        jail.create_dir_all("config")?;
+        jail.create_file("config/key.pem", "public")?;
+        jail.create_file("secret.pem", "private")?;
+
+        let figment = Figment::new()
+            .policy(Policy::unrestricted().file_root("config"))
+            .merge(("key", "config/key.pem"))
+            .merge(("secret", "secret.pem"));
+
+        let key: Deferred<String, FileContents> = figment.extract_inner("key")?;
+        assert_eq!(key.get()?, "public");
+
+        let secret: Deferred<String, FileContents> = figment.extract_inner("secret")?;
+        let path = std::path::PathBuf::from("secret.pem");
+        assert_eq!(secret.get().unwrap_err().kind, Kind::Denied(Capability::File(path)));
+        Ok(())
+    });
+}
@@ -0,0 +1,91 @@
+#![cfg(all(feature = "preserve-order", feature = "toml", feature = "json"))]
+
+use figment::{Figment, Jail, value::Dict};
+use figment::providers::{Format, Toml, Json, Env};
+
+fn keys(table: &toml::value::Table) -> Vec<&str> {
+    table.keys().map(|k| k.as_str()).collect()
+}
+
+#[test]
+fn dicts_keep_keys_in_provided_order() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", r#"
+            zebra = 1
+            apple = 2
+
+            [server]
+            port = 80
+            host = "localhost"
+
+            [[routes]]
+            path = "/"
+            method = "GET"
+        "#)?;
+
+        jail.set_env("APP_MANGO", 3);
+
+        let figment = Figment::from(Toml::file("App.toml"))
+            .merge(Json::string(r#"{ "server": { "workers": 4, "host": "0.0.0.0" }, "cherry": 5 }"#))
+            .merge(Env::prefixed("APP_"));
+
+        let table: toml::value::Table = figment.extract()?;
+        assert_eq!(keys(&table), ["zebra", "apple", "server", "routes", "cherry", "mango"]);
+        assert_eq!(keys(table["server"].as_table().unwrap()), ["port", "host", "workers"]);
+
+        let route = table["routes"][0].as_table().unwrap();
+        assert_eq!(keys(route), ["path", "method"]);
+
+        let server: toml::value::Table = figment.extract_inner("server")?;
+        assert_eq!(keys(&server), ["port", "host", "workers"]);
+
+        let json: serde_json::Map<String, serde_json::Value> = figment.extract()?;
+        let json_keys: Vec<&str> = json.keys().map(|k| k.as_str()).collect();
+        assert_eq!(json_keys, keys(&table));
+
+        let dict: Dict = figment.extract()?;
+        let dict_keys: Vec<&str> = dict.keys().map(|k| k.as_str()).collect();
+        assert_eq!(dict_keys, keys(&table));
+
+        let value = figment.find_value("server")?;
+        let debug = format!("{:?}", value);
+        assert!(debug.find("port").unwrap() < debug.find("host").unwrap());
+        assert!(debug.find("host").unwrap() < debug.find("workers").unwrap());
+
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"port":80,"host":"0.0.0.0","workers":4}"#);
+        Ok(())
+    });
+}
+
+#[test]
+fn order_survives_profiles_focus_and_reproviding() {
+    let toml = Toml::string(r#"
+        [default]
+        zebra = 1
+        apple = 2
+
+        [debug]
+        yak = 3
+        bee = 4
+
+        [debug.nested]
+        second = 1
+        first = 2
+    "#).nested();
+
+    let figment = Figment::from(toml).select("debug");
+    let table: toml::value::Table = figment.extract().unwrap();
+    assert_eq!(keys(&table), ["zebra", "apple", "yak", "bee", "nested"]);
+
+    let nested: toml::value::Table = figment.focus("nested").extract().unwrap();
+    assert_eq!(keys(&nested), ["second", "first"]);
+
+    let figment = Figment::from(figment).merge(("aardvark", 0));
+    let table: toml::value::Table = figment.extract().unwrap();
+    assert_eq!(keys(&table), ["zebra", "apple", "yak", "bee", "nested", "aardvark"]);
+
+    let figment = figment.set("ant", 1);
+    let table: toml::value::Table = figment.extract().unwrap();
+    assert_eq!(keys(&table).last(), Some(&"ant"));
+}
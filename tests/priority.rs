@@ -0,0 +1,59 @@
+use figment::{Figment, providers::Serialized, util::map};
+
+#[test]
+fn priority_layers_independent_of_order() {
+    let providers = [
+        (0, Serialized::defaults(map!["a" => 0, "b" => 0, "c" => 0, "d" => 0])),
+        (10, Serialized::defaults(map!["b" => 10, "c" => 10, "d" => 10])),
+        (20, Serialized::defaults(map!["c" => 20, "d" => 20])),
+        (30, Serialized::defaults(map!["d" => 30])),
+    ];
+
+    let orders = [[0, 1, 2, 3], [3, 2, 1, 0], [2, 0, 3, 1], [1, 3, 0, 2]];
+    for order in &orders {
+        let figment = order.iter().fold(Figment::new(), |figment, &i| {
+            let (priority, provider) = providers[i].clone();
+            figment.merge_with_priority(priority, provider)
+        });
+
+        let values: Vec<i32> = ["a", "b", "c", "d"].iter()
+            .map(|key| figment.extract_inner(key).unwrap())
+            .collect();
+
+        assert_eq!(values, [0, 10, 20, 30], "order: {:?}", order);
+    }
+}
+
+#[test]
+fn equal_priorities_and_plain_providers_merge_in_order() {
+    let figment = Figment::new()
+        .merge_with_priority(5, Serialized::default("port", 1))
+        .merge_with_priority(5, Serialized::default("port", 2))
+        .merge(Serialized::default("port", 3))
+        .join(Serialized::default("host", "localhost"));
+
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 2);
+    assert_eq!(figment.extract_inner::<String>("host").unwrap(), "localhost");
+
+    let figment = figment.merge_with_priority(5, Serialized::default("port", 4));
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 4);
+}
+
+#[test]
+fn higher_priority_values_keep_their_metadata_and_dicts_merge() {
+    let pools = Serialized::defaults(map!["db" => map!["pool" => 8]]);
+    let figment = Figment::new().merge_with_priority(50, pools);
+    let line = line!() - 1;
+
+    let figment = figment.merge(Serialized::defaults(map!["db" => map!["pool" => 2, "url" => 1]]));
+    assert_eq!(figment.extract_inner::<u8>("db.pool").unwrap(), 8);
+    assert_eq!(figment.extract_inner::<u8>("db.url").unwrap(), 1);
+
+    let pool = figment.find_value("db.pool").unwrap();
+    let metadata = figment.get_metadata(pool.tag()).unwrap();
+    assert_eq!(metadata.provide_location.unwrap().line(), line);
+
+    // Priorities survive focusing.
+    let db = figment.focus("db").merge(Serialized::default("pool", 1));
+    assert_eq!(db.extract_inner::<u8>("pool").unwrap(), 8);
+}
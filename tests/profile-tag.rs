@@ -28,3 +28,46 @@ fn check_values_are_tagged_with_profile() {
         Ok(())
     });
 }
+
+#[test]
+fn check_key_profile_annotations() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_NAME@STAGING", "env-staging");
+        jail.set_env(r"APP_EMAIL\@HOST", "literal");
+
+        let figment = Figment::new()
+            .merge(Serialized::default("name", "default"))
+            .merge(Serialized::default("name@debug", "debug").profile_annotations())
+            .merge(Serialized::default(r#"users["bob@example.com"]@debug"#, 1).profile_annotations())
+            .merge(figment::providers::Env::prefixed("APP_").profile_annotations());
+
+        assert_eq!(figment.extract_inner::<String>("name")?, "default");
+        assert_eq!(figment.extract_inner::<String>("email@host")?, "literal");
+        assert!(figment.find_value("users").is_err());
+
+        let debug = figment.clone().select("debug");
+        assert_eq!(debug.extract_inner::<String>("name")?, "debug");
+        assert_eq!(debug.extract_inner::<u8>(r#"users["bob@example.com"]"#)?, 1);
+
+        let staging = figment.select("staging");
+        assert_eq!(staging.extract_inner::<String>("name")?, "env-staging");
+
+        Ok(())
+    });
+}
+
+#[test]
+fn check_key_profile_annotations_are_opt_in() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_ADMIN@LOCALHOST", "env");
+
+        let figment = Figment::new()
+            .merge(Serialized::default("name@debug", "debug"))
+            .merge(figment::providers::Env::prefixed("APP_"));
+
+        assert_eq!(figment.extract_inner::<String>("name@debug")?, "debug");
+        assert_eq!(figment.extract_inner::<String>("admin@localhost")?, "env");
+        assert!(figment.select("debug").find_value("name").is_err());
+        Ok(())
+    });
+}
@@ -0,0 +1,80 @@
+use figment::{Figment, Provider, Metadata, Profile, Error};
+use figment::providers::Serialized;
+use figment::value::{Map, Dict, Tag, Value, magic::Tagged};
+
+struct Secrets {
+    tags: Vec<(Tag, &'static str)>,
+    unregistered: Tag,
+}
+
+impl Secrets {
+    fn new() -> Self {
+        Secrets { tags: vec![(Tag::unique(), "db"), (Tag::unique(), "api")], unregistered: Tag::unique() }
+    }
+}
+
+impl Provider for Secrets {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("Secrets")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut db = Dict::new();
+        db.insert("password".into(), Value::from("hunter2"));
+        db.insert("port".into(), Value::from(5432));
+
+        let mut dict = Dict::new();
+        dict.insert("db".into(), Value::from(db).tagged(self.tags[0].0));
+        dict.insert("api_key".into(), Value::from("key").tagged(self.tags[1].0));
+        dict.insert("other".into(), Value::from("x").tagged(self.unregistered));
+
+        let mut map = Profile::Default.collect(dict.clone());
+        map.insert("debug".into(), dict);
+        Ok(map)
+    }
+
+    fn tags(&self) -> Map<Tag, Metadata> {
+        self.tags.iter()
+            .map(|(tag, path)| (*tag, Metadata::named("Secrets").source(*path)))
+            .collect()
+    }
+}
+
+#[test]
+fn provider_tags_attribute_values_to_their_metadata() {
+    let figment = Figment::from(Secrets::new());
+    let source = |key: &str| figment.find_metadata(key).unwrap().source.clone().map(|s| s.to_string());
+
+    assert_eq!(source("db").as_deref(), Some("db"));
+    assert_eq!(source("db.password").as_deref(), Some("db"));
+    assert_eq!(source("db.port").as_deref(), Some("db"));
+    assert_eq!(source("api_key").as_deref(), Some("api"));
+    assert_eq!(source("other"), None);
+
+    let key: Tagged<String> = figment.extract_inner("api_key").unwrap();
+    assert_eq!(figment.get_metadata(key.tag()).unwrap().source.as_ref().unwrap().custom(), Some("api"));
+    assert_eq!(key.tag().profile(), Some(Profile::Default));
+    assert!(figment.get_metadata(key.tag()).unwrap().provide_location.is_some());
+
+    let figment = figment.select("debug");
+    let key: Tagged<String> = figment.extract_inner("api_key").unwrap();
+    assert_eq!(key.tag().profile(), None, "a custom profile");
+
+    let err = figment.extract_inner::<u16>("db.password").unwrap_err();
+    assert_eq!(err.metadata.unwrap().source.unwrap().custom(), Some("db"));
+}
+
+#[test]
+fn provider_tags_survive_merging_and_wrapping() {
+    let secrets = Secrets::new();
+    let figment = Figment::from(Serialized::default("db.port", 1))
+        .merge(&secrets)
+        .merge(Serialized::default("db.user", "admin"));
+
+    let source = |key: &str| figment.find_metadata(key).unwrap().source.clone();
+    assert_eq!(source("db.port").unwrap().custom(), Some("db"));
+    assert_eq!(source("db.user").unwrap().custom(), None);
+
+    let nested = Figment::new().merge(figment.clone());
+    assert_eq!(nested.find_metadata("api_key").unwrap().source.as_ref().unwrap().custom(), Some("api"));
+}
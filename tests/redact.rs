@@ -0,0 +1,47 @@
+use figment::{Figment, util::map, value::{Dict, KeyCase, Value}};
+
+#[test]
+fn redactions_apply_through_arrays_and_case() {
+    let servers = vec![
+        map!["host" => "a", "password" => "one"],
+        map!["host" => "b", "password" => "two"],
+    ];
+
+    let figment = Figment::from(("servers", servers))
+        .merge(("apiToken", "secret"))
+        .canonicalize_keys(KeyCase::Snake)
+        .redact(["servers.password", "apiToken"]);
+
+    let value: Value = figment.extract().unwrap();
+    let rendered = figment.redacted(&value).to_string();
+    assert!(!rendered.contains("one") && !rendered.contains("secret"), "{}", rendered);
+    assert!(rendered.contains(r#"{host = "a", password = <redacted>}"#), "{}", rendered);
+    assert!(rendered.contains("api_token = <redacted>"), "{}", rendered);
+}
+
+#[test]
+fn truncation_counts_characters() {
+    let value = Value::from(map!["name" => "ñandú-ñandú"]);
+    assert_eq!(value.redacted().max_len(5).to_string(), r#"{name = "ñandú"... (+6 chars)}"#);
+    assert_eq!(value.redacted().max_len(0).to_string(), "{... (+1 more)}");
+    assert_eq!(Value::from("ñandú").redacted().max_len(5).to_string(), r#""ñandú""#);
+}
+
+#[test]
+fn focused_and_library_redactions() {
+    let figment = Figment::from(("database", map!["url" => "postgres://", "user" => "admin"]))
+        .redact(["database"]);
+
+    let focused = figment.focus("database");
+    let dict: Dict = focused.extract().unwrap();
+    assert_eq!(focused.redacted(&dict).to_string(), "<redacted>");
+    assert_eq!(focused.focus("url").redacted(&dict).to_string(), "<redacted>");
+
+    let library = Figment::from(("name", "lib")).merge(("token", "abc")).redact(["token"]);
+    let figment = Figment::new().adjoin_library("lib", library);
+    let value: Value = figment.extract().unwrap();
+    let rendered = figment.redacted(&value).to_string();
+    assert_eq!(rendered, r#"{lib = {name = "lib", token = <redacted>}}"#);
+
+    assert_eq!(Figment::new().redact([""]).redacted(&value).to_string(), "<redacted>");
+}
@@ -0,0 +1,69 @@
+use serde::{Serialize, Deserialize};
+use figment::{Figment, Jail, providers::{Format, Toml, Serialized}};
+use figment::value::{Value, magic::Tagged};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Config {
+    name: Tagged<String>,
+    port: u16,
+    server: Value,
+}
+
+#[test]
+fn reprovided_values_keep_their_provenance() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "name = \"app\"\nport = 80\nserver = { host = \"localhost\" }")?;
+
+        let base = Figment::from(Toml::file("App.toml"));
+        let config: Config = base.extract()?;
+        let layered = base.clone().merge(Serialized::defaults(&config));
+
+        let name = layered.find_metadata("name").unwrap();
+        assert!(name.name.contains("TOML"));
+        assert!(layered.find_metadata("server.host").unwrap().name.contains("TOML"));
+        assert!(layered.find_metadata("port").unwrap().name.contains("Config"));
+
+        let config: Config = layered.extract()?;
+        assert_eq!(*config.name, "app");
+        assert_eq!(layered.get_metadata(config.name.tag()), base.get_metadata(config.name.tag()));
+
+        // A figment that merges the original knows the tags' metadata.
+        let composed = Figment::new()
+            .merge(base.clone())
+            .merge(Serialized::default("copy", base.find_value("server")?));
+
+        assert!(composed.find_metadata("copy.host").unwrap().name.contains("TOML"));
+
+        // One that doesn't attributes the values to the new provider.
+        let fresh = Figment::from(Serialized::defaults(&config));
+        assert!(fresh.find_metadata("name").unwrap().name.contains("Config"));
+        assert!(fresh.find_metadata("server.host").unwrap().name.contains("Config"));
+        assert!(fresh.extract::<Config>().is_ok());
+
+        // As do overrides.
+        let set = Figment::new().set("copy", base.find_value("server")?);
+        assert_eq!(set.find_metadata("copy.host").unwrap().name, "runtime override");
+        let set = base.clone().set("copy", base.find_value("server")?);
+        assert!(set.find_metadata("copy.host").unwrap().name.contains("TOML"));
+        Ok(())
+    });
+}
+
+#[test]
+fn values_serialize_with_their_tags() {
+    let figment = Figment::from(("key", vec![1, 2])).merge(("other", "x"));
+    let value = figment.find_value("key").unwrap();
+    let serialized = Value::serialize(&value).unwrap();
+    assert_eq!(serialized, value);
+    assert_eq!(serialized.tag(), value.tag());
+    assert_eq!(serialized.as_array().unwrap()[0].tag(), value.as_array().unwrap()[0].tag());
+
+    let tagged: Tagged<String> = figment.extract_inner("other").unwrap();
+    let serialized = Value::serialize(vec![tagged.clone()]).unwrap();
+    assert_eq!(serialized.as_array().unwrap()[0].tag(), tagged.tag());
+    assert_eq!(serialized.as_array().unwrap()[0].as_str(), Some("x"));
+
+    // Other serializers don't see tags.
+    #[cfg(feature = "json")]
+    assert_eq!(serde_json::to_string(&value).unwrap(), "[1,2]");
+}
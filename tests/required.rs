@@ -0,0 +1,91 @@
+use figment::{Figment, providers::Serialized, util::map};
+
+#[test]
+fn all_absent_required_keys_are_reported_in_order() {
+    let figment = Figment::from(("database", map!["pool" => 8]))
+        .require(["database.url", "database.pool", "auth.token", "name"]);
+
+    let errors: Vec<_> = figment.extract_inner::<u32>("database.pool").unwrap_err()
+        .into_iter()
+        .map(|e| (e.key(), e.kind.to_string()))
+        .collect();
+
+    assert_eq!(errors, vec![
+        (Some("database".into()), "missing field `url`".into()),
+        (Some("auth".into()), "missing field `token`".into()),
+        (None, "missing field `name`".into()),
+    ]);
+}
+
+#[test]
+fn required_keys_respect_key_configuration() {
+    let figment = Figment::from(("Database", map!["URL" => "postgres://"]))
+        .case_insensitive(true)
+        .require(["database.url"]);
+
+    assert!(figment.find_value("Database").is_ok());
+
+    let figment = Figment::new()
+        .join(("name", "app"))
+        .require(["name", "port"])
+        .pre_extract(|dict| {
+            dict.insert("port".into(), 8000.into());
+            Ok(())
+        });
+
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8000);
+}
+
+#[test]
+fn focus_rebases_required_keys() {
+    let figment = Figment::from(Serialized::defaults(map!["database" => map!["url" => 1]]))
+        .require(["database.url"]);
+
+    let error = figment.focus("database").require(["pool"]).find_value("url").unwrap_err();
+    assert_eq!(error.kind.to_string(), "missing field `pool`");
+
+    let figment = Figment::from(("database", map!["url" => "postgres://"]))
+        .require(["database.url", "name"]);
+
+    let focused = figment.focus("database");
+    assert_eq!(focused.extract_inner::<String>("url").unwrap(), "postgres://");
+
+    let figment = Figment::from(("database", map!["pool" => 8])).require(["database.url"]);
+    let error = figment.focus("database").extract_inner::<u32>("pool").unwrap_err();
+    assert_eq!(error.kind.to_string(), "missing field `url`");
+    assert_eq!(error.key(), None);
+}
+
+#[test]
+fn required_keys_index_arrays() {
+    let figment = Figment::from(("servers", [map!["port" => 80]]))
+        .require(["servers.0.port"]);
+
+    assert_eq!(figment.extract_inner::<u16>("servers.0.port").unwrap(), 80);
+
+    let figment = figment.require(["servers.0.host", "servers.1.port"]);
+    let errors: Vec<_> = figment.find_value("servers").unwrap_err()
+        .into_iter()
+        .map(|e| (e.key(), e.kind.to_string(), e.help().map(String::from)))
+        .collect();
+
+    // Values defined in code can't supply a missing key, so none is named.
+    assert_eq!(errors, vec![
+        (Some("servers.0".into()), "missing field `host`".into(), None),
+        (Some("servers.1".into()), "missing field `port`".into(), None),
+    ]);
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn required_key_hints_name_only_external_providers() {
+    use figment::providers::{Format, Toml};
+
+    let figment = Figment::from(Serialized::defaults(map!["a" => 1]))
+        .merge(("c", 3))
+        .merge(Toml::string("[debug]\nb = 2").nested())
+        .require(["b"]);
+
+    let error = figment.find_value("a").unwrap_err();
+    assert_eq!(error.help(), Some("`b` can be provided as `default.b` in TOML source string"));
+}
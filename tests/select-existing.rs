@@ -0,0 +1,52 @@
+use figment::{Figment, Jail, providers::{Format, Toml, Serialized}};
+
+#[test]
+fn select_existing_accepts_known_profiles() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", "[default]\nport = 80\n\n[staging]\nport = 8080\n\n[Prod]\n")?;
+
+        let figment = Figment::from(Toml::file("Config.toml").nested());
+        assert_eq!(figment.clone().select_existing("staging")?.extract_inner::<u16>("port")?, 8080);
+        assert_eq!(figment.clone().select_existing("STAGING")?.profile(), "staging");
+        assert_eq!(figment.clone().select_existing("prod")?.extract_inner::<u16>("port")?, 80);
+        assert_eq!(figment.clone().select_existing("default")?.profile(), "default");
+        assert_eq!(figment.select_existing("global")?.profile(), "global");
+
+        assert_eq!(Figment::new().select_existing("default")?.profile(), "default");
+        Ok(())
+    });
+}
+
+#[test]
+fn select_existing_rejects_unknown_profiles() {
+    let figment = Figment::new()
+        .merge(Serialized::default("port", 80))
+        .merge(Serialized::default("port", 8080).profile("staging"))
+        .merge(Serialized::default("port", 443).profile("production"));
+
+    let error = figment.clone().select_existing("stagin").unwrap_err();
+    assert_eq!(error.to_string(), "profile `stagin` has no configuration \
+        (known profiles: `production`, `staging`) (did you mean `staging`?)");
+    assert_eq!(error.help(), Some("did you mean `staging`?"));
+
+    let error = figment.clone().select_existing("PRODUCTON").unwrap_err();
+    assert_eq!(error.help(), Some("did you mean `production`?"));
+
+    let error = figment.select_existing("debug").unwrap_err();
+    assert!(error.help().is_none());
+
+    let error = Figment::from(("port", 80)).select_existing("debug").unwrap_err();
+    assert!(error.to_string().contains("no custom profiles are known"));
+}
+
+#[test]
+fn select_existing_returns_merge_errors() {
+    Jail::expect_with(|jail| {
+        jail.create_file("Config.toml", "port = ")?;
+
+        let figment = Figment::from(Toml::file("Config.toml"));
+        let error = figment.select_existing("debug").unwrap_err();
+        assert!(!error.to_string().contains("has no configuration"));
+        Ok(())
+    });
+}
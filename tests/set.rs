@@ -0,0 +1,73 @@
+use figment::{Figment, value::{KeyCase, Value}, util::map};
+use figment::providers::Serialized;
+
+fn server(name: &str, port: u16) -> figment::value::Map<&'static str, Value> {
+    map!["name" => name.into(), "port" => port.into()]
+}
+
+#[test]
+fn set_values_stay_on_top() {
+    let figment = Figment::from(("log", map!["level" => "info", "color" => "auto"]))
+        .set("log.level", "debug")
+        .merge(("log", map!["level" => "warn", "color" => "never"]))
+        .join(("log.level", "error"));
+
+    assert_eq!(figment.extract_inner::<String>("log.level").unwrap(), "debug");
+    assert_eq!(figment.extract_inner::<String>("log.color").unwrap(), "never");
+    assert_ne!(figment.find_metadata("log.color").unwrap().name, "runtime override");
+
+    let metadata = figment.find_metadata("log.level").unwrap();
+    assert_eq!(metadata.name, "runtime override");
+    assert!(metadata.provide_location.unwrap().file().ends_with("set.rs"));
+
+    let figment = figment.set("log", map!["color" => "always"]);
+    assert_eq!(figment.extract_inner::<String>("log.level").unwrap(), "debug");
+    assert_eq!(figment.extract_inner::<String>("log.color").unwrap(), "always");
+    assert_eq!(figment.metadata().filter(|md| md.name == "runtime override").count(), 1);
+}
+
+#[test]
+fn set_in_profile_and_unset() {
+    let figment = Figment::new()
+        .set_in_profile("debug", "port", 8080)
+        .merge(Serialized::default("port", 80))
+        .merge(Serialized::from(map!["port" => 8000, "host" => 1], "debug"));
+
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 80);
+    assert_eq!(figment.clone().select("debug").extract_inner::<u16>("port").unwrap(), 8080);
+
+    let figment = figment.unset("host").select("debug");
+    assert!(figment.find_value("host").is_err());
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 8080);
+
+    let figment = figment.set("port", std::collections::BTreeMap::from([(1, 2)]));
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap_err().code(), "unsupported_key");
+}
+
+#[test]
+fn set_respects_key_configuration_and_focus() {
+    let figment = Figment::new()
+        .canonicalize_keys(KeyCase::Snake)
+        .merge(("serverConfig", map!["maxConnections" => 10, "port" => 80]))
+        .set("serverConfig.maxConnections", 20)
+        .merge(("server_config.max_connections", 30));
+
+    assert_eq!(figment.extract_inner::<u32>("server_config.max_connections").unwrap(), 20);
+
+    let focused = figment.focus("server_config").merge(("max_connections", 40));
+    assert_eq!(focused.extract_inner::<u32>("max_connections").unwrap(), 20);
+    assert_eq!(focused.extract_inner::<u16>("port").unwrap(), 80);
+
+    let figment = Figment::new()
+        .merge_arrays_by("name")
+        .merge(("servers", vec![server("a", 1), server("b", 2)]))
+        .set("servers", vec![server("b", 3)])
+        .merge(("servers", vec![server("b", 4), server("c", 5)]));
+
+    let ports: Vec<u16> = figment.extract_inner::<Vec<Value>>("servers").unwrap()
+        .into_iter()
+        .map(|v| v.find("port").unwrap().to_u128().unwrap() as u16)
+        .collect();
+
+    assert_eq!(ports, [1, 3, 5]);
+}
@@ -0,0 +1,194 @@
+#![cfg(all(feature = "env", feature = "toml"))]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use figment::{Figment, Jail, Metadata, Profile, Provider, Error};
+use figment::providers::{Env, Format, Toml};
+use figment::value::{Map, Dict};
+use figment::warning::Warning;
+
+/// A provider that counts the calls to each of its methods and provides the
+/// number of calls to `data()` as `reads`.
+#[derive(Default, Clone)]
+struct Counting {
+    metadata: Arc<AtomicUsize>,
+    data: Arc<AtomicUsize>,
+    warnings: Arc<AtomicUsize>,
+}
+
+impl Provider for Counting {
+    fn metadata(&self) -> Metadata {
+        self.metadata.fetch_add(1, Ordering::SeqCst);
+        Metadata::named("counting")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let reads = self.data.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut dict = Dict::new();
+        dict.insert("reads".into(), reads.into());
+        Ok(Profile::Default.collect(dict))
+    }
+
+    fn warnings(&self) -> Vec<Warning> {
+        self.warnings.fetch_add(1, Ordering::SeqCst);
+        vec![]
+    }
+}
+
+impl Counting {
+    fn counts(&self) -> [usize; 3] {
+        [&self.metadata, &self.data, &self.warnings].map(|n| n.load(Ordering::SeqCst))
+    }
+}
+
+#[test]
+fn providers_are_read_exactly_once() {
+    let provider = Counting::default();
+    let figment = Figment::from(provider.clone()).merge(("port", 8000));
+    assert_eq!(provider.counts(), [1, 1, 1]);
+
+    for _ in 0..3 {
+        assert_eq!(figment.extract_inner::<usize>("reads").unwrap(), 1);
+        assert_eq!(figment.extract::<Dict>().unwrap().len(), 2);
+        assert_eq!(figment.find_metadata("reads").unwrap().name, "counting");
+    }
+
+    assert_eq!(provider.counts(), [1, 1, 1]);
+
+    // A snapshot is read once and provides the same data wherever it's merged.
+    let snapshot = provider.snapshot();
+    assert_eq!(provider.counts(), [2, 2, 2]);
+
+    let a = Figment::from(&snapshot);
+    let b = Figment::new().join(snapshot.clone()).select("release");
+    assert_eq!(a.extract_inner::<usize>("reads").unwrap(), 2);
+    assert_eq!(b.extract_inner::<usize>("reads").unwrap(), 2);
+    assert_eq!(provider.counts(), [2, 2, 2]);
+}
+
+#[test]
+fn env_snapshots_are_self_consistent() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", "eighty");
+        jail.set_env("APP_NAME", "app");
+
+        let snapshot = Env::prefixed("APP_").snapshot();
+        jail.clear_env();
+        jail.set_env("APP_WORKERS", 4);
+
+        let figment = Figment::from(&snapshot);
+        assert_eq!(figment.extract_inner::<String>("name")?, "app");
+        assert!(figment.find_value("workers").is_err());
+
+        let error = figment.extract_inner::<u16>("port").unwrap_err();
+        assert!(error.to_string().contains("from `APP_PORT`"), "{}", error);
+        Ok(())
+    });
+}
+
+#[test]
+fn extractions_observe_one_snapshot() {
+    #[derive(serde::Deserialize)]
+    struct Config { a: usize, b: usize }
+
+    Jail::expect_with(|jail| {
+        jail.create_file("A.toml", "a = 0")?;
+        jail.create_file("B.toml", "b = 0")?;
+        jail.set_env("APP_C", 0);
+
+        let figment = Figment::from(Toml::file("A.toml"))
+            .merge(Toml::file("B.toml"))
+            .merge(Env::prefixed("APP_"));
+
+        // Change every source; the figment, already built, is unaffected.
+        jail.create_file("A.toml", "a = 1")?;
+        jail.create_file("B.toml", "b = 1")?;
+        jail.set_env("APP_C", 1);
+
+        let config: Config = figment.extract()?;
+        assert_eq!((config.a, config.b), (0, 0));
+        assert_eq!(figment.extract_inner::<usize>("c")?, 0);
+
+        let figment = Figment::from(Toml::file("A.toml")).merge(Toml::file("B.toml"));
+        let config: Config = figment.extract()?;
+        assert_eq!((config.a, config.b), (1, 1));
+        Ok(())
+    });
+}
+
+#[test]
+#[cfg(feature = "shared")]
+fn extractions_observe_one_snapshot_across_reloads() {
+    use figment::SharedFigment;
+
+    #[derive(serde::Deserialize)]
+    struct Config { a: usize, b: usize }
+
+    Jail::expect_with(|jail| {
+        jail.create_file("A.toml", "a = 0")?;
+        jail.create_file("B.toml", "b = 0")?;
+        let (a, b) = (jail.directory().join("A.toml"), jail.directory().join("B.toml"));
+
+        let load = || Figment::from(Toml::file(&a)).merge(Toml::file(&b));
+        let shared = SharedFigment::new(load());
+        std::thread::scope(|s| {
+            s.spawn(|| for i in 1..=50 {
+                std::fs::write(&a, format!("a = {}", i)).unwrap();
+                std::fs::write(&b, format!("b = {}", i)).unwrap();
+                shared.store(load());
+            });
+
+            for _ in 0..4 {
+                s.spawn(|| for _ in 0..200 {
+                    let config = shared.extract::<Config>().unwrap();
+                    assert_eq!(config.a, config.b);
+
+                    let figment = shared.load();
+                    let a: usize = figment.extract_inner("a").unwrap();
+                    assert_eq!(figment.extract_inner::<usize>("b").unwrap(), a);
+                });
+            }
+        });
+
+        assert_eq!(shared.extract_inner::<usize>("a")?, 50);
+        Ok(())
+    });
+}
+
+#[test]
+fn wrapped_env_snapshots_are_self_consistent() {
+    use figment::test::Recorder;
+
+    // Sets `APP_WORKERS` the first time the environment is read, so a second
+    // read observes a variable the first didn't.
+    let env = || Env::prefixed("APP_").filter(|key| {
+        if key == "port" && std::env::var("APP_WORKERS").is_err() {
+            std::env::set_var("APP_WORKERS", "4");
+        }
+
+        true
+    });
+
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORT", 80);
+        let figment = Figment::new().merge_into_profile("release", env()).select("release");
+        assert_eq!(figment.extract_inner::<u16>("port")?, 80);
+        assert!(figment.find_value("workers").is_err());
+
+        jail.clear_env();
+        jail.set_env("APP_PORT", 80);
+        let recorder = Recorder::new(env());
+        let figment = Figment::from(&recorder);
+        assert_eq!(figment.extract_inner::<u16>("port")?, 80);
+        assert!(figment.find_value("workers").is_err());
+        assert_eq!(recorder.evaluations(), 1);
+
+        jail.clear_env();
+        jail.set_env("APP_PORT", 80);
+        let figment = Figment::new().adjoin_library("lib", Figment::from(env()));
+        assert_eq!(figment.extract_inner::<u16>("lib.port")?, 80);
+        assert!(figment.find_value("lib.workers").is_err());
+        Ok(())
+    });
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "toml")]
+
+use serde::{Serialize, Deserialize};
+use figment::{Figment, Jail, providers::{Env, Format, Toml}};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct UserId(u32);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    owner: UserId,
+    members: Vec<UserId>,
+    verbose: bool,
+}
+
+fn user_id(s: &str) -> Result<UserId, String> {
+    s.strip_prefix("user-")
+        .and_then(|id| id.parse().ok())
+        .map(UserId)
+        .ok_or_else(|| format!("invalid user ID `{}`", s))
+}
+
+#[test]
+fn coercers_apply_across_providers_and_depths() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", r#"
+            owner = "user-1"
+            members = ["user-2", 3]
+            verbose = "yes"
+        "#)?;
+
+        let figment = Figment::from(Toml::file("App.toml"))
+            .coerce_str(user_id)
+            .coerce_str(|s: &str| match s {
+                "yes" => Ok(true),
+                "no" => Ok(false),
+                _ => Err("expected `yes` or `no`"),
+            });
+
+        let config: Config = figment.extract()?;
+        assert_eq!(config, Config {
+            owner: UserId(1),
+            members: vec![UserId(2), UserId(3)],
+            verbose: true,
+        });
+
+        jail.set_env("APP_OWNER", "user-7");
+        jail.set_env("APP_VERBOSE", "no");
+        let config: Config = figment.merge(Env::prefixed("APP_")).extract()?;
+        assert_eq!(config.owner, UserId(7));
+        assert!(!config.verbose);
+        Ok(())
+    });
+}
+
+#[test]
+fn coercer_errors_point_to_the_value() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", "[server]\nowner = \"admin\"")?;
+
+        let figment = Figment::from(Toml::file("App.toml")).coerce_str(user_id);
+        let error = figment.extract_inner::<UserId>("server.owner").unwrap_err();
+        assert_eq!(error.path, ["server", "owner"]);
+        assert_eq!(error.metadata.as_ref().unwrap().name, "TOML file");
+        assert!(error.to_string().starts_with("invalid user ID `admin`"), "{}", error);
+        Ok(())
+    });
+}
+
+#[test]
+fn coercers_only_apply_to_their_type() {
+    let figment = Figment::from(("id", "user-5"))
+        .merge(("count", "user-5"))
+        .coerce_str(user_id);
+
+    assert_eq!(figment.extract_inner::<UserId>("id").unwrap(), UserId(5));
+    assert_eq!(figment.extract_inner::<String>("id").unwrap(), "user-5");
+    assert!(figment.extract_inner::<u32>("count").is_err());
+
+    // Coercers survive refocusing.
+    let focused = Figment::from(("user", figment::util::map!["id" => "user-9"]))
+        .coerce_str(user_id)
+        .focus("user");
+
+    assert_eq!(focused.extract_inner::<UserId>("id").unwrap(), UserId(9));
+}
+
+#[test]
+#[should_panic(expected = "has no serde name")]
+fn string_coercers_cannot_be_registered() {
+    let _ = Figment::new().coerce_str(|s: &str| Ok::<_, String>(s.to_uppercase()));
+}
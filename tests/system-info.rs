@@ -0,0 +1,41 @@
+use figment::{Figment, providers::{Serialized, SystemInfo}};
+
+#[test]
+fn system_info_reports_host_facts() {
+    let figment = Figment::from(SystemInfo::new());
+    assert_eq!(figment.extract_inner::<String>("system.os").unwrap(), std::env::consts::OS);
+    assert_eq!(figment.extract_inner::<String>("system.family").unwrap(), std::env::consts::FAMILY);
+    assert_eq!(figment.extract_inner::<String>("system.arch").unwrap(), std::env::consts::ARCH);
+
+    let cpus = std::thread::available_parallelism().unwrap().get();
+    assert_eq!(figment.extract_inner::<usize>("system.cpus").unwrap(), cpus);
+
+    if cfg!(target_os = "linux") {
+        assert!(figment.extract_inner::<u64>("system.memory").unwrap() > 0);
+        assert!(!figment.extract_inner::<String>("system.hostname").unwrap().is_empty());
+    }
+
+    let metadata = figment.find_metadata("system.os").unwrap();
+    assert_eq!(metadata.name, "system information");
+    assert!(metadata.source.as_ref().unwrap().code_location().unwrap().file().ends_with("system-info.rs"));
+}
+
+#[test]
+fn joined_system_info_has_low_priority() {
+    let figment = Figment::from(Serialized::default("system.cpus", 1024))
+        .merge(Serialized::default("system.os", "custom"))
+        .join(SystemInfo::new());
+
+    assert_eq!(figment.extract_inner::<usize>("system.cpus").unwrap(), 1024);
+    assert_eq!(figment.extract_inner::<String>("system.os").unwrap(), "custom");
+    assert_eq!(figment.extract_inner::<String>("system.arch").unwrap(), std::env::consts::ARCH);
+}
+
+#[test]
+fn system_info_prefix_and_profile() {
+    let figment = Figment::from(SystemInfo::new().prefix("").profile("debug"));
+    assert!(figment.find_value("os").is_err());
+
+    let figment = figment.select("debug");
+    assert_eq!(figment.extract_inner::<String>("os").unwrap(), std::env::consts::OS);
+}
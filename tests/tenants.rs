@@ -0,0 +1,43 @@
+use figment::{Figment, providers::{Format, Toml}};
+
+#[test]
+fn tenant_values_override_per_profile() {
+    let figment = Figment::from(Toml::string(r#"
+        [default]
+        plan = "free"
+        seats = 5
+        tenants.acme.plan = "team"
+
+        [release]
+        seats = 10
+        tenants.acme.seats = 50
+    "#).nested());
+
+    let acme = figment.for_tenant("acme");
+    assert_eq!(acme.extract_inner::<String>("plan").unwrap(), "team");
+    assert_eq!(acme.extract_inner::<u32>("seats").unwrap(), 5);
+    assert!(acme.find_value("tenants").is_err());
+
+    let acme = figment.clone().select("release").for_tenant("acme");
+    assert_eq!(acme.extract_inner::<String>("plan").unwrap(), "team");
+    assert_eq!(acme.extract_inner::<u32>("seats").unwrap(), 50);
+
+    let other = figment.select("release").for_tenant("other");
+    assert_eq!(other.extract_inner::<u32>("seats").unwrap(), 10);
+}
+
+#[test]
+fn tenant_lookup_ignores_case_if_configured() {
+    let figment = Figment::new()
+        .case_insensitive(true)
+        .merge(Toml::string(r#"
+            Plan = "free"
+
+            [Tenants.Acme]
+            plan = "team"
+        "#));
+
+    let acme = figment.for_tenant("acme");
+    assert_eq!(acme.extract_inner::<String>("plan").unwrap(), "team");
+    assert!(acme.find_value("tenants").is_err());
+}
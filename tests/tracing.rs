@@ -0,0 +1,143 @@
+#![cfg(feature = "tracing")]
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use tracing::{Event, Metadata, Subscriber, field::{Field, Visit}, span};
+
+use figment::{Figment, Profile, Provider, Error};
+use figment::value::{Map, Dict, Value};
+
+/// A subscriber that records every event as `message: field=value ...`.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+struct Line<'a>(&'a mut String);
+
+impl Visit for Line<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.0.insert_str(0, &format!("{:?}:", value)),
+            name => self.0.push_str(&format!(" {}={:?}", name, value)),
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool { true }
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id { span::Id::from_u64(1) }
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+    fn enter(&self, _: &span::Id) {}
+    fn exit(&self, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = String::new();
+        event.record(&mut Line(&mut line));
+        self.0.lock().unwrap().push(line);
+    }
+}
+
+/// Provides `password = $1` from a source named `$0`.
+struct Named(&'static str, &'static str);
+
+impl Provider for Named {
+    fn metadata(&self) -> figment::Metadata {
+        figment::Metadata::named(self.0)
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let dict = Dict::from([("password".into(), Value::from(self.1))]);
+        Ok(Profile::Default.collect(dict))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    #[allow(dead_code)]
+    password: u32,
+}
+
+#[test]
+fn events_never_contain_values() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let figment = Figment::new()
+            .merge(Named("defaults", "hunter1"))
+            .merge(Named("overrides", "hunter2"))
+            .select("debug");
+
+        assert!(figment.extract::<Config>().is_err());
+    });
+
+    let lines = recorder.0.lock().unwrap();
+    assert!(lines.iter().all(|line| !line.contains("hunter")), "{:#?}", lines);
+
+    let find = |prefix: &str| lines.iter()
+        .find(|line| line.starts_with(prefix))
+        .unwrap_or_else(|| panic!("no `{}` event in {:#?}", prefix, lines));
+
+    let conflict = find("resolved conflicting values:");
+    assert!(conflict.contains("key=password"));
+    assert!(conflict.contains("kept=\"overrides\""));
+    assert!(conflict.contains("discarded=\"defaults\""));
+
+    assert!(find("selected profile:").contains("profile=debug"));
+
+    let failure = find("extraction failed:");
+    assert!(failure.contains("code=\"invalid_type\""));
+    assert!(failure.contains("path=password"));
+    assert!(failure.contains("source=\"overrides\""));
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    port: figment::value::magic::Tagged<u16>,
+    hosts: Vec<String>,
+    tls: Option<bool>,
+}
+
+#[test]
+fn deserializer_events_trace_the_path_taken() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let figment = Figment::from(("server.port", 8000))
+            .merge(("server.hosts", ["hunter1", "hunter2"]))
+            .merge(("server.tls", "hunter3"));
+
+        assert!(figment.extract_inner::<Server>("server").is_err());
+    });
+
+    let lines = recorder.0.lock().unwrap();
+    assert!(lines.iter().all(|line| !line.contains("hunter")), "{:#?}", lines);
+
+    let has = |line: &str| lines.iter().any(|l| l == line);
+    assert!(has("deserializing struct: name=\"Server\" strategy=\"any\""), "{:#?}", lines);
+    assert!(has("deserializing struct: name=\"___figment_tagged_item\" strategy=\"magic: Tagged\""));
+    assert!(has("deserializing field: key=port"));
+    assert!(has("deserializing element: index=1"));
+    assert!(has("deserializing option: present=true"));
+    assert!(has("deserializing value: kind=\"string\""));
+}
+
+#[test]
+fn secret_reads_are_audited() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let figment = Figment::new()
+            .merge(Named("vault", "hunter2"))
+            .merge(("name", "app"))
+            .redact(["password"]);
+
+        assert!(figment.extract_inner::<String>("name").is_ok());
+        assert!(figment.extract_inner::<String>("password").is_ok());
+    });
+
+    let lines = recorder.0.lock().unwrap();
+    assert!(lines.iter().all(|line| !line.contains("hunter")), "{:#?}", lines);
+
+    let audits: Vec<_> = lines.iter().filter(|l| l.starts_with("secret read:")).collect();
+    assert_eq!(audits, ["secret read: key=password requested=password profile=default"]);
+}
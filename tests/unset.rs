@@ -0,0 +1,73 @@
+use serde::Deserialize;
+use figment::{Figment, Jail, providers::{Format, Json, Toml, Serialized}};
+use figment::value::{Empty, Value};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: Option<String>,
+    #[serde(default)]
+    port: u16,
+    tls: Option<Tls>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Tls {
+    cert: String,
+}
+
+#[test]
+fn unset_removes_keys_from_earlier_providers_and_profiles() {
+    Jail::expect_with(|jail| {
+        jail.create_file("App.toml", r#"
+            [default]
+            name = "app"
+            port = 8000
+            tls = { cert = "cert.pem" }
+
+            [debug]
+            port = 9000
+        "#)?;
+
+        let figment = Figment::new()
+            .merge(Toml::file("App.toml").nested())
+            .merge(Serialized::default("tls", Empty::Unset).profile("debug"))
+            .merge(Serialized::default("port", Value::from(Empty::Unset)).profile("debug"));
+
+        let config: Config = figment.extract()?;
+        assert_eq!(config, Config {
+            name: Some("app".into()),
+            port: 8000,
+            tls: Some(Tls { cert: "cert.pem".into() }),
+        });
+
+        let debug = figment.clone().select("debug");
+        let config: Config = debug.extract()?;
+        assert_eq!(config, Config { name: Some("app".into()), port: 0, tls: None });
+        assert!(debug.find_value("tls.cert").is_err());
+
+        // A later provider can set the key again.
+        let config: Config = figment.merge(Serialized::default("port", 1).profile("debug"))
+            .select("debug")
+            .extract()?;
+
+        assert_eq!(config.port, 1);
+        Ok(())
+    });
+}
+
+#[test]
+fn null_is_none_not_unset() {
+    let figment = Figment::new()
+        .merge(Json::string(r#"{ "name": "app", "port": 80 }"#))
+        .merge(Json::string(r#"{ "name": null }"#));
+
+    let config: Config = figment.extract().unwrap();
+    assert_eq!(config, Config { name: None, port: 80, tls: None });
+    assert!(figment.find_value("name").is_ok());
+}
+
+#[test]
+fn unset_serializes_as_none() {
+    let json = serde_json::to_string(&Value::from(Empty::Unset)).unwrap();
+    assert_eq!(json, "null");
+}
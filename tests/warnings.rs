@@ -0,0 +1,98 @@
+use figment::{Figment, Jail, Provider, Metadata, Profile};
+use figment::value::{Map, Dict};
+use figment::warning::{Warning, Kind};
+use figment::providers::{Env, Format, Toml};
+
+struct Legacy;
+
+impl Provider for Legacy {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("legacy")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
+        Ok(Map::new())
+    }
+
+    fn warnings(&self) -> Vec<Warning> {
+        vec![Warning::new(Kind::Deprecated(Some("server.port".into()))).with_key("port")]
+    }
+}
+
+#[test]
+fn warnings_are_collected_in_order() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_A__B", "1");
+        jail.set_env("APP_NAME", "{ not a dict");
+        jail.create_file("Config.toml", r#"
+            Port = 1
+            port = 2
+        "#)?;
+
+        let figment = Figment::new()
+            .case_insensitive(true)
+            .merge(Legacy)
+            .merge(Toml::file("Config.toml"))
+            .merge(Env::prefixed("APP_").split("_"));
+
+        let warnings: Vec<_> = figment.warnings().iter().collect();
+        assert_eq!(warnings.len(), 4);
+
+        assert_eq!(warnings[0].key.as_deref(), Some("port"));
+        assert_eq!(warnings[0].metadata.as_ref().unwrap().name, "legacy");
+
+        assert_eq!(warnings[1].kind, Kind::Shadowed);
+        assert_eq!(warnings[1].key.as_deref(), Some("Port"));
+
+        assert_eq!(warnings[2].kind, Kind::IgnoredEnvVar("APP_A__B".into()));
+        assert_eq!(warnings[3].kind, Kind::ParseFallback("{ not a dict".into()));
+        assert_eq!(warnings[3].key.as_deref(), Some("name"));
+
+        // Warnings survive being merged into another figment.
+        let outer = Figment::new().merge(figment);
+        assert_eq!(outer.warnings().len(), 4);
+        Ok(())
+    });
+}
+
+#[test]
+fn no_warnings_by_default() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_PORTS", "[80, 443]");
+        jail.create_file("Config.toml", "Port = 1\nport = 2")?;
+
+        let figment = Figment::new()
+            .merge(Toml::file("Config.toml"))
+            .merge(Env::prefixed("APP_"));
+
+        assert!(figment.warnings().is_empty());
+        Ok(())
+    });
+}
+
+#[test]
+fn only_repeats_of_the_last_provider_are_skipped() {
+    use figment::providers::Serialized;
+
+    let a = || Serialized::default("port", 1);
+    let b = || Serialized::default("port", 2);
+
+    let figment = Figment::new().dedup_providers(true).merge(a()).merge(a());
+    assert_eq!(figment.warnings().iter().next().unwrap().kind, Kind::DuplicateProvider);
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 1);
+
+    let figment = Figment::new().dedup_providers(true).merge(a()).merge(b()).merge(a());
+    assert!(figment.warnings().is_empty());
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 1);
+
+    let figment = Figment::new().dedup_providers(true).join(a()).merge(b()).merge(a());
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 1);
+
+    let figment = Figment::new().dedup_providers(true)
+        .merge_with_priority(1, a())
+        .merge_with_priority(2, a())
+        .merge(b());
+
+    assert!(figment.warnings().is_empty());
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 1);
+}
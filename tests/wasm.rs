@@ -0,0 +1,70 @@
+//! Run with `wasm-pack test --headless --firefox -- --features web,json`.
+#![cfg(all(target_arch = "wasm32", feature = "web", feature = "json"))]
+
+use serde::Deserialize;
+use wasm_bindgen_test::*;
+use figment::{Figment, providers::{Format, Json, Js, LocalStorage, Serialized}};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: String,
+    ports: Vec<u16>,
+    ratio: f32,
+    debug: Option<bool>,
+}
+
+#[wasm_bindgen_test]
+fn js_value() {
+    let value = js_sys::JSON::parse(r#"{
+        "name": "app", "ports": [80, 443], "ratio": 0.5, "debug": null
+    }"#).unwrap();
+
+    let config: Config = Figment::from(Js::value(value)).extract().unwrap();
+    assert_eq!(config, Config {
+        name: "app".into(),
+        ports: vec![80, 443],
+        ratio: 0.5,
+        debug: None,
+    });
+
+    let value = js_sys::JSON::parse(r#"{ "ports": [80, -1] }"#).unwrap();
+    let err = Figment::from(Js::value(value)).extract_inner::<Vec<u16>>("ports").unwrap_err();
+    assert_eq!(err.display_path().unwrap(), "ports[1]");
+
+    let value = js_sys::JSON::parse("[1, 2]").unwrap();
+    assert!(Figment::from(Js::value(value)).extract::<Config>().is_err());
+}
+
+#[wasm_bindgen_test]
+fn local_storage() {
+    let storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
+    storage.set_item("app.name", "app").unwrap();
+    storage.set_item("app.ports", "[8000]").unwrap();
+    storage.set_item("app.net.ratio", "0.25").unwrap();
+    storage.set_item("other.name", "other").unwrap();
+
+    let figment = Figment::new()
+        .merge(Json::string(r#"{ "ratio": 1.0, "debug": true }"#))
+        .merge(LocalStorage::prefixed("app."));
+
+    let config: Config = figment.extract().unwrap();
+    assert_eq!(config, Config {
+        name: "app".into(),
+        ports: vec![8000],
+        ratio: 1.0,
+        debug: Some(true),
+    });
+
+    assert_eq!(figment.extract_inner::<f32>("net.ratio").unwrap(), 0.25);
+    storage.clear().unwrap();
+}
+
+#[wasm_bindgen_test]
+fn file_and_serialized() {
+    let figment = Figment::from(Serialized::defaults(("name", "app")))
+        .merge(Json::file("Missing.json"));
+
+    assert_eq!(figment.extract_inner::<String>("name").unwrap(), "app");
+}